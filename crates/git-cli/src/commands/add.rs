@@ -95,7 +95,7 @@ fn add_all(
                 let path = work_tree.join(e.path.to_str_lossy().as_ref());
                 !path.exists()
             })
-            .map(|e| e.path.clone())
+            .map(|e| e.path.to_bstring())
             .collect()
     };
 
@@ -126,7 +126,7 @@ fn add_update(
         index
             .iter()
             .filter(|e| e.stage == Stage::Normal)
-            .map(|e| e.path.clone())
+            .map(|e| e.path.to_bstring())
             .collect()
     };
 
@@ -246,11 +246,17 @@ fn add_single_file(
         return Ok(());
     }
 
+    // If this path had a pending rerere conflict and its staged content no
+    // longer has conflict markers, record it as that entry's resolution.
+    if let Ok(text) = std::str::from_utf8(&content) {
+        super::rerere::record_resolution(repo, &rel_path.to_str_lossy(), text)?;
+    }
+
     // Write blob to ODB
     let oid = repo.odb().write_raw(ObjectType::Blob, &content)?;
 
     let entry = IndexEntry {
-        path: rel_path,
+        path: rel_path.into(),
         oid,
         mode,
         stage: Stage::Normal,
@@ -2,11 +2,13 @@ use std::fs;
 use std::io::{self, Read as IoRead, Write};
 use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 use bstr::BString;
 use clap::{Args, ValueEnum};
 use git_index::{EntryFlags, Index, IndexEntry, Stage, StatData};
-use git_object::FileMode;
+use git_merge::content::{merge_content, MergeLabels};
+use git_merge::{ContentMergeResult, MergeOptions};
+use git_object::{FileMode, Object, ObjectType};
 
 use super::open_repo;
 use crate::Cli;
@@ -76,6 +78,12 @@ pub struct ApplyArgs {
     #[arg(long, value_enum)]
     pub whitespace: Option<WhitespaceAction>,
 
+    /// Attempt a three-way merge when a hunk doesn't apply cleanly against
+    /// the target, using the pre-image blob recorded in the patch as the
+    /// merge base
+    #[arg(long = "3way")]
+    pub three_way: bool,
+
     /// Patch files (read from stdin if empty)
     pub patches: Vec<String>,
 }
@@ -113,6 +121,7 @@ pub fn run(args: &ApplyArgs, cli: &Cli) -> Result<i32> {
             .map(|mut fp| {
                 // Swap old/new paths
                 std::mem::swap(&mut fp.old_path, &mut fp.new_path);
+                std::mem::swap(&mut fp.old_oid, &mut fp.new_oid);
                 // Swap file status
                 fp.status = match fp.status {
                     PatchFileStatus::Added => PatchFileStatus::Deleted,
@@ -155,15 +164,34 @@ pub fn run(args: &ApplyArgs, cli: &Cli) -> Result<i32> {
         return Ok(0);
     }
 
-    // Determine working directory
-    let work_dir = if args.cached || args.index {
-        // Need a repo
-        let repo = open_repo(cli)?;
-        repo.work_tree()
-            .map(|p| p.to_path_buf())
-            .unwrap_or_else(|| PathBuf::from("."))
+    // Determine working directory and, when touching the index, load the repo
+    // and the current index up front so every file in the patch series is
+    // staged against the same snapshot. `--3way` also needs a repo, since the
+    // fallback looks up the patch's recorded pre-image blob in the odb.
+    let repo = if args.cached || args.index || args.three_way {
+        Some(open_repo(cli)?)
     } else {
-        PathBuf::from(".")
+        None
+    };
+
+    let work_dir = match &repo {
+        Some(repo) => repo
+            .work_tree()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from(".")),
+        None => PathBuf::from("."),
+    };
+
+    let mut index = match &repo {
+        Some(repo) => {
+            let index_path = repo.git_dir().join("index");
+            Some(if index_path.exists() {
+                Index::read_from(&index_path)?
+            } else {
+                Index::new()
+            })
+        }
+        None => None,
     };
 
     // Whitespace handling
@@ -173,13 +201,38 @@ pub fn run(args: &ApplyArgs, cli: &Cli) -> Result<i32> {
     let mut had_errors = false;
 
     for fp in &all_file_patches {
+        // Check/report trailing-whitespace issues on added lines before
+        // touching anything, per `--whitespace`.
+        if matches!(
+            ws_action,
+            WhitespaceAction::Warn | WhitespaceAction::Error | WhitespaceAction::ErrorAll
+        ) {
+            let issues = whitespace_issues(fp);
+            if !issues.is_empty() {
+                for (line_no, line) in &issues {
+                    writeln!(err, "{}:{}: trailing whitespace.", fp.new_path, line_no)?;
+                    writeln!(err, "+{}", line)?;
+                }
+                if matches!(ws_action, WhitespaceAction::Error | WhitespaceAction::ErrorAll) {
+                    writeln!(
+                        err,
+                        "error: {} has whitespace errors, refusing to apply",
+                        fp.new_path
+                    )?;
+                    had_errors = true;
+                    continue;
+                }
+            }
+        }
+
         match fp.status {
             PatchFileStatus::Added => {
-                let target = work_dir.join(&fp.new_path);
+                let content = reconstruct_added_file(fp);
+                let content = apply_whitespace_fix(&content, ws_action);
+                let mode = mode_for(fp.new_mode.as_deref());
+
                 if !args.cached && !args.check {
-                    // Reconstruct content from hunks (all lines should be additions)
-                    let content = reconstruct_added_file(fp);
-                    let content = apply_whitespace_fix(&content, ws_action);
+                    let target = work_dir.join(&fp.new_path);
                     if let Some(parent) = target.parent() {
                         fs::create_dir_all(parent)?;
                     }
@@ -191,10 +244,13 @@ pub fn run(args: &ApplyArgs, cli: &Cli) -> Result<i32> {
                 if args.check && args.verbose {
                     writeln!(err, "check: create {}", fp.new_path)?;
                 }
+                if !args.check {
+                    stage_result(&mut index, &repo, &fp.new_path, Some(content.as_bytes()), mode)?;
+                }
             }
             PatchFileStatus::Deleted => {
-                let target = work_dir.join(&fp.old_path);
                 if !args.cached && !args.check {
+                    let target = work_dir.join(&fp.old_path);
                     if target.exists() {
                         fs::remove_file(&target)?;
                     }
@@ -205,127 +261,125 @@ pub fn run(args: &ApplyArgs, cli: &Cli) -> Result<i32> {
                 if args.check && args.verbose {
                     writeln!(err, "check: delete {}", fp.old_path)?;
                 }
+                if !args.check {
+                    stage_result(&mut index, &repo, &fp.old_path, None, FileMode::Regular)?;
+                }
             }
             PatchFileStatus::Modified => {
                 let target = work_dir.join(&fp.new_path);
+                let original =
+                    read_original(&fp.old_path, &target, args.cached, &repo, index.as_ref())?;
 
-                if args.cached {
-                    // Only update the index, skip working tree
-                    if args.verbose {
-                        writeln!(err, "applied (cached): {}", fp.new_path)?;
-                    }
-                    continue;
-                }
-
-                if !target.exists() && !args.check {
-                    writeln!(
-                        err,
-                        "error: {}: No such file or directory",
-                        fp.new_path
-                    )?;
-                    had_errors = true;
-                    continue;
-                }
-
-                if args.check {
-                    // Verify the patch can be applied
-                    if target.exists() {
-                        let original = fs::read_to_string(&target)?;
-                        match try_apply_hunks(&original, &fp.hunks) {
-                            Ok(_) => {
-                                if args.verbose {
-                                    writeln!(err, "check: {}", fp.new_path)?;
-                                }
-                            }
-                            Err(e) => {
-                                writeln!(err, "error: patch failed: {}: {}", fp.new_path, e)?;
-                                had_errors = true;
-                            }
-                        }
-                    } else {
-                        writeln!(
-                            err,
-                            "error: {}: does not exist in the working tree",
-                            fp.new_path
-                        )?;
+                let original = match original {
+                    Some(o) => o,
+                    None => {
+                        writeln!(err, "error: {}: No such file or directory", fp.new_path)?;
                         had_errors = true;
+                        continue;
                     }
-                } else {
-                    let original = fs::read_to_string(&target)?;
-                    match try_apply_hunks(&original, &fp.hunks) {
-                        Ok(result) => {
-                            let result = apply_whitespace_fix(&result, ws_action);
+                };
+
+                match apply_hunks_with_fallback(
+                    &original,
+                    fp,
+                    &fp.new_path,
+                    args.three_way,
+                    &repo,
+                    &mut err,
+                )? {
+                    Ok((result, conflicted)) => {
+                        let result = apply_whitespace_fix(&result, ws_action);
+                        let mode = mode_for(fp.new_mode.as_deref());
+
+                        if !args.cached && !args.check {
                             fs::write(&target, &result)?;
-                            if args.verbose {
-                                writeln!(err, "applied: {}", fp.new_path)?;
-                            }
                         }
-                        Err(e) => {
-                            writeln!(err, "error: patch failed: {}: {}", fp.new_path, e)?;
+                        if args.verbose {
+                            let verb = if args.check { "check" } else { "applied" };
+                            writeln!(err, "{}: {}", verb, fp.new_path)?;
+                        }
+                        if conflicted {
                             had_errors = true;
+                        } else if !args.check {
+                            stage_result(&mut index, &repo, &fp.new_path, Some(result.as_bytes()), mode)?;
                         }
                     }
+                    Err(e) => {
+                        writeln!(err, "error: patch failed: {}: {}", fp.new_path, e)?;
+                        writeln!(err, "error: {}: patch does not apply", fp.new_path)?;
+                        had_errors = true;
+                    }
                 }
             }
             PatchFileStatus::Renamed => {
                 let old_target = work_dir.join(&fp.old_path);
                 let new_target = work_dir.join(&fp.new_path);
+                let original =
+                    read_original(&fp.old_path, &old_target, args.cached, &repo, index.as_ref())?;
 
-                if !args.cached && !args.check {
-                    if old_target.exists() {
-                        if !fp.hunks.is_empty() {
-                            let original = fs::read_to_string(&old_target)?;
-                            match try_apply_hunks(&original, &fp.hunks) {
-                                Ok(result) => {
-                                    let result = apply_whitespace_fix(&result, ws_action);
-                                    if let Some(parent) = new_target.parent() {
-                                        fs::create_dir_all(parent)?;
-                                    }
-                                    fs::write(&new_target, &result)?;
-                                }
-                                Err(e) => {
-                                    writeln!(
-                                        err,
-                                        "error: patch failed: {}: {}",
-                                        fp.new_path, e
-                                    )?;
-                                    had_errors = true;
-                                    continue;
-                                }
-                            }
-                        } else {
-                            if let Some(parent) = new_target.parent() {
-                                fs::create_dir_all(parent)?;
-                            }
-                            fs::rename(&old_target, &new_target)?;
-                        }
-                        // Remove old file if it still exists (and wasn't just renamed in place)
-                        if old_target.exists() && old_target != new_target {
-                            let _ = fs::remove_file(&old_target);
+                let original = match original {
+                    Some(o) => o,
+                    None => {
+                        writeln!(err, "error: {}: No such file or directory", fp.old_path)?;
+                        had_errors = true;
+                        continue;
+                    }
+                };
+
+                let (result, conflicted) = if fp.hunks.is_empty() {
+                    (original.clone(), false)
+                } else {
+                    match apply_hunks_with_fallback(
+                        &original,
+                        fp,
+                        &fp.new_path,
+                        args.three_way,
+                        &repo,
+                        &mut err,
+                    )? {
+                        Ok(r) => r,
+                        Err(e) => {
+                            writeln!(err, "error: patch failed: {}: {}", fp.new_path, e)?;
+                            writeln!(err, "error: {}: patch does not apply", fp.new_path)?;
+                            had_errors = true;
+                            continue;
                         }
                     }
+                };
+                let result = apply_whitespace_fix(&result, ws_action);
+                let mode = mode_for(fp.new_mode.as_deref());
+
+                if !args.cached && !args.check {
+                    if let Some(parent) = new_target.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&new_target, &result)?;
+                    if old_target.exists() && old_target != new_target {
+                        let _ = fs::remove_file(&old_target);
+                    }
                     if args.verbose {
-                        writeln!(
-                            err,
-                            "applied: rename {} => {}",
-                            fp.old_path, fp.new_path
-                        )?;
+                        writeln!(err, "applied: rename {} => {}", fp.old_path, fp.new_path)?;
                     }
                 }
                 if args.check && args.verbose {
-                    writeln!(
-                        err,
-                        "check: rename {} => {}",
-                        fp.old_path, fp.new_path
-                    )?;
+                    writeln!(err, "check: rename {} => {}", fp.old_path, fp.new_path)?;
+                }
+                if conflicted {
+                    had_errors = true;
+                } else if !args.check {
+                    stage_result(&mut index, &repo, &fp.old_path, None, FileMode::Regular)?;
+                    stage_result(&mut index, &repo, &fp.new_path, Some(result.as_bytes()), mode)?;
                 }
             }
         }
     }
 
-    // Update the index if --index or --cached
+    // Write the updated index back if --index or --cached
     if (args.index || args.cached) && !args.check && !had_errors {
-        update_index_for_patches(cli, &work_dir, &all_file_patches)?;
+        if let (Some(repo), Some(index)) = (&repo, &index) {
+            let index_path = repo.git_dir().join("index");
+            index.write_to(&index_path)?;
+        }
     }
 
     if had_errors {
@@ -335,6 +389,77 @@ pub fn run(args: &ApplyArgs, cli: &Cli) -> Result<i32> {
     }
 }
 
+/// Resolve a file's mode string from a patch header, defaulting to a regular file.
+fn mode_for(mode_str: Option<&str>) -> FileMode {
+    mode_str
+        .and_then(|m| FileMode::from_bytes(m.as_bytes()).ok())
+        .unwrap_or(FileMode::Regular)
+}
+
+/// Read the content a hunk series should be applied against: the index blob
+/// for `--cached`, otherwise the working tree file. Returns `None` if the
+/// source doesn't exist.
+fn read_original(
+    path: &str,
+    worktree_target: &Path,
+    cached: bool,
+    repo: &Option<git_repository::Repository>,
+    index: Option<&Index>,
+) -> Result<Option<String>> {
+    if cached {
+        let repo = repo.as_ref().expect("--cached requires an open repository");
+        let index = index.expect("--cached requires a loaded index");
+        let bpath = BString::from(path);
+        match index.get(bpath.as_ref(), Stage::Normal) {
+            Some(entry) => match repo.odb().read(&entry.oid)? {
+                Some(Object::Blob(b)) => Ok(Some(String::from_utf8_lossy(&b.data).to_string())),
+                _ => Ok(None),
+            },
+            None => Ok(None),
+        }
+    } else if worktree_target.exists() {
+        Ok(Some(fs::read_to_string(worktree_target)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Stage a patch outcome into the in-memory index: writes the blob to the
+/// object database and records it at stage 0, or removes the path entirely
+/// when `content` is `None` (deletion).
+fn stage_result(
+    index: &mut Option<Index>,
+    repo: &Option<git_repository::Repository>,
+    path: &str,
+    content: Option<&[u8]>,
+    mode: FileMode,
+) -> Result<()> {
+    let (Some(index), Some(repo)) = (index.as_mut(), repo.as_ref()) else {
+        return Ok(());
+    };
+
+    let bpath = BString::from(path);
+    match content {
+        Some(content) => {
+            let oid = repo.odb().write_raw(ObjectType::Blob, content)?;
+            index.remove(bpath.as_ref(), Stage::Normal);
+            index.add(IndexEntry {
+                path: bpath.into(),
+                oid,
+                mode,
+                stage: Stage::Normal,
+                stat: StatData::default(),
+                flags: EntryFlags::default(),
+            });
+        }
+        None => {
+            index.remove(bpath.as_ref(), Stage::Normal);
+        }
+    }
+
+    Ok(())
+}
+
 // --- Patch data structures ---
 
 #[derive(Debug, Clone)]
@@ -353,6 +478,10 @@ struct FilePatch {
     hunks: Vec<Hunk>,
     old_mode: Option<String>,
     new_mode: Option<String>,
+    /// Pre-image blob id from the patch's `index <old>..<new> <mode>` line,
+    /// used to locate the three-way merge base for `--3way`.
+    old_oid: Option<String>,
+    new_oid: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -384,6 +513,8 @@ fn parse_patch(content: &str, strip: usize, directory: Option<&str>) -> Vec<File
     let mut rename_to: Option<String> = None;
     let mut old_mode: Option<String> = None;
     let mut new_mode: Option<String> = None;
+    let mut old_oid: Option<String> = None;
+    let mut new_oid: Option<String> = None;
 
     for line in content.lines() {
         if let Some(rest) = line.strip_prefix("diff --git ") {
@@ -408,6 +539,8 @@ fn parse_patch(content: &str, strip: usize, directory: Option<&str>) -> Vec<File
                 hunks: Vec::new(),
                 old_mode: None,
                 new_mode: None,
+                old_oid: None,
+                new_oid: None,
             });
             is_new_file = false;
             is_deleted = false;
@@ -416,6 +549,16 @@ fn parse_patch(content: &str, strip: usize, directory: Option<&str>) -> Vec<File
             rename_to = None;
             old_mode = None;
             new_mode = None;
+            old_oid = None;
+            new_oid = None;
+        } else if let Some(rest) = line.strip_prefix("index ") {
+            // "index <old>..<new>[ <mode>]"
+            if let Some(range) = rest.split_whitespace().next() {
+                if let Some((old_hex, new_hex)) = range.split_once("..") {
+                    old_oid = Some(old_hex.to_string());
+                    new_oid = Some(new_hex.to_string());
+                }
+            }
         } else if let Some(rest) = line.strip_prefix("new file mode ") {
             is_new_file = true;
             new_mode = Some(rest.to_string());
@@ -476,6 +619,8 @@ fn parse_patch(content: &str, strip: usize, directory: Option<&str>) -> Vec<File
                 }
                 fp.old_mode = old_mode.clone();
                 fp.new_mode = new_mode.clone();
+                fp.old_oid = old_oid.clone();
+                fp.new_oid = new_oid.clone();
             }
         } else if line.starts_with("@@ ") {
             // Parse hunk header
@@ -599,55 +744,56 @@ fn parse_range(s: &str) -> (usize, usize) {
 
 // --- Patch application ---
 
-/// Try to apply hunks to the original content, returning the result or an error.
-fn try_apply_hunks(original: &str, hunks: &[Hunk]) -> Result<String> {
+/// Maximum number of context lines git is willing to trim from each end of a
+/// hunk's pre-image when an exact-context match fails to locate it.
+const MAX_FUZZ: usize = 2;
+
+/// Apply `hunks` to `original`, locating each hunk by its leading context
+/// (searching outward from the line the hunk header claims, then retrying
+/// with progressively less context on a mismatch), and writing a
+/// `Hunk #k succeeded at L ...` diagnostic to `err` whenever a hunk didn't
+/// land exactly where its header said. Fails with `patch does not apply`
+/// semantics (via the returned `Err`) when a hunk can't be placed at any
+/// fuzz level.
+fn apply_hunks_report(
+    original: &str,
+    hunks: &[Hunk],
+    path: &str,
+    err: &mut impl Write,
+) -> Result<String> {
     let original_lines: Vec<&str> = original.lines().collect();
     let mut result_lines: Vec<String> = Vec::new();
     let mut old_idx: usize = 0;
 
-    for hunk in hunks {
-        let hunk_start = if hunk.old_start > 0 {
-            hunk.old_start - 1
-        } else {
-            0
-        };
+    for (hunk_no, hunk) in hunks.iter().enumerate() {
+        let preimage: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Remove(s) => Some(s.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect();
+
+        let want_start = hunk.old_start.saturating_sub(1);
+
+        let (pos, offset, fuzz) =
+            locate_hunk(&original_lines, &preimage, want_start, old_idx).ok_or_else(|| {
+                anyhow::anyhow!("{}: hunk #{} failed to apply", path, hunk_no + 1)
+            })?;
 
-        // Copy unchanged lines before this hunk
-        while old_idx < hunk_start && old_idx < original_lines.len() {
+        // Copy unchanged lines up to where this hunk actually landed.
+        while old_idx < pos {
             result_lines.push(original_lines[old_idx].to_string());
             old_idx += 1;
         }
 
-        // Verify context lines match
-        let mut hunk_old_idx = old_idx;
         for hline in &hunk.lines {
             match hline {
-                HunkLine::Context(expected) => {
-                    if hunk_old_idx < original_lines.len() {
-                        let actual = original_lines[hunk_old_idx];
-                        if actual != expected.as_str() {
-                            bail!(
-                                "context mismatch at line {}: expected {:?}, got {:?}",
-                                hunk_old_idx + 1,
-                                expected,
-                                actual
-                            );
-                        }
+                HunkLine::Context(_) => {
+                    if old_idx < original_lines.len() {
+                        result_lines.push(original_lines[old_idx].to_string());
                     }
-                    hunk_old_idx += 1;
-                }
-                HunkLine::Remove(_) => {
-                    hunk_old_idx += 1;
-                }
-                HunkLine::Add(_) => {}
-            }
-        }
-
-        // Now apply the hunk
-        for hline in &hunk.lines {
-            match hline {
-                HunkLine::Context(s) => {
-                    result_lines.push(s.clone());
                     old_idx += 1;
                 }
                 HunkLine::Add(s) => {
@@ -658,6 +804,10 @@ fn try_apply_hunks(original: &str, hunks: &[Hunk]) -> Result<String> {
                 }
             }
         }
+
+        if offset != 0 || fuzz != 0 {
+            report_hunk_success(err, hunk_no + 1, pos + 1, offset, fuzz)?;
+        }
     }
 
     // Copy remaining lines
@@ -674,6 +824,184 @@ fn try_apply_hunks(original: &str, hunks: &[Hunk]) -> Result<String> {
     Ok(output)
 }
 
+/// Apply `hunks` to `original`, falling back to a three-way merge (when
+/// `three_way` is set) if they don't apply cleanly. Returns `(result,
+/// conflicted)`; `conflicted` is true when the fallback left conflict markers
+/// in `result` rather than a clean merge. Propagates the original
+/// "does not apply" error when there's no fallback available (no `--3way`,
+/// no repo, or the patch didn't record a pre-image blob).
+fn apply_hunks_with_fallback(
+    original: &str,
+    fp: &FilePatch,
+    path: &str,
+    three_way: bool,
+    repo: &Option<git_repository::Repository>,
+    err: &mut impl Write,
+) -> Result<Result<(String, bool)>> {
+    match apply_hunks_report(original, &fp.hunks, path, err) {
+        Ok(result) => Ok(Ok((result, false))),
+        Err(e) => {
+            if three_way {
+                if let Some(outcome) = three_way_fallback(original, fp, path, repo, err)? {
+                    return Ok(Ok(outcome));
+                }
+            }
+            Ok(Err(e))
+        }
+    }
+}
+
+/// Reconstruct the merge base from the patch's recorded pre-image blob and
+/// run a three-way content merge against `original` (ours) and the patch
+/// applied to that base (theirs). Returns `None` when the fallback isn't
+/// possible (no repo, no recorded pre-image oid, or the blob isn't present),
+/// in which case the caller should report the original apply failure.
+fn three_way_fallback(
+    original: &str,
+    fp: &FilePatch,
+    path: &str,
+    repo: &Option<git_repository::Repository>,
+    err: &mut impl Write,
+) -> Result<Option<(String, bool)>> {
+    let Some(repo) = repo else {
+        return Ok(None);
+    };
+    let Some(old_oid) = &fp.old_oid else {
+        return Ok(None);
+    };
+
+    let base_oid = match repo.odb().resolve_prefix(old_oid) {
+        Ok(oid) => oid,
+        Err(_) => return Ok(None),
+    };
+    let base_content = match repo.odb().read(&base_oid)? {
+        Some(Object::Blob(b)) => String::from_utf8_lossy(&b.data).to_string(),
+        _ => return Ok(None),
+    };
+
+    // The hunks were generated against this exact pre-image, so they should
+    // always apply to it without fuzz; if they don't, the recorded blob
+    // doesn't actually match this patch and there's nothing sound to merge.
+    let theirs = match apply_hunks_report(&base_content, &fp.hunks, path, &mut io::sink()) {
+        Ok(theirs) => theirs,
+        Err(_) => return Ok(None),
+    };
+
+    let labels = MergeLabels {
+        base: "constructed merge base",
+        ours: "current",
+        theirs: path,
+    };
+    let merged = merge_content(
+        base_content.as_bytes(),
+        original.as_bytes(),
+        theirs.as_bytes(),
+        &MergeOptions::default(),
+        &labels,
+    );
+
+    match merged {
+        ContentMergeResult::Clean(data) => {
+            Ok(Some((String::from_utf8_lossy(&data).to_string(), false)))
+        }
+        ContentMergeResult::Conflict { content, conflict_count } => {
+            writeln!(
+                err,
+                "Applied patch to '{}' with {} conflict{}.",
+                path,
+                conflict_count,
+                if conflict_count == 1 { "" } else { "s" }
+            )?;
+            Ok(Some((String::from_utf8_lossy(&content).to_string(), true)))
+        }
+    }
+}
+
+/// Locate a hunk's pre-image (context + removed lines) in `lines`, starting
+/// the search at `want_start` and never landing before `min_pos` (the end of
+/// the previous hunk). Tries an exact match first, then falls back to
+/// trimming up to `MAX_FUZZ` lines of context from each end of the preimage.
+/// Returns `(position, offset-from-want_start, fuzz-level)`.
+fn locate_hunk(
+    lines: &[&str],
+    preimage: &[&str],
+    want_start: usize,
+    min_pos: usize,
+) -> Option<(usize, isize, usize)> {
+    let max_fuzz = MAX_FUZZ.min(preimage.len() / 2);
+
+    for fuzz in 0..=max_fuzz {
+        let trimmed = &preimage[fuzz..preimage.len() - fuzz];
+        let anchor = want_start + fuzz;
+        let min_anchor = min_pos + fuzz;
+
+        if let Some(trimmed_pos) = search_outward(lines, trimmed, anchor, min_anchor) {
+            let pos = trimmed_pos - fuzz;
+            let offset = pos as isize - want_start as isize;
+            return Some((pos, offset, fuzz));
+        }
+    }
+
+    None
+}
+
+/// Search for `needle` in `lines`, trying `anchor` first and then scanning
+/// outward (+1, -1, +2, -2, ...), never considering a position before
+/// `min_pos`. Returns the matching start position.
+fn search_outward(lines: &[&str], needle: &[&str], anchor: usize, min_pos: usize) -> Option<usize> {
+    if needle.is_empty() {
+        return (anchor >= min_pos && anchor <= lines.len()).then_some(anchor);
+    }
+    if needle.len() > lines.len() {
+        return None;
+    }
+
+    let max_start = lines.len() - needle.len();
+
+    for delta in 0..=lines.len() {
+        for sign in [1i64, -1i64] {
+            if delta == 0 && sign < 0 {
+                continue;
+            }
+            let candidate = anchor as i64 + sign * delta as i64;
+            if candidate < 0 {
+                continue;
+            }
+            let candidate = candidate as usize;
+            if candidate < min_pos || candidate > max_start {
+                continue;
+            }
+            if lines[candidate..candidate + needle.len()] == *needle {
+                return Some(candidate);
+            }
+        }
+    }
+
+    None
+}
+
+/// Write git's "Hunk #k succeeded at L ..." diagnostic for a hunk that
+/// didn't land exactly where its header claimed.
+fn report_hunk_success(
+    err: &mut impl Write,
+    hunk_no: usize,
+    line: usize,
+    offset: isize,
+    fuzz: usize,
+) -> Result<()> {
+    if fuzz == 0 {
+        let unit = if offset.abs() == 1 { "line" } else { "lines" };
+        writeln!(
+            err,
+            "Hunk #{} succeeded at {} (offset {} {}).",
+            hunk_no, line, offset, unit
+        )?;
+    } else {
+        writeln!(err, "Hunk #{} succeeded at {} with fuzz {}.", hunk_no, line, fuzz)?;
+    }
+    Ok(())
+}
+
 /// Reconstruct file content from hunks of a newly added file (all additions).
 fn reconstruct_added_file(fp: &FilePatch) -> String {
     let mut lines = Vec::new();
@@ -694,6 +1022,28 @@ fn reconstruct_added_file(fp: &FilePatch) -> String {
     content
 }
 
+/// Find trailing-whitespace issues among a patch's added lines, as `(new
+/// file line number, line content)` pairs, for `--whitespace=warn|error`.
+fn whitespace_issues(fp: &FilePatch) -> Vec<(usize, String)> {
+    let mut issues = Vec::new();
+    for hunk in &fp.hunks {
+        let mut line_no = hunk.new_start;
+        for hline in &hunk.lines {
+            match hline {
+                HunkLine::Add(s) => {
+                    if s != s.trim_end() {
+                        issues.push((line_no, s.clone()));
+                    }
+                    line_no += 1;
+                }
+                HunkLine::Context(_) => line_no += 1,
+                HunkLine::Remove(_) => {}
+            }
+        }
+    }
+    issues
+}
+
 /// Apply whitespace fixes based on the action setting.
 fn apply_whitespace_fix(content: &str, action: WhitespaceAction) -> String {
     match action {
@@ -857,77 +1207,3 @@ fn print_summary(patches: &[FilePatch], out: &mut impl Write) -> Result<()> {
 
     Ok(())
 }
-
-// --- Index update ---
-
-fn update_index_for_patches(
-    cli: &Cli,
-    work_dir: &Path,
-    patches: &[FilePatch],
-) -> Result<()> {
-    let repo = open_repo(cli)?;
-    let index_path = repo.git_dir().join("index");
-    let mut index = if index_path.exists() {
-        Index::read_from(&index_path)?
-    } else {
-        Index::new()
-    };
-
-    for fp in patches {
-        match fp.status {
-            PatchFileStatus::Deleted => {
-                let path = BString::from(fp.old_path.as_str());
-                index.remove(path.as_ref(), Stage::Normal);
-            }
-            PatchFileStatus::Added | PatchFileStatus::Modified => {
-                let file_path = work_dir.join(&fp.new_path);
-                if file_path.exists() {
-                    let content = fs::read(&file_path)?;
-                    let oid = git_hash::hasher::Hasher::hash_object(
-                        git_hash::HashAlgorithm::Sha1,
-                        "blob",
-                        &content,
-                    )?;
-                    let metadata = fs::metadata(&file_path)?;
-                    let path = BString::from(fp.new_path.as_str());
-                    index.remove(path.as_ref(), Stage::Normal);
-                    index.add(IndexEntry {
-                        path,
-                        oid,
-                        mode: FileMode::Regular,
-                        stage: Stage::Normal,
-                        stat: StatData::from_metadata(&metadata),
-                        flags: EntryFlags::default(),
-                    });
-                }
-            }
-            PatchFileStatus::Renamed => {
-                let old_path = BString::from(fp.old_path.as_str());
-                index.remove(old_path.as_ref(), Stage::Normal);
-
-                let file_path = work_dir.join(&fp.new_path);
-                if file_path.exists() {
-                    let content = fs::read(&file_path)?;
-                    let oid = git_hash::hasher::Hasher::hash_object(
-                        git_hash::HashAlgorithm::Sha1,
-                        "blob",
-                        &content,
-                    )?;
-                    let metadata = fs::metadata(&file_path)?;
-                    let path = BString::from(fp.new_path.as_str());
-                    index.add(IndexEntry {
-                        path,
-                        oid,
-                        mode: FileMode::Regular,
-                        stage: Stage::Normal,
-                        stat: StatData::from_metadata(&metadata),
-                        flags: EntryFlags::default(),
-                    });
-                }
-            }
-        }
-    }
-
-    index.write_to(&index_path)?;
-    Ok(())
-}
@@ -48,11 +48,11 @@ pub struct BlameArgs {
 }
 
 /// A blame entry: which commit last changed a range of lines.
-struct BlameEntry {
-    commit: ObjectId,
-    original_line: u32,
-    final_line: u32,
-    num_lines: u32,
+pub(crate) struct BlameEntry {
+    pub(crate) commit: ObjectId,
+    pub(crate) original_line: u32,
+    pub(crate) final_line: u32,
+    pub(crate) num_lines: u32,
 }
 
 pub fn run(args: &BlameArgs, cli: &Cli) -> Result<i32> {
@@ -100,6 +100,16 @@ pub fn run(args: &BlameArgs, cli: &Cli) -> Result<i32> {
         }
     }
 
+    // Unlike log/shortlog, blame canonicalizes author identities via the
+    // mailmap unconditionally, with no opt-in flag or config.
+    if let Some(mailmap) = super::load_mailmap(&repo) {
+        for commit in commit_cache.values_mut() {
+            let (name, email) = mailmap.lookup(&commit.author.name, &commit.author.email);
+            commit.author.name = name;
+            commit.author.email = email;
+        }
+    }
+
     // Find longest author name for alignment
     let max_author_len = commit_cache
         .values()
@@ -245,8 +255,59 @@ fn format_tz(offset_minutes: i32) -> String {
     format!("{}{:02}{:02}", sign, hours, mins)
 }
 
+/// For each hunk of each file in `result`, find the commits (reachable from
+/// `start_oid`) whose blamed lines overlap the hunk's old-side range and
+/// record them in [`Hunk::locks`](git_diff::Hunk::locks) — the commits this
+/// hunk depends on ("locks" onto, in GitButler's terminology) and so would
+/// need to be reordered or split together with. Used by `--annotate-locks`.
+pub(crate) fn annotate_hunk_locks(
+    repo: &git_repository::Repository,
+    start_oid: &ObjectId,
+    result: &mut git_diff::DiffResult,
+) -> Result<()> {
+    for file in &mut result.files {
+        if file.hunks.is_empty() {
+            continue;
+        }
+        let Some(path) = file.old_path.as_ref() else {
+            continue;
+        };
+        let path = path.to_str_lossy().into_owned();
+
+        let file_lines = match read_file_at_rev(repo, start_oid, &path) {
+            Ok(lines) => lines,
+            Err(_) => continue,
+        };
+        if file_lines.is_empty() {
+            continue;
+        }
+
+        let entries = blame_file(repo, start_oid, &path, &file_lines)?;
+
+        for hunk in &mut file.hunks {
+            if hunk.old_count == 0 {
+                continue;
+            }
+            let hunk_start = hunk.old_start;
+            let hunk_end = hunk.old_start + hunk.old_count;
+
+            let mut locks = Vec::new();
+            for entry in &entries {
+                let entry_start = entry.final_line;
+                let entry_end = entry.final_line + entry.num_lines;
+                if entry_start < hunk_end && hunk_start < entry_end && !locks.contains(&entry.commit) {
+                    locks.push(entry.commit);
+                }
+            }
+            hunk.locks = locks;
+        }
+    }
+
+    Ok(())
+}
+
 /// Blame algorithm: walk backwards through history, attributing lines to commits.
-fn blame_file(
+pub(crate) fn blame_file(
     repo: &git_repository::Repository,
     start_oid: &ObjectId,
     path: &str,
@@ -414,7 +475,7 @@ fn diff_blame_changed_set(
 }
 
 /// Read a file's content at a specific revision.
-fn read_file_at_rev(
+pub(crate) fn read_file_at_rev(
     repo: &git_repository::Repository,
     commit_oid: &ObjectId,
     path: &str,
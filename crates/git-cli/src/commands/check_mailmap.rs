@@ -0,0 +1,63 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::Cli;
+use super::{load_mailmap, open_repo};
+
+#[derive(Args)]
+pub struct CheckMailmapArgs {
+    /// Read additional contacts from stdin, one per line
+    #[arg(long)]
+    stdin: bool,
+
+    /// Contacts to resolve, in `Name <email>` form
+    contacts: Vec<String>,
+}
+
+pub fn run(args: &CheckMailmapArgs, cli: &Cli) -> Result<i32> {
+    let repo = open_repo(cli)?;
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mailmap = load_mailmap(&repo).unwrap_or_default();
+
+    let mut contacts = args.contacts.clone();
+    if args.stdin {
+        let stdin_handle = io::stdin();
+        for line in stdin_handle.lock().lines() {
+            let line = line?;
+            let line = line.trim().to_string();
+            if !line.is_empty() {
+                contacts.push(line);
+            }
+        }
+    }
+
+    for contact in &contacts {
+        let (name, email) = parse_contact(contact);
+        let (resolved_name, resolved_email) = mailmap.lookup(name.as_bytes(), email.as_bytes());
+        writeln!(
+            out,
+            "{} <{}>",
+            String::from_utf8_lossy(&resolved_name),
+            String::from_utf8_lossy(&resolved_email),
+        )?;
+    }
+
+    Ok(0)
+}
+
+/// Parse a `Name <email>` contact string. A bare string with no `<email>`
+/// part is treated as a name with an empty email, matching how mailmap
+/// lookups key on email but tolerate a missing one.
+fn parse_contact(contact: &str) -> (&str, &str) {
+    if let Some(gt_pos) = contact.rfind('>') {
+        if let Some(lt_pos) = contact[..gt_pos].rfind('<') {
+            let name = contact[..lt_pos].trim();
+            let email = &contact[lt_pos + 1..gt_pos];
+            return (name, email);
+        }
+    }
+    (contact.trim(), "")
+}
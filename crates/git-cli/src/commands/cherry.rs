@@ -60,11 +60,10 @@ pub fn run(args: &CherryArgs, cli: &Cli) -> Result<i32> {
 
     for entry in &entries {
         let hex = entry.oid.to_hex();
-        let short_oid = &hex[..7.min(hex.len())];
         if args.verbose {
-            writeln!(out, "{} {} {}", entry.marker, short_oid, entry.subject)?;
+            writeln!(out, "{} {} {}", entry.marker, hex, entry.subject)?;
         } else {
-            writeln!(out, "{} {}", entry.marker, short_oid)?;
+            writeln!(out, "{} {}", entry.marker, hex)?;
         }
     }
 
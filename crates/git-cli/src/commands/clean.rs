@@ -69,7 +69,7 @@ pub fn run(args: &CleanArgs, cli: &Cli) -> Result<i32> {
 
     let indexed_paths: std::collections::HashSet<BString> = {
         let index = repo.index()?;
-        index.iter().map(|e| e.path.clone()).collect()
+        index.iter().map(|e| e.path.to_bstring()).collect()
     };
 
     let stdout = io::stdout();
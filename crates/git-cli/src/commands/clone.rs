@@ -365,7 +365,7 @@ fn checkout_tree_recursive(
             // Build index entry
             let metadata = std::fs::symlink_metadata(&file_path)?;
             entries.push(IndexEntry {
-                path,
+                path: path.into(),
                 oid: entry.oid,
                 mode: entry.mode,
                 stage: Stage::Normal,
@@ -1,22 +1,24 @@
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{bail, Result};
 use bstr::{BString, ByteSlice};
 use clap::Args;
 use git_hash::ObjectId;
-use git_index::{EntryFlags, IndexEntry, Stage, StatData};
+use git_index::{EntryFlags, Index, IndexEntry, Pathspec, Stage, StatData};
 use git_object::{Commit, FileMode, Object, ObjectType};
 use git_ref::reflog::{append_reflog_entry, ReflogEntry};
 use git_ref::{RefName, RefStore, Reference};
 use git_repository::gpg::GpgSigner;
 use git_repository::hooks::{HookRunner, HookType};
-use git_revwalk::resolve_revision;
+use git_revwalk::{resolve_revision, RevWalk};
 use git_utils::date::{GitDate, Signature};
 
 use crate::Cli;
 use super::open_repo;
+use super::read_tree;
+use super::status;
 
 #[derive(Args)]
 pub struct CommitArgs {
@@ -60,8 +62,9 @@ pub struct CommitArgs {
     #[arg(short = 'c', long = "reedit-message", value_name = "commit")]
     reedit_message: Option<String>,
 
-    /// Construct a commit message for use with rebase --autosquash (fixup! ...)
-    #[arg(long, value_name = "commit")]
+    /// Construct a commit message for use with rebase --autosquash
+    /// (fixup! ...), or with an "amend:"/"reword:" prefix, amend! ...
+    #[arg(long, value_name = "[amend:|reword:]commit")]
     fixup: Option<String>,
 
     /// Construct a commit message for use with rebase --autosquash (squash! ...)
@@ -99,6 +102,268 @@ pub struct CommitArgs {
     /// GPG sign the commit
     #[arg(short = 'S', long = "gpg-sign", num_args = 0..=1, default_missing_value = "")]
     gpg_sign: Option<String>,
+
+    /// How to clean up the commit message: strip, whitespace, verbatim,
+    /// scissors, or default (falls back to commit.cleanup, then "default")
+    #[arg(long, value_name = "mode")]
+    cleanup: Option<String>,
+
+    /// Use the contents of the given file to pre-fill the editable region
+    /// of the commit message template (falls back to commit.template)
+    #[arg(short = 't', long = "template", value_name = "file")]
+    template: Option<PathBuf>,
+
+    /// Commit only changes to the named paths (COMMIT_PARTIAL), leaving
+    /// any other staged changes out of the resulting commit
+    #[arg(value_name = "pathspec")]
+    paths: Vec<PathBuf>,
+}
+
+/// How whitespace, comments, and the scissors line are handled in the final
+/// commit message. See `resolve_cleanup_mode` and `apply_cleanup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CleanupMode {
+    /// Strip trailing whitespace, leading/trailing blank lines, and runs of
+    /// blank lines, then drop `#`-prefixed comment lines.
+    Strip,
+    /// Like `Strip`, but comment lines are kept.
+    Whitespace,
+    /// Leave the message exactly as given.
+    Verbatim,
+    /// Drop everything at and below a scissors line, then apply `Whitespace`.
+    Scissors,
+    /// `Strip` if an editor was launched for the message, `Whitespace` otherwise.
+    Default,
+}
+
+/// The exact scissors marker git places in a commit message template; a
+/// `--cleanup=scissors` commit discards this line and everything below it.
+const SCISSORS_LINE: &str = "# ------------------------ >8 ------------------------";
+
+/// The boilerplate shown in the editor when no other message template applies.
+const DEFAULT_MESSAGE_TEMPLATE: &str = "\n# Enter the commit message for your changes.\n\
+     # Lines starting with '#' will be ignored.\n";
+
+fn parse_cleanup_mode(s: &str) -> Result<CleanupMode> {
+    match s {
+        "strip" => Ok(CleanupMode::Strip),
+        "whitespace" => Ok(CleanupMode::Whitespace),
+        "verbatim" => Ok(CleanupMode::Verbatim),
+        "scissors" => Ok(CleanupMode::Scissors),
+        "default" => Ok(CleanupMode::Default),
+        other => bail!("invalid cleanup mode '{}'", other),
+    }
+}
+
+/// Resolve the effective cleanup mode from `--cleanup`, falling back to
+/// `commit.cleanup`, then to `CleanupMode::Scissors` when `-v/--verbose`
+/// would otherwise leave its appended diff in the final message, and
+/// finally to `CleanupMode::Default`.
+fn resolve_cleanup_mode(args: &CommitArgs, repo: &git_repository::Repository) -> Result<CleanupMode> {
+    if let Some(ref mode) = args.cleanup {
+        return parse_cleanup_mode(mode);
+    }
+    if let Some(value) = repo.config().get_string("commit.cleanup").ok().flatten() {
+        return parse_cleanup_mode(&value);
+    }
+    if args.verbose {
+        return Ok(CleanupMode::Scissors);
+    }
+    Ok(CleanupMode::Default)
+}
+
+/// Trim trailing whitespace per line, drop leading/trailing blank lines, and
+/// collapse runs of blank lines down to one.
+fn apply_whitespace_cleanup(text: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().map(|l| l.trim_end()).collect();
+    while lines.first().is_some_and(|l| l.is_empty()) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+
+    let mut collapsed: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut prev_blank = false;
+    for line in lines {
+        let blank = line.is_empty();
+        if blank && prev_blank {
+            continue;
+        }
+        collapsed.push(line);
+        prev_blank = blank;
+    }
+
+    if collapsed.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", collapsed.join("\n"))
+    }
+}
+
+/// `Whitespace` cleanup, plus dropping every `#`-prefixed comment line.
+fn apply_strip_cleanup(text: &str) -> String {
+    let without_comments = text
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    apply_whitespace_cleanup(&without_comments)
+}
+
+/// Drop the scissors line and everything below it, then apply `Whitespace`
+/// cleanup to what remains above it.
+fn apply_scissors_cleanup(text: &str) -> String {
+    let kept = text
+        .lines()
+        .take_while(|l| *l != SCISSORS_LINE)
+        .collect::<Vec<_>>()
+        .join("\n");
+    apply_whitespace_cleanup(&kept)
+}
+
+/// Append `diff` below a scissors line to `template` (or to the default
+/// editor boilerplate, if there was no template), for `-v/--verbose`. The
+/// scissors cleanup mode is what strips this back out before the message is
+/// finalized, so callers must only pass `Some(diff)` once they've confirmed
+/// the effective cleanup mode is `CleanupMode::Scissors`.
+fn with_verbose_diff(template: Option<&str>, diff: Option<&str>) -> Option<String> {
+    let diff = match diff {
+        Some(diff) => diff,
+        None => return template.map(|s| s.to_string()),
+    };
+    let mut buf = template.unwrap_or(DEFAULT_MESSAGE_TEMPLATE).to_string();
+    if !buf.ends_with('\n') {
+        buf.push('\n');
+    }
+    buf.push_str(SCISSORS_LINE);
+    buf.push_str("\n# Do not modify or remove the line above.\n# Everything below it will be ignored.\n");
+    buf.push_str(diff);
+    Some(buf)
+}
+
+/// Apply `mode` to `message`, resolving `CleanupMode::Default` based on
+/// whether an editor was launched to produce it.
+fn apply_cleanup(message: &BString, mode: CleanupMode, editor_invoked: bool) -> BString {
+    let effective = match mode {
+        CleanupMode::Default if editor_invoked => CleanupMode::Strip,
+        CleanupMode::Default => CleanupMode::Whitespace,
+        other => other,
+    };
+
+    let text = message.to_str_lossy();
+    let cleaned = match effective {
+        CleanupMode::Verbatim => text.to_string(),
+        CleanupMode::Whitespace => apply_whitespace_cleanup(&text),
+        CleanupMode::Strip => apply_strip_cleanup(&text),
+        CleanupMode::Scissors => apply_scissors_cleanup(&text),
+        CleanupMode::Default => unreachable!("resolved above"),
+    };
+    BString::from(cleaned)
+}
+
+/// Whether `line` looks like a trailer ("Key: value") or a folded
+/// continuation of one (leading whitespace), for the purpose of deciding
+/// whether a message's final paragraph is an existing trailer block.
+fn looks_like_trailer_line(line: &str) -> bool {
+    if line.starts_with(' ') || line.starts_with('\t') {
+        return true;
+    }
+    match line.find(':') {
+        Some(colon) if colon > 0 => {
+            let key = &line[..colon];
+            let rest = &line[colon + 1..];
+            !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && (rest.is_empty() || rest.starts_with(' '))
+        }
+        _ => false,
+    }
+}
+
+/// Format a `--trailer` spec ("key=value", "key:value", or an already
+/// formatted "Key: value" line) as a canonical "Key: value" trailer line.
+fn format_custom_trailer(spec: &str) -> String {
+    match spec.find(['=', ':']) {
+        Some(pos) => format!("{}: {}", spec[..pos].trim(), spec[pos + 1..].trim()),
+        None => spec.to_string(),
+    }
+}
+
+/// Insert `signoff` (a `Signed-off-by: Name <email>` line, deduped against
+/// an identical existing one) and each `--trailer` entry into the message's
+/// trailer block, interpret-trailers style: the block is the message's
+/// final paragraph if a strict majority of its lines already look like
+/// trailers, otherwise a new block is started after a blank line. A
+/// trailing run of `#`-prefixed comment lines, and a scissors line and
+/// everything below it, are left untouched below the trailer block.
+fn insert_trailers(message: &BString, signoff: Option<&Signature>, trailers: &[String]) -> Result<BString> {
+    if signoff.is_none() && trailers.is_empty() {
+        return Ok(message.clone());
+    }
+
+    let text = message.to_str_lossy();
+    let lines: Vec<&str> = text.lines().collect();
+
+    let scissors_at = lines.iter().position(|l| *l == SCISSORS_LINE);
+    let (core_lines, tail_lines): (&[&str], &[&str]) = match scissors_at {
+        Some(idx) => (&lines[..idx], &lines[idx..]),
+        None => (&lines[..], &[]),
+    };
+
+    let mut comment_start = core_lines.len();
+    while comment_start > 0 && core_lines[comment_start - 1].starts_with('#') {
+        comment_start -= 1;
+    }
+    let (body_lines, trailing_comment_lines) = core_lines.split_at(comment_start);
+
+    let mut trimmed: Vec<&str> = body_lines.to_vec();
+    while trimmed.last().is_some_and(|l| l.trim().is_empty()) {
+        trimmed.pop();
+    }
+    let last_blank = trimmed.iter().rposition(|l| l.trim().is_empty());
+    let para_start = last_blank.map(|i| i + 1).unwrap_or(0);
+    let paragraph = &trimmed[para_start..];
+
+    let trailer_like = paragraph.iter().filter(|l| looks_like_trailer_line(l)).count();
+    let is_trailer_block = !paragraph.is_empty() && trailer_like * 2 > paragraph.len();
+
+    let mut out: Vec<String> = trimmed[..para_start].iter().map(|s| s.to_string()).collect();
+    let mut trailer_block: Vec<String> = if is_trailer_block {
+        paragraph.iter().map(|s| s.to_string()).collect()
+    } else {
+        // No existing trailer block: start a fresh one after a blank
+        // line, even for a subject-only message with no body at all.
+        out.push(String::new());
+        Vec::new()
+    };
+
+    if let Some(sig) = signoff {
+        let line = format!("Signed-off-by: {} <{}>", sig.name.to_str_lossy(), sig.email.to_str_lossy());
+        if !trailer_block.iter().any(|l| *l == line) {
+            trailer_block.push(line);
+        }
+    }
+    for trailer in trailers {
+        trailer_block.push(format_custom_trailer(trailer));
+    }
+
+    out.extend(trailer_block);
+
+    let mut result = out.join("\n");
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    if !trailing_comment_lines.is_empty() {
+        result.push_str(&trailing_comment_lines.join("\n"));
+        result.push('\n');
+    }
+    if !tail_lines.is_empty() {
+        result.push_str(&tail_lines.join("\n"));
+        result.push('\n');
+    }
+
+    Ok(BString::from(result))
 }
 
 pub fn run(args: &CommitArgs, cli: &Cli) -> Result<i32> {
@@ -135,10 +400,18 @@ pub fn run(args: &CommitArgs, cli: &Cli) -> Result<i32> {
         }
     }
 
-    // 3. Build tree from index via write_tree
-    let index_path = repo.git_dir().join("index");
-    let index = git_index::Index::read_from(&index_path)?;
-    let tree_oid = index.write_tree(repo.odb())?;
+    // 3. An in-progress merge or cherry-pick (MERGE_HEAD / CHERRY_PICK_HEAD)
+    // shapes the parent list and the default message below. --amend is
+    // unrelated to finishing one of these, so it must never consult them.
+    let merge_head_path = repo.git_dir().join("MERGE_HEAD");
+    let cherry_pick_head_path = repo.git_dir().join("CHERRY_PICK_HEAD");
+    let merge_head_oids = if !args.amend && merge_head_path.exists() {
+        read_oid_list(&merge_head_path)?
+    } else {
+        Vec::new()
+    };
+    let in_progress_merge = !merge_head_oids.is_empty();
+    let in_progress_cherry_pick = !args.amend && cherry_pick_head_path.exists();
 
     // 4. Get parent commit(s) from HEAD (or none for initial commit)
     let is_unborn = repo.is_unborn()?;
@@ -167,10 +440,54 @@ pub fn run(args: &CommitArgs, cli: &Cli) -> Result<i32> {
         if let Some(head_oid) = repo.head_oid()? {
             parents.push(head_oid);
         }
+        // A merge commit gets one additional parent per MERGE_HEAD entry,
+        // appended after HEAD in the order they were recorded.
+        parents.extend(merge_head_oids.iter().copied());
     }
 
-    // Check for empty commits (tree unchanged from parent)
-    if !args.allow_empty && !args.amend && !is_unborn {
+    // --fixup=reword:<commit> produces a message-only autosquash commit: its
+    // tree must be identical to its parent's, regardless of what's staged.
+    let fixup_reword = args
+        .fixup
+        .as_deref()
+        .map(|spec| parse_fixup_spec(spec).0 == FixupKind::Reword)
+        .unwrap_or(false);
+
+    // 5. Build tree from index via write_tree, or — when pathspecs were
+    // given on the command line — from a temporary index seeded from the
+    // base tree and overridden only at the named paths (COMMIT_PARTIAL),
+    // so other staged changes are left out of this commit.
+    let index_path = repo.git_dir().join("index");
+    let mut index = git_index::Index::read_from(&index_path)?;
+    let tree_oid = if fixup_reword {
+        match parents.first() {
+            Some(parent_oid) => commit_tree(&repo, parent_oid)?,
+            None => index.write_tree(repo.odb())?,
+        }
+    } else if args.paths.is_empty() {
+        index.write_tree(repo.odb())?
+    } else {
+        // --amend restricted to pathspecs is based on the amended commit's
+        // *parent* tree, not the amended commit's own tree, since that
+        // commit is what's being replaced.
+        let base_tree = if args.amend {
+            match prev_commit.as_ref().and_then(|pc| pc.parents.first()) {
+                Some(parent_oid) => Some(commit_tree(&repo, parent_oid)?),
+                None => None,
+            }
+        } else if let Some(head_oid) = parents.first() {
+            Some(commit_tree(&repo, head_oid)?)
+        } else {
+            None
+        };
+        build_partial_tree(&repo, base_tree.as_ref(), &index, &args.paths)?
+    };
+
+    // Check for empty commits (tree unchanged from parent). A merge commit
+    // is allowed to have the same tree as HEAD (e.g. an "ours"-resolved
+    // conflict that reintroduces no changes), so this only applies to
+    // ordinary single-parent commits.
+    if !args.allow_empty && !args.amend && !is_unborn && !in_progress_merge && !fixup_reword {
         if let Some(parent_oid) = parents.first() {
             let parent_obj = repo
                 .odb()
@@ -208,13 +525,13 @@ pub fn run(args: &CommitArgs, cli: &Cli) -> Result<i32> {
         // --reset-author: use committer identity as author
         let mut a = committer.clone();
         if let Some(ref date_str) = args.date {
-            a.date = GitDate::parse_raw(date_str)?;
+            a.date = GitDate::parse_approxidate_now(date_str)?;
         }
         a
     } else if let Some(ref author_str) = args.author {
-        let mut a = parse_author_override(author_str)?;
+        let mut a = parse_author_override(author_str, &repo)?;
         if let Some(ref date_str) = args.date {
-            a.date = GitDate::parse_raw(date_str)?;
+            a.date = GitDate::parse_approxidate_now(date_str)?;
         }
         a
     } else if args.amend {
@@ -222,49 +539,62 @@ pub fn run(args: &CommitArgs, cli: &Cli) -> Result<i32> {
         if let Some(ref pc) = prev_commit {
             let mut a = pc.author.clone();
             if let Some(ref date_str) = args.date {
-                a.date = GitDate::parse_raw(date_str)?;
+                a.date = GitDate::parse_approxidate_now(date_str)?;
             }
             a
         } else {
             let mut a = get_signature("GIT_AUTHOR_NAME", "GIT_AUTHOR_EMAIL", "GIT_AUTHOR_DATE", &repo)?;
             if let Some(ref date_str) = args.date {
-                a.date = GitDate::parse_raw(date_str)?;
+                a.date = GitDate::parse_approxidate_now(date_str)?;
             }
             a
         }
     } else {
         let mut a = get_signature("GIT_AUTHOR_NAME", "GIT_AUTHOR_EMAIL", "GIT_AUTHOR_DATE", &repo)?;
         if let Some(ref date_str) = args.date {
-            a.date = GitDate::parse_raw(date_str)?;
+            a.date = GitDate::parse_approxidate_now(date_str)?;
         }
         a
     };
 
     // 6/7. Determine commit message
-    let mut message = determine_message(args, prev_commit.as_ref(), &repo)?;
-
-    // Append signoff trailer if requested
-    if args.signoff {
-        let signoff_line = format!(
-            "\nSigned-off-by: {} <{}>\n",
-            committer.name.to_str_lossy(),
-            committer.email.to_str_lossy()
-        );
-        // Check if already present
-        let msg_str = message.to_str_lossy().to_string();
-        if !msg_str.contains(signoff_line.trim()) {
-            let mut msg = msg_str.trim_end().to_string();
-            msg.push_str(&signoff_line);
-            message = BString::from(msg);
-        }
-    }
+    let merge_msg_template = if in_progress_merge || in_progress_cherry_pick {
+        let merge_msg_path = repo.git_dir().join("MERGE_MSG");
+        std::fs::read_to_string(&merge_msg_path).ok()
+    } else {
+        None
+    };
+    let cleanup_mode = resolve_cleanup_mode(args, &repo)?;
+
+    // -v/--verbose shows the staged diff in the editor buffer, below a
+    // scissors line, so the user can review it while writing the message.
+    // This only makes sense when the effective cleanup mode will actually
+    // cut the diff back out before the message is finalized.
+    let verbose_diff = if args.verbose && cleanup_mode == CleanupMode::Scissors {
+        let diff_opts = git_diff::DiffOptions::default();
+        let staged = git_diff::worktree::diff_head_to_index(&mut repo, &diff_opts)?;
+        Some(git_diff::format::format_diff(&staged, &diff_opts))
+    } else {
+        None
+    };
+    let status_comment = build_status_comment(&mut repo)?;
 
-    // Append custom trailers
-    for trailer in &args.trailer {
-        let mut msg = message.to_str_lossy().to_string();
-        let msg_trimmed = msg.trim_end().to_string();
-        msg = format!("{}\n{}\n", msg_trimmed, trailer);
-        message = BString::from(msg);
+    let (message, editor_invoked) = determine_message(
+        args,
+        prev_commit.as_ref(),
+        &repo,
+        merge_msg_template.as_deref(),
+        verbose_diff.as_deref(),
+        &status_comment,
+    )?;
+    let mut message = apply_cleanup(&message, cleanup_mode, editor_invoked);
+
+    // Insert Signed-off-by and --trailer entries into the message's
+    // trailer block (its final paragraph, if that already looks like one,
+    // else a freshly started block), rather than naively appending lines.
+    if args.signoff || !args.trailer.is_empty() {
+        let signoff_sig = if args.signoff { Some(&committer) } else { None };
+        message = insert_trailers(&message, signoff_sig, &args.trailer)?;
     }
 
     // Run prepare-commit-msg hook
@@ -340,19 +670,10 @@ pub fn run(args: &CommitArgs, cli: &Cli) -> Result<i32> {
         message,
     };
 
-    // GPG sign if requested
+    // GPG sign if requested. `-S<keyid>` overrides `user.signingKey`; `-S`
+    // with no argument (an empty string here) falls back to it as usual.
     if should_sign {
-        let mut signer = GpgSigner::from_config(repo.config());
-        // If -S was given with an explicit key, override
-        if let Some(ref key_arg) = args.gpg_sign {
-            if !key_arg.is_empty() {
-                signer = GpgSigner::from_config(repo.config());
-                // We need to use the key from the command line; rebuild signer isn't
-                // directly supported, so we set user.signingKey equivalently by
-                // creating a temporary config. Instead, sign the data directly
-                // after adjusting.
-            }
-        }
+        let signer = GpgSigner::with_key(repo.config(), args.gpg_sign.as_deref());
         // Serialize the commit without the signature to get the data to sign
         let commit_content = commit.serialize_content();
         match signer.sign(&commit_content) {
@@ -378,6 +699,8 @@ pub fn run(args: &CommitArgs, cli: &Cli) -> Result<i32> {
             format!("commit (initial): {}", String::from_utf8_lossy(commit.summary()))
         } else if args.amend {
             format!("commit (amend): {}", String::from_utf8_lossy(commit.summary()))
+        } else if in_progress_merge {
+            format!("commit (merge): {}", String::from_utf8_lossy(commit.summary()))
         } else {
             format!("commit: {}", String::from_utf8_lossy(commit.summary()))
         };
@@ -391,6 +714,14 @@ pub fn run(args: &CommitArgs, cli: &Cli) -> Result<i32> {
         append_reflog_entry(repo.git_dir(), &head_ref, &entry)?;
     }
 
+    // This commit concludes any in-progress merge or cherry-pick.
+    if in_progress_merge || in_progress_cherry_pick {
+        let git_dir = repo.git_dir();
+        for name in &["MERGE_HEAD", "MERGE_MSG", "MERGE_MODE", "CHERRY_PICK_HEAD"] {
+            let _ = std::fs::remove_file(git_dir.join(name));
+        }
+    }
+
     // Run post-commit hook (ignore exit code)
     let _ = hook_runner.run(HookType::PostCommit, &[], None);
 
@@ -459,7 +790,7 @@ fn auto_stage_tracked(
             };
 
             let entry = IndexEntry {
-                path: BString::from(path_str.as_str()),
+                path: BString::from(path_str.as_str()).into(),
                 oid,
                 mode,
                 stage: Stage::Normal,
@@ -481,15 +812,29 @@ fn auto_stage_tracked(
 }
 
 /// Determine the commit message from flags and editor.
+///
+/// `merge_msg_template` is the content of `MERGE_MSG` when a merge or
+/// cherry-pick is in progress; it is used as the editor template in place
+/// of the -t/commit.template contents when no other message source (-m,
+/// -F, -C, --amend, ...) takes precedence. `verbose_diff`, when given, is
+/// the `-v/--verbose` staged diff appended below a scissors line in any
+/// editor template. `status_comment` is the `#`-prefixed status scaffold
+/// (branch, staged/unstaged changes, untracked files) appended below the
+/// editable region whenever the editor is launched with no other message
+/// source. Returns the raw message alongside whether an editor was launched
+/// to produce it, since that decides how `CleanupMode::Default` resolves.
 fn determine_message(
     args: &CommitArgs,
     prev_commit: Option<&Commit>,
     repo: &git_repository::Repository,
-) -> Result<BString> {
+    merge_msg_template: Option<&str>,
+    verbose_diff: Option<&str>,
+    status_comment: &str,
+) -> Result<(BString, bool)> {
     // --no-edit with --amend: reuse previous message
     if args.no_edit && args.amend {
         if let Some(pc) = prev_commit {
-            return Ok(pc.message.clone());
+            return Ok((pc.message.clone(), false));
         }
         bail!("--no-edit requires --amend with an existing commit");
     }
@@ -497,30 +842,53 @@ fn determine_message(
     // -C / --reuse-message: read message from specified commit
     if let Some(ref rev) = args.reuse_message {
         let msg = read_commit_message(repo, rev)?;
-        return Ok(msg);
+        return Ok((msg, false));
     }
 
     // -c / --reedit-message: read message from specified commit, then edit
     if let Some(ref rev) = args.reedit_message {
         let msg = read_commit_message(repo, rev)?;
         let msg_str = msg.to_str_lossy().to_string();
-        return launch_editor(Some(&msg_str));
+        let template = with_verbose_diff(Some(&msg_str), verbose_diff);
+        return Ok((launch_editor(template.as_deref())?, true));
     }
 
-    // --fixup: prefix message with "fixup! <subject>"
-    if let Some(ref rev) = args.fixup {
+    // --fixup[=amend:|reword:]: see FixupKind for the three forms
+    if let Some(ref spec) = args.fixup {
+        if !args.message.is_empty() {
+            bail!("cannot combine --fixup with -m");
+        }
+        let (kind, rev) = parse_fixup_spec(spec);
         let msg = read_commit_message(repo, rev)?;
         let subject = msg.to_str_lossy().lines().next().unwrap_or("").to_string();
-        let fixup_msg = format!("fixup! {}\n", subject);
-        return Ok(BString::from(fixup_msg));
+        match kind {
+            FixupKind::Plain => {
+                let fixup_msg = format!("fixup! {}\n", subject);
+                return Ok((BString::from(fixup_msg), false));
+            }
+            FixupKind::Amend => {
+                let amend_msg = format!("amend! {}\n\n{}", subject, msg.to_str_lossy());
+                return Ok((BString::from(amend_msg), false));
+            }
+            FixupKind::Reword => {
+                // Reword always opens the editor, pre-filled with just the
+                // "amend! <subject>" header, unlike the plain/amend forms.
+                let header = format!("amend! {}\n", subject);
+                let template = with_verbose_diff(Some(&header), verbose_diff);
+                return Ok((launch_editor(template.as_deref())?, true));
+            }
+        }
     }
 
     // --squash: prefix message with "squash! <subject>"
     if let Some(ref rev) = args.squash {
+        if !args.message.is_empty() {
+            bail!("cannot combine --squash with -m");
+        }
         let msg = read_commit_message(repo, rev)?;
         let subject = msg.to_str_lossy().lines().next().unwrap_or("").to_string();
         let squash_msg = format!("squash! {}\n", subject);
-        return Ok(BString::from(squash_msg));
+        return Ok((BString::from(squash_msg), false));
     }
 
     // -F / --file: read message from file
@@ -539,9 +907,9 @@ fn determine_message(
                 .map_err(|e| anyhow::anyhow!("could not read file '{}': {}", file_path.display(), e))?
         };
         if !content.ends_with('\n') {
-            return Ok(BString::from(format!("{}\n", content)));
+            return Ok((BString::from(format!("{}\n", content)), false));
         }
-        return Ok(BString::from(content));
+        return Ok((BString::from(content), false));
     }
 
     // -m messages provided
@@ -555,23 +923,173 @@ fn determine_message(
 
         // If -e is also specified, open editor with the pre-filled message
         if args.edit {
-            return launch_editor(Some(&msg));
+            let template = with_verbose_diff(Some(&msg), verbose_diff);
+            return Ok((launch_editor(template.as_deref())?, true));
         }
 
-        return Ok(BString::from(msg));
+        return Ok((BString::from(msg), false));
     }
 
-    // No -m and no --no-edit: launch editor
-    let template = if args.amend {
+    // No -m and no --no-edit: launch editor. The editable region is the
+    // previous message when amending, the merge/cherry-pick message when
+    // one is in progress, or the -t/commit.template contents otherwise;
+    // the status scaffold is always appended below it.
+    let editable = if args.amend {
         prev_commit.map(|pc| {
             let msg: &[u8] = pc.message.as_ref();
             String::from_utf8_lossy(msg).to_string()
         })
+    } else if let Some(merge_msg) = merge_msg_template {
+        Some(merge_msg.to_string())
     } else {
-        None
+        load_commit_template(args, repo)?
     };
 
-    launch_editor(template.as_deref())
+    let mut template = editable.unwrap_or_default();
+    if !template.is_empty() && !template.ends_with('\n') {
+        template.push('\n');
+    }
+    template.push_str(status_comment);
+    let template = with_verbose_diff(Some(&template), verbose_diff);
+
+    Ok((launch_editor(template.as_deref())?, true))
+}
+
+/// Resolve the `-t/--template` flag or `commit.template` config into initial
+/// content for the editable region of the commit message template.
+fn load_commit_template(args: &CommitArgs, repo: &git_repository::Repository) -> Result<Option<String>> {
+    let path = if let Some(ref path) = args.template {
+        Some(path.clone())
+    } else {
+        repo.config()
+            .get_string("commit.template")
+            .ok()
+            .flatten()
+            .map(PathBuf::from)
+    };
+    let Some(path) = path else { return Ok(None) };
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("could not read template file '{}': {}", path.display(), e))?;
+    Ok(Some(content))
+}
+
+/// Build the `#`-prefixed status scaffold shown below the editable region of
+/// the commit message template: the current branch, staged changes (with
+/// their status char, as in `git status`), unstaged changes, and untracked
+/// files. Mirrors `status::print_long_status`'s wording.
+fn build_status_comment(repo: &mut git_repository::Repository) -> Result<String> {
+    let mut buf = String::new();
+    buf.push_str("# Please enter the commit message for your changes. Lines starting\n");
+    buf.push_str("# with '#' will be ignored, and an empty message aborts the commit.\n#\n");
+
+    match repo.current_branch() {
+        Ok(Some(branch)) => buf.push_str(&format!("# On branch {}\n", branch)),
+        Ok(None) => match repo.head_oid() {
+            Ok(Some(oid)) => {
+                let hex = oid.to_hex();
+                buf.push_str(&format!("# HEAD detached at {}\n", &hex[..7.min(hex.len())]));
+            }
+            _ => buf.push_str("# HEAD detached\n"),
+        },
+        Err(_) => {}
+    }
+
+    let diff_opts = git_diff::DiffOptions::default();
+    let staged = git_diff::worktree::diff_head_to_index(repo, &diff_opts)?;
+    if !staged.files.is_empty() {
+        buf.push_str("#\n# Changes to be committed:\n");
+        for file in &staged.files {
+            buf.push_str(&format!("#\t{}:   {}\n", file.status.as_char(), file.path().to_str_lossy()));
+        }
+    }
+
+    let unstaged = git_diff::worktree::diff_index_to_worktree(repo, &diff_opts)?;
+    if !unstaged.files.is_empty() {
+        buf.push_str("#\n# Changes not staged for commit:\n");
+        for file in &unstaged.files {
+            buf.push_str(&format!("#\t{}:   {}\n", file.status.as_char(), file.path().to_str_lossy()));
+        }
+    }
+
+    if let Some(work_tree) = repo.work_tree().map(Path::to_path_buf) {
+        let untracked = status::find_untracked(repo, &work_tree)?;
+        if !untracked.is_empty() {
+            buf.push_str("#\n# Untracked files:\n");
+            for path in &untracked {
+                buf.push_str(&format!("#\t{}\n", path.to_str_lossy()));
+            }
+        }
+    }
+
+    Ok(buf)
+}
+
+/// Resolve a commit OID to its tree OID.
+fn commit_tree(repo: &git_repository::Repository, oid: &ObjectId) -> Result<ObjectId> {
+    match repo.odb().read(oid)? {
+        Some(Object::Commit(c)) => Ok(c.tree),
+        _ => bail!("object '{}' is not a commit", oid.to_hex()),
+    }
+}
+
+/// Build a tree for a commit restricted to `paths` (COMMIT_PARTIAL): seed a
+/// temporary index from `base_tree` (HEAD's tree, or the amended commit's
+/// parent tree with --amend), then override only the entries the pathspec
+/// matches with their current blob/mode from the real (staged) index — an
+/// entry present in `base_tree` but absent from the real index is a staged
+/// deletion and is simply left out. Writing the tree from this temporary
+/// index, rather than from the real index, keeps every other staged change
+/// out of the resulting commit; the real index itself is untouched, since it
+/// already holds exactly the content used for the matched paths.
+fn build_partial_tree(
+    repo: &git_repository::Repository,
+    base_tree: Option<&ObjectId>,
+    real_index: &Index,
+    paths: &[PathBuf],
+) -> Result<ObjectId> {
+    let path_strs: Vec<String> = paths.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    let path_refs: Vec<&str> = path_strs.iter().map(String::as_str).collect();
+    let pathspec = Pathspec::parse(&path_refs)?;
+
+    let mut temp_index = Index::new();
+    if let Some(tree_oid) = base_tree {
+        read_tree::read_tree_into_index(repo.odb(), tree_oid, "", &mut temp_index, false, &mut io::sink())?;
+    }
+
+    let matched = temp_index.iter().any(|e| pathspec.matches(e.path.as_bstr(), false))
+        || real_index
+            .iter()
+            .any(|e| e.stage == Stage::Normal && pathspec.matches(e.path.as_bstr(), false));
+    if !matched {
+        bail!("pathspec '{}' did not match any tracked files", path_strs.join(" "));
+    }
+
+    let base_paths: Vec<BString> = temp_index.iter().map(|e| e.path.to_bstring()).collect();
+    for path in &base_paths {
+        if pathspec.matches(path.as_bstr(), false) {
+            temp_index.remove(path.as_ref(), Stage::Normal);
+        }
+    }
+    for entry in real_index.iter() {
+        if entry.stage == Stage::Normal && pathspec.matches(entry.path.as_bstr(), false) {
+            temp_index.add(entry.clone());
+        }
+    }
+
+    temp_index.write_tree(repo.odb())
+}
+
+/// Read a list of object IDs from a file such as MERGE_HEAD, one per line.
+fn read_oid_list(path: &std::path::Path) -> Result<Vec<ObjectId>> {
+    let content = std::fs::read_to_string(path)?;
+    let mut oids = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !trimmed.is_empty() {
+            oids.push(ObjectId::from_hex(trimmed)?);
+        }
+    }
+    Ok(oids)
 }
 
 /// Read the commit message from the given revision.
@@ -588,6 +1106,32 @@ fn read_commit_message(repo: &git_repository::Repository, rev: &str) -> Result<B
     }
 }
 
+/// Which autosquash form a `--fixup` value requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FixupKind {
+    /// `--fixup=<commit>`: `fixup! <subject>`.
+    Plain,
+    /// `--fixup=amend:<commit>`: `amend! <subject>` followed by the target
+    /// commit's full original message, so autosquash replaces both the
+    /// target's tree and its message.
+    Amend,
+    /// `--fixup=reword:<commit>`: `amend! <subject>`, reworded via the
+    /// editor, with an otherwise-empty tree change — autosquash replaces
+    /// only the target's message.
+    Reword,
+}
+
+/// Split the optional `amend:`/`reword:` prefix off a `--fixup` value.
+fn parse_fixup_spec(spec: &str) -> (FixupKind, &str) {
+    if let Some(rev) = spec.strip_prefix("amend:") {
+        (FixupKind::Amend, rev)
+    } else if let Some(rev) = spec.strip_prefix("reword:") {
+        (FixupKind::Reword, rev)
+    } else {
+        (FixupKind::Plain, spec)
+    }
+}
+
 /// Launch an editor to compose the commit message.
 fn launch_editor(initial_content: Option<&str>) -> Result<BString> {
     let editor = std::env::var("GIT_EDITOR")
@@ -602,9 +1146,7 @@ fn launch_editor(initial_content: Option<&str>) -> Result<BString> {
     let content = if let Some(initial) = initial_content {
         initial.to_string()
     } else {
-        "\n# Enter the commit message for your changes.\n\
-         # Lines starting with '#' will be ignored.\n"
-            .to_string()
+        DEFAULT_MESSAGE_TEMPLATE.to_string()
     };
     std::fs::write(&msg_path, &content)?;
 
@@ -618,23 +1160,12 @@ fn launch_editor(initial_content: Option<&str>) -> Result<BString> {
         bail!("editor '{}' exited with non-zero status", editor);
     }
 
-    // Read back the edited message, stripping comment lines
+    // Read back the raw edited message; comment-stripping and whitespace
+    // cleanup are applied afterwards by `apply_cleanup`, not here.
     let raw = std::fs::read_to_string(&msg_path)?;
-    let filtered: Vec<&str> = raw
-        .lines()
-        .filter(|line| !line.starts_with('#'))
-        .collect();
-    let mut message = filtered.join("\n");
-
-    // Ensure trailing newline
-    if !message.ends_with('\n') {
-        message.push('\n');
-    }
-
-    // Clean up
     let _ = std::fs::remove_file(&msg_path);
 
-    Ok(BString::from(message))
+    Ok(BString::from(raw))
 }
 
 /// Update HEAD to point to the new commit.
@@ -750,23 +1281,58 @@ fn print_summary(
 }
 
 /// Parse --author="Name <email>" override.
-fn parse_author_override(author_str: &str) -> Result<Signature> {
+fn parse_author_override(author_str: &str, repo: &git_repository::Repository) -> Result<Signature> {
     // Expected format: "Name <email>"
-    let gt_pos = author_str
-        .rfind('>')
-        .ok_or_else(|| anyhow::anyhow!("invalid --author format, expected 'Name <email>'"))?;
-    let lt_pos = author_str[..gt_pos]
-        .rfind('<')
-        .ok_or_else(|| anyhow::anyhow!("invalid --author format, expected 'Name <email>'"))?;
+    if let Some(gt_pos) = author_str.rfind('>') {
+        if let Some(lt_pos) = author_str[..gt_pos].rfind('<') {
+            let name = author_str[..lt_pos].trim();
+            let email = &author_str[lt_pos + 1..gt_pos];
+
+            return Ok(Signature {
+                name: BString::from(name),
+                email: BString::from(email),
+                date: GitDate::now(),
+            });
+        }
+    }
 
-    let name = author_str[..lt_pos].trim();
-    let email = &author_str[lt_pos + 1..gt_pos];
+    // Not in "Name <email>" form: treat it as a pattern and search existing
+    // commits (most recent first) for one whose "Name <email>" matches,
+    // reusing that author's identity with a fresh timestamp.
+    find_author_by_pattern(author_str, repo)
+}
 
-    Ok(Signature {
-        name: BString::from(name),
-        email: BString::from(email),
-        date: GitDate::now(),
-    })
+/// Search commit history from HEAD (newest first) for an author whose
+/// `Name <email>` string matches `pattern` as a regex, reusing that
+/// signature's name and email with a fresh timestamp.
+fn find_author_by_pattern(pattern: &str, repo: &git_repository::Repository) -> Result<Signature> {
+    let re = regex::Regex::new(pattern)
+        .map_err(|e| anyhow::anyhow!("invalid --author pattern '{}': {}", pattern, e))?;
+
+    let mut walk = RevWalk::new(repo)?;
+    if let Some(head_oid) = repo.head_oid()? {
+        walk.push(head_oid)?;
+    }
+
+    for result in &mut walk {
+        let oid = result?;
+        if let Some(Object::Commit(commit)) = repo.odb().read(&oid)? {
+            let author_line = format!(
+                "{} <{}>",
+                commit.author.name.to_str_lossy(),
+                commit.author.email.to_str_lossy()
+            );
+            if re.is_match(&author_line) {
+                return Ok(Signature {
+                    name: commit.author.name.clone(),
+                    email: commit.author.email.clone(),
+                    date: GitDate::now(),
+                });
+            }
+        }
+    }
+
+    bail!("no commit author matches --author='{}'", pattern)
 }
 
 /// Build a Signature from environment variables or config.
@@ -797,7 +1363,11 @@ pub(crate) fn get_signature(
         .unwrap_or_else(|| "unknown@unknown".to_string());
 
     let date = if let Ok(date_str) = std::env::var(date_var) {
-        GitDate::parse_raw(&date_str)?
+        // Accept any git-recognized date form here (raw, ISO 8601, RFC
+        // 2822, or a relative expression like "2 hours ago"), not just
+        // the raw `<unix> <±HHMM>` form, since GIT_AUTHOR_DATE/
+        // GIT_COMMITTER_DATE accept the same syntax as `--date`.
+        GitDate::parse_approxidate_now(&date_str)?
     } else {
         GitDate::now()
     };
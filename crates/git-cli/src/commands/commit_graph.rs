@@ -3,6 +3,7 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 
+use git_diff::DiffOptions;
 use git_hash::HashAlgorithm;
 use git_object::Object;
 use git_revwalk::{CommitGraph, CommitGraphWriter, RevWalk};
@@ -19,22 +20,34 @@ pub struct CommitGraphArgs {
 #[derive(Subcommand)]
 enum CommitGraphAction {
     /// Write a commit-graph file from reachable commits
-    Write,
+    Write {
+        /// Write as a layer appended to a split commit-graph chain instead
+        /// of rewriting the whole monolithic file
+        #[arg(long)]
+        split: bool,
+    },
     /// Verify the commit-graph file integrity
     Verify,
 }
 
 pub fn run(args: &CommitGraphArgs, cli: &Cli) -> Result<i32> {
     match &args.action {
-        CommitGraphAction::Write => run_write(cli),
+        CommitGraphAction::Write { split } => run_write(cli, *split),
         CommitGraphAction::Verify => run_verify(cli),
     }
 }
 
-fn run_write(cli: &Cli) -> Result<i32> {
+fn run_write(cli: &Cli, split: bool) -> Result<i32> {
     let repo = open_repo(cli)?;
     let objects_dir = repo.odb().objects_dir().to_path_buf();
     let graph_path = objects_dir.join("info").join("commit-graph");
+    let chain_dir = objects_dir.join("info").join("commit-graphs");
+
+    let base = if split {
+        CommitGraph::open_from_repo(&repo).ok()
+    } else {
+        None
+    };
 
     // Walk all reachable commits.
     let mut walk = RevWalk::new(&repo)?;
@@ -46,23 +59,50 @@ fn run_write(cli: &Cli) -> Result<i32> {
 
     for result in &mut walk {
         let oid = result?;
+        if let Some(ref base) = base {
+            if base.contains(&oid) {
+                continue;
+            }
+        }
         // Read full commit to get tree and parents.
         let obj = repo.odb().read(&oid)?;
         if let Some(Object::Commit(commit)) = obj {
             let tree_oid = commit.tree;
             let parents = commit.parents;
             let commit_time = commit.committer.date.timestamp;
-            writer.add_commit(oid, tree_oid, parents, commit_time);
+
+            let parent_tree = match parents.first() {
+                Some(parent) => match repo.odb().read(parent)? {
+                    Some(Object::Commit(parent_commit)) => Some(parent_commit.tree),
+                    _ => None,
+                },
+                None => None,
+            };
+            let diff_opts = DiffOptions::default();
+            match git_diff::tree::diff_trees(repo.odb(), parent_tree.as_ref(), Some(&tree_oid), &diff_opts) {
+                Ok(diff) => {
+                    let changed_paths = diff.files.into_iter().map(|f| f.path().clone()).collect();
+                    writer.add_commit_with_changed_paths(oid, tree_oid, parents, commit_time, changed_paths);
+                }
+                Err(_) => writer.add_commit(oid, tree_oid, parents, commit_time),
+            }
             count += 1;
         }
     }
 
-    if count == 0 {
+    if count == 0 && base.is_none() {
         eprintln!("No commits found.");
         return Ok(0);
     }
 
-    writer.write(&graph_path)?;
+    if split {
+        if count == 0 {
+            return Ok(0);
+        }
+        writer.append_layer(&chain_dir, base.as_ref())?;
+    } else {
+        writer.write(&graph_path)?;
+    }
 
     Ok(0)
 }
@@ -114,6 +114,23 @@ pub fn run(args: &ConfigArgs, cli: &Cli) -> Result<i32> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
 
+    // `--type`/`--bool`/`--int`/`--path` ask for a precise, type-checked
+    // value, so unlike a plain `--get` they opt out of the lenient parsing
+    // that plumbing/porcelain gets by default and surface a hard error on
+    // a malformed value instead of silently treating it as absent.
+    let requested_type = args.value_type.clone().or(if args.bool_type {
+        Some("bool".to_string())
+    } else if args.int_type {
+        Some("int".to_string())
+    } else if args.path_type {
+        Some("path".to_string())
+    } else {
+        None
+    });
+    if requested_type.is_some() {
+        repo.config_mut().set_lenient(false);
+    }
+
     // Handle --unset
     if args.unset {
         let key = args.key.as_deref().ok_or_else(|| {
@@ -254,12 +271,35 @@ pub fn run(args: &ConfigArgs, cli: &Cli) -> Result<i32> {
             return Ok(1);
         }
 
-        match repo.config().get_string(key)? {
-            Some(value) => {
-                writeln!(out, "{}", value)?;
-                Ok(0)
-            }
-            None => Ok(1),
+        match requested_type.as_deref() {
+            Some("bool") => match repo.config().get_bool(key)? {
+                Some(value) => {
+                    writeln!(out, "{}", if value { "true" } else { "false" })?;
+                    Ok(0)
+                }
+                None => Ok(1),
+            },
+            Some("int") => match repo.config().get_int(key)? {
+                Some(value) => {
+                    writeln!(out, "{}", value)?;
+                    Ok(0)
+                }
+                None => Ok(1),
+            },
+            Some("path") => match repo.config().get_path(key)? {
+                Some(value) => {
+                    writeln!(out, "{}", value.display())?;
+                    Ok(0)
+                }
+                None => Ok(1),
+            },
+            _ => match repo.config().get_string(key)? {
+                Some(value) => {
+                    writeln!(out, "{}", value)?;
+                    Ok(0)
+                }
+                None => Ok(1),
+            },
         }
     } else {
         eprintln!("error: usage: git config [--get] [--list] [--show-origin] [--local] [key] [value]");
@@ -4,6 +4,8 @@ use std::path::Path;
 
 use anyhow::Result;
 use clap::Args;
+use git_hash::ObjectId;
+use git_pack::index::PackIndex;
 
 use crate::Cli;
 use super::open_repo;
@@ -19,147 +21,175 @@ pub struct CountObjectsArgs {
     human_readable: bool,
 }
 
+/// Tally of loose objects under `.git/objects/xx/...`.
+#[derive(Default)]
+struct LooseStats {
+    count: u64,
+    disk_bytes: u64,
+    oids: Vec<ObjectId>,
+    garbage_count: u64,
+    garbage_bytes: u64,
+}
+
+/// Tally of packed objects under `.git/objects/pack/`.
+#[derive(Default)]
+struct PackStats {
+    pack_count: u64,
+    object_count: u64,
+    disk_bytes: u64,
+    indexes: Vec<PackIndex>,
+}
+
 pub fn run(args: &CountObjectsArgs, cli: &Cli) -> Result<i32> {
     let repo = open_repo(cli)?;
     let stdout = io::stdout();
     let mut out = stdout.lock();
 
     let objects_dir = repo.git_dir().join("objects");
+    let loose = scan_loose_objects(&objects_dir);
+    let loose_size_kib = loose.disk_bytes / 1024;
+
+    if !args.verbose {
+        writeln!(
+            out,
+            "{} objects, {} kilobytes",
+            loose.count, loose_size_kib,
+        )?;
+        return Ok(0);
+    }
 
-    // Count loose objects and their total size
-    let mut loose_count: u64 = 0;
-    let mut loose_size: u64 = 0;
+    let packs = scan_packs(&objects_dir.join("pack"));
+    let pack_size_kib = packs.disk_bytes / 1024;
+    let garbage_size_kib = loose.garbage_bytes / 1024;
+    let prune_packable = loose
+        .oids
+        .iter()
+        .filter(|oid| packs.indexes.iter().any(|idx| idx.lookup(oid).is_some()))
+        .count() as u64;
+
+    writeln!(out, "count: {}", loose.count)?;
+    writeln!(out, "size: {}", format_size(loose_size_kib, args.human_readable))?;
+    writeln!(out, "in-pack: {}", packs.object_count)?;
+    writeln!(out, "packs: {}", packs.pack_count)?;
+    writeln!(out, "size-pack: {}", format_size(pack_size_kib, args.human_readable))?;
+    writeln!(out, "prune-packable: {}", prune_packable)?;
+    writeln!(out, "garbage: {}", loose.garbage_count)?;
+    writeln!(out, "size-garbage: {}", format_size(garbage_size_kib, args.human_readable))?;
 
-    for prefix in 0..=0xffu32 {
-        let subdir = objects_dir.join(format!("{:02x}", prefix));
-        if !subdir.is_dir() {
+    Ok(0)
+}
+
+/// Walk the `xx/` fan-out directories under `objects/`, separating valid
+/// loose objects (two-hex-digit subdir + 38-hex-digit filename) from
+/// anything else, which counts as garbage.
+fn scan_loose_objects(objects_dir: &Path) -> LooseStats {
+    let mut stats = LooseStats::default();
+
+    let Ok(top_entries) = fs::read_dir(objects_dir) else {
+        return stats;
+    };
+
+    for top_entry in top_entries.flatten() {
+        let name = top_entry.file_name();
+        let name_str = name.to_string_lossy();
+
+        // "pack" and "info" are expected fan-out siblings, not garbage.
+        if name_str == "pack" || name_str == "info" {
             continue;
         }
 
-        let entries = match fs::read_dir(&subdir) {
-            Ok(entries) => entries,
-            Err(_) => continue,
+        let Ok(meta) = top_entry.metadata() else {
+            continue;
         };
 
-        for entry in entries {
-            let entry = match entry {
-                Ok(e) => e,
-                Err(_) => continue,
-            };
-            let meta = match entry.metadata() {
-                Ok(m) => m,
-                Err(_) => continue,
+        if !meta.is_dir() || name_str.len() != 2 || !is_hex(&name_str) {
+            stats.garbage_count += 1;
+            stats.garbage_bytes += on_disk_bytes(&meta);
+            continue;
+        }
+
+        let Ok(sub_entries) = fs::read_dir(top_entry.path()) else {
+            continue;
+        };
+
+        for sub_entry in sub_entries.flatten() {
+            let Ok(meta) = sub_entry.metadata() else {
+                continue;
             };
-            if meta.is_file() {
-                loose_count += 1;
-                loose_size += meta.len();
+            if !meta.is_file() {
+                continue;
             }
-        }
-    }
 
-    // Size in KiB (matching git's output)
-    let loose_size_kib = loose_size / 1024;
-
-    if args.verbose {
-        // Also report pack information
-        let mut pack_count: u64 = 0;
-        let mut packed_objects: u64 = 0;
-        let mut pack_size: u64 = 0;
-
-        let pack_dir = objects_dir.join("pack");
-        if pack_dir.is_dir() {
-            if let Ok(entries) = fs::read_dir(&pack_dir) {
-                for entry in entries {
-                    let entry = match entry {
-                        Ok(e) => e,
-                        Err(_) => continue,
-                    };
-                    let path = entry.path();
-                    let name = entry.file_name();
-                    let name_str = name.to_string_lossy();
-
-                    if name_str.ends_with(".pack") {
-                        let meta = entry.metadata()?;
-                        pack_count += 1;
-                        pack_size += meta.len();
-                    }
-
-                    if name_str.ends_with(".idx") {
-                        // Count objects from v2 idx file size:
-                        // v2 idx layout: 1032 byte header + 24 bytes per entry + ...
-                        // fanout (256*4=1024) + signature(4) + version(4) = 1032 header
-                        // then: oids (20*n) + crc (4*n) = 24*n
-                        packed_objects += count_idx_entries(&path);
-                    }
+            let sub_name = sub_entry.file_name();
+            let sub_name_str = sub_name.to_string_lossy();
+            let hex = format!("{}{}", name_str, sub_name_str);
+
+            if sub_name_str.len() == 38 && is_hex(&sub_name_str) {
+                if let Ok(oid) = ObjectId::from_hex(&hex) {
+                    stats.count += 1;
+                    stats.disk_bytes += on_disk_bytes(&meta);
+                    stats.oids.push(oid);
+                    continue;
                 }
             }
-        }
-
-        let pack_size_kib = pack_size / 1024;
 
-        writeln!(out, "count: {}", loose_count)?;
-        writeln!(out, "size: {}", format_size(loose_size_kib, args.human_readable))?;
-        writeln!(out, "in-pack: {}", packed_objects)?;
-        writeln!(out, "packs: {}", pack_count)?;
-        writeln!(out, "size-pack: {}", format_size(pack_size_kib, args.human_readable))?;
-        writeln!(out, "prune-packable: 0")?;
-        writeln!(out, "garbage: 0")?;
-        writeln!(out, "size-garbage: {}", format_size(0, args.human_readable))?;
-    } else {
-        writeln!(
-            out,
-            "count: {}",
-            loose_count,
-        )?;
-        writeln!(
-            out,
-            "size: {}",
-            format_size(loose_size_kib, args.human_readable),
-        )?;
+            stats.garbage_count += 1;
+            stats.garbage_bytes += on_disk_bytes(&meta);
+        }
     }
 
-    Ok(0)
+    stats
 }
 
-/// Count entries in a v2 pack index file from its file size.
-///
-/// v2 idx layout:
-///   - 4-byte magic + 4-byte version = 8 bytes
-///   - 256 * 4-byte fanout table = 1024 bytes
-///   - n * 20-byte SHA1 entries
-///   - n * 4-byte CRC32 entries
-///   - n * 4-byte offset entries
-///   - (possibly 8-byte large offsets)
-///   - 20-byte pack checksum + 20-byte idx checksum = 40 bytes
-///
-/// The last fanout entry (at offset 1028..1032) gives the total object count.
-/// We read it directly for accuracy.
-fn count_idx_entries(idx_path: &Path) -> u64 {
-    let data = match fs::read(idx_path) {
-        Ok(d) => d,
-        Err(_) => return 0,
+/// Walk `objects/pack/`, opening each `.idx` alongside its `.pack`.
+fn scan_packs(pack_dir: &Path) -> PackStats {
+    let mut stats = PackStats::default();
+
+    let Ok(entries) = fs::read_dir(pack_dir) else {
+        return stats;
     };
 
-    // v2 idx: magic 0xff744f63, version 2, then fanout[256]
-    if data.len() < 1032 {
-        return 0;
-    }
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
 
-    // Check v2 magic
-    if &data[0..4] == b"\xfftOc" && data[4..8] == [0, 0, 0, 2] {
-        // Last fanout entry (index 255) at offset 8 + 255*4 = 1028
-        let offset = 8 + 255 * 4;
-        u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
-            as u64
-    } else {
-        // v1 idx: fanout[256] starts at offset 0, last entry at 255*4=1020
-        if data.len() < 1024 {
-            return 0;
+        if let Ok(meta) = entry.metadata() {
+            if name_str.ends_with(".pack") || name_str.ends_with(".idx") {
+                stats.disk_bytes += on_disk_bytes(&meta);
+            }
+        }
+
+        if name_str.ends_with(".pack") {
+            stats.pack_count += 1;
+        }
+
+        if name_str.ends_with(".idx") {
+            if let Ok(idx) = PackIndex::open(&path) {
+                stats.object_count += idx.num_objects() as u64;
+                stats.indexes.push(idx);
+            }
         }
-        let offset = 255 * 4;
-        u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
-            as u64
     }
+
+    stats
+}
+
+fn is_hex(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Bytes actually allocated on disk for a file, matching C git's `du`-style
+/// accounting (filesystem block count, not logical length).
+#[cfg(unix)]
+fn on_disk_bytes(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn on_disk_bytes(meta: &fs::Metadata) -> u64 {
+    meta.len()
 }
 
 /// Format a size value, optionally with human-readable suffixes.
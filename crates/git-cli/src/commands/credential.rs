@@ -1,6 +1,12 @@
-use std::io::{self, BufRead, Write};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use bstr::ByteSlice;
 use clap::Args;
 
 use crate::Cli;
@@ -11,6 +17,20 @@ pub struct CredentialArgs {
     operation: String,
 }
 
+/// Hidden subcommand that runs the `credential-cache` background daemon;
+/// spawned by the built-in `cache` helper, never invoked directly by users.
+#[derive(Args)]
+pub struct CredentialCacheDaemonArgs {
+    /// Unix socket path to listen on
+    #[arg(long)]
+    socket: PathBuf,
+
+    /// Seconds a stored credential stays cached (counted from when it was
+    /// stored; lookups do not extend it)
+    #[arg(long, default_value = "900")]
+    timeout: u64,
+}
+
 pub fn run(args: &CredentialArgs, cli: &Cli) -> Result<i32> {
     match args.operation.as_str() {
         "fill" => credential_fill(cli),
@@ -22,6 +42,20 @@ pub fn run(args: &CredentialArgs, cli: &Cli) -> Result<i32> {
     }
 }
 
+/// A credential request/response, using git's key=value wire format.
+#[derive(Default, Clone, PartialEq)]
+struct CredentialRequest {
+    protocol: Option<String>,
+    host: Option<String>,
+    path: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    password_expiry_utc: Option<String>,
+    authtype: Option<String>,
+    credential: Option<String>,
+    wwwauth: Vec<String>,
+}
+
 /// Parse credential attributes from stdin.
 fn parse_credential_input() -> Result<CredentialRequest> {
     let stdin = io::stdin();
@@ -35,40 +69,56 @@ fn parse_credential_input() -> Result<CredentialRequest> {
         }
 
         if let Some((key, value)) = line.split_once('=') {
-            match key {
-                "protocol" => cred.protocol = Some(value.to_string()),
-                "host" => cred.host = Some(value.to_string()),
-                "path" => cred.path = Some(value.to_string()),
-                "username" => cred.username = Some(value.to_string()),
-                "password" => cred.password = Some(value.to_string()),
-                "password_expiry_utc" => cred.password_expiry_utc = Some(value.to_string()),
-                "url" => {
-                    // Parse URL into components
-                    if let Some((proto, rest)) = value.split_once("://") {
-                        cred.protocol = Some(proto.to_string());
-                        if let Some((host, path)) = rest.split_once('/') {
-                            cred.host = Some(host.to_string());
-                            cred.path = Some(path.to_string());
-                        } else {
-                            cred.host = Some(rest.to_string());
-                        }
-                    }
-                }
-                _ => {
-                    // Ignore unknown keys
-                }
-            }
+            apply_credential_field(&mut cred, key, value);
         }
     }
 
     Ok(cred)
 }
 
+/// Apply one `key=value` wire-format line onto `cred`, handling the `url`
+/// shorthand (which decomposes into protocol/host/path) and the newer
+/// `wwwauth[]` capability lines (which accumulate, one value per header).
+fn apply_credential_field(cred: &mut CredentialRequest, key: &str, value: &str) {
+    match key {
+        "protocol" => cred.protocol = Some(value.to_string()),
+        "host" => cred.host = Some(value.to_string()),
+        "path" => cred.path = Some(value.to_string()),
+        "username" => cred.username = Some(value.to_string()),
+        "password" => cred.password = Some(value.to_string()),
+        "password_expiry_utc" => cred.password_expiry_utc = Some(value.to_string()),
+        "authtype" => cred.authtype = Some(value.to_string()),
+        "credential" => cred.credential = Some(value.to_string()),
+        "wwwauth[]" => cred.wwwauth.push(value.to_string()),
+        "url" => {
+            // Parse URL into components
+            if let Some((proto, rest)) = value.split_once("://") {
+                cred.protocol = Some(proto.to_string());
+                if let Some((host, path)) = rest.split_once('/') {
+                    cred.host = Some(host.to_string());
+                    cred.path = Some(path.to_string());
+                } else {
+                    cred.host = Some(rest.to_string());
+                }
+            }
+        }
+        _ => {
+            // Ignore unknown keys
+        }
+    }
+}
+
 /// Write credential attributes to stdout.
 fn write_credential_output(cred: &CredentialRequest) -> Result<()> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
+    write_credential_to(&mut out, cred)?;
+    writeln!(out)?; // Empty line to terminate
+    Ok(())
+}
 
+/// Write credential attributes (without the terminating blank line) to `out`.
+fn write_credential_to(out: &mut impl Write, cred: &CredentialRequest) -> Result<()> {
     if let Some(ref protocol) = cred.protocol {
         writeln!(out, "protocol={}", protocol)?;
     }
@@ -87,8 +137,15 @@ fn write_credential_output(cred: &CredentialRequest) -> Result<()> {
     if let Some(ref expiry) = cred.password_expiry_utc {
         writeln!(out, "password_expiry_utc={}", expiry)?;
     }
-    writeln!(out)?; // Empty line to terminate
-
+    if let Some(ref authtype) = cred.authtype {
+        writeln!(out, "authtype={}", authtype)?;
+    }
+    if let Some(ref credential) = cred.credential {
+        writeln!(out, "credential={}", credential)?;
+    }
+    for header in &cred.wwwauth {
+        writeln!(out, "wwwauth[]={}", header)?;
+    }
     Ok(())
 }
 
@@ -96,7 +153,7 @@ fn credential_fill(cli: &Cli) -> Result<i32> {
     let mut cred = parse_credential_input()?;
 
     // Try to get helpers from config
-    let helpers = get_credential_helpers(cli);
+    let helpers = get_credential_helpers(cli, &cred);
 
     for helper in &helpers {
         if let Ok(result) = run_credential_helper(helper, "get", &cred) {
@@ -116,7 +173,7 @@ fn credential_fill(cli: &Cli) -> Result<i32> {
 
 fn credential_approve(cli: &Cli) -> Result<i32> {
     let cred = parse_credential_input()?;
-    let helpers = get_credential_helpers(cli);
+    let helpers = get_credential_helpers(cli, &cred);
 
     for helper in &helpers {
         let _ = run_credential_helper(helper, "store", &cred);
@@ -127,7 +184,7 @@ fn credential_approve(cli: &Cli) -> Result<i32> {
 
 fn credential_reject(cli: &Cli) -> Result<i32> {
     let cred = parse_credential_input()?;
-    let helpers = get_credential_helpers(cli);
+    let helpers = get_credential_helpers(cli, &cred);
 
     for helper in &helpers {
         let _ = run_credential_helper(helper, "erase", &cred);
@@ -136,92 +193,565 @@ fn credential_reject(cli: &Cli) -> Result<i32> {
     Ok(0)
 }
 
-/// Get configured credential helpers.
-fn get_credential_helpers(cli: &Cli) -> Vec<String> {
+/// Get configured credential helpers that apply to `cred`'s URL, in config
+/// file order. Plain `credential.helper` entries always apply; a
+/// `credential.<url>.helper` entry applies only when `<url>` matches the
+/// request's protocol/host/path. An empty `credential.helper` value clears
+/// everything configured so far, matching git's reset semantics.
+fn get_credential_helpers(cli: &Cli, cred: &CredentialRequest) -> Vec<String> {
     let mut helpers = Vec::new();
 
-    // Try to read from config
-    if let Ok(repo) = super::open_repo(cli) {
-        if let Ok(Some(helper)) = repo.config().get_string("credential.helper") {
-            helpers.push(helper);
+    let Ok(repo) = super::open_repo(cli) else {
+        return helpers;
+    };
+
+    for entry in repo.config().all_entries() {
+        if entry.key.section.to_str_lossy() != "credential" || entry.key.name.to_str_lossy() != "helper" {
+            continue;
+        }
+
+        let applies = match &entry.key.subsection {
+            None => true,
+            Some(url) => credential_url_matches(&url.to_str_lossy(), cred),
+        };
+        if !applies {
+            continue;
+        }
+
+        let value = entry.value.as_ref().map(|v| v.to_str_lossy().to_string()).unwrap_or_default();
+        if value.is_empty() {
+            helpers.clear();
+        } else {
+            helpers.push(value);
         }
     }
 
     helpers
 }
 
-/// Run a credential helper subprocess.
-fn run_credential_helper(
-    helper: &str,
-    action: &str,
-    cred: &CredentialRequest,
-) -> Result<CredentialRequest> {
-    // Build the command name
-    let cmd = if helper.starts_with('/') || helper.starts_with('!') {
-        helper.trim_start_matches('!').to_string()
-    } else {
-        format!("git-credential-{}", helper)
+/// Whether a `credential.<url>.helper` URL pattern applies to `cred`'s
+/// protocol/host/path. The pattern may include a `user@` prefix (ignored
+/// here, as git matches it against any username) and an optional path,
+/// which must be a prefix of the request's path.
+fn credential_url_matches(pattern: &str, cred: &CredentialRequest) -> bool {
+    let Some((pattern_protocol, rest)) = pattern.split_once("://") else {
+        return false;
     };
-
-    // Build stdin input
-    let mut input = String::new();
-    if let Some(ref protocol) = cred.protocol {
-        input.push_str(&format!("protocol={}\n", protocol));
+    if Some(pattern_protocol) != cred.protocol.as_deref() {
+        return false;
     }
-    if let Some(ref host) = cred.host {
-        input.push_str(&format!("host={}\n", host));
+
+    let (pattern_authority, pattern_path) = match rest.split_once('/') {
+        Some((a, p)) => (a, Some(p)),
+        None => (rest, None),
+    };
+    let pattern_host = pattern_authority.rsplit('@').next().unwrap_or(pattern_authority);
+    if Some(pattern_host) != cred.host.as_deref() {
+        return false;
     }
-    if let Some(ref path) = cred.path {
-        input.push_str(&format!("path={}\n", path));
+
+    match pattern_path {
+        Some(p) if !p.is_empty() => cred.path.as_deref().is_some_and(|path| path.starts_with(p)),
+        _ => true,
     }
-    if let Some(ref username) = cred.username {
-        input.push_str(&format!("username={}\n", username));
+}
+
+/// Run a credential helper, dispatching to a built-in implementation for
+/// `store`/`cache` and spawning a subprocess (`git-credential-<name>`, a
+/// literal path, or a `!`-prefixed shell command) otherwise.
+fn run_credential_helper(helper: &str, action: &str, cred: &CredentialRequest) -> Result<CredentialRequest> {
+    if let Some(shell_cmd) = helper.strip_prefix('!') {
+        return run_shell_credential_helper(shell_cmd, action, cred);
     }
-    if let Some(ref password) = cred.password {
-        input.push_str(&format!("password={}\n", password));
+
+    let mut words = helper.split_whitespace();
+    let name = words.next().unwrap_or("");
+    let extra_args: Vec<&str> = words.collect();
+
+    match name {
+        "store" => return builtin_credential_store(action, cred, &extra_args),
+        "cache" => return builtin_credential_cache(action, cred, &extra_args),
+        _ => {}
     }
-    input.push('\n');
 
-    let output = std::process::Command::new(&cmd)
+    let cmd = if name.starts_with('/') {
+        name.to_string()
+    } else {
+        format!("git-credential-{}", name)
+    };
+
+    spawn_credential_helper(&cmd, &extra_args, action, cred)
+}
+
+fn run_shell_credential_helper(shell_cmd: &str, action: &str, cred: &CredentialRequest) -> Result<CredentialRequest> {
+    let mut input = Vec::new();
+    write_credential_to(&mut input, cred)?;
+
+    let output = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} {}", shell_cmd, action))
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(ref mut stdin) = child.stdin {
+                stdin.write_all(&input)?;
+            }
+            child.wait_with_output()
+        })?;
+
+    parse_credential_output(&output.stdout)
+}
+
+fn spawn_credential_helper(cmd: &str, extra_args: &[&str], action: &str, cred: &CredentialRequest) -> Result<CredentialRequest> {
+    let mut input = Vec::new();
+    write_credential_to(&mut input, cred)?;
+
+    let output = std::process::Command::new(cmd)
+        .args(extra_args)
         .arg(action)
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::null())
         .spawn()
         .and_then(|mut child| {
-            use std::io::Write;
             if let Some(ref mut stdin) = child.stdin {
-                stdin.write_all(input.as_bytes())?;
+                stdin.write_all(&input)?;
             }
             child.wait_with_output()
         })?;
 
-    // Parse output
+    parse_credential_output(&output.stdout)
+}
+
+fn parse_credential_output(stdout: &[u8]) -> Result<CredentialRequest> {
     let mut result = CredentialRequest::default();
-    let stdout_str = String::from_utf8_lossy(&output.stdout);
+    let stdout_str = String::from_utf8_lossy(stdout);
     for line in stdout_str.lines() {
         if line.is_empty() {
             break;
         }
         if let Some((key, value)) = line.split_once('=') {
-            match key {
-                "username" => result.username = Some(value.to_string()),
-                "password" => result.password = Some(value.to_string()),
-                "password_expiry_utc" => result.password_expiry_utc = Some(value.to_string()),
-                _ => {}
+            apply_credential_field(&mut result, key, value);
+        }
+    }
+    Ok(result)
+}
+
+// === Built-in `credential-store` helper ===
+//
+// A plaintext `~/.git-credentials` file, one `protocol://[user[:pass]@]host[/path]`
+// URL per line, matched against by protocol/host (and username, when given).
+
+fn builtin_credential_store(action: &str, cred: &CredentialRequest, extra_args: &[&str]) -> Result<CredentialRequest> {
+    let path = credential_store_path(extra_args)?;
+    let mut lines: Vec<String> = fs::read_to_string(&path)
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.to_string())
+        .collect();
+
+    let mut result = CredentialRequest::default();
+
+    match action {
+        "get" => {
+            for line in &lines {
+                let Some(parsed) = parse_store_line(line) else { continue };
+                if store_entry_matches(&parsed, cred) {
+                    result = parsed;
+                    break;
+                }
+            }
+        }
+        "store" => {
+            if cred.username.is_some() && cred.password.is_some() {
+                lines.retain(|line| {
+                    parse_store_line(line).map_or(true, |parsed| !store_entry_matches(&parsed, cred))
+                });
+                lines.push(format_store_line(cred));
+                write_credential_store(&path, &lines)?;
             }
         }
+        "erase" => {
+            let before = lines.len();
+            lines.retain(|line| {
+                parse_store_line(line).map_or(true, |parsed| !store_entry_matches(&parsed, cred))
+            });
+            if lines.len() != before {
+                write_credential_store(&path, &lines)?;
+            }
+        }
+        _ => {}
     }
 
     Ok(result)
 }
 
-#[derive(Default)]
-struct CredentialRequest {
-    protocol: Option<String>,
-    host: Option<String>,
-    path: Option<String>,
-    username: Option<String>,
-    password: Option<String>,
-    password_expiry_utc: Option<String>,
+fn credential_store_path(extra_args: &[&str]) -> Result<PathBuf> {
+    for arg in extra_args {
+        if let Some(file) = arg.strip_prefix("--file=") {
+            return Ok(PathBuf::from(file));
+        }
+    }
+    let home = std::env::var_os("HOME").context("credential-store: HOME is not set")?;
+    Ok(PathBuf::from(home).join(".git-credentials"))
+}
+
+/// Whether a stored entry matches a lookup/store/erase request: protocol and
+/// host must match exactly, and the username must match when the request
+/// specifies one.
+fn store_entry_matches(stored: &CredentialRequest, cred: &CredentialRequest) -> bool {
+    stored.protocol == cred.protocol
+        && stored.host == cred.host
+        && (cred.username.is_none() || stored.username == cred.username)
+}
+
+fn parse_store_line(line: &str) -> Option<CredentialRequest> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (protocol, rest) = line.split_once("://")?;
+    let (authority, path) = match rest.split_once('/') {
+        Some((a, p)) => (a, Some(p.to_string())),
+        None => (rest, None),
+    };
+    let (userinfo, host) = match authority.rsplit_once('@') {
+        Some((u, h)) => (Some(u), h),
+        None => (None, authority),
+    };
+    let (username, password) = match userinfo {
+        Some(u) => match u.split_once(':') {
+            Some((user, pass)) => (Some(urldecode(user)), Some(urldecode(pass))),
+            None => (Some(urldecode(u)), None),
+        },
+        None => (None, None),
+    };
+
+    Some(CredentialRequest {
+        protocol: Some(protocol.to_string()),
+        host: Some(host.to_string()),
+        path,
+        username,
+        password,
+        ..Default::default()
+    })
+}
+
+fn format_store_line(cred: &CredentialRequest) -> String {
+    let protocol = cred.protocol.as_deref().unwrap_or("https");
+    let host = cred.host.as_deref().unwrap_or("");
+
+    let mut url = format!("{}://", protocol);
+    if let Some(ref user) = cred.username {
+        url.push_str(&urlencode(user));
+        if let Some(ref pass) = cred.password {
+            url.push(':');
+            url.push_str(&urlencode(pass));
+        }
+        url.push('@');
+    }
+    url.push_str(host);
+    url
+}
+
+fn write_credential_store(path: &Path, lines: &[String]) -> Result<()> {
+    let mut content = lines.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+
+    // Create with 0600 from the start (matching C git's credential-store
+    // helper): opening with default permissions and chmod'ing afterward
+    // leaves a window — and, if the process dies in between, a permanent
+    // state — where a file full of plaintext credentials is world/group
+    // readable.
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(content.as_bytes())?;
+
+    // `mode()` only applies to a newly created file; if `path` already
+    // existed with looser permissions, fix them up explicitly.
+    use std::os::unix::fs::PermissionsExt;
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+
+    Ok(())
+}
+
+fn urlencode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn urldecode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Ok(byte) = u8::from_str_radix(&s[i + 1..i + 3], 16) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+// === Built-in `credential-cache` helper + background daemon ===
+//
+// Caches credentials in memory (never on disk) over a Unix socket, so
+// repeated operations against the same remote don't need to re-prompt or
+// re-run a slower helper. The daemon is spawned on first use and exits on
+// its own once it has been idle past `--timeout` with nothing left cached.
+
+const CACHE_PROTOCOL_GET: &str = "get";
+const CACHE_PROTOCOL_STORE: &str = "store";
+const CACHE_PROTOCOL_ERASE: &str = "erase";
+
+fn builtin_credential_cache(action: &str, cred: &CredentialRequest, extra_args: &[&str]) -> Result<CredentialRequest> {
+    let (socket_path, timeout) = cache_helper_args(extra_args)?;
+
+    if connect_cache_socket(&socket_path).is_err() {
+        if action == "get" {
+            // Nothing can be cached yet if the daemon has never run.
+            return Ok(CredentialRequest::default());
+        }
+        spawn_cache_daemon(&socket_path, timeout)?;
+    }
+
+    let mut stream = match connect_cache_socket(&socket_path) {
+        Ok(s) => s,
+        Err(_) => return Ok(CredentialRequest::default()),
+    };
+
+    let mut request = format!("{}\n", action);
+    {
+        let mut buf = Vec::new();
+        write_credential_to(&mut buf, cred)?;
+        request.push_str(&String::from_utf8_lossy(&buf));
+    }
+    request.push('\n');
+
+    stream.write_all(request.as_bytes())?;
+    stream.flush()?;
+    let _ = stream.shutdown(std::net::Shutdown::Write);
+
+    if action != "get" {
+        return Ok(CredentialRequest::default());
+    }
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    parse_credential_output(response.as_bytes())
+}
+
+fn cache_helper_args(extra_args: &[&str]) -> Result<(PathBuf, u64)> {
+    let mut socket = default_cache_socket_path()?;
+    let mut timeout = 900u64;
+
+    for arg in extra_args {
+        if let Some(s) = arg.strip_prefix("--socket=") {
+            socket = PathBuf::from(s);
+        } else if let Some(t) = arg.strip_prefix("--timeout=") {
+            timeout = t.parse().unwrap_or(timeout);
+        }
+    }
+
+    Ok((socket, timeout))
+}
+
+fn default_cache_socket_path() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").context("credential-cache: HOME is not set")?;
+    Ok(PathBuf::from(home).join(".git-credential-cache").join("socket"))
+}
+
+/// Like `fs::create_dir_all`, but the leaf directory (and any directories
+/// created along the way) get mode 0700 regardless of umask. The cache
+/// daemon's socket lives here, and anyone who can connect to it gets a
+/// cached plaintext credential back with no auth of its own — matching C
+/// git's credential-cache--daemon, which `mkdir`s this directory 0700 for
+/// the same reason.
+fn create_private_dir_all(dir: &Path) -> Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+
+    // `mode()` only applies to directories newly created by this call; if
+    // `dir` already existed with looser permissions, fix it up explicitly.
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    Ok(())
+}
+
+fn connect_cache_socket(socket_path: &Path) -> io::Result<UnixStream> {
+    UnixStream::connect(socket_path)
+}
+
+/// Spawn the background daemon (a hidden re-invocation of this same binary)
+/// and wait for its socket to come up.
+fn spawn_cache_daemon(socket_path: &Path, timeout: u64) -> Result<()> {
+    if let Some(parent) = socket_path.parent() {
+        create_private_dir_all(parent)?;
+    }
+
+    let exe = std::env::current_exe().context("credential-cache: could not find own executable")?;
+    std::process::Command::new(exe)
+        .arg("credential-cache-daemon")
+        .arg("--socket")
+        .arg(socket_path)
+        .arg("--timeout")
+        .arg(timeout.to_string())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .context("credential-cache: failed to start credential-cache-daemon")?;
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        if connect_cache_socket(socket_path).is_ok() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    Ok(())
+}
+
+struct CacheEntry {
+    cred: CredentialRequest,
+    expires_at: Instant,
+}
+
+/// Entry point for the hidden `credential-cache-daemon` subcommand: run the
+/// long-lived process that backs the `cache` helper over `args.socket`.
+pub fn run_cache_daemon(args: &CredentialCacheDaemonArgs) -> Result<i32> {
+    let _ = fs::remove_file(&args.socket);
+    if let Some(parent) = args.socket.parent() {
+        create_private_dir_all(parent)?;
+    }
+
+    let listener = UnixListener::bind(&args.socket)?;
+    // The `get` handler does no authentication of its own: anyone who can
+    // connect to the socket gets a cached plaintext password back. Matching
+    // C git's credential-cache--daemon, the containing directory is already
+    // created 0700 above; also tighten the socket file itself to 0600 so a
+    // looser umask on the bind() call can't leave it group/world-accessible.
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(&args.socket, fs::Permissions::from_mode(0o600))?;
+    let timeout = Duration::from_secs(args.timeout);
+
+    let mut cache: HashMap<String, CacheEntry> = HashMap::new();
+    let mut idle_since: Option<Instant> = None;
+
+    // Poll with a short accept timeout so we can periodically purge expired
+    // entries and exit once we've had nothing cached for a full timeout.
+    listener.set_nonblocking(true)?;
+
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                idle_since = None;
+                if let Err(e) = handle_cache_connection(stream, &mut cache, timeout) {
+                    eprintln!("credential-cache-daemon: {}", e);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let now = Instant::now();
+        cache.retain(|_, entry| entry.expires_at > now);
+
+        if cache.is_empty() {
+            let idle_start = *idle_since.get_or_insert(now);
+            if now.duration_since(idle_start) > timeout {
+                break;
+            }
+        } else {
+            idle_since = None;
+        }
+    }
+
+    let _ = fs::remove_file(&args.socket);
+    Ok(0)
+}
+
+fn handle_cache_connection(
+    mut stream: UnixStream,
+    cache: &mut HashMap<String, CacheEntry>,
+    timeout: Duration,
+) -> Result<()> {
+    let mut request = String::new();
+    stream.read_to_string(&mut request)?;
+
+    let mut lines = request.lines();
+    let action = lines.next().unwrap_or("").trim();
+
+    let mut cred = CredentialRequest::default();
+    for line in lines {
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            apply_credential_field(&mut cred, key, value);
+        }
+    }
+
+    let key = cache_key(&cred);
+
+    match action {
+        CACHE_PROTOCOL_GET => {
+            if let Some(entry) = cache.get(&key) {
+                if entry.expires_at > Instant::now() {
+                    write_credential_to(&mut stream, &entry.cred)?;
+                }
+            }
+        }
+        CACHE_PROTOCOL_STORE => {
+            if cred.username.is_some() && cred.password.is_some() {
+                cache.insert(
+                    key,
+                    CacheEntry {
+                        cred,
+                        expires_at: Instant::now() + timeout,
+                    },
+                );
+            }
+        }
+        CACHE_PROTOCOL_ERASE => {
+            cache.remove(&key);
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn cache_key(cred: &CredentialRequest) -> String {
+    format!(
+        "{}:{}:{}:{}",
+        cred.protocol.as_deref().unwrap_or(""),
+        cred.host.as_deref().unwrap_or(""),
+        cred.path.as_deref().unwrap_or(""),
+        cred.username.as_deref().unwrap_or(""),
+    )
 }
@@ -1,6 +1,8 @@
 use std::io::{self, Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use anyhow::{bail, Result};
 use clap::Args;
@@ -113,15 +115,30 @@ pub fn run(args: &DaemonArgs, _cli: &Cli) -> Result<i32> {
         writeln!(err, "Listening on {}", addr)?;
     }
 
+    let active_connections = Arc::new(AtomicUsize::new(0));
+    let max_connections = args.max_connections as usize;
+
     for stream in listener.incoming() {
         match stream {
-            Ok(stream) => {
+            Ok(mut stream) => {
+                if max_connections > 0 && active_connections.load(Ordering::SeqCst) >= max_connections {
+                    if args.verbose {
+                        writeln!(err, "Too many connections, dropping one")?;
+                    }
+                    let _ = write_pktline_stream(&mut stream, b"ERR too many connections\n");
+                    continue;
+                }
+
                 let verbose = args.verbose;
                 let export_all = args.export_all;
                 let base_path = args.base_path.clone();
                 let directories = args.directories.clone();
                 let strict_paths = args.strict_paths;
+                let enable = args.enable.clone();
+                let disable = args.disable.clone();
+                let active_connections = Arc::clone(&active_connections);
 
+                active_connections.fetch_add(1, Ordering::SeqCst);
                 std::thread::spawn(move || {
                     if let Err(e) = handle_client(
                         stream,
@@ -130,9 +147,12 @@ pub fn run(args: &DaemonArgs, _cli: &Cli) -> Result<i32> {
                         base_path.as_deref(),
                         &directories,
                         strict_paths,
+                        &enable,
+                        &disable,
                     ) {
                         eprintln!("client error: {}", e);
                     }
+                    active_connections.fetch_sub(1, Ordering::SeqCst);
                 });
             }
             Err(e) => {
@@ -146,6 +166,20 @@ pub fn run(args: &DaemonArgs, _cli: &Cli) -> Result<i32> {
     Ok(0)
 }
 
+/// Whether `service_name` (e.g. "upload-pack", "receive-pack") is allowed,
+/// applying `--enable`/`--disable` over the git-daemon default policy:
+/// `upload-pack` is served by default, everything else (`receive-pack`,
+/// `upload-archive`) must be explicitly enabled.
+fn service_enabled(service_name: &str, enable: &[String], disable: &[String]) -> bool {
+    if disable.iter().any(|s| s == service_name) {
+        return false;
+    }
+    if enable.iter().any(|s| s == service_name) {
+        return true;
+    }
+    service_name == "upload-pack"
+}
+
 fn run_inetd(args: &DaemonArgs) -> Result<i32> {
     let mut stdin = io::stdin();
     let mut stdout = io::stdout();
@@ -169,6 +203,12 @@ fn run_inetd(args: &DaemonArgs) -> Result<i32> {
         bail!("unknown service request: {}", cmd_and_path);
     };
 
+    let service_name = service.trim_start_matches("git-");
+    if !service_enabled(service_name, &args.enable, &args.disable) {
+        write_pktline(&mut stdout, b"ERR service not enabled\n")?;
+        return Ok(1);
+    }
+
     // Resolve path
     let repo_path = if let Some(ref base) = args.base_path {
         base.join(path.trim_start_matches('/'))
@@ -208,6 +248,8 @@ fn handle_client(
     base_path: Option<&Path>,
     directories: &[PathBuf],
     strict_paths: bool,
+    enable: &[String],
+    disable: &[String],
 ) -> Result<()> {
     if verbose {
         if let Ok(addr) = stream.peer_addr() {
@@ -231,6 +273,12 @@ fn handle_client(
         return Ok(());
     };
 
+    let service_name = service.trim_start_matches("git-");
+    if !service_enabled(service_name, enable, disable) {
+        write_pktline_stream(&mut stream, b"ERR service not enabled\n")?;
+        return Ok(());
+    }
+
     // Resolve path
     let repo_path = if let Some(base) = base_path {
         base.join(path.trim_start_matches('/'))
@@ -260,7 +308,7 @@ fn handle_client(
     }
 
     // Spawn the service process
-    let child = std::process::Command::new("git")
+    let mut child = std::process::Command::new("git")
         .arg(service)
         .arg(&repo_path)
         .stdin(std::process::Stdio::piped())
@@ -304,6 +352,9 @@ fn handle_client(
 
     let _ = reader.join();
 
+    // Reap the service process so it doesn't linger as a zombie.
+    let _ = child.wait();
+
     Ok(())
 }
 
@@ -110,6 +110,10 @@ pub struct DiffArgs {
     #[arg(long)]
     minimal: bool,
 
+    /// Disable sliding ambiguous hunk boundaries to the most readable position
+    #[arg(long = "no-indent-heuristic")]
+    no_indent_heuristic: bool,
+
     /// Compare two paths outside a git repo
     #[arg(long)]
     no_index: bool,
@@ -159,6 +163,7 @@ pub fn run(args: &DiffArgs, cli: &Cli) -> Result<i32> {
         rename_threshold: args.find_renames.unwrap_or(50),
         detect_copies: args.find_copies.is_some(),
         copy_threshold: args.find_copies.unwrap_or(50),
+        indent_heuristic: !args.no_indent_heuristic,
         ..DiffOptions::default()
     };
     if let Some(ctx) = args.context_lines {
@@ -206,7 +211,7 @@ pub fn run(args: &DiffArgs, cli: &Cli) -> Result<i32> {
         if is_cached {
             // Compare commit tree vs index
             let index_path = repo.git_dir().join("index");
-            let index = if index_path.exists() {
+            let mut index = if index_path.exists() {
                 git_index::Index::read_from(&index_path)?
             } else {
                 git_index::Index::new()
@@ -242,7 +247,13 @@ pub fn run(args: &DiffArgs, cli: &Cli) -> Result<i32> {
                                 let hunks = if binary {
                                     Vec::new()
                                 } else {
-                                    git_diff::algorithm::diff_lines(&old_data, &new_data, diff_opts.algorithm, diff_opts.context_lines)
+                                    git_diff::algorithm::diff_lines(
+                                        &old_data,
+                                        &new_data,
+                                        diff_opts.algorithm,
+                                        diff_opts.context_lines,
+                                        diff_opts.indent_heuristic,
+                                    )
                                 };
                                 let old_oid = git_hash::hasher::Hasher::hash_object(git_hash::HashAlgorithm::Sha1, "blob", &old_data).ok();
                                 let new_oid = git_hash::hasher::Hasher::hash_object(git_hash::HashAlgorithm::Sha1, "blob", &new_data).ok();
@@ -555,6 +566,7 @@ fn reverse_diff(result: &git_diff::DiffResult) -> git_diff::DiffResult {
                         new_count: h.old_count,
                         header: h.header.clone(),
                         lines,
+                        locks: h.locks.clone(),
                     }
                 })
                 .collect();
@@ -14,7 +14,7 @@ use crate::Cli;
 
 #[derive(Args)]
 pub struct DiffFilesArgs {
-    /// Generate patch output (default)
+    /// Generate patch output instead of raw diff
     #[arg(short = 'p', long = "patch")]
     patch: bool,
 
@@ -26,6 +26,10 @@ pub struct DiffFilesArgs {
     #[arg(long)]
     raw: bool,
 
+    /// Show diffstat instead of patch
+    #[arg(long)]
+    stat: bool,
+
     /// Show only names of changed files
     #[arg(long)]
     name_only: bool,
@@ -34,6 +38,11 @@ pub struct DiffFilesArgs {
     #[arg(long)]
     name_status: bool,
 
+    /// Exit with 1 if there are differences, 0 otherwise (plumbing default
+    /// is to always exit 0)
+    #[arg(long)]
+    exit_code: bool,
+
     /// Paths to limit diff to
     #[arg(value_name = "path")]
     pathspecs: Vec<String>,
@@ -56,7 +65,7 @@ pub fn run(args: &DiffFilesArgs, cli: &Cli) -> Result<i32> {
             .iter()
             .filter(|e| e.stage == Stage::Normal)
             .filter(|e| matches_pathspecs(&e.path, &args.pathspecs))
-            .map(|e| (e.path.clone(), e.oid, e.mode))
+            .map(|e| (e.path.to_bstring(), e.oid, e.mode))
             .collect()
     };
 
@@ -70,11 +79,11 @@ pub fn run(args: &DiffFilesArgs, cli: &Cli) -> Result<i32> {
             // File deleted from working tree
             let old_data = read_blob_data(odb, index_oid);
             let binary = old_data.as_ref().is_some_and(|d| git_diff::binary::is_binary(d));
-            let hunks = if binary || args.raw || args.name_only || args.name_status || args.quiet {
+            let hunks = if binary || !needs_hunks(args) || args.quiet {
                 Vec::new()
             } else {
                 let data = old_data.unwrap_or_default();
-                git_diff::algorithm::diff_lines(&data, &[], git_diff::DiffAlgorithm::Myers, 3)
+                git_diff::algorithm::diff_lines(&data, &[], git_diff::DiffAlgorithm::Myers, 3, true)
             };
             files.push(FileDiff {
                 status: FileStatus::Deleted,
@@ -110,7 +119,7 @@ pub fn run(args: &DiffFilesArgs, cli: &Cli) -> Result<i32> {
         let binary = git_diff::binary::is_binary(&old_data)
             || git_diff::binary::is_binary(&worktree_content);
 
-        let hunks = if binary || args.raw || args.name_only || args.name_status || args.quiet {
+        let hunks = if binary || !needs_hunks(args) || args.quiet {
             Vec::new()
         } else {
             git_diff::algorithm::diff_lines(
@@ -118,6 +127,7 @@ pub fn run(args: &DiffFilesArgs, cli: &Cli) -> Result<i32> {
                 &worktree_content,
                 git_diff::DiffAlgorithm::Myers,
                 3,
+                true,
             )
         };
 
@@ -159,6 +169,7 @@ pub fn run(args: &DiffFilesArgs, cli: &Cli) -> Result<i32> {
         let output_format = determine_output_format(args);
         let diff_opts = DiffOptions {
             output_format,
+            full_index: true,
             ..DiffOptions::default()
         };
 
@@ -166,22 +177,37 @@ pub fn run(args: &DiffFilesArgs, cli: &Cli) -> Result<i32> {
         write!(out, "{}", output)?;
     }
 
-    Ok(if has_changes { 1 } else { 0 })
+    if args.exit_code {
+        Ok(if has_changes { 1 } else { 0 })
+    } else {
+        Ok(0)
+    }
 }
 
 fn determine_output_format(args: &DiffFilesArgs) -> DiffOutputFormat {
-    if args.raw {
+    if args.patch {
+        DiffOutputFormat::Unified
+    } else if args.stat {
+        DiffOutputFormat::Stat
+    } else if args.raw {
         DiffOutputFormat::Raw
     } else if args.name_only {
         DiffOutputFormat::NameOnly
     } else if args.name_status {
         DiffOutputFormat::NameStatus
     } else {
-        DiffOutputFormat::Unified
+        // Plumbing default is raw format, matching C git's diff-files.
+        DiffOutputFormat::Raw
     }
 }
 
-fn matches_pathspecs(path: &bstr::BString, pathspecs: &[String]) -> bool {
+/// Whether hunks (and thus insertion/deletion counts) need to be computed
+/// for the selected output format.
+fn needs_hunks(args: &DiffFilesArgs) -> bool {
+    args.patch || args.stat
+}
+
+fn matches_pathspecs(path: &bstr::BStr, pathspecs: &[String]) -> bool {
     if pathspecs.is_empty() {
         return true;
     }
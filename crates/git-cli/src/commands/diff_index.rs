@@ -26,6 +26,10 @@ pub struct DiffIndexArgs {
     #[arg(long)]
     raw: bool,
 
+    /// Show diffstat instead of patch
+    #[arg(long)]
+    stat: bool,
+
     /// Show only names of changed files
     #[arg(long)]
     name_only: bool,
@@ -34,6 +38,11 @@ pub struct DiffIndexArgs {
     #[arg(long)]
     name_status: bool,
 
+    /// Exit with 1 if there are differences, 0 otherwise (plumbing default
+    /// is to always exit 0)
+    #[arg(long)]
+    exit_code: bool,
+
     /// Tree-ish to compare against
     #[arg(value_name = "tree-ish")]
     tree_ish: String,
@@ -63,7 +72,7 @@ pub fn run(args: &DiffIndexArgs, cli: &Cli) -> Result<i32> {
                 .iter()
                 .filter(|e| e.stage == Stage::Normal)
                 .filter(|e| matches_pathspecs(&e.path, &args.pathspecs))
-                .map(|e| (e.path.clone(), e.oid, e.mode))
+                .map(|e| (e.path.to_bstring(), e.oid, e.mode))
                 .collect()
         };
 
@@ -74,6 +83,7 @@ pub fn run(args: &DiffIndexArgs, cli: &Cli) -> Result<i32> {
         if has_changes {
             let diff_opts = DiffOptions {
                 output_format: determine_output_format(args),
+                full_index: true,
                 ..DiffOptions::default()
             };
 
@@ -88,7 +98,11 @@ pub fn run(args: &DiffIndexArgs, cli: &Cli) -> Result<i32> {
             write!(out, "{}", output)?;
         }
 
-        Ok(if has_changes { 1 } else { 0 })
+        if args.exit_code {
+            Ok(if has_changes { 1 } else { 0 })
+        } else {
+            Ok(0)
+        }
     } else {
         // Compare tree against working tree
         let work_tree = repo
@@ -102,7 +116,7 @@ pub fn run(args: &DiffIndexArgs, cli: &Cli) -> Result<i32> {
             index
                 .iter()
                 .filter(|e| e.stage == Stage::Normal)
-                .map(|e| (e.path.clone(), e.oid, e.mode))
+                .map(|e| (e.path.to_bstring(), e.oid, e.mode))
                 .collect()
         };
 
@@ -177,6 +191,7 @@ pub fn run(args: &DiffIndexArgs, cli: &Cli) -> Result<i32> {
                                 &worktree_content,
                                 git_diff::DiffAlgorithm::Myers,
                                 3,
+                                true,
                             )
                         };
 
@@ -210,6 +225,7 @@ pub fn run(args: &DiffIndexArgs, cli: &Cli) -> Result<i32> {
                                 &worktree_content,
                                 git_diff::DiffAlgorithm::Myers,
                                 3,
+                                true,
                             )
                         };
 
@@ -236,13 +252,18 @@ pub fn run(args: &DiffIndexArgs, cli: &Cli) -> Result<i32> {
         if has_changes {
             let diff_opts = DiffOptions {
                 output_format: determine_output_format(args),
+                full_index: true,
                 ..DiffOptions::default()
             };
             let output = format_diff(&result, &diff_opts);
             write!(out, "{}", output)?;
         }
 
-        Ok(if has_changes { 1 } else { 0 })
+        if args.exit_code {
+            Ok(if has_changes { 1 } else { 0 })
+        } else {
+            Ok(0)
+        }
     }
 }
 
@@ -362,6 +383,7 @@ fn recompute_with_hunks(
                     &new_data,
                     opts.algorithm,
                     opts.context_lines,
+                    opts.indent_heuristic,
                 )
             };
             FileDiff {
@@ -382,18 +404,26 @@ fn recompute_with_hunks(
 }
 
 fn needs_content(opts: &DiffOptions) -> bool {
-    matches!(opts.output_format, DiffOutputFormat::Unified)
+    matches!(
+        opts.output_format,
+        DiffOutputFormat::Unified | DiffOutputFormat::Stat
+    )
 }
 
 fn determine_output_format(args: &DiffIndexArgs) -> DiffOutputFormat {
-    if args.raw {
+    if args.patch {
+        DiffOutputFormat::Unified
+    } else if args.stat {
+        DiffOutputFormat::Stat
+    } else if args.raw {
         DiffOutputFormat::Raw
     } else if args.name_only {
         DiffOutputFormat::NameOnly
     } else if args.name_status {
         DiffOutputFormat::NameStatus
     } else {
-        DiffOutputFormat::Unified
+        // Plumbing default is raw format, matching C git's diff-index.
+        DiffOutputFormat::Raw
     }
 }
 
@@ -456,7 +486,7 @@ fn read_tree_recursive_inner(
     Ok(())
 }
 
-fn matches_pathspecs(path: &BString, pathspecs: &[String]) -> bool {
+fn matches_pathspecs(path: &bstr::BStr, pathspecs: &[String]) -> bool {
     if pathspecs.is_empty() {
         return true;
     }
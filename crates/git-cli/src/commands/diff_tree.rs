@@ -1,7 +1,7 @@
 use std::io::{self, Write};
 
 use anyhow::Result;
-use bstr::BString;
+use bstr::{BString, ByteSlice};
 use clap::Args;
 use git_diff::format::format_diff;
 use git_diff::{DiffOptions, DiffOutputFormat, DiffResult, FileDiff, FileStatus};
@@ -25,6 +25,10 @@ pub struct DiffTreeArgs {
     #[arg(long)]
     raw: bool,
 
+    /// Show diffstat instead of patch
+    #[arg(long)]
+    stat: bool,
+
     /// Show only names of changed files
     #[arg(long)]
     name_only: bool,
@@ -37,6 +41,22 @@ pub struct DiffTreeArgs {
     #[arg(long)]
     root: bool,
 
+    /// Do not print the commit id header before the diff
+    #[arg(long)]
+    no_commit_id: bool,
+
+    /// Exit with 1 if there are differences, 0 otherwise (plumbing default
+    /// is to always exit 0)
+    #[arg(long)]
+    exit_code: bool,
+
+    /// For each hunk, print the commits (reachable from the first tree-ish)
+    /// whose blamed lines the hunk's old-side range overlaps — the commits
+    /// this hunk "locks" onto and would need to be reordered or split along
+    /// with before rewriting history.
+    #[arg(long)]
+    annotate_locks: bool,
+
     /// First tree-ish to compare
     #[arg(value_name = "tree-ish")]
     tree_ish_a: String,
@@ -53,6 +73,10 @@ pub fn run(args: &DiffTreeArgs, cli: &Cli) -> Result<i32> {
 
     let odb = repo.odb();
 
+    // The header line identifies whatever the first tree-ish resolved to,
+    // whether that's a commit or a bare tree.
+    let header_oid = git_revwalk::resolve_revision(&repo, &args.tree_ish_a)?;
+
     // Resolve tree OIDs
     let (tree_a, tree_b) = if let Some(ref b) = args.tree_ish_b {
         let oid_a = resolve_to_tree(&repo, &args.tree_ish_a)?;
@@ -94,10 +118,11 @@ pub fn run(args: &DiffTreeArgs, cli: &Cli) -> Result<i32> {
 
     let diff_opts = DiffOptions {
         output_format: determine_output_format(args),
+        full_index: true,
         ..DiffOptions::default()
     };
 
-    let result = if args.recursive || args.patch {
+    let mut result = if args.recursive || args.patch || args.stat || args.annotate_locks {
         // Full recursive diff using the existing tree diff engine
         git_diff::tree::diff_trees(odb, tree_a.as_ref(), tree_b.as_ref(), &diff_opts)?
     } else {
@@ -105,14 +130,48 @@ pub fn run(args: &DiffTreeArgs, cli: &Cli) -> Result<i32> {
         diff_trees_toplevel(odb, tree_a.as_ref(), tree_b.as_ref())?
     };
 
+    if args.annotate_locks {
+        // Blame from the first tree-ish's resolved commit: the old side of
+        // every hunk is exactly the content at that revision, so its blame
+        // history is what a hunk could "lock" a later rewrite onto.
+        super::blame::annotate_hunk_locks(&repo, &header_oid, &mut result)?;
+    }
+
     let has_changes = !result.is_empty();
 
+    // A single tree-ish argument diffs a commit against its parent, so by
+    // default we prefix the commit id, as C git does; two explicit
+    // tree-ish arguments never get a header.
+    if args.tree_ish_b.is_none() && !args.no_commit_id {
+        writeln!(out, "{}", header_oid)?;
+    }
+
     if has_changes {
         let output = format_diff(&result, &diff_opts);
         write!(out, "{}", output)?;
     }
 
-    Ok(if has_changes { 1 } else { 0 })
+    if args.annotate_locks {
+        for file in &result.files {
+            let path = file.path().to_str_lossy();
+            for hunk in &file.hunks {
+                if hunk.locks.is_empty() {
+                    continue;
+                }
+                write!(out, "lock {} {},{}", path, hunk.old_start, hunk.old_count)?;
+                for oid in &hunk.locks {
+                    write!(out, " {}", oid.to_hex())?;
+                }
+                writeln!(out)?;
+            }
+        }
+    }
+
+    if args.exit_code {
+        Ok(if has_changes { 1 } else { 0 })
+    } else {
+        Ok(0)
+    }
 }
 
 /// Compare only top-level entries of two trees (no recursion into subtrees).
@@ -273,12 +332,16 @@ fn resolve_to_tree(repo: &git_repository::Repository, rev: &str) -> Result<Objec
 fn determine_output_format(args: &DiffTreeArgs) -> DiffOutputFormat {
     if args.patch {
         DiffOutputFormat::Unified
+    } else if args.stat {
+        DiffOutputFormat::Stat
+    } else if args.raw {
+        DiffOutputFormat::Raw
     } else if args.name_only {
         DiffOutputFormat::NameOnly
     } else if args.name_status {
         DiffOutputFormat::NameStatus
     } else {
-        // Default for diff-tree is raw format
+        // Plumbing default is raw format, matching C git's diff-tree.
         DiffOutputFormat::Raw
     }
 }
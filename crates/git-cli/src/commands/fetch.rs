@@ -21,6 +21,10 @@ pub struct FetchArgs {
     #[arg(short, long)]
     pub prune: bool,
 
+    /// Never prune, even if remote.<name>.prune or fetch.prune is set
+    #[arg(long = "no-prune")]
+    pub no_prune: bool,
+
     /// Limit fetching to specified depth
     #[arg(long)]
     pub depth: Option<u32>,
@@ -33,6 +37,10 @@ pub struct FetchArgs {
     #[arg(short, long)]
     pub quiet: bool,
 
+    /// Allow non-fast-forward updates to remote-tracking refs
+    #[arg(short, long)]
+    pub force: bool,
+
     /// Remote name
     pub remote: Option<String>,
 
@@ -41,9 +49,18 @@ pub struct FetchArgs {
 }
 
 pub fn run(args: &FetchArgs, cli: &Cli) -> Result<i32> {
-    let repo = open_repo(cli)?;
     let stderr = io::stderr();
     let mut err = stderr.lock();
+    run_into(args, cli, &mut err)
+}
+
+/// Does the actual work of `run`, writing progress/status lines to `err`
+/// instead of locking `io::stderr()` itself. Lets a caller that's fetching
+/// several remotes concurrently (`remote update`'s `job_limit > 1` path)
+/// buffer each remote's output and flush it as one block, instead of having
+/// every remote's lines interleave on the shared stderr as they're produced.
+pub(crate) fn run_into(args: &FetchArgs, cli: &Cli, err: &mut impl Write) -> Result<i32> {
+    let repo = open_repo(cli)?;
 
     let remote_name = args.remote.as_deref().unwrap_or("origin");
 
@@ -105,7 +122,7 @@ pub fn run(args: &FetchArgs, cli: &Cli) -> Result<i32> {
     let pack_dir = repo.common_dir().join("objects").join("pack");
     std::fs::create_dir_all(&pack_dir)?;
 
-    let _result = git_protocol::fetch::fetch(
+    let result = git_protocol::fetch::fetch_with_local_check(
         transport.as_mut(),
         &advertised_refs,
         &capabilities,
@@ -113,19 +130,54 @@ pub fn run(args: &FetchArgs, cli: &Cli) -> Result<i32> {
         &wanted_refs,
         &fetch_opts,
         Some(&pack_dir),
+        |oid| repo.odb().contains(oid),
     )?;
 
+    if !args.quiet {
+        print_transfer_stats(&result.transfer, &mut err)?;
+    }
+
     // Update remote-tracking refs
-    let mapped = git_protocol::remote::map_refs(&advertised_refs, &refspecs);
-    for (oid, _source, dest) in &mapped {
+    let mapped = git_protocol::remote::map_refs_with_force(&advertised_refs, &refspecs);
+    for (oid, _source, dest, refspec_force) in &mapped {
         if !dest.is_empty() {
             let ref_name = RefName::new(BString::from(dest.as_str()))?;
-            let is_new = repo.refs().resolve(&ref_name)?.is_none();
+            let previous_oid = repo.refs().resolve_to_oid(&ref_name)?;
+            let short_dest = dest.strip_prefix("refs/remotes/").unwrap_or(dest);
+
+            let is_new = previous_oid.is_none();
+            let is_forced = match previous_oid {
+                Some(prev) if prev != *oid => {
+                    !git_revwalk::is_ancestor(&repo, &prev, oid).unwrap_or(false)
+                }
+                _ => false,
+            };
+
+            if is_forced && !*refspec_force && !args.force {
+                if !args.quiet {
+                    writeln!(
+                        err,
+                        " ! [rejected]        {} -> {}  (non-fast-forward)",
+                        _source, short_dest
+                    )?;
+                }
+                continue;
+            }
+
             repo.refs().write_ref(&ref_name, oid)?;
             if !args.quiet {
-                let short_dest = dest.strip_prefix("refs/remotes/").unwrap_or(dest);
                 if is_new {
                     writeln!(err, " * [new branch]      {} -> {}", _source, short_dest)?;
+                } else if is_forced {
+                    let prev = previous_oid.unwrap();
+                    writeln!(
+                        err,
+                        " + {}...{} {} -> {}  (forced update)",
+                        &prev.to_hex()[..7],
+                        &oid.to_hex()[..7],
+                        _source,
+                        short_dest
+                    )?;
                 }
             }
         }
@@ -148,8 +200,16 @@ pub fn run(args: &FetchArgs, cli: &Cli) -> Result<i32> {
         }
     }
 
-    // Prune refs that no longer exist on remote
-    if args.prune {
+    // Prune refs that no longer exist on remote. An explicit --prune/--no-prune
+    // always wins; otherwise fall back to remote.<name>.prune, then fetch.prune.
+    let prune_explicit = if args.no_prune {
+        Some(false)
+    } else if args.prune {
+        Some(true)
+    } else {
+        None
+    };
+    if resolve_prune(repo.config(), remote_name, prune_explicit)? {
         let remote_ref_names: std::collections::HashSet<String> = advertised_refs
             .iter()
             .filter_map(|(_, name)| {
@@ -175,7 +235,68 @@ pub fn run(args: &FetchArgs, cli: &Cli) -> Result<i32> {
                 }
             }
         }
+
+        // Prune stale tags the same way, when tags were requested for this fetch.
+        if args.tags {
+            let advertised_tags: std::collections::HashSet<String> = advertised_refs
+                .iter()
+                .filter_map(|(_, name)| {
+                    let n = name.to_str_lossy().to_string();
+                    n.starts_with("refs/tags/").then_some(n)
+                })
+                .collect();
+
+            if let Ok(iter) = repo.refs().iter(Some("refs/tags/")) {
+                for r in iter {
+                    if let Ok(r) = r {
+                        let name = r.name().as_str().to_string();
+                        if !advertised_tags.contains(&name) {
+                            let ref_name = RefName::new(BString::from(name.as_str()))?;
+                            repo.refs().delete_ref(&ref_name)?;
+                            if !args.quiet {
+                                let short = name.strip_prefix("refs/tags/").unwrap_or(&name);
+                                writeln!(err, " - [deleted]         (tag) {}", short)?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
     }
 
     Ok(0)
 }
+
+/// Resolve whether a fetch against `remote_name` should prune.
+///
+/// An explicit `--prune`/`--no-prune` on the command line always wins. Absent
+/// that, git defaults pruning on through `remote.<name>.prune`, falling back
+/// to the repository-wide `fetch.prune`, and otherwise leaves it off.
+pub(crate) fn resolve_prune(
+    config: &git_config::ConfigSet,
+    remote_name: &str,
+    explicit: Option<bool>,
+) -> Result<bool> {
+    if let Some(explicit) = explicit {
+        return Ok(explicit);
+    }
+    if let Some(v) = config.get_bool(&format!("remote.{}.prune", remote_name))? {
+        return Ok(v);
+    }
+    Ok(config.get_bool_or("fetch.prune", false)?)
+}
+
+/// Print a summary line of what the pack negotiation actually transferred,
+/// in the style of canonical git's "Receiving objects" progress line.
+/// Suppressed when nothing new was fetched.
+fn print_transfer_stats(stats: &git_protocol::fetch::TransferStats, out: &mut impl Write) -> Result<()> {
+    if stats.received_objects == 0 {
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "Receiving {}/{} objects, {} bytes (reused {} local objects)",
+        stats.received_objects, stats.total_objects, stats.bytes, stats.local_objects
+    )?;
+    Ok(())
+}
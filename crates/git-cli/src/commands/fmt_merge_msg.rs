@@ -2,7 +2,11 @@ use std::io::{self, BufRead, Write};
 
 use anyhow::Result;
 use clap::Args;
+use git_hash::ObjectId;
+use git_object::Object;
+use git_revwalk::RevWalk;
 
+use super::open_repo;
 use crate::Cli;
 
 #[derive(Args)]
@@ -24,7 +28,12 @@ pub struct FmtMergeMsgArgs {
     file: Option<String>,
 }
 
-pub fn run(args: &FmtMergeMsgArgs, _cli: &Cli) -> Result<i32> {
+/// One commit pulled in by a merged branch, as needed for the `--log` body.
+struct MergedCommit {
+    subject: String,
+}
+
+pub fn run(args: &FmtMergeMsgArgs, cli: &Cli) -> Result<i32> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
 
@@ -111,17 +120,21 @@ pub fn run(args: &FmtMergeMsgArgs, _cli: &Cli) -> Result<i32> {
     }
     msg.push('\n');
 
-    // With --log (and not --no-log), append one-line descriptions
-    let include_log = args.log.is_some() && !args.no_log;
-    if include_log {
-        let max_entries = args.log.unwrap_or(20) as usize;
-        if !descriptions.is_empty() {
-            msg.push('\n');
-            let count = descriptions.len().min(max_entries);
-            for (branch_name, oid) in descriptions.iter().take(count) {
-                let short_oid = if oid.len() >= 7 { &oid[..7] } else { oid };
-                msg.push_str(&format!("  {} {}\n", short_oid, branch_name));
-            }
+    // With --log (or merge.log/merge.summary) and not --no-log, append a
+    // shortlog-style body: a "By"/"Via" credit line followed by each
+    // branch's one-line commit descriptions.
+    let repo = if args.no_log { None } else { open_repo(cli).ok() };
+    let log_limit = if args.no_log {
+        None
+    } else if let Some(n) = args.log {
+        Some(n as usize)
+    } else {
+        repo.as_ref().and_then(merge_log_config_limit)
+    };
+
+    if let Some(limit) = log_limit.filter(|&n| n > 0) {
+        if let Some(ref repo) = repo {
+            append_log_body(&mut msg, repo, &descriptions, limit)?;
         }
     }
 
@@ -130,6 +143,160 @@ pub fn run(args: &FmtMergeMsgArgs, _cli: &Cli) -> Result<i32> {
     Ok(0)
 }
 
+/// Read `merge.log`/`merge.summary` to determine the implicit `--log` limit.
+/// `merge.log` may be a boolean (`true` means the default of 20) or a number.
+fn merge_log_config_limit(repo: &git_repository::Repository) -> Option<usize> {
+    let cfg = repo.config();
+    if let Ok(Some(n)) = cfg.get_usize("merge.log") {
+        return Some(n);
+    }
+    if let Ok(Some(true)) = cfg.get_bool("merge.log") {
+        return Some(20);
+    }
+    if let Ok(Some(true)) = cfg.get_bool("merge.summary") {
+        return Some(20);
+    }
+    None
+}
+
+/// Append the `--log` shortlog body (credit line(s) plus per-branch commit
+/// listings) to `msg`.
+fn append_log_body(
+    msg: &mut String,
+    repo: &git_repository::Repository,
+    descriptions: &[(String, String)],
+    limit: usize,
+) -> Result<()> {
+    let head_oid = repo.head_oid()?;
+    let me = super::commit::get_signature(
+        "GIT_COMMITTER_NAME",
+        "GIT_COMMITTER_EMAIL",
+        "GIT_COMMITTER_DATE",
+        repo,
+    )
+    .ok();
+    let me = me.map(|s| {
+        (
+            String::from_utf8_lossy(&s.name).to_string(),
+            String::from_utf8_lossy(&s.email).to_string(),
+        )
+    });
+
+    // Walk each branch's new commits independently (for the per-branch
+    // body), while also tallying authors/committers across all of them
+    // combined (for the "By"/"Via" credit line).
+    let mut per_branch: Vec<(String, Vec<MergedCommit>)> = Vec::new();
+    // (name, email) -> (count, first-seen order)
+    let mut author_counts: Vec<((String, String), usize)> = Vec::new();
+    let mut latest_committer: Option<(i64, String, String)> = None;
+
+    for (branch_name, oid_str) in descriptions {
+        let Ok(oid) = ObjectId::from_hex(oid_str) else {
+            continue;
+        };
+
+        let mut walker = RevWalk::new(repo)?;
+        walker.push(oid)?;
+        if let Some(head) = head_oid {
+            walker.hide(head)?;
+        }
+
+        let mut commits = Vec::new();
+        for oid_result in walker {
+            let oid = oid_result?;
+            if let Some(Object::Commit(commit)) = repo.odb().read(&oid)? {
+                let author_name = String::from_utf8_lossy(&commit.author.name).to_string();
+                let author_email = String::from_utf8_lossy(&commit.author.email).to_string();
+                let committer_name = String::from_utf8_lossy(&commit.committer.name).to_string();
+                let committer_email = String::from_utf8_lossy(&commit.committer.email).to_string();
+                let committer_date = commit.committer.date.timestamp;
+                let subject = String::from_utf8_lossy(commit.summary()).to_string();
+
+                credit(&mut author_counts, (author_name, author_email));
+                let is_newest = match &latest_committer {
+                    Some((d, _, _)) => committer_date > *d,
+                    None => true,
+                };
+                if is_newest {
+                    latest_committer = Some((committer_date, committer_name, committer_email));
+                }
+
+                commits.push(MergedCommit { subject });
+            }
+        }
+        per_branch.push((branch_name.clone(), commits));
+    }
+
+    msg.push('\n');
+
+    if let Some(by_line) = credit_line(&author_counts, me.as_ref()) {
+        msg.push_str("# By ");
+        msg.push_str(&by_line);
+        msg.push('\n');
+    }
+    if let Some((_, name, email)) = &latest_committer {
+        if me.as_ref() != Some(&(name.clone(), email.clone())) {
+            msg.push_str(&format!("# Via {}\n", name));
+        }
+    }
+
+    for (i, (branch_name, commits)) in per_branch.iter().enumerate() {
+        if i > 0 {
+            msg.push('\n');
+        }
+        if commits.len() > limit {
+            msg.push_str(&format!("* {}: ({} commits)\n", branch_name, commits.len()));
+        } else {
+            msg.push_str(&format!("* {}:\n", branch_name));
+        }
+        for commit in commits.iter().take(limit) {
+            msg.push_str(&format!("  {}\n", commit.subject));
+        }
+        if commits.len() > limit {
+            msg.push_str("  ...\n");
+        }
+    }
+
+    Ok(())
+}
+
+/// Record one more commit credited to `person` (entries keep first-seen order).
+fn credit(counts: &mut Vec<((String, String), usize)>, person: (String, String)) {
+    if let Some(entry) = counts.iter_mut().find(|(p, _)| *p == person) {
+        entry.1 += 1;
+    } else {
+        counts.push((person, 1));
+    }
+}
+
+/// Render the "By ..." credit line body (without the leading "# By "),
+/// or `None` if it should be omitted entirely (no contributors besides
+/// the person running the command).
+fn credit_line(counts: &[((String, String), usize)], me: Option<&(String, String)>) -> Option<String> {
+    if counts.is_empty() {
+        return None;
+    }
+    if counts.len() == 1 {
+        let (person, _) = &counts[0];
+        if Some(person) == me {
+            return None;
+        }
+        return Some(person.0.clone());
+    }
+
+    let mut sorted = counts.to_vec();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1));
+
+    if sorted.len() == 2 {
+        Some(format!(
+            "{} ({}) and {} ({})",
+            sorted[0].0 .0, sorted[0].1, sorted[1].0 .0, sorted[1].1
+        ))
+    } else {
+        Some(format!("{} ({}) and others", sorted[0].0 .0, sorted[0].1))
+    }
+}
+
 /// Extract a branch or tag name from a FETCH_HEAD description.
 ///
 /// Patterns:
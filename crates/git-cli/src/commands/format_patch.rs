@@ -4,13 +4,13 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::Args;
+use git_diff::email::{format_patch_email, PatchNumber};
 use git_diff::format::format_diff;
 use git_diff::format::nameonly::format_summary;
 use git_diff::{DiffOptions, DiffOutputFormat};
 use git_hash::ObjectId;
 use git_object::{Commit, Object};
 use git_revwalk::RevWalk;
-use git_utils::date::DateFormat;
 
 use super::open_repo;
 use crate::Cli;
@@ -110,67 +110,8 @@ pub fn run(args: &FormatPatchArgs, cli: &Cli) -> Result<i32> {
         // Output all patches to stdout
         for (i, (oid, commit)) in commits.iter().rev().enumerate() {
             let patch_num = args.start_number + i;
-            let subject = String::from_utf8_lossy(commit.summary());
-
-            writeln!(out, "From {} Mon Sep 17 00:00:00 2001", oid.to_hex())?;
-            writeln!(out, "From: {} <{}>",
-                String::from_utf8_lossy(&commit.author.name),
-                String::from_utf8_lossy(&commit.author.email))?;
-            writeln!(out, "Date: {}", commit.author.date.format(&DateFormat::Rfc2822))?;
-
-            if args.numbered || total > 1 {
-                writeln!(out, "Subject: [{} {}/{}] {}", args.subject_prefix, patch_num, total, subject)?;
-            } else {
-                writeln!(out, "Subject: [{}] {}", args.subject_prefix, subject)?;
-            }
-
-            writeln!(out)?;
-
-            if let Some(body) = commit.body() {
-                let body_str = String::from_utf8_lossy(body);
-                write!(out, "{}", body_str)?;
-                writeln!(out)?;
-            }
-
-            writeln!(out, "---")?;
-
-            let parent_tree = if let Some(parent_oid) = commit.first_parent() {
-                match repo.odb().read(parent_oid)? {
-                    Some(Object::Commit(pc)) => Some(pc.tree),
-                    _ => None,
-                }
-            } else {
-                None
-            };
-
-            let mut diff_opts = DiffOptions {
-                output_format: DiffOutputFormat::Stat,
-                ..DiffOptions::default()
-            };
-
-            let stat_result = git_diff::tree::diff_trees(
-                repo.odb(), parent_tree.as_ref(), Some(&commit.tree), &diff_opts)?;
-            if !stat_result.is_empty() {
-                let stat_output = format_diff(&stat_result, &diff_opts);
-                write!(out, "{}", stat_output)?;
-                let summary = format_summary(&stat_result);
-                if !summary.is_empty() {
-                    write!(out, "{}", summary)?;
-                }
-            }
-
-            writeln!(out)?;
-
-            diff_opts.output_format = DiffOutputFormat::Unified;
-            let diff_result = git_diff::tree::diff_trees(
-                repo.odb(), parent_tree.as_ref(), Some(&commit.tree), &diff_opts)?;
-            if !diff_result.is_empty() {
-                let diff_output = format_diff(&diff_result, &diff_opts);
-                write!(out, "{}", diff_output)?;
-            }
-
-            writeln!(out, "-- ")?;
-            writeln!(out, "{}", git_version_string())?;
+            let email = render_patch_email(&repo, oid, commit, args, patch_num, total)?;
+            write!(out, "{}", email)?;
             writeln!(out)?;
         }
 
@@ -202,9 +143,9 @@ pub fn run(args: &FormatPatchArgs, cli: &Cli) -> Result<i32> {
     // Generate patches (in chronological order)
     for (i, (oid, commit)) in commits.iter().rev().enumerate() {
         let patch_num = args.start_number + i;
-        let subject = String::from_utf8_lossy(commit.summary());
+        let email = render_patch_email(&repo, oid, commit, args, patch_num, total)?;
 
-        // Build filename
+        let subject = String::from_utf8_lossy(commit.summary());
         let sanitized_subject: String = subject
             .chars()
             .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '-' })
@@ -216,79 +157,78 @@ pub fn run(args: &FormatPatchArgs, cli: &Cli) -> Result<i32> {
         ));
 
         let mut file = fs::File::create(&filename)?;
+        write!(file, "{}", email)?;
 
-        // Write email headers
-        writeln!(file, "From {} Mon Sep 17 00:00:00 2001", oid.to_hex())?;
-        writeln!(file, "From: {} <{}>",
-            String::from_utf8_lossy(&commit.author.name),
-            String::from_utf8_lossy(&commit.author.email))?;
-        writeln!(file, "Date: {}", commit.author.date.format(&DateFormat::Rfc2822))?;
-
-        // Subject line
-        if args.numbered || total > 1 {
-            writeln!(file, "Subject: [{} {}/{}] {}", args.subject_prefix, patch_num, total, subject)?;
-        } else {
-            writeln!(file, "Subject: [{}] {}", args.subject_prefix, subject)?;
-        }
-
-        if args.thread {
-            writeln!(file, "Message-Id: <{}.{}.git-gitr@localhost>", oid.to_hex(), patch_num)?;
-        }
+        writeln!(out, "{}", filename.display())?;
+    }
 
-        writeln!(file)?;
+    Ok(0)
+}
 
-        // Body
-        if let Some(body) = commit.body() {
-            let body_str = String::from_utf8_lossy(body);
-            write!(file, "{}", body_str)?;
-            writeln!(file)?;
+/// Render one commit as a complete mbox message (envelope, subject, body,
+/// diffstat, and unified diff) via [`git_diff::email::format_patch_email`].
+fn render_patch_email(
+    repo: &git_repository::Repository,
+    oid: &ObjectId,
+    commit: &Commit,
+    args: &FormatPatchArgs,
+    patch_num: usize,
+    total: usize,
+) -> Result<String> {
+    let parent_tree = if let Some(parent_oid) = commit.first_parent() {
+        match repo.odb().read(parent_oid)? {
+            Some(Object::Commit(pc)) => Some(pc.tree),
+            _ => None,
         }
+    } else {
+        None
+    };
 
-        writeln!(file, "---")?;
-
-        // Diff
-        let parent_tree = if let Some(parent_oid) = commit.first_parent() {
-            match repo.odb().read(parent_oid)? {
-                Some(Object::Commit(pc)) => Some(pc.tree),
-                _ => None,
-            }
-        } else {
-            None
-        };
-
-        let mut diff_opts = DiffOptions {
-            output_format: DiffOutputFormat::Stat,
-            ..DiffOptions::default()
-        };
-
-        let stat_result = git_diff::tree::diff_trees(
-            repo.odb(), parent_tree.as_ref(), Some(&commit.tree), &diff_opts)?;
-        if !stat_result.is_empty() {
-            let stat_output = format_diff(&stat_result, &diff_opts);
-            write!(file, "{}", stat_output)?;
-            let summary = format_summary(&stat_result);
-            if !summary.is_empty() {
-                write!(file, "{}", summary)?;
-            }
-        }
+    let mut diff_opts = DiffOptions {
+        output_format: DiffOutputFormat::Stat,
+        ..DiffOptions::default()
+    };
 
-        writeln!(file)?;
+    let mut diff_text = String::new();
 
-        diff_opts.output_format = DiffOutputFormat::Unified;
-        let diff_result = git_diff::tree::diff_trees(
-            repo.odb(), parent_tree.as_ref(), Some(&commit.tree), &diff_opts)?;
-        if !diff_result.is_empty() {
-            let diff_output = format_diff(&diff_result, &diff_opts);
-            write!(file, "{}", diff_output)?;
+    let stat_result =
+        git_diff::tree::diff_trees(repo.odb(), parent_tree.as_ref(), Some(&commit.tree), &diff_opts)?;
+    if !stat_result.is_empty() {
+        diff_text.push_str(&format_diff(&stat_result, &diff_opts));
+        let summary = format_summary(&stat_result);
+        if !summary.is_empty() {
+            diff_text.push_str(&summary);
         }
+    }
 
-        writeln!(file, "-- ")?;
-        writeln!(file, "{}", git_version_string())?;
+    diff_text.push('\n');
 
-        writeln!(out, "{}", filename.display())?;
+    diff_opts.output_format = DiffOutputFormat::Unified;
+    let diff_result =
+        git_diff::tree::diff_trees(repo.odb(), parent_tree.as_ref(), Some(&commit.tree), &diff_opts)?;
+    if !diff_result.is_empty() {
+        diff_text.push_str(&format_diff(&diff_result, &diff_opts));
     }
 
-    Ok(0)
+    let numbering = if !args.no_numbered && (args.numbered || total > 1) {
+        Some(PatchNumber { number: patch_num, total })
+    } else {
+        None
+    };
+
+    let message_id = args
+        .thread
+        .then(|| format!("{}.{}.git-gitr@localhost", oid.to_hex(), patch_num));
+
+    Ok(format_patch_email(
+        oid,
+        commit,
+        numbering,
+        &args.subject_prefix,
+        message_id.as_deref(),
+        &diff_text,
+        &git_version_string(),
+    ))
 }
 
 fn collect_commits(
@@ -10,8 +10,8 @@ use git_hash::ObjectId;
 use git_object::{Commit, Object};
 use git_ref::RefStore;
 use git_revwalk::{
-    format_builtin_with_decorations, format_commit_with_decorations, BuiltinFormat, FormatOptions,
-    GraphDrawer, RevWalk, SortOrder, WalkOptions,
+    format_builtin_with_decorations, resolve_format, BuiltinFormat, FormatOptions, GraphDrawer,
+    ParsedFormat, ResolvedFormat, RevWalk, SortOrder, WalkOptions,
 };
 use git_utils::color::{ColorConfig, ColorSlot};
 use git_utils::date::DateFormat;
@@ -148,6 +148,18 @@ pub struct LogArgs {
     #[arg(long)]
     use_mailmap: bool,
 
+    /// Show the note(s) for each commit, appended after the commit body
+    #[arg(long)]
+    show_notes: bool,
+
+    /// Show notes from the given ref instead of the default (refs/notes/commits)
+    #[arg(long = "notes", value_name = "ref")]
+    notes_ref: Option<String>,
+
+    /// Check GPG/SSH signatures on signed commits and show the status
+    #[arg(long)]
+    show_signature: bool,
+
     /// Track file renames
     #[arg(long)]
     follow: bool,
@@ -195,26 +207,19 @@ pub fn run(args: &LogArgs, cli: &Cli) -> Result<i32> {
         // If no symmetric range provided, they're silently ignored (matching git behavior)
     }
 
-    // Load mailmap if requested
+    // Load mailmap if requested, honoring mailmap.file/mailmap.blob
     let mailmap = if args.use_mailmap {
-        let work_tree = repo.work_tree().map(|p| p.to_path_buf());
-        if let Some(ref wt) = work_tree {
-            let mailmap_path = wt.join(".mailmap");
-            if mailmap_path.exists() {
-                git_utils::mailmap::Mailmap::from_file(&mailmap_path).ok()
-            } else {
-                None
-            }
-        } else {
-            None
-        }
+        super::load_mailmap(&repo)
     } else {
         None
     };
 
+    // Notes ref to consult for --show-notes (default: refs/notes/commits)
+    let notes_ref_name = args.notes_ref.as_deref().unwrap_or("refs/notes/commits");
+
     // Parse format
-    let (builtin, custom_format) = parse_format(args);
-    let mut format_options = FormatOptions::default();
+    let (builtin, custom_format, format_terminator) = parse_format(args, &repo);
+    let mut format_options = FormatOptions { color: effective_mode, ..FormatOptions::default() };
     // --oneline is shorthand for --format=oneline --abbrev-commit
     if args.oneline || args.abbrev_commit {
         format_options.abbrev_len = 7;
@@ -223,6 +228,8 @@ pub fn run(args: &LogArgs, cli: &Cli) -> Result<i32> {
     if let Some(ref date_str) = args.date {
         format_options.date_format = parse_date_format(date_str);
     }
+    // Parse the custom --format template once; it's reused for every commit.
+    let parsed_format = custom_format.as_deref().map(ParsedFormat::parse);
 
     // Build walk options
     let mut walk_opts = WalkOptions {
@@ -380,11 +387,14 @@ pub fn run(args: &LogArgs, cli: &Cli) -> Result<i32> {
             &revs,
             builtin,
             &custom_format,
+            parsed_format.as_ref(),
+            format_terminator,
             &format_options,
             decorations.as_ref(),
             color_enabled,
             &color_config,
             args,
+            notes_ref_name,
             &mut out,
         );
     }
@@ -513,18 +523,46 @@ pub fn run(args: &LogArgs, cli: &Cli) -> Result<i32> {
             }
         }
 
-        // Apply mailmap transformations if requested
-        let commit = if let Some(ref mm) = mailmap {
-            apply_mailmap(&commit, mm)
+        // Format the commit. Custom --format strings get the raw commit plus
+        // the mailmap itself, so %an/%ae (raw) and %aN/%aE (mailmap-resolved)
+        // can diverge; builtin presets have no raw/resolved distinction in
+        // their fixed layout, so mailmap is applied to the whole commit up
+        // front there, matching --use-mailmap's effect on the Author:/Commit:
+        // lines.
+        let formatted = if let Some(ref parsed) = parsed_format {
+            parsed.render(
+                &commit,
+                &oid,
+                &format_options,
+                mailmap.as_ref(),
+                decorations.as_ref(),
+            )
+        } else {
+            let display_commit = match &mailmap {
+                Some(mm) => apply_mailmap(&commit, mm),
+                None => commit.clone(),
+            };
+            format_builtin_with_decorations(&display_commit, &oid, builtin, &format_options, decorations.as_ref())
+        };
+
+        // --show-signature: verify the gpgsig and inject `gpg: ...` status
+        // lines after the commit header. Only meaningful for the
+        // multi-line builtin formats; --oneline and custom --format have
+        // no header line to anchor the status on.
+        let formatted = if args.show_signature
+            && custom_format.is_none()
+            && builtin != BuiltinFormat::Oneline
+        {
+            insert_signature_status(&formatted, &repo, &commit)
         } else {
-            commit
+            formatted
         };
 
-        // Format the commit
-        let formatted = if let Some(ref fmt) = custom_format {
-            format_commit_with_decorations(&commit, &oid, fmt, &format_options, decorations.as_ref())
+        // --show-notes: append the commit's note, indented, after its body
+        let formatted = if args.show_notes {
+            append_notes_section(&formatted, &repo, notes_ref_name, &oid)
         } else {
-            format_builtin_with_decorations(&commit, &oid, builtin, &format_options, decorations.as_ref())
+            formatted
         };
 
         // Add prefix annotations for --left-right, --cherry-mark, --source
@@ -558,8 +596,12 @@ pub fn run(args: &LogArgs, cli: &Cli) -> Result<i32> {
             }
         };
 
-        // Add separator between commits for multi-line formats
-        let needs_separator = custom_format.is_none() && builtin != BuiltinFormat::Oneline;
+        // Add separator between commits for multi-line formats, and for
+        // user formats using `format:` separator semantics (no newline
+        // forced after the last commit, only between commits).
+        let is_separator_format = custom_format.is_some() && !format_terminator;
+        let needs_separator =
+            (custom_format.is_none() && builtin != BuiltinFormat::Oneline) || is_separator_format;
         if needs_separator && !first_commit {
             writeln!(out)?;
         }
@@ -591,7 +633,12 @@ pub fn run(args: &LogArgs, cli: &Cli) -> Result<i32> {
             }
         } else {
             write!(out, "{}", formatted)?;
-            if custom_format.is_some() || builtin == BuiltinFormat::Oneline {
+            // `tformat:`/implicit-`%` user formats and `--oneline` always
+            // force a trailing newline per commit; plain `format:` formats
+            // don't (the separator written above handles joining instead).
+            if ((custom_format.is_some() && format_terminator) || builtin == BuiltinFormat::Oneline)
+                && !formatted.ends_with('\n')
+            {
                 writeln!(out)?;
             }
         }
@@ -605,6 +652,35 @@ pub fn run(args: &LogArgs, cli: &Cli) -> Result<i32> {
     Ok(0)
 }
 
+/// Append a `Notes:` section (indented, matching git's layout) after a
+/// formatted commit's body, if a note exists for it on `notes_ref_name`.
+fn append_notes_section(
+    formatted: &str,
+    repo: &git_repository::Repository,
+    notes_ref_name: &str,
+    oid: &ObjectId,
+) -> String {
+    let Some(text) = super::notes::lookup_note_text(repo, notes_ref_name, oid)
+        .ok()
+        .flatten()
+    else {
+        return formatted.to_string();
+    };
+
+    let mut out = formatted.to_string();
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push('\n');
+    out.push_str("Notes:\n");
+    for line in text.lines() {
+        out.push_str("    ");
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
 /// Public wrapper for building a decoration map, used by show.rs.
 pub fn build_decoration_map_for_show(
     repo: &git_repository::Repository,
@@ -697,33 +773,22 @@ fn build_decoration_map(repo: &git_repository::Repository) -> Result<HashMap<Obj
     Ok(map)
 }
 
-fn parse_format(args: &LogArgs) -> (BuiltinFormat, Option<String>) {
+fn parse_format(args: &LogArgs, repo: &git_repository::Repository) -> (BuiltinFormat, Option<String>, bool) {
     if args.oneline {
-        return (BuiltinFormat::Oneline, None);
+        return (BuiltinFormat::Oneline, None, false);
     }
 
     let fmt_str = args.format.as_deref().or(args.pretty.as_deref());
 
     match fmt_str {
-        Some("oneline") => (BuiltinFormat::Oneline, None),
-        Some("short") => (BuiltinFormat::Short, None),
-        Some("medium") => (BuiltinFormat::Medium, None),
-        Some("full") => (BuiltinFormat::Full, None),
-        Some("fuller") => (BuiltinFormat::Fuller, None),
-        Some("email") => (BuiltinFormat::Email, None),
-        Some("raw") => (BuiltinFormat::Raw, None),
-        Some(custom) => {
-            // Custom format string (e.g., "format:%H %s")
-            let fmt = if let Some(stripped) = custom.strip_prefix("format:") {
-                stripped
-            } else if let Some(stripped) = custom.strip_prefix("tformat:") {
-                stripped
-            } else {
-                custom
-            };
-            (BuiltinFormat::Medium, Some(fmt.to_string()))
-        }
-        None => (BuiltinFormat::Medium, None),
+        None => (BuiltinFormat::Medium, None, false),
+        Some(arg) => match resolve_format(arg, repo.config()) {
+            Some(ResolvedFormat::Builtin(builtin)) => (builtin, None, false),
+            Some(ResolvedFormat::User { template, terminator }) => {
+                (BuiltinFormat::Medium, Some(template), terminator)
+            }
+            None => (BuiltinFormat::Medium, None, false),
+        },
     }
 }
 
@@ -749,12 +814,12 @@ fn parse_date_format(s: &str) -> DateFormat {
     }
 }
 
+/// Parse a `--since`/`--until` date, accepting bare epoch seconds as well as
+/// any approxidate form ("2 weeks ago", "yesterday", ISO/RFC dates, ...).
 fn parse_date(s: &str) -> Option<i64> {
-    // Try parsing as unix timestamp
-    if let Ok(ts) = s.parse::<i64>() {
-        return Some(ts);
-    }
-    None
+    git_utils::date::GitDate::parse_approxidate_now(s)
+        .ok()
+        .map(|d| d.timestamp)
 }
 
 fn show_commit_diff(
@@ -999,6 +1064,69 @@ fn apply_mailmap(commit: &Commit, mm: &git_utils::mailmap::Mailmap) -> Commit {
     commit
 }
 
+/// Insert `gpg: ...` status lines (as produced by `--show-signature`) right
+/// after the `commit <hash>` header line of a formatted commit block.
+/// No-op for commits with no `gpgsig` header.
+fn insert_signature_status(
+    formatted: &str,
+    repo: &git_repository::Repository,
+    commit: &Commit,
+) -> String {
+    let Some(status_lines) = gpg_status_lines(repo, commit) else {
+        return formatted.to_string();
+    };
+    match formatted.split_once('\n') {
+        Some((first_line, rest)) => {
+            let mut out = String::new();
+            out.push_str(first_line);
+            out.push('\n');
+            for line in &status_lines {
+                out.push_str(line);
+                out.push('\n');
+            }
+            out.push_str(rest);
+            out
+        }
+        None => formatted.to_string(),
+    }
+}
+
+/// Verify a signed commit's `gpgsig` against its reconstructed unsigned
+/// content, returning `gpg:`-prefixed status lines. Returns `None` for
+/// unsigned commits.
+fn gpg_status_lines(repo: &git_repository::Repository, commit: &Commit) -> Option<Vec<String>> {
+    let sig = commit.gpgsig.as_ref()?;
+    let mut unsigned = commit.clone();
+    unsigned.gpgsig = None;
+    let data = unsigned.serialize_content();
+
+    let signer = git_repository::gpg::GpgSigner::from_config(repo.config());
+    let mut lines = Vec::new();
+    match signer.verify(&data, sig) {
+        Ok(result) => {
+            let signer_name = result.signer.as_deref().unwrap_or("unknown");
+            match result.status {
+                git_repository::gpg::SignatureStatus::Good => {
+                    lines.push(format!("gpg: Good signature from \"{}\"", signer_name));
+                }
+                git_repository::gpg::SignatureStatus::Bad => {
+                    lines.push(format!("gpg: BAD signature from \"{}\"", signer_name));
+                }
+                git_repository::gpg::SignatureStatus::Unknown => {
+                    lines.push("gpg: Can't check signature: No public key".to_string());
+                }
+            }
+            if let Some(ref key_id) = result.key_id {
+                lines.push(format!("gpg:                using key {}", key_id));
+            }
+        }
+        Err(e) => {
+            lines.push(format!("gpg: error: {}", e));
+        }
+    }
+    Some(lines)
+}
+
 /// Walk reflog entries instead of the commit graph (-g/--walk-reflogs).
 #[allow(clippy::too_many_arguments)]
 fn walk_reflogs_mode(
@@ -1006,11 +1134,14 @@ fn walk_reflogs_mode(
     revs: &[String],
     builtin: BuiltinFormat,
     custom_format: &Option<String>,
+    parsed_format: Option<&ParsedFormat>,
+    format_terminator: bool,
     format_options: &FormatOptions,
     decorations: Option<&HashMap<ObjectId, Vec<String>>>,
     color_enabled: bool,
     color_config: &ColorConfig,
     args: &LogArgs,
+    notes_ref_name: &str,
     out: &mut impl Write,
 ) -> Result<i32> {
     use git_ref::RefName;
@@ -1042,14 +1173,8 @@ fn walk_reflogs_mode(
             _ => continue,
         };
 
-        let formatted = if let Some(ref fmt) = custom_format {
-            format_commit_with_decorations(
-                &commit,
-                &oid,
-                fmt,
-                format_options,
-                decorations,
-            )
+        let formatted = if let Some(parsed) = parsed_format {
+            parsed.render(&commit, &oid, format_options, None, decorations)
         } else {
             format_builtin_with_decorations(
                 &commit,
@@ -1060,7 +1185,15 @@ fn walk_reflogs_mode(
             )
         };
 
-        let needs_separator = custom_format.is_none() && builtin != BuiltinFormat::Oneline;
+        let formatted = if args.show_notes {
+            append_notes_section(&formatted, repo, notes_ref_name, &oid)
+        } else {
+            formatted
+        };
+
+        let is_separator_format = custom_format.is_some() && !format_terminator;
+        let needs_separator =
+            (custom_format.is_none() && builtin != BuiltinFormat::Oneline) || is_separator_format;
         if needs_separator && !first_commit {
             writeln!(out)?;
         }
@@ -1073,7 +1206,9 @@ fn walk_reflogs_mode(
         };
 
         write!(out, "{}", formatted)?;
-        if custom_format.is_some() || builtin == BuiltinFormat::Oneline {
+        if ((custom_format.is_some() && format_terminator) || builtin == BuiltinFormat::Oneline)
+            && !formatted.ends_with('\n')
+        {
             writeln!(out)?;
         }
 
@@ -2,6 +2,7 @@ use std::io::{self, Write};
 
 use anyhow::{bail, Result};
 use clap::{Args, Subcommand};
+use git_diff::DiffOptions;
 use git_hash::HashAlgorithm;
 use git_object::Object;
 use git_ref::RefStore;
@@ -222,7 +223,22 @@ fn write_commit_graph(cli: &Cli) -> Result<()> {
             let tree_oid = commit.tree;
             let parents = commit.parents;
             let commit_time = commit.committer.date.timestamp;
-            writer.add_commit(oid, tree_oid, parents, commit_time);
+
+            let parent_tree = match parents.first() {
+                Some(parent) => match repo.odb().read(parent)? {
+                    Some(Object::Commit(parent_commit)) => Some(parent_commit.tree),
+                    _ => None,
+                },
+                None => None,
+            };
+            let diff_opts = DiffOptions::default();
+            match git_diff::tree::diff_trees(repo.odb(), parent_tree.as_ref(), Some(&tree_oid), &diff_opts) {
+                Ok(diff) => {
+                    let changed_paths = diff.files.into_iter().map(|f| f.path().clone()).collect();
+                    writer.add_commit_with_changed_paths(oid, tree_oid, parents, commit_time, changed_paths);
+                }
+                Err(_) => writer.add_commit(oid, tree_oid, parents, commit_time),
+            }
             count += 1;
         }
     }
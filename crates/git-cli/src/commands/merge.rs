@@ -6,11 +6,11 @@ use bstr::{BString, ByteSlice, ByteVec};
 use clap::Args;
 use git_hash::ObjectId;
 use git_index::{EntryFlags, Index, IndexEntry, Stage, StatData};
-use git_merge::{ConflictStyle, MergeOptions, MergeStrategyType, ConflictEntry};
+use git_merge::{ConflictFavor, ConflictStyle, MergeOptions, MergeStrategyType, ConflictEntry};
 use git_object::{Commit, FileMode, Object};
 use git_ref::{RefName, RefStore};
 use git_ref::reflog::{ReflogEntry, append_reflog_entry};
-use git_revwalk::{merge_base_one, resolve_revision};
+use git_revwalk::{merge_base_many, merge_base_one, resolve_revision};
 use git_utils::date::{GitDate, Signature};
 
 use crate::Cli;
@@ -152,8 +152,11 @@ pub fn run(args: &MergeArgs, cli: &Cli) -> Result<i32> {
         return Ok(0);
     }
 
-    // Find merge base
-    let base_oid = merge_base_one(&repo, &head_oid, &theirs_oid)?;
+    // Find merge base(s). Criss-cross histories can have more than one
+    // lowest common ancestor; keep the full list so the real merge below
+    // can build a virtual base instead of picking one arbitrarily.
+    let candidate_bases = merge_base_many(&repo, &[head_oid, theirs_oid])?;
+    let base_oid = candidate_bases.first().copied();
 
     // Check if theirs is already an ancestor of HEAD (already merged)
     if let Some(ref base) = base_oid {
@@ -232,14 +235,37 @@ pub fn run(args: &MergeArgs, cli: &Cli) -> Result<i32> {
 
     // Run the merge strategy
     let base = base_oid.unwrap_or(ObjectId::NULL_SHA1);
-    let options = build_merge_options(args, &repo)?;
-    let merge_result = git_merge::strategy::dispatch_merge(
-        &mut repo,
-        &head_oid,
-        &theirs_oid,
-        &base,
-        &options,
-    )?;
+    let mut options = build_merge_options(args, &repo)?;
+    // Default conflict-marker labels to the short name of the branch/commit
+    // being merged and the merge base's short oid, rather than the generic
+    // placeholders `merge`/`base` used when no base exists yet.
+    options.their_label = Some(theirs_label.clone());
+    if base_oid.is_some() {
+        options.ancestor_label = Some(base.to_hex()[..7].to_string());
+    }
+    let merge_result = if candidate_bases.len() > 1
+        && matches!(
+            options.strategy,
+            MergeStrategyType::Ort | MergeStrategyType::Recursive
+        ) {
+        // More than one lowest common ancestor: fold them into a single
+        // virtual base tree instead of merging against just the first one.
+        git_merge::strategy::ort::OrtStrategy.merge_with_bases(
+            &mut repo,
+            &head_oid,
+            &theirs_oid,
+            &candidate_bases,
+            &options,
+        )?
+    } else {
+        git_merge::strategy::dispatch_merge(
+            &mut repo,
+            &head_oid,
+            &theirs_oid,
+            &base,
+            &options,
+        )?
+    };
 
     if merge_result.is_clean {
         let tree_oid = merge_result
@@ -320,8 +346,9 @@ pub fn run(args: &MergeArgs, cli: &Cli) -> Result<i32> {
     // Write conflict entries to the index
     write_conflict_index(&mut repo, &merge_result.conflicts)?;
 
-    // Write conflict markers to working tree files
-    write_conflict_files(&repo, &merge_result.conflicts, theirs_label)?;
+    // Write conflict markers to working tree files (auto-replaying any
+    // previously recorded rerere resolution that matches)
+    write_conflict_files(&repo, &merge_result.conflicts, theirs_label, &mut err)?;
 
     // Write MERGE_HEAD and MERGE_MSG for future --continue
     write_merge_head(&repo, &[theirs_oid])?;
@@ -407,13 +434,13 @@ fn run_octopus_merge(
         return Ok(0);
     }
 
-    // Compute merge bases for remaining heads
+    // Compute merge bases for remaining heads. Keep the full candidate set
+    // per head (not just the best one) so a criss-cross history lets the
+    // octopus strategy fold them into a virtual base instead of picking one
+    // arbitrarily.
     let mut bases = Vec::new();
     for theirs in &remaining_heads {
-        match merge_base_one(repo, &current_oid, theirs)? {
-            Some(b) => bases.push(b),
-            None => bases.push(ObjectId::NULL_SHA1),
-        }
+        bases.push(merge_base_many(repo, &[current_oid, *theirs])?);
     }
 
     let octopus = git_merge::strategy::octopus::OctopusStrategy;
@@ -491,6 +518,14 @@ fn build_merge_options(args: &MergeArgs, repo: &git_repository::Repository) -> R
     // Pass through -X / --strategy-option values.
     options.strategy_options = args.strategy_option.clone();
 
+    // `-X ours`/`-X theirs`/`-X union` also set the typed favor used for
+    // per-hunk conflict resolution.
+    for opt in &options.strategy_options {
+        if let Some(favor) = ConflictFavor::from_name(opt) {
+            options.favor = favor;
+        }
+    }
+
     // Read merge.conflictStyle from config.
     if let Some(style_name) = repo.config().get_string("merge.conflictStyle")?.as_deref() {
         if let Some(style) = ConflictStyle::from_name(style_name) {
@@ -575,7 +610,7 @@ fn handle_continue(
 
     // Build tree from current index
     let index_path = repo.git_dir().join("index");
-    let index = Index::read_from(&index_path)?;
+    let mut index = Index::read_from(&index_path)?;
     let tree_oid = index.write_tree(repo.odb())?;
 
     // Read merge message
@@ -829,7 +864,7 @@ fn checkout_tree_recursive(
 
             let metadata = std::fs::symlink_metadata(&file_path)?;
             entries.push(IndexEntry {
-                path,
+                path: path.into(),
                 oid: entry.oid,
                 mode: entry.mode,
                 stage: Stage::Normal,
@@ -858,7 +893,7 @@ fn write_conflict_index(
         // Write stage 1 (base)
         if let Some(ref side) = conflict.base {
             index.add(IndexEntry {
-                path: path_bstr.clone(),
+                path: path_bstr.clone().into(),
                 oid: side.oid,
                 mode: side.mode,
                 stage: Stage::Base,
@@ -870,7 +905,7 @@ fn write_conflict_index(
         // Write stage 2 (ours)
         if let Some(ref side) = conflict.ours {
             index.add(IndexEntry {
-                path: path_bstr.clone(),
+                path: path_bstr.clone().into(),
                 oid: side.oid,
                 mode: side.mode,
                 stage: Stage::Ours,
@@ -882,7 +917,7 @@ fn write_conflict_index(
         // Write stage 3 (theirs)
         if let Some(ref side) = conflict.theirs {
             index.add(IndexEntry {
-                path: path_bstr.clone(),
+                path: path_bstr.clone().into(),
                 oid: side.oid,
                 mode: side.mode,
                 stage: Stage::Theirs,
@@ -896,11 +931,14 @@ fn write_conflict_index(
     Ok(())
 }
 
-/// Write conflict markers to working tree files for content conflicts.
+/// Write conflict markers to working tree files for content conflicts. Where
+/// `rerere.enabled` and the conflict matches one already resolved before,
+/// the recorded resolution is written instead and reported to `err`.
 fn write_conflict_files(
     repo: &git_repository::Repository,
     conflicts: &[ConflictEntry],
     theirs_label: &str,
+    err: &mut impl Write,
 ) -> Result<()> {
     let work_tree = match repo.work_tree() {
         Some(wt) => wt.to_path_buf(),
@@ -951,7 +989,19 @@ fn write_conflict_files(
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        std::fs::write(&path, merged)?;
+
+        let rel_path = conflict.path.to_str_lossy();
+        let resolved = String::from_utf8(merged.clone())
+            .ok()
+            .and_then(|content| super::rerere::record_conflict(repo, &rel_path, &content).ok())
+            .flatten();
+
+        if let Some(postimage) = resolved {
+            std::fs::write(&path, &postimage)?;
+            writeln!(err, "Resolved '{}' using previous resolution.", rel_path)?;
+        } else {
+            std::fs::write(&path, merged)?;
+        }
     }
 
     Ok(())
@@ -1093,6 +1143,9 @@ fn conflict_type_label(conflict: &ConflictEntry) -> &'static str {
         git_merge::ConflictType::RenameRename => "rename/rename",
         git_merge::ConflictType::RenameDelete => "rename/delete",
         git_merge::ConflictType::DirectoryFile => "directory/file",
+        git_merge::ConflictType::Submodule => "submodule",
+        git_merge::ConflictType::DirectoryRename => "directory rename",
+        git_merge::ConflictType::TypeChange => "type change",
     }
 }
 
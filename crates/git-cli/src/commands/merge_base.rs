@@ -24,6 +24,10 @@ pub struct MergeBaseArgs {
     #[arg(long)]
     fork_point: bool,
 
+    /// Print commits not reachable from any other given commit
+    #[arg(long)]
+    independent: bool,
+
     /// Commits to find common ancestor of
     commits: Vec<String>,
 }
@@ -33,7 +37,29 @@ pub fn run(args: &MergeBaseArgs, cli: &Cli) -> Result<i32> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
 
-    if args.commits.len() < 2 && !args.fork_point {
+    if args.fork_point {
+        if args.commits.is_empty() || args.commits.len() > 2 {
+            anyhow::bail!("--fork-point requires a ref and an optional commit");
+        }
+        let ref_name = &args.commits[0];
+        let commit = if args.commits.len() == 2 {
+            git_revwalk::resolve_revision(&repo, &args.commits[1])?
+        } else {
+            git_revwalk::resolve_revision(&repo, "HEAD")?
+        };
+        return match git_revwalk::fork_point(&repo, ref_name, &commit)? {
+            Some(base) => {
+                writeln!(out, "{}", base.to_hex())?;
+                Ok(0)
+            }
+            None => {
+                // No fork point found
+                Ok(1)
+            }
+        };
+    }
+
+    if args.commits.len() < 2 {
         anyhow::bail!("merge-base requires at least two commits");
     }
 
@@ -52,20 +78,12 @@ pub fn run(args: &MergeBaseArgs, cli: &Cli) -> Result<i32> {
         return Ok(if result { 0 } else { 1 });
     }
 
-    if args.fork_point {
-        if oids.len() != 2 {
-            anyhow::bail!("--fork-point requires exactly two commits");
-        }
-        match git_revwalk::fork_point(&repo, &oids[0], &oids[1])? {
-            Some(base) => {
-                writeln!(out, "{}", base.to_hex())?;
-                Ok(0)
-            }
-            None => {
-                // No fork point found
-                Ok(1)
-            }
+    if args.independent {
+        let independent = git_revwalk::independent_commits(&repo, &oids)?;
+        for oid in &independent {
+            writeln!(out, "{}", oid.to_hex())?;
         }
+        Ok(0)
     } else if args.octopus {
         match git_revwalk::merge_base_octopus(&repo, &oids)? {
             Some(base) => {
@@ -78,7 +96,7 @@ pub fn run(args: &MergeBaseArgs, cli: &Cli) -> Result<i32> {
             }
         }
     } else if args.all {
-        let bases = git_revwalk::merge_base(&repo, &oids[0], &oids[1])?;
+        let bases = git_revwalk::merge_base_many(&repo, &oids)?;
         if bases.is_empty() {
             return Ok(1);
         }
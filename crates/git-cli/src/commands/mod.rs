@@ -8,6 +8,7 @@ pub mod bundle;
 pub mod cat_file;
 pub mod check_attr;
 pub mod check_ignore;
+pub mod check_mailmap;
 pub mod check_ref_format;
 pub mod checkout;
 pub mod cherry_pick;
@@ -126,6 +127,8 @@ pub enum Commands {
     CheckIgnore(check_ignore::CheckIgnoreArgs),
     /// Display gitattributes information
     CheckAttr(check_attr::CheckAttrArgs),
+    /// Show the canonical mailmap identity for given contacts
+    CheckMailmap(check_mailmap::CheckMailmapArgs),
     /// Build a tree-object from ls-tree formatted text
     Mktree(mktree::MktreeArgs),
     /// Creates a tag object with extra validation
@@ -242,6 +245,9 @@ pub enum Commands {
     VerifyTag(verify_tag::VerifyTagArgs),
     /// Retrieve and store user credentials
     Credential(credential::CredentialArgs),
+    /// Background daemon backing the built-in `credential-cache` helper
+    #[command(hide = true)]
+    CredentialCacheDaemon(credential::CredentialCacheDaemonArgs),
     /// Backend for fast Git data importers
     FastImport(fast_import::FastImportArgs),
     /// Create, unpack, and manipulate bundle files
@@ -314,6 +320,7 @@ impl Commands {
             Commands::UpdateIndex(_) => "update-index",
             Commands::CheckIgnore(_) => "check-ignore",
             Commands::CheckAttr(_) => "check-attr",
+            Commands::CheckMailmap(_) => "check-mailmap",
             Commands::Mktree(_) => "mktree",
             Commands::Mktag(_) => "mktag",
             Commands::Commit(_) => "commit",
@@ -371,6 +378,7 @@ impl Commands {
             Commands::VerifyCommit(_) => "verify-commit",
             Commands::VerifyTag(_) => "verify-tag",
             Commands::Credential(_) => "credential",
+            Commands::CredentialCacheDaemon(_) => "credential-cache-daemon",
             Commands::FastImport(_) => "fast-import",
             Commands::Bundle(_) => "bundle",
             Commands::Daemon(_) => "daemon",
@@ -411,6 +419,42 @@ pub fn open_repo(cli: &Cli) -> Result<git_repository::Repository> {
     Ok(repo)
 }
 
+/// Load the mailmap governing author/committer identity canonicalization,
+/// per `mailmap.file` (a path, relative to the work tree root if not
+/// absolute), falling back to `.mailmap` at the work tree root, and finally
+/// `mailmap.blob` (a blob-ish revision, for bare repositories or checkouts
+/// without a `.mailmap` file on disk). Returns `None` if none of these
+/// yield a mailmap.
+pub fn load_mailmap(repo: &git_repository::Repository) -> Option<git_utils::mailmap::Mailmap> {
+    let work_tree = repo.work_tree();
+
+    let file_path = repo
+        .config()
+        .get_string("mailmap.file")
+        .ok()
+        .flatten()
+        .map(std::path::PathBuf::from)
+        .or_else(|| work_tree.map(|wt| wt.join(".mailmap")));
+
+    if let Some(path) = file_path {
+        let path = if path.is_relative() {
+            work_tree.map(|wt| wt.join(&path)).unwrap_or(path)
+        } else {
+            path
+        };
+        if let Ok(mailmap) = git_utils::mailmap::Mailmap::from_file(&path) {
+            return Some(mailmap);
+        }
+    }
+
+    let blob_rev = repo.config().get_string("mailmap.blob").ok().flatten()?;
+    let oid = git_revwalk::resolve_revision(repo, &blob_rev).ok()?;
+    match repo.odb().read(&oid).ok()?? {
+        git_object::Object::Blob(blob) => Some(git_utils::mailmap::Mailmap::from_bytes(&blob.data)),
+        _ => None,
+    }
+}
+
 pub fn run(cli: Cli) -> Result<i32> {
     match &cli.command {
         Commands::CatFile(args) => cat_file::run(args, &cli),
@@ -425,6 +469,7 @@ pub fn run(cli: Cli) -> Result<i32> {
         Commands::UpdateIndex(args) => update_index::run(args, &cli),
         Commands::CheckIgnore(args) => check_ignore::run(args, &cli),
         Commands::CheckAttr(args) => check_attr::run(args, &cli),
+        Commands::CheckMailmap(args) => check_mailmap::run(args, &cli),
         Commands::Mktree(args) => mktree::run(args, &cli),
         Commands::Mktag(args) => mktag::run(args, &cli),
         Commands::Commit(args) => commit::run(args, &cli),
@@ -483,6 +528,7 @@ pub fn run(cli: Cli) -> Result<i32> {
         Commands::VerifyCommit(args) => verify_commit::run(args, &cli),
         Commands::VerifyTag(args) => verify_tag::run(args, &cli),
         Commands::Credential(args) => credential::run(args, &cli),
+        Commands::CredentialCacheDaemon(args) => credential::run_cache_daemon(args),
         Commands::FastImport(args) => fast_import::run(args, &cli),
         Commands::Bundle(args) => bundle::run(args, &cli),
         Commands::Daemon(args) => daemon::run(args, &cli),
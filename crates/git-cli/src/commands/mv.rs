@@ -78,7 +78,7 @@ pub fn run(args: &MvArgs, cli: &Cli) -> Result<i32> {
     // Update index: remove old, add new
     let metadata = std::fs::symlink_metadata(&dst_fs)?;
     let new_entry = IndexEntry {
-        path: dst_rel,
+        path: dst_rel.into(),
         oid: entry.oid,
         mode: entry.mode,
         stage: Stage::Normal,
@@ -1,12 +1,12 @@
-use std::collections::HashMap;
-use std::io::{self, Write};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::io::{self, Read, Write};
 
 use anyhow::Result;
 use clap::Args;
 use git_hash::ObjectId;
 use git_object::Object;
 use git_ref::RefStore;
-use git_revwalk::RevWalk;
 
 use super::open_repo;
 use crate::Cli;
@@ -33,14 +33,62 @@ pub struct NameRevArgs {
     #[arg(long)]
     name_only: bool,
 
+    /// Read from stdin, rewriting any 40-hex object id found as `<oid> (<name>)`
+    #[arg(long)]
+    stdin: bool,
+
     /// Commits to name
     commits: Vec<String>,
 }
 
-/// A ref candidate for naming a commit.
+/// A ref candidate for naming commits: the ref's short display name and the
+/// commit it resolves to, after peeling past any annotated tag object.
 struct RefCandidate {
     name: String,
     oid: ObjectId,
+    is_tag: bool,
+}
+
+/// Extra cost charged for following a non-first parent, so that first-parent
+/// (`~N`) paths are always preferred over ones that cross a merge (`^M`).
+const MERGE_TRAVERSAL_WEIGHT: u32 = 65535;
+
+/// The best name found so far for a commit.
+struct NameInfo {
+    name: String,
+}
+
+/// An entry in the naming walk's priority queue.
+struct QueueEntry {
+    oid: ObjectId,
+    tip_name: String,
+    generation: u32,
+    distance: u32,
+    from_tag: bool,
+}
+
+impl PartialEq for QueueEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.from_tag == other.from_tag && self.distance == other.distance
+    }
+}
+
+impl Eq for QueueEntry {}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Max-heap: prefer tag-derived names, then the shortest distance.
+        match self.from_tag.cmp(&other.from_tag) {
+            Ordering::Equal => other.distance.cmp(&self.distance),
+            ord => ord,
+        }
+    }
 }
 
 pub fn run(args: &NameRevArgs, cli: &Cli) -> Result<i32> {
@@ -48,6 +96,17 @@ pub fn run(args: &NameRevArgs, cli: &Cli) -> Result<i32> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
 
+    let candidates = collect_ref_candidates(&repo, args)?;
+    let names = name_commits(&repo, &candidates)?;
+
+    if args.stdin {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        let rewritten = rewrite_stdin(&repo, &input, &names, args);
+        write!(out, "{}", rewritten)?;
+        return Ok(0);
+    }
+
     // Resolve each input commit
     let mut targets = Vec::new();
     for rev in &args.commits {
@@ -64,19 +123,13 @@ pub fn run(args: &NameRevArgs, cli: &Cli) -> Result<i32> {
         }
     }
 
-    // Collect all relevant refs
-    let candidates = collect_ref_candidates(&repo, args)?;
-
-    // Build a map from OID -> (ref name, distance) for each target
     for target in &targets {
-        let name = find_name_for_commit(&repo, target, &candidates)?;
-
-        match name {
-            Some(ref desc) => {
+        match names.get(target) {
+            Some(info) => {
                 if args.name_only {
-                    writeln!(out, "{}", desc)?;
+                    writeln!(out, "{}", info.name)?;
                 } else {
-                    writeln!(out, "{} {}", target.to_hex(), desc)?;
+                    writeln!(out, "{} {}", target.to_hex(), info.name)?;
                 }
             }
             None => {
@@ -124,20 +177,21 @@ fn collect_ref_candidates(
                 let r = r?;
                 let full_name = r.name().as_str().to_string();
                 let short_name = shorten_ref_name(&full_name);
+                let is_tag = full_name.starts_with("refs/tags/");
 
-                // Peel tags to their target commit
-                let oid = if let Ok(peeled) = r.peel_to_oid(repo.refs()) {
-                    peeled
-                } else if let Some(oid) = r.target_oid() {
-                    // Try to peel annotated tags
-                    peel_to_commit(repo, &oid).unwrap_or(oid)
-                } else {
+                let Some(direct_oid) = r.target_oid() else {
+                    continue;
+                };
+                // peel_to_oid() only follows symbolic ref chains; an
+                // annotated tag object still needs peeling to its commit.
+                let Some(oid) = peel_to_commit(repo, &direct_oid) else {
                     continue;
                 };
 
                 candidates.push(RefCandidate {
                     name: short_name,
                     oid,
+                    is_tag,
                 });
             }
         }
@@ -146,19 +200,14 @@ fn collect_ref_candidates(
     Ok(candidates)
 }
 
-/// Peel an OID to a commit (follow annotated tags).
-fn peel_to_commit(
-    repo: &git_repository::Repository,
-    oid: &ObjectId,
-) -> Result<ObjectId> {
-    let obj = repo
-        .odb()
-        .read(oid)?
-        .ok_or_else(|| anyhow::anyhow!("object not found: {}", oid))?;
+/// Peel an OID to a commit, following annotated tag objects. Returns `None`
+/// if the object doesn't exist or ultimately isn't a commit.
+fn peel_to_commit(repo: &git_repository::Repository, oid: &ObjectId) -> Option<ObjectId> {
+    let obj = repo.odb().read(oid).ok()??;
     match obj {
         Object::Tag(tag) => peel_to_commit(repo, &tag.target),
-        Object::Commit(_) => Ok(*oid),
-        _ => anyhow::bail!("object {} is not a commit or tag", oid),
+        Object::Commit(_) => Some(*oid),
+        _ => None,
     }
 }
 
@@ -175,88 +224,136 @@ fn shorten_ref_name(full: &str) -> String {
     }
 }
 
-/// Find the best name for a commit by walking backwards from each ref
-/// and checking distance.
-fn find_name_for_commit(
+/// Name every commit reachable from the candidate refs.
+///
+/// This walks first-parent and merge-parent edges from each ref tip,
+/// appending `~N` for N steps along the first parent and `^M` when crossing
+/// to the Mth parent of a merge, coalescing consecutive first-parent steps
+/// into a single `~N` the way C git's name-rev does. Ties are broken by
+/// preferring tag-derived names, then the shortest distance from a tip.
+fn name_commits(
     repo: &git_repository::Repository,
-    target: &ObjectId,
     candidates: &[RefCandidate],
-) -> Result<Option<String>> {
-    // First check for exact matches
+) -> Result<HashMap<ObjectId, NameInfo>> {
+    let mut names: HashMap<ObjectId, NameInfo> = HashMap::new();
+    let mut queue: BinaryHeap<QueueEntry> = BinaryHeap::new();
+
     for cand in candidates {
-        if cand.oid == *target {
-            return Ok(Some(cand.name.clone()));
-        }
+        queue.push(QueueEntry {
+            oid: cand.oid,
+            tip_name: cand.name.clone(),
+            generation: 0,
+            distance: 0,
+            from_tag: cand.is_tag,
+        });
     }
 
-    // Walk from each candidate ref tip and find the one closest to the target
-    let mut best: Option<(String, u32)> = None;
+    while let Some(entry) = queue.pop() {
+        if names.contains_key(&entry.oid) {
+            // Already named optimally: the heap guarantees the first pop
+            // for any commit is its best (tag preference, then distance).
+            continue;
+        }
 
-    for cand in candidates {
-        if let Some(distance) = walk_distance(repo, &cand.oid, target)? {
-            match best {
-                Some((_, best_dist)) if distance < best_dist => {
-                    best = Some((cand.name.clone(), distance));
-                }
-                None => {
-                    best = Some((cand.name.clone(), distance));
-                }
-                _ => {}
+        let full_name = if entry.generation > 0 {
+            format!("{}~{}", entry.tip_name, entry.generation)
+        } else {
+            entry.tip_name.clone()
+        };
+        names.insert(entry.oid, NameInfo { name: full_name });
+
+        let Ok(Some(Object::Commit(commit))) = repo.odb().read(&entry.oid) else {
+            continue;
+        };
+
+        for (i, parent) in commit.parents.iter().enumerate() {
+            if i == 0 {
+                queue.push(QueueEntry {
+                    oid: *parent,
+                    tip_name: entry.tip_name.clone(),
+                    generation: entry.generation + 1,
+                    distance: entry.distance + 1,
+                    from_tag: entry.from_tag,
+                });
+            } else {
+                let parent_number = i + 1;
+                let tip_name = if entry.generation > 0 {
+                    format!("{}~{}^{}", entry.tip_name, entry.generation, parent_number)
+                } else {
+                    format!("{}^{}", entry.tip_name, parent_number)
+                };
+                queue.push(QueueEntry {
+                    oid: *parent,
+                    tip_name,
+                    generation: 0,
+                    distance: entry.distance + MERGE_TRAVERSAL_WEIGHT,
+                    from_tag: entry.from_tag,
+                });
             }
         }
     }
 
-    Ok(best.map(|(name, distance)| {
-        if distance == 0 {
-            name
-        } else {
-            format!("{}~{}", name, distance)
-        }
-    }))
+    Ok(names)
 }
 
-/// Walk from `from` towards `target`, returning the distance if `target`
-/// is an ancestor of `from`.
-fn walk_distance(
+/// Replace every 40-hex object id found in `input` with `<oid> (<name>)`.
+fn rewrite_stdin(
     repo: &git_repository::Repository,
-    from: &ObjectId,
-    target: &ObjectId,
-) -> Result<Option<u32>> {
-    if from == target {
-        return Ok(Some(0));
-    }
-
-    // Collect ancestors of `from` via first-parent walk, recording distances
-    let mut walker = RevWalk::new(repo)?;
-    walker.push(*from)?;
-
-    let mut distances: HashMap<ObjectId, u32> = HashMap::new();
-    distances.insert(*from, 0);
-
-    // Limit the walk to avoid excessive traversal
-    let max_walk = 10_000u32;
-    let mut count = 0u32;
-
-    for oid_result in walker {
-        let oid = oid_result?;
-        let dist = distances.get(&oid).copied().unwrap_or(0);
-
-        if oid == *target {
-            return Ok(Some(dist));
+    input: &str,
+    names: &HashMap<ObjectId, NameInfo>,
+    args: &NameRevArgs,
+) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if !c.is_ascii_hexdigit() {
+            output.push(c);
+            chars.next();
+            continue;
         }
 
-        count += 1;
-        if count > max_walk {
-            break;
+        let mut end = start + c.len_utf8();
+        chars.next();
+        while let Some(&(idx, c2)) = chars.peek() {
+            if !c2.is_ascii_hexdigit() {
+                break;
+            }
+            end = idx + c2.len_utf8();
+            chars.next();
         }
 
-        // Read commit parents to propagate distances
-        if let Some(Object::Commit(commit)) = repo.odb().read(&oid)? {
-            for parent in &commit.parents {
-                distances.entry(*parent).or_insert(dist + 1);
+        let run = &input[start..end];
+        if run.len() == 40 {
+            match describe_hex(repo, run, names, args) {
+                Some(replaced) => output.push_str(&replaced),
+                None => output.push_str(run),
             }
+        } else {
+            output.push_str(run);
         }
     }
 
-    Ok(None)
+    output
+}
+
+/// Describe a single 40-hex token as `<oid> (<name>)`, if it names a known
+/// object.
+fn describe_hex(
+    repo: &git_repository::Repository,
+    hex: &str,
+    names: &HashMap<ObjectId, NameInfo>,
+    args: &NameRevArgs,
+) -> Option<String> {
+    let oid = ObjectId::from_hex(hex).ok()?;
+    repo.odb().read(&oid).ok()??;
+
+    match names.get(&oid) {
+        Some(info) => Some(format!("{} ({})", hex, info.name)),
+        None if args.always => {
+            let abbrev = &hex[..7.min(hex.len())];
+            Some(format!("{} ({})", hex, abbrev))
+        }
+        None => None,
+    }
 }
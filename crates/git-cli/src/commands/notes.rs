@@ -89,6 +89,16 @@ pub enum NotesSubcommand {
         object: Option<String>,
     },
 
+    /// Edit the note for an object, opening it in $EDITOR
+    Edit {
+        /// Note message (skips the editor)
+        #[arg(short, long)]
+        message: Option<String>,
+
+        /// Object to annotate (defaults to HEAD)
+        object: Option<String>,
+    },
+
     /// Remove notes for non-existing/unreachable objects
     Prune {
         /// Dry run
@@ -102,6 +112,12 @@ pub enum NotesSubcommand {
 
     /// Print the notes ref
     GetRef,
+
+    /// Merge another notes ref into the current notes ref
+    Merge {
+        /// Notes ref to merge in (e.g. refs/notes/other or just "other")
+        other_ref: String,
+    },
 }
 
 pub fn run(args: &NotesArgs, cli: &Cli) -> Result<i32> {
@@ -153,6 +169,9 @@ pub fn run(args: &NotesArgs, cli: &Cli) -> Result<i32> {
             *allow_empty,
             object.as_deref(),
         ),
+        Some(NotesSubcommand::Edit { message, object }) => {
+            notes_edit(cli, notes_ref_name, message.as_deref(), object.as_deref())
+        }
         Some(NotesSubcommand::Prune { dry_run, verbose }) => {
             notes_prune(cli, notes_ref_name, *dry_run, *verbose)
         }
@@ -162,6 +181,9 @@ pub fn run(args: &NotesArgs, cli: &Cli) -> Result<i32> {
             writeln!(out, "{}", notes_ref_name)?;
             Ok(0)
         }
+        Some(NotesSubcommand::Merge { other_ref }) => {
+            notes_merge(cli, notes_ref_name, other_ref)
+        }
     }
 }
 
@@ -445,6 +467,85 @@ fn notes_append(
     Ok(0)
 }
 
+fn notes_edit(
+    cli: &Cli,
+    notes_ref_name: &str,
+    message: Option<&str>,
+    object: Option<&str>,
+) -> Result<i32> {
+    let repo = open_repo(cli)?;
+
+    let target_oid = if let Some(spec) = object {
+        git_revwalk::resolve_revision(&repo, spec)?
+    } else {
+        repo.head_oid()?
+            .ok_or_else(|| anyhow::anyhow!("HEAD is not valid"))?
+    };
+
+    let existing = lookup_note_text(&repo, notes_ref_name, &target_oid)?.unwrap_or_default();
+
+    let content = match message {
+        Some(msg) => msg.to_string(),
+        None => launch_notes_editor(&existing)?,
+    };
+
+    let note_oid = repo.odb().write_raw(ObjectType::Blob, content.as_bytes())?;
+    update_note(&repo, notes_ref_name, &target_oid, Some(note_oid))?;
+
+    Ok(0)
+}
+
+/// Launch $GIT_EDITOR/$EDITOR on the note's current text, returning the
+/// edited content. Mirrors `commit.rs`'s editor-invocation pattern, but
+/// notes have no comment-stripping cleanup pass and no default template.
+fn launch_notes_editor(initial_content: &str) -> Result<String> {
+    let editor = std::env::var("GIT_EDITOR")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    let tmp_dir = std::env::temp_dir();
+    let msg_path = tmp_dir.join("NOTES_EDITMSG");
+
+    std::fs::write(&msg_path, initial_content)?;
+
+    let status = std::process::Command::new(&editor)
+        .arg(&msg_path)
+        .status()
+        .map_err(|e| anyhow::anyhow!("failed to launch editor '{}': {}", editor, e))?;
+
+    if !status.success() {
+        bail!("editor '{}' exited with non-zero status", editor);
+    }
+
+    let edited = std::fs::read_to_string(&msg_path)?;
+    let _ = std::fs::remove_file(&msg_path);
+
+    Ok(edited)
+}
+
+/// Look up the note text for `target_oid` on `notes_ref_name`, if any.
+/// Shared with `git log --show-notes` / `git show --show-notes`.
+pub(crate) fn lookup_note_text(
+    repo: &git_repository::Repository,
+    notes_ref_name: &str,
+    target_oid: &ObjectId,
+) -> Result<Option<String>> {
+    let notes_tree_oid = match get_notes_tree(repo, notes_ref_name)? {
+        Some(oid) => oid,
+        None => return Ok(None),
+    };
+
+    let note_oid = match find_note(repo, &notes_tree_oid, target_oid)? {
+        Some(oid) => oid,
+        None => return Ok(None),
+    };
+
+    match repo.odb().read(&note_oid)? {
+        Some(Object::Blob(blob)) => Ok(Some(String::from_utf8_lossy(&blob.data).into_owned())),
+        _ => Ok(None),
+    }
+}
+
 fn notes_prune(cli: &Cli, notes_ref_name: &str, dry_run: bool, verbose: bool) -> Result<i32> {
     let repo = open_repo(cli)?;
     let stderr = io::stderr();
@@ -483,6 +584,138 @@ fn notes_prune(cli: &Cli, notes_ref_name: &str, dry_run: bool, verbose: bool) ->
     Ok(0)
 }
 
+/// Merge `other_ref` (a short name or full notes ref) into `notes_ref_name`.
+///
+/// Reuses [`git_merge::notes_merge`]'s entry-keyed three-way merge: clean
+/// results become a new two-parent notes commit, reusing the incoming
+/// commit's message (there's no meaningful "merge message" for a notes
+/// merge the way there is for branches). Conflicts are staged under
+/// `NOTES_MERGE_WORKTREE` in the git dir, one file per conflicting object
+/// id, for the user to resolve by hand.
+fn notes_merge(cli: &Cli, notes_ref_name: &str, other_ref: &str) -> Result<i32> {
+    let mut repo = open_repo(cli)?;
+    let stderr = io::stderr();
+    let mut err = stderr.lock();
+
+    let other_ref_name = if other_ref.contains('/') {
+        other_ref.to_string()
+    } else {
+        format!("refs/notes/{}", other_ref)
+    };
+
+    let ours_oid = {
+        let refname = RefName::new(BString::from(notes_ref_name))?;
+        repo.refs().resolve_to_oid(&refname)?
+    };
+    let theirs_oid = {
+        let refname = RefName::new(BString::from(other_ref_name.as_str()))?;
+        repo.refs()
+            .resolve_to_oid(&refname)?
+            .ok_or_else(|| anyhow::anyhow!("notes ref '{}' not found", other_ref_name))?
+    };
+
+    let ours_oid = match ours_oid {
+        Some(oid) => oid,
+        None => {
+            // No notes committed on our side yet — fast-forward.
+            let refname = RefName::new(BString::from(notes_ref_name))?;
+            repo.refs().write_ref(&refname, &theirs_oid)?;
+            writeln!(
+                err,
+                "Fast-forwarded notes ref {} to {}",
+                notes_ref_name,
+                theirs_oid.to_hex()
+            )?;
+            return Ok(0);
+        }
+    };
+
+    if ours_oid == theirs_oid {
+        return Ok(0);
+    }
+
+    let base_oid = git_revwalk::merge_base_one(&repo, &ours_oid, &theirs_oid)?;
+
+    let commit_tree = |repo: &git_repository::Repository, oid: &ObjectId| -> Result<ObjectId> {
+        match repo
+            .odb()
+            .read(oid)?
+            .ok_or_else(|| anyhow::anyhow!("notes commit not found"))?
+        {
+            Object::Commit(c) => Ok(c.tree),
+            _ => bail!("notes ref does not point to a commit"),
+        }
+    };
+
+    let base_tree = base_oid.map(|oid| commit_tree(&repo, &oid)).transpose()?;
+    let ours_tree = commit_tree(&repo, &ours_oid)?;
+    let theirs_tree = commit_tree(&repo, &theirs_oid)?;
+
+    let result = git_merge::notes_merge::merge_notes_trees(
+        repo.odb(),
+        base_tree,
+        Some(ours_tree),
+        Some(theirs_tree),
+    )?;
+
+    if result.is_clean {
+        let tree_oid = result
+            .tree
+            .ok_or_else(|| anyhow::anyhow!("clean notes merge produced no tree"))?;
+        let theirs_message = match repo
+            .odb()
+            .read(&theirs_oid)?
+            .ok_or_else(|| anyhow::anyhow!("notes commit not found"))?
+        {
+            Object::Commit(c) => c.message,
+            _ => bail!("notes ref does not point to a commit"),
+        };
+
+        let author = super::tag::build_signature(&repo)?;
+        let commit = git_object::Commit {
+            tree: tree_oid,
+            parents: vec![ours_oid, theirs_oid],
+            author: author.clone(),
+            committer: author,
+            encoding: None,
+            gpgsig: None,
+            extra_headers: Vec::new(),
+            message: theirs_message,
+        };
+        let commit_oid = repo.odb().write(&Object::Commit(commit))?;
+
+        let refname = RefName::new(BString::from(notes_ref_name))?;
+        repo.refs().write_ref(&refname, &commit_oid)?;
+        writeln!(err, "Auto-merging notes ref {}", notes_ref_name)?;
+        return Ok(0);
+    }
+
+    // Conflicts: stage the merged tree's conflict blobs (each holding both
+    // note bodies behind conflict markers) under NOTES_MERGE_WORKTREE, one
+    // file per conflicting object id, for manual resolution.
+    let worktree_dir = repo.git_dir().join("NOTES_MERGE_WORKTREE");
+    std::fs::create_dir_all(&worktree_dir)?;
+    if let Some(tree_oid) = result.tree {
+        if let Some(Object::Tree(tree)) = repo.odb().read(&tree_oid)? {
+            for conflict in &result.conflicts {
+                let target_hex = conflict.path.to_str_lossy().into_owned();
+                writeln!(err, "CONFLICT (notes): notes for object {} conflict", target_hex)?;
+                if let Some(entry) = tree.entries.iter().find(|e| e.name == conflict.path) {
+                    if let Some(Object::Blob(blob)) = repo.odb().read(&entry.oid)? {
+                        std::fs::write(worktree_dir.join(&target_hex), &blob.data)?;
+                    }
+                }
+            }
+        }
+    }
+    writeln!(
+        err,
+        "Automatic notes merge failed; fix conflicts in {} and commit the result.",
+        worktree_dir.display()
+    )?;
+    Ok(1)
+}
+
 /// Update (add/remove) a note in the notes tree and update the notes ref.
 fn update_note(
     repo: &git_repository::Repository,
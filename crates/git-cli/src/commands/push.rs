@@ -5,6 +5,7 @@ use bstr::{BString, ByteSlice};
 use clap::Args;
 use git_hash::ObjectId;
 use git_config::types::PushDefault;
+use git_repository::hooks::{HookRunner, HookType};
 use git_protocol::push::{PushUpdate, PushOptions as ProtoPushOptions, PushRefResult};
 use git_protocol::remote::RemoteConfig;
 use git_ref::{RefName, RefStore};
@@ -141,6 +142,24 @@ pub fn run(args: &PushArgs, cli: &Cli) -> Result<i32> {
         return Ok(0);
     }
 
+    // Run pre-push hook (skip if --no-verify)
+    let hook_runner = HookRunner::new(&repo);
+    if !args.no_verify && hook_runner.hook_exists(HookType::PrePush) {
+        let mut stdin = String::new();
+        for update in &updates {
+            let local_ref = update.local_ref.as_deref().unwrap_or("(delete)");
+            let local_sha = update.local_oid.map(|o| o.to_hex()).unwrap_or_else(|| ObjectId::NULL_SHA1.to_hex());
+            let remote_sha = find_remote_oid(&advertised_refs, &update.remote_ref)
+                .map(|o| o.to_hex())
+                .unwrap_or_else(|| ObjectId::NULL_SHA1.to_hex());
+            stdin.push_str(&format!("{} {} {} {}\n", local_ref, local_sha, update.remote_ref, remote_sha));
+        }
+        let result = hook_runner.run(HookType::PrePush, &[&remote_name, push_url_str], Some(stdin.as_bytes()))?;
+        if !result.success() {
+            bail!("pre-push hook declined");
+        }
+    }
+
     // Compute objects to send
     let local_oids: Vec<ObjectId> = updates.iter()
         .filter_map(|u| u.local_oid)
@@ -239,6 +258,7 @@ fn resolve_push_updates(
                     remote_ref: remote_full,
                     force: args.force,
                     expected_remote_oid: None,
+                    local_ref: None,
                 });
             } else if let Some((src, dst)) = spec.split_once(':') {
                 let local_ref = if src.starts_with("refs/") {
@@ -261,6 +281,7 @@ fn resolve_push_updates(
                     } else {
                         None
                     },
+                    local_ref: Some(local_ref),
                 });
             } else {
                 // Same source and destination
@@ -272,9 +293,10 @@ fn resolve_push_updates(
                 let oid = resolve_ref_oid(repo, &refname)?;
                 updates.push(PushUpdate {
                     local_oid: Some(oid),
-                    remote_ref: refname,
+                    remote_ref: refname.clone(),
                     force: args.force,
                     expected_remote_oid: None,
+                    local_ref: Some(refname),
                 });
             }
         }
@@ -294,9 +316,10 @@ fn resolve_push_updates(
                     let oid = resolve_ref_oid(repo, &refname)?;
                     updates.push(PushUpdate {
                         local_oid: Some(oid),
-                        remote_ref: refname,
+                        remote_ref: refname.clone(),
                         force: args.force,
                         expected_remote_oid: None,
+                        local_ref: Some(refname),
                     });
                 }
             }
@@ -317,6 +340,7 @@ fn resolve_push_updates(
                         remote_ref,
                         force: args.force,
                         expected_remote_oid: None,
+                        local_ref: Some(local_ref),
                     });
                 }
             }
@@ -329,9 +353,10 @@ fn resolve_push_updates(
                             if let Some(oid) = r.target_oid() {
                                 updates.push(PushUpdate {
                                     local_oid: Some(oid),
-                                    remote_ref: name,
+                                    remote_ref: name.clone(),
                                     force: args.force,
                                     expected_remote_oid: None,
+                                    local_ref: Some(name),
                                 });
                             }
                         }
@@ -350,9 +375,10 @@ fn resolve_push_updates(
                     if find_remote_oid(advertised_refs, &name).is_none() {
                         updates.push(PushUpdate {
                             local_oid: Some(oid),
-                            remote_ref: name,
+                            remote_ref: name.clone(),
                             force: false,
                             expected_remote_oid: None,
+                            local_ref: Some(name),
                         });
                     }
                 }
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::io::{self, Write};
 
 use anyhow::{bail, Result};
@@ -20,7 +21,7 @@ pub struct ReadTreeArgs {
     #[arg(short = 'u')]
     update: bool,
 
-    /// Same as -u, also discard untracked files under new directories
+    /// Same as -u, also discard conflicting working-tree changes
     #[arg(long)]
     reset: bool,
 
@@ -45,6 +46,10 @@ pub struct ReadTreeArgs {
     tree_ish: Vec<String>,
 }
 
+/// A flat path -> (oid, mode) view of a tree or the index, used to compare
+/// the three sides of a merge.
+type EntryMap = BTreeMap<String, (ObjectId, FileMode)>;
+
 pub fn run(args: &ReadTreeArgs, cli: &Cli) -> Result<i32> {
     let mut repo = open_repo(cli)?;
     let stderr = io::stderr();
@@ -87,6 +92,14 @@ pub fn run(args: &ReadTreeArgs, cli: &Cli) -> Result<i32> {
             format!("{}/", p)
         }
     });
+    let prefix = prefix.as_deref().unwrap_or("");
+
+    // The paths tracked before this read-tree, needed so `-u` can remove
+    // files that disappear from the resulting index.
+    let old_paths: Vec<BString> = {
+        let index = repo.index()?;
+        index.iter().map(|e| e.path.to_bstring()).collect()
+    };
 
     let mut new_index = Index::new();
 
@@ -95,48 +108,36 @@ pub fn run(args: &ReadTreeArgs, cli: &Cli) -> Result<i32> {
         if tree_oids.len() != 1 {
             bail!("fatal: exactly one tree-ish required without -m");
         }
-        read_tree_into_index(
-            repo.odb(),
-            &tree_oids[0],
-            prefix.as_deref().unwrap_or(""),
-            &mut new_index,
-            args.verbose,
-            &mut err,
-        )?;
+        read_tree_into_index(repo.odb(), &tree_oids[0], prefix, &mut new_index, args.verbose, &mut err)?;
     } else {
         match tree_oids.len() {
             1 => {
                 // 1-tree merge: reset index to this tree
-                read_tree_into_index(
-                    repo.odb(),
-                    &tree_oids[0],
-                    prefix.as_deref().unwrap_or(""),
-                    &mut new_index,
-                    args.verbose,
-                    &mut err,
-                )?;
+                read_tree_into_index(repo.odb(), &tree_oids[0], prefix, &mut new_index, args.verbose, &mut err)?;
             }
             2 => {
-                // 2-tree merge: compare current index with old tree, apply new tree
-                // Simplified: read the second tree
-                read_tree_into_index(
-                    repo.odb(),
-                    &tree_oids[1],
-                    prefix.as_deref().unwrap_or(""),
-                    &mut new_index,
-                    args.verbose,
-                    &mut err,
-                )?;
+                // Fast two-way merge: ancestor is the first tree, theirs the
+                // second, ours is the current index.
+                let ours = {
+                    let index = repo.index()?;
+                    index
+                        .iter()
+                        .filter(|e| e.stage == Stage::Normal)
+                        .map(|e| (e.path.to_string(), (e.oid, e.mode)))
+                        .collect::<EntryMap>()
+                };
+                let old = collect_tree_entries(repo.odb(), &tree_oids[0], prefix)?;
+                let new = collect_tree_entries(repo.odb(), &tree_oids[1], prefix)?;
+                two_way_merge(&old, &ours, &new, args.reset, &mut new_index, args.verbose, &mut err)?;
             }
             3 => {
                 // 3-way merge: ancestor, ours, theirs
-                // Write conflict entries for differing paths
                 three_way_merge(
                     repo.odb(),
                     &tree_oids[0],
                     &tree_oids[1],
                     &tree_oids[2],
-                    prefix.as_deref().unwrap_or(""),
+                    prefix,
                     &mut new_index,
                     args.verbose,
                     &mut err,
@@ -152,37 +153,14 @@ pub fn run(args: &ReadTreeArgs, cli: &Cli) -> Result<i32> {
     // Optionally update working tree
     let should_update_wt = (args.update || args.reset) && !args.index_only;
     if should_update_wt {
-        if let Some(wt) = repo.work_tree() {
-            let wt = wt.to_path_buf();
-            // Collect entries first to avoid borrow conflict between index and odb
-            let entries: Vec<_> = {
-                let index = repo.index()?;
-                index.iter().map(|e| (e.path.clone(), e.oid)).collect()
-            };
-            for (path, oid) in &entries {
-                let file_path = wt.join(path.to_string());
-                if let Some(parent) = file_path.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
-                // Read blob and write to working tree
-                if let Some(Object::Blob(blob)) = repo.odb().read(oid)? {
-                    std::fs::write(&file_path, &blob.data)?;
-                    if args.verbose {
-                        writeln!(err, "Checking out {}", path)?;
-                    }
-                }
-            }
-        }
+        update_working_tree(&repo, &old_paths, args.reset, args.verbose, &mut err)?;
     }
 
     Ok(0)
 }
 
 /// Resolve an OID to a tree OID (dereference commits).
-fn resolve_to_tree(
-    repo: &git_repository::Repository,
-    oid: &ObjectId,
-) -> Result<ObjectId> {
+fn resolve_to_tree(repo: &git_repository::Repository, oid: &ObjectId) -> Result<ObjectId> {
     match repo.odb().read(oid)? {
         Some(Object::Tree(_)) => Ok(*oid),
         Some(Object::Commit(commit)) => Ok(commit.tree),
@@ -192,7 +170,7 @@ fn resolve_to_tree(
 }
 
 /// Read a tree recursively into the index.
-fn read_tree_into_index(
+pub(crate) fn read_tree_into_index(
     odb: &git_odb::ObjectDatabase,
     tree_oid: &ObjectId,
     prefix: &str,
@@ -227,15 +205,7 @@ fn read_tree_into_index(
             if verbose {
                 writeln!(err, "{:06o} {} {}\t{}", entry.mode.raw(), entry.oid.to_hex(), 0, full_path)?;
             }
-            let idx_entry = IndexEntry {
-                path: BString::from(full_path.as_bytes()),
-                oid: entry.oid,
-                mode: entry.mode,
-                stage: Stage::Normal,
-                stat: StatData::default(),
-                flags: EntryFlags::default(),
-            };
-            index.add(idx_entry);
+            index.add(normal_entry(full_path, entry.oid, entry.mode));
         }
     }
 
@@ -247,8 +217,8 @@ fn collect_tree_entries(
     odb: &git_odb::ObjectDatabase,
     tree_oid: &ObjectId,
     prefix: &str,
-) -> Result<std::collections::BTreeMap<String, (ObjectId, FileMode)>> {
-    let mut entries = std::collections::BTreeMap::new();
+) -> Result<EntryMap> {
+    let mut entries = EntryMap::new();
     collect_tree_entries_recursive(odb, tree_oid, prefix, &mut entries)?;
     Ok(entries)
 }
@@ -257,7 +227,7 @@ fn collect_tree_entries_recursive(
     odb: &git_odb::ObjectDatabase,
     tree_oid: &ObjectId,
     prefix: &str,
-    entries: &mut std::collections::BTreeMap<String, (ObjectId, FileMode)>,
+    entries: &mut EntryMap,
 ) -> Result<()> {
     let tree = match odb.read(tree_oid)? {
         Some(Object::Tree(t)) => t,
@@ -282,6 +252,81 @@ fn collect_tree_entries_recursive(
     Ok(())
 }
 
+fn normal_entry(path: String, oid: ObjectId, mode: FileMode) -> IndexEntry {
+    IndexEntry {
+        path: BString::from(path.as_bytes()).into(),
+        oid,
+        mode,
+        stage: Stage::Normal,
+        stat: StatData::default(),
+        flags: EntryFlags::default(),
+    }
+}
+
+fn conflict_entry(path: &str, oid: ObjectId, mode: FileMode, stage: Stage) -> IndexEntry {
+    IndexEntry {
+        path: BString::from(path.as_bytes()).into(),
+        oid,
+        mode,
+        stage,
+        stat: StatData::default(),
+        flags: EntryFlags::default(),
+    }
+}
+
+/// Perform git's fast two-way merge: `ours` (the current index) is brought
+/// up to date with `new`, using `old` as the common ancestor. A path that
+/// didn't change between `old` and `new` keeps whatever `ours` has; a path
+/// unchanged in `ours` since `old` fast-forwards to `new`; anything else is
+/// a conflict, which `--reset` resolves in favor of `new`.
+fn two_way_merge(
+    old: &EntryMap,
+    ours: &EntryMap,
+    new: &EntryMap,
+    reset: bool,
+    index: &mut Index,
+    verbose: bool,
+    err: &mut impl Write,
+) -> Result<()> {
+    let mut all_paths = std::collections::BTreeSet::new();
+    all_paths.extend(old.keys().cloned());
+    all_paths.extend(ours.keys().cloned());
+    all_paths.extend(new.keys().cloned());
+
+    for path in &all_paths {
+        let o = old.get(path);
+        let u = ours.get(path);
+        let n = new.get(path);
+
+        let resolved = if n == o {
+            // Unchanged upstream: keep whatever the index currently has.
+            u
+        } else if u == o {
+            // Unmodified locally: fast-forward to the new tree.
+            n
+        } else if u == n {
+            // Already at the new state.
+            u
+        } else if reset {
+            if verbose {
+                writeln!(err, "Discarding {}", path)?;
+            }
+            n
+        } else {
+            if verbose {
+                writeln!(err, "Refusing to lose local changes in {}", path)?;
+            }
+            u
+        };
+
+        if let Some((oid, mode)) = resolved {
+            index.add(normal_entry(path.clone(), *oid, *mode));
+        }
+    }
+
+    Ok(())
+}
+
 /// Perform a 3-way merge of three trees into the index.
 #[allow(clippy::too_many_arguments)]
 fn three_way_merge(
@@ -300,111 +345,43 @@ fn three_way_merge(
 
     // Collect all unique paths
     let mut all_paths = std::collections::BTreeSet::new();
-    for key in ancestor.keys() {
-        all_paths.insert(key.clone());
-    }
-    for key in ours.keys() {
-        all_paths.insert(key.clone());
-    }
-    for key in theirs.keys() {
-        all_paths.insert(key.clone());
-    }
+    all_paths.extend(ancestor.keys().cloned());
+    all_paths.extend(ours.keys().cloned());
+    all_paths.extend(theirs.keys().cloned());
 
     for path in &all_paths {
         let a = ancestor.get(path);
         let o = ours.get(path);
         let t = theirs.get(path);
 
-        match (a, o, t) {
-            // All three agree
-            (Some((a_oid, _)), Some((o_oid, o_mode)), Some((t_oid, _)))
-                if a_oid == o_oid && o_oid == t_oid =>
-            {
-                let entry = IndexEntry {
-                    path: BString::from(path.as_bytes()),
-                    oid: *o_oid,
-                    mode: *o_mode,
-                    stage: Stage::Normal,
-                    stat: StatData::default(),
-                    flags: EntryFlags::default(),
-                };
-                index.add(entry);
-            }
-            // Ours and theirs agree (both changed same way)
-            (_, Some((o_oid, o_mode)), Some((t_oid, _))) if o_oid == t_oid => {
-                let entry = IndexEntry {
-                    path: BString::from(path.as_bytes()),
-                    oid: *o_oid,
-                    mode: *o_mode,
-                    stage: Stage::Normal,
-                    stat: StatData::default(),
-                    flags: EntryFlags::default(),
-                };
-                index.add(entry);
-            }
-            // Only ours changed from ancestor
-            (Some((a_oid, _)), Some((o_oid, o_mode)), Some((t_oid, _)))
-                if a_oid == t_oid && a_oid != o_oid =>
-            {
-                let entry = IndexEntry {
-                    path: BString::from(path.as_bytes()),
-                    oid: *o_oid,
-                    mode: *o_mode,
-                    stage: Stage::Normal,
-                    stat: StatData::default(),
-                    flags: EntryFlags::default(),
-                };
-                index.add(entry);
-            }
-            // Only theirs changed from ancestor
-            (Some((a_oid, _)), Some((o_oid, _)), Some((t_oid, t_mode)))
-                if a_oid == o_oid && a_oid != t_oid =>
-            {
-                let entry = IndexEntry {
-                    path: BString::from(path.as_bytes()),
-                    oid: *t_oid,
-                    mode: *t_mode,
-                    stage: Stage::Normal,
-                    stat: StatData::default(),
-                    flags: EntryFlags::default(),
-                };
-                index.add(entry);
-            }
-            // Conflict: both sides changed differently
-            _ => {
+        // Trivial merge rule: if one side didn't change from the ancestor,
+        // take the other side (whatever it is, including a deletion); if
+        // both sides agree, take either. Anything else is a conflict.
+        let clean = if o == t {
+            Some(o)
+        } else if a == o {
+            Some(t)
+        } else if a == t {
+            Some(o)
+        } else {
+            None
+        };
+
+        match clean {
+            Some(Some((oid, mode))) => index.add(normal_entry(path.clone(), *oid, *mode)),
+            Some(None) => {} // cleanly deleted on both sides, or added+removed
+            None => {
                 if verbose {
                     writeln!(err, "CONFLICT (content): Merge conflict in {}", path)?;
                 }
-                // Write stage entries for conflict
-                if let Some((a_oid, a_mode)) = a {
-                    index.add(IndexEntry {
-                        path: BString::from(path.as_bytes()),
-                        oid: *a_oid,
-                        mode: *a_mode,
-                        stage: Stage::Base,
-                        stat: StatData::default(),
-                        flags: EntryFlags::default(),
-                    });
+                if let Some((oid, mode)) = a {
+                    index.add(conflict_entry(path, *oid, *mode, Stage::Base));
                 }
-                if let Some((o_oid, o_mode)) = o {
-                    index.add(IndexEntry {
-                        path: BString::from(path.as_bytes()),
-                        oid: *o_oid,
-                        mode: *o_mode,
-                        stage: Stage::Ours,
-                        stat: StatData::default(),
-                        flags: EntryFlags::default(),
-                    });
+                if let Some((oid, mode)) = o {
+                    index.add(conflict_entry(path, *oid, *mode, Stage::Ours));
                 }
-                if let Some((t_oid, t_mode)) = t {
-                    index.add(IndexEntry {
-                        path: BString::from(path.as_bytes()),
-                        oid: *t_oid,
-                        mode: *t_mode,
-                        stage: Stage::Theirs,
-                        stat: StatData::default(),
-                        flags: EntryFlags::default(),
-                    });
+                if let Some((oid, mode)) = t {
+                    index.add(conflict_entry(path, *oid, *mode, Stage::Theirs));
                 }
             }
         }
@@ -412,3 +389,101 @@ fn three_way_merge(
 
     Ok(())
 }
+
+/// Update the working tree to match the new index: write out files that are
+/// present (creating parent directories), and remove files that were
+/// tracked before this `read-tree` but no longer are. Without `--reset`,
+/// leave locally modified files in place rather than clobbering them.
+fn update_working_tree(
+    repo: &git_repository::Repository,
+    old_paths: &[BString],
+    reset: bool,
+    verbose: bool,
+    err: &mut impl Write,
+) -> Result<()> {
+    let Some(wt) = repo.work_tree() else {
+        return Ok(());
+    };
+    let wt = wt.to_path_buf();
+
+    let new_entries: Vec<_> = {
+        let index = repo.index()?;
+        index
+            .iter()
+            .filter(|e| e.stage == Stage::Normal)
+            .map(|e| (e.path.to_bstring(), e.oid, e.mode))
+            .collect()
+    };
+    let new_paths: std::collections::HashSet<_> = new_entries.iter().map(|(p, _, _)| p.clone()).collect();
+
+    // Remove files that disappeared from the index.
+    for old_path in old_paths {
+        if new_paths.contains(old_path) {
+            continue;
+        }
+        let file_path = wt.join(old_path.to_string());
+        if file_path.is_file() {
+            std::fs::remove_file(&file_path)?;
+            if verbose {
+                writeln!(err, "Removing {}", old_path)?;
+            }
+        }
+    }
+
+    // Write out the files the new index expects.
+    for (path, oid, mode) in &new_entries {
+        let file_path = wt.join(path.to_string());
+
+        if file_path.is_file() {
+            if let Ok(existing) = std::fs::read(&file_path) {
+                if let Some(existing_oid) = hash_blob(&existing) {
+                    if existing_oid == *oid {
+                        // Already matches; nothing to do.
+                        continue;
+                    }
+                    if !reset {
+                        // Leave a locally modified file alone rather than
+                        // clobbering it; --reset forces the overwrite.
+                        if verbose {
+                            writeln!(err, "Refusing to lose local changes in {}", path)?;
+                        }
+                        continue;
+                    }
+                }
+            }
+        }
+
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if let Some(Object::Blob(blob)) = repo.odb().read(oid)? {
+            std::fs::write(&file_path, &blob.data)?;
+            if *mode == FileMode::Executable {
+                set_executable(&file_path)?;
+            }
+            if verbose {
+                writeln!(err, "Checking out {}", path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn hash_blob(data: &[u8]) -> Option<ObjectId> {
+    git_hash::hasher::Hasher::hash_object(git_hash::HashAlgorithm::Sha1, "blob", data).ok()
+}
+
+#[cfg(unix)]
+fn set_executable(path: &std::path::Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = std::fs::metadata(path)?.permissions();
+    perms.set_mode(perms.mode() | 0o111);
+    std::fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &std::path::Path) -> Result<()> {
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use std::io::{self, Write};
 
 use anyhow::{bail, Result};
-use bstr::BString;
+use bstr::{BStr, BString, ByteSlice, ByteVec};
 use clap::{Args, Subcommand};
 use git_ref::{RefName, RefStore};
 
@@ -71,6 +71,12 @@ pub enum RemoteSubcommand {
         /// Prune stale branches during update
         #[arg(short, long)]
         prune: bool,
+        /// Never prune, even if remote.<name>.prune or fetch.prune is set
+        #[arg(long = "no-prune")]
+        no_prune: bool,
+        /// Number of remotes to fetch in parallel
+        #[arg(short = 'j', long)]
+        jobs: Option<usize>,
     },
     /// Change the list of branches tracked by a remote
     #[command(name = "set-branches")]
@@ -93,6 +99,16 @@ pub enum RemoteSubcommand {
         #[arg(long)]
         all: bool,
     },
+    /// Classify and optionally delete local branches relative to a remote
+    Trim {
+        name: String,
+        /// Report what would be deleted without actually doing it
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        /// Delete local branches classified as merged or stray
+        #[arg(long, visible_alias = "delete")]
+        delete_local: bool,
+    },
 }
 
 pub fn run(args: &RemoteArgs, cli: &Cli) -> Result<i32> {
@@ -126,8 +142,15 @@ pub fn run(args: &RemoteArgs, cli: &Cli) -> Result<i32> {
         Some(RemoteSubcommand::Prune { name, dry_run }) => {
             prune_remote(&repo, name, *dry_run, &mut out)?;
         }
-        Some(RemoteSubcommand::Update { group, prune }) => {
-            update_remotes(&repo, group.as_deref(), *prune, cli)?;
+        Some(RemoteSubcommand::Update { group, prune, no_prune, jobs }) => {
+            let prune_explicit = if *no_prune {
+                Some(false)
+            } else if *prune {
+                Some(true)
+            } else {
+                None
+            };
+            update_remotes(&repo, group.as_deref(), prune_explicit, *jobs, cli)?;
         }
         Some(RemoteSubcommand::SetBranches { name, branches, add }) => {
             set_branches(&repo, name, branches, *add)?;
@@ -135,6 +158,9 @@ pub fn run(args: &RemoteArgs, cli: &Cli) -> Result<i32> {
         Some(RemoteSubcommand::GetUrl { name, push, all }) => {
             get_url(&repo, name, *push, *all, &mut out)?;
         }
+        Some(RemoteSubcommand::Trim { name, dry_run, delete_local }) => {
+            trim_remote(&repo, name, *dry_run, *delete_local, &mut out)?;
+        }
     }
 
     Ok(0)
@@ -700,6 +726,41 @@ fn prune_remote(
     // list local tracking refs and compare against what the remote advertises.
 
     // Actually connect to the remote to get its current refs
+    let remote_ref_names = advertised_tracking_refs(repo, name)?;
+
+    // Find local tracking refs that are no longer on the remote
+    let prefix = format!("refs/remotes/{}/", name);
+    if let Ok(iter) = repo.refs().iter(Some(&prefix)) {
+        for r in iter.flatten() {
+            let ref_full = r.name().as_str().to_string();
+            // Skip HEAD
+            if ref_full == format!("refs/remotes/{}/HEAD", name) {
+                continue;
+            }
+            if !remote_ref_names.contains(&ref_full) {
+                let short = ref_full.strip_prefix("refs/remotes/").unwrap_or(&ref_full);
+                if dry_run {
+                    writeln!(out, " * [would prune] {}", short)?;
+                } else {
+                    let ref_name = RefName::new(BString::from(ref_full.as_str()))?;
+                    repo.refs().delete_ref(&ref_name)?;
+                    writeln!(out, " * [pruned] {}", short)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Connect to `name` and compute the set of local tracking refs (e.g.
+/// `refs/remotes/origin/main`) that its current advertisement maps to via the
+/// remote's fetch refspecs. Used by both `remote prune` and `remote trim` to
+/// decide which tracking refs no longer correspond to anything on the remote.
+fn advertised_tracking_refs(
+    repo: &git_repository::Repository,
+    name: &str,
+) -> Result<std::collections::HashSet<String>> {
     let remote_config = git_protocol::remote::RemoteConfig::from_config(repo.config(), name)?
         .ok_or_else(|| anyhow::anyhow!("fatal: '{}' does not appear to be a git repository", name))?;
 
@@ -709,35 +770,205 @@ fn prune_remote(
     let reader = &mut git_protocol::pktline::PktLineReader::new(transport.reader());
     let (advertised_refs, _capabilities) = git_protocol::v1::parse_ref_advertisement(reader)?;
 
-    // Build set of remote ref destinations using refspecs
     let refspecs: Vec<git_protocol::remote::RefSpec> = remote_config.fetch_refspecs.clone();
 
-    let remote_ref_names: std::collections::HashSet<String> = advertised_refs
+    Ok(advertised_refs
         .iter()
         .filter_map(|(_, rname)| {
             let n = String::from_utf8_lossy(rname.as_ref()).to_string();
             refspecs.iter().find_map(|rs| rs.map_to_destination(&n))
         })
-        .collect();
+        .collect())
+}
 
-    // Find local tracking refs that are no longer on the remote
-    let prefix = format!("refs/remotes/{}/", name);
-    if let Ok(iter) = repo.refs().iter(Some(&prefix)) {
+/// Category a local branch falls into relative to a base branch, as computed
+/// by `remote trim`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrimCategory {
+    /// Fully merged into base, either by fast-forward/merge or by a squash
+    /// whose tree matches what the merge-base already had.
+    MergedLocal,
+    /// The upstream tracking ref is gone from the advertisement, but its
+    /// last-known OID was itself merged into base before being deleted
+    /// upstream (e.g. a PR branch deleted by the hosting service after merge).
+    MergedRemote,
+    /// The upstream tracking ref is gone from the advertisement and there's
+    /// no evidence it was ever merged into base.
+    Stray,
+    /// Has commits base doesn't have and is not squash-merged. Whether base
+    /// also has commits the branch lacks is not checked, so a branch that is
+    /// only ahead of base lands here too.
+    Diverged,
+}
+
+impl TrimCategory {
+    fn label(&self) -> &'static str {
+        match self {
+            TrimCategory::MergedLocal => "merged",
+            TrimCategory::MergedRemote => "merged-remote",
+            TrimCategory::Stray => "stray",
+            TrimCategory::Diverged => "diverged",
+        }
+    }
+}
+
+/// Resolve the base branch tip for `remote trim`: the remote's HEAD if set,
+/// falling back to `main` then `master` among the remote's tracking refs.
+fn resolve_trim_base(
+    repo: &git_repository::Repository,
+    name: &str,
+) -> Result<git_hash::ObjectId> {
+    let head_ref_name = format!("refs/remotes/{}/HEAD", name);
+    if let Ok(rn) = RefName::new(head_ref_name.as_str()) {
+        if let Ok(Some(oid)) = repo.refs().resolve_to_oid(&rn) {
+            return Ok(oid);
+        }
+    }
+
+    for candidate in &["main", "master"] {
+        let candidate_ref = format!("refs/remotes/{}/{}", name, candidate);
+        if let Ok(rn) = RefName::new(candidate_ref.as_str()) {
+            if let Ok(Some(oid)) = repo.refs().resolve_to_oid(&rn) {
+                return Ok(oid);
+            }
+        }
+    }
+
+    bail!(
+        "fatal: could not determine a base branch for remote '{}' (no HEAD, main, or master)",
+        name
+    );
+}
+
+/// The short branch name (e.g. `"main"`) that `resolve_trim_base` resolved
+/// to, so callers can make sure `remote trim` never deletes the base branch
+/// itself even if it happens to be tracked from this remote.
+fn resolve_trim_base_name(repo: &git_repository::Repository, name: &str) -> Option<String> {
+    let head_ref_name = format!("refs/remotes/{}/HEAD", name);
+    if let Ok(rn) = RefName::new(head_ref_name.as_str()) {
+        if let Ok(Some(git_ref::Reference::Symbolic { target, .. })) = repo.refs().resolve(&rn) {
+            let prefix = format!("refs/remotes/{}/", name);
+            if let Some(short) = target.as_str().strip_prefix(&prefix) {
+                return Some(short.to_string());
+            }
+        }
+    }
+
+    for candidate in &["main", "master"] {
+        let candidate_ref = format!("refs/remotes/{}/{}", name, candidate);
+        if let Ok(rn) = RefName::new(candidate_ref.as_str()) {
+            if repo.refs().resolve_to_oid(&rn).ok().flatten().is_some() {
+                return Some(candidate.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+fn trim_remote(
+    repo: &git_repository::Repository,
+    name: &str,
+    dry_run: bool,
+    delete_local: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    let config_path = repo.git_dir().join("config");
+    let content = std::fs::read_to_string(&config_path).unwrap_or_default();
+    if !content.contains(&format!("[remote \"{}\"]", name)) {
+        bail!("fatal: No such remote '{}'", name);
+    }
+
+    let base_oid = resolve_trim_base(repo, name)?;
+    let base_branch = resolve_trim_base_name(repo, name);
+    let remote_ref_names = advertised_tracking_refs(repo, name)?;
+
+    let current_branch = repo.current_branch().unwrap_or(None);
+
+    let mut results = Vec::new();
+    if let Ok(iter) = repo.refs().iter(Some("refs/heads/")) {
         for r in iter.flatten() {
-            let ref_full = r.name().as_str().to_string();
-            // Skip HEAD
-            if ref_full == format!("refs/remotes/{}/HEAD", name) {
+            let full = r.name().as_str().to_string();
+            let branch = full.strip_prefix("refs/heads/").unwrap_or(&full).to_string();
+
+            let remote_key = format!("branch.{}.remote", branch);
+            if repo.config().get_string(&remote_key)?.as_deref() != Some(name) {
                 continue;
             }
-            if !remote_ref_names.contains(&ref_full) {
-                let short = ref_full.strip_prefix("refs/remotes/").unwrap_or(&ref_full);
-                if dry_run {
-                    writeln!(out, " * [would prune] {}", short)?;
+            let merge_key = format!("branch.{}.merge", branch);
+            let Some(merge) = repo.config().get_string(&merge_key)? else {
+                continue;
+            };
+            let merge_short = merge.strip_prefix("refs/heads/").unwrap_or(&merge);
+            let tracking_ref = format!("refs/remotes/{}/{}", name, merge_short);
+
+            let Ok(branch_oid) = r.peel_to_oid(repo.refs()) else {
+                continue;
+            };
+
+            let category = if !remote_ref_names.contains(&tracking_ref) {
+                // Upstream disappeared from the advertisement. If we still
+                // have its last-known tracking OID and that OID was itself
+                // merged into base, the PR/branch was merged upstream and
+                // then deleted there (e.g. by a hosting service); otherwise
+                // it's just stray.
+                let tracking_rn = RefName::new(tracking_ref.as_str()).ok();
+                let was_merged = tracking_rn
+                    .and_then(|rn| repo.refs().resolve_to_oid(&rn).ok().flatten())
+                    .map(|oid| git_revwalk::is_ancestor(repo, &oid, &base_oid).unwrap_or(false))
+                    .unwrap_or(false);
+                if was_merged {
+                    TrimCategory::MergedRemote
                 } else {
-                    let ref_name = RefName::new(BString::from(ref_full.as_str()))?;
-                    repo.refs().delete_ref(&ref_name)?;
-                    writeln!(out, " * [pruned] {}", short)?;
+                    TrimCategory::Stray
                 }
+            } else if git_revwalk::is_ancestor(repo, &branch_oid, &base_oid).unwrap_or(false) {
+                TrimCategory::MergedLocal
+            } else {
+                let squash_merged = git_revwalk::merge_base_one(repo, &base_oid, &branch_oid)
+                    .ok()
+                    .flatten()
+                    .and_then(|merge_base_oid| trees_equal(repo, &merge_base_oid, &branch_oid).ok())
+                    .unwrap_or(false);
+                if squash_merged {
+                    TrimCategory::MergedLocal
+                } else {
+                    TrimCategory::Diverged
+                }
+            };
+
+            results.push((branch, tracking_ref, category));
+        }
+    }
+
+    for (branch, tracking_ref, category) in &results {
+        writeln!(out, "  {} [{}]", branch, category.label())?;
+
+        if !delete_local {
+            continue;
+        }
+        if *category == TrimCategory::Diverged {
+            continue;
+        }
+        if current_branch.as_deref() == Some(branch.as_str()) {
+            continue;
+        }
+        if base_branch.as_deref() == Some(branch.as_str()) {
+            continue;
+        }
+
+        if dry_run {
+            writeln!(out, "    would delete local branch {}", branch)?;
+            continue;
+        }
+
+        let branch_ref = RefName::new(format!("refs/heads/{}", branch))?;
+        repo.refs().delete_ref(&branch_ref)?;
+        writeln!(out, "    deleted local branch {}", branch)?;
+
+        if *category == TrimCategory::Stray {
+            if let Ok(rn) = RefName::new(tracking_ref.as_str()) {
+                let _ = repo.refs().delete_ref(&rn);
             }
         }
     }
@@ -745,10 +976,31 @@ fn prune_remote(
     Ok(())
 }
 
+/// Whether the tree of `a` equals the tree of `b`, used to detect a
+/// squash-merged branch whose content is already subsumed by the base.
+fn trees_equal(
+    repo: &git_repository::Repository,
+    a: &git_hash::ObjectId,
+    b: &git_hash::ObjectId,
+) -> Result<bool> {
+    let tree_a = commit_tree_oid(repo, a)?;
+    let tree_b = commit_tree_oid(repo, b)?;
+    let diff = git_diff::tree::diff_trees(repo.odb(), Some(&tree_a), Some(&tree_b), &git_diff::DiffOptions::default())?;
+    Ok(diff.is_empty())
+}
+
+fn commit_tree_oid(repo: &git_repository::Repository, oid: &git_hash::ObjectId) -> Result<git_hash::ObjectId> {
+    match repo.odb().read(oid)? {
+        Some(git_object::Object::Commit(commit)) => Ok(commit.tree),
+        _ => bail!("fatal: {} is not a commit", oid),
+    }
+}
+
 fn update_remotes(
     repo: &git_repository::Repository,
     group: Option<&str>,
-    prune: bool,
+    prune_explicit: Option<bool>,
+    jobs: Option<usize>,
     cli: &Cli,
 ) -> Result<()> {
     let config_path = repo.git_dir().join("config");
@@ -784,39 +1036,56 @@ fn update_remotes(
         names
     };
 
-    let stderr = io::stderr();
-    let mut err = stderr.lock();
+    // A jobs limit of 1 (the default) preserves the original sequential
+    // behavior and output ordering; anything higher fans the fetches out
+    // across a small pool of worker threads pulling from a shared queue.
+    let job_limit = jobs.unwrap_or(1).max(1).min(remote_names.len().max(1));
+    let queue: std::sync::Mutex<std::collections::VecDeque<String>> =
+        std::sync::Mutex::new(remote_names.iter().cloned().collect());
+
+    // Buffer each remote's "Fetching ..." line and everything fetch::run_into
+    // prints, then flush it as one write. With job_limit > 1 several of
+    // these run concurrently against the same stderr; writing line-by-line
+    // straight to it would interleave one remote's output with another's.
+    let fetch_one = |remote_name: &str| {
+        let mut buf: Vec<u8> = Vec::new();
+        let _ = writeln!(buf, "Fetching {}", remote_name);
 
-    for remote_name in &remote_names {
-        writeln!(err, "Fetching {}", remote_name)?;
+        let prune = fetch::resolve_prune(repo.config(), remote_name, prune_explicit)
+            .unwrap_or(false);
 
         let fetch_args = fetch::FetchArgs {
             all: false,
             prune,
+            no_prune: false,
             depth: None,
             tags: false,
             quiet: false,
-            verbose: false,
             force: false,
-            dry_run: false,
-            jobs: None,
-            shallow_since: None,
-            shallow_exclude: None,
-            unshallow: false,
-            deepen: None,
-            recurse_submodules: false,
-            set_upstream: false,
-            remote: Some(remote_name.clone()),
+            remote: Some(remote_name.to_string()),
             refspec: vec![],
         };
 
-        match fetch::run(&fetch_args, cli) {
-            Ok(_) => {}
-            Err(e) => {
-                writeln!(err, "error: Could not fetch {}: {}", remote_name, e)?;
-            }
+        if let Err(e) = fetch::run_into(&fetch_args, cli, &mut buf) {
+            let _ = writeln!(buf, "error: Could not fetch {}: {}", remote_name, e);
         }
-    }
+
+        let stderr = io::stderr();
+        let mut err = stderr.lock();
+        let _ = err.write_all(&buf);
+    };
+
+    std::thread::scope(|scope| {
+        for _ in 0..job_limit {
+            scope.spawn(|| loop {
+                let remote_name = match queue.lock().unwrap().pop_front() {
+                    Some(n) => n,
+                    None => break,
+                };
+                fetch_one(&remote_name);
+            });
+        }
+    });
 
     Ok(())
 }
@@ -828,102 +1097,44 @@ fn set_branches(
     add: bool,
 ) -> Result<()> {
     let config_path = repo.git_dir().join("config");
-    let content = std::fs::read_to_string(&config_path)?;
+    let mut config = git_config::file::ConfigFile::load(&config_path, git_config::ConfigScope::Local)?;
 
-    let section_header = format!("[remote \"{}\"]", name);
-    if !content.contains(&section_header) {
+    let section_key = git_config::ConfigKey::parse(&format!("remote.{}.url", name))?;
+    if config.get(&section_key).is_none() {
         bail!("fatal: No such remote '{}'", name);
     }
 
-    // Build the new fetch refspecs for the specified branches
+    let fetch_key = git_config::ConfigKey::parse(&format!("remote.{}.fetch", name))?;
+
+    let existing_fetches: Vec<BString> = config
+        .get_all(&fetch_key)
+        .into_iter()
+        .flatten()
+        .map(|v| v.to_owned())
+        .collect();
+
     let new_refspecs: Vec<String> = branches
         .iter()
         .map(|b| format!("+refs/heads/{}:refs/remotes/{}/{}", b, name, b))
         .collect();
 
-    // Parse the config and rebuild the remote section
-    let mut new_content = String::new();
-    let mut in_section = false;
-    let mut existing_fetches: Vec<String> = Vec::new();
-    let mut section_ended = false;
-    let mut fetches_written = false;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
+    config.remove_all(&fetch_key);
 
-        if trimmed == section_header {
-            in_section = true;
-            new_content.push_str(line);
-            new_content.push('\n');
-            continue;
+    if add {
+        for f in &existing_fetches {
+            config.append(&fetch_key, f.as_bstr());
         }
-
-        if in_section && trimmed.starts_with('[') {
-            // End of the remote section; write the fetch lines before the next section
-            in_section = false;
-            section_ended = true;
-
-            if !fetches_written {
-                write_fetch_lines(&mut new_content, &existing_fetches, &new_refspecs, add);
-                fetches_written = true;
+        for f in &new_refspecs {
+            if !existing_fetches.iter().any(|e| e.as_bytes() == f.as_bytes()) {
+                config.append(&fetch_key, BStr::new(f.as_bytes()));
             }
-
-            new_content.push_str(line);
-            new_content.push('\n');
-            continue;
         }
-
-        if in_section {
-            if trimmed.starts_with("fetch = ") {
-                // Collect existing fetch refspecs (we'll replace or append)
-                if let Some(f) = trimmed.strip_prefix("fetch = ") {
-                    existing_fetches.push(f.to_string());
-                }
-                // Don't write old fetch lines; we'll write them later
-                continue;
-            }
-            new_content.push_str(line);
-            new_content.push('\n');
-        } else {
-            new_content.push_str(line);
-            new_content.push('\n');
+    } else {
+        for f in &new_refspecs {
+            config.append(&fetch_key, BStr::new(f.as_bytes()));
         }
     }
 
-    // If the remote section was the last section in the file
-    if in_section && !fetches_written {
-        write_fetch_lines(&mut new_content, &existing_fetches, &new_refspecs, add);
-    }
-    // If section ended via EOF without encountering another section header
-    if !section_ended && !in_section && !fetches_written {
-        // Already handled above
-    }
-
-    std::fs::write(&config_path, new_content)?;
+    config.write_to(&config_path)?;
     Ok(())
 }
-
-fn write_fetch_lines(
-    content: &mut String,
-    existing: &[String],
-    new_refspecs: &[String],
-    add: bool,
-) {
-    if add {
-        // Keep existing and append new ones
-        for f in existing {
-            content.push_str(&format!("\tfetch = {}\n", f));
-        }
-        for f in new_refspecs {
-            // Only add if not already present
-            if !existing.contains(f) {
-                content.push_str(&format!("\tfetch = {}\n", f));
-            }
-        }
-    } else {
-        // Replace: only write the new refspecs
-        for f in new_refspecs {
-            content.push_str(&format!("\tfetch = {}\n", f));
-        }
-    }
-}
@@ -1,4 +1,5 @@
-use std::io::{self, Write};
+use std::io::{self, BufRead, Write};
+use std::process::Command;
 
 use anyhow::{bail, Result};
 use clap::Args;
@@ -7,6 +8,7 @@ use git_diff::{DiffOptions, DiffOutputFormat};
 use git_hash::ObjectId;
 use git_object::Object;
 use git_revwalk::RevWalk;
+use git_utils::date::DateFormat;
 
 use super::open_repo;
 use crate::Cli;
@@ -50,12 +52,17 @@ pub fn run(args: &RequestPullArgs, cli: &Cli) -> Result<i32> {
         _ => bail!("not a commit: {}", end_oid.to_hex()),
     };
 
-    // Determine the branch name
-    let branch_name = if end_ref == "HEAD" {
-        repo.current_branch()?
-            .unwrap_or_else(|| end_oid.to_hex().to_string())
-    } else {
-        end_ref.to_string()
+    // Find which ref on the remote actually points at the end commit; this
+    // both confirms the commits are reachable there and gives us the branch
+    // name to advertise.
+    let branch_name = match find_remote_branch(&args.url, &end_oid)? {
+        Some(branch) => branch,
+        None => {
+            eprintln!("warn: No branch of {} is at:", args.url);
+            eprintln!("warn:   {} {}", short_oid(&end_oid), first_line(end_commit.message.as_ref()));
+            eprintln!("warn: Are you sure you pushed '{}' there?", end_ref);
+            return Ok(1);
+        }
     };
 
     // Collect commits between start and end
@@ -82,7 +89,8 @@ pub fn run(args: &RequestPullArgs, cli: &Cli) -> Result<i32> {
     // Show start commit summary
     if let Some(Object::Commit(start_commit)) = repo.odb().read(&start_oid)? {
         let summary = first_line(start_commit.message.as_ref());
-        writeln!(out, "  {} ({})", summary, start_oid.to_hex())?;
+        let date = start_commit.author.date.format(&DateFormat::Default);
+        writeln!(out, "  {} ({})", summary, date)?;
     }
 
     writeln!(out)?;
@@ -99,7 +107,8 @@ pub fn run(args: &RequestPullArgs, cli: &Cli) -> Result<i32> {
 
     // Show end commit summary
     let end_summary = first_line(end_commit.message.as_ref());
-    writeln!(out, "  {} ({})", end_summary, end_oid.to_hex())?;
+    let end_date = end_commit.author.date.format(&DateFormat::Default);
+    writeln!(out, "  {} ({})", end_summary, end_date)?;
     writeln!(out)?;
 
     // Separator
@@ -108,13 +117,19 @@ pub fn run(args: &RequestPullArgs, cli: &Cli) -> Result<i32> {
         "----------------------------------------------------------------"
     )?;
 
-    // Show shortlog of commits
+    // Show shortlog of commits, canonicalizing authors via the mailmap like
+    // `git shortlog` does
+    let mailmap = super::load_mailmap(&repo);
     let mut author_commits: std::collections::BTreeMap<String, Vec<String>> =
         std::collections::BTreeMap::new();
 
     for commit_oid in &commits {
         if let Some(Object::Commit(commit)) = repo.odb().read(commit_oid)? {
-            let author = String::from_utf8_lossy(&commit.author.name).to_string();
+            let author_name = match &mailmap {
+                Some(mm) => mm.lookup(&commit.author.name, &commit.author.email).0,
+                None => commit.author.name.clone(),
+            };
+            let author = String::from_utf8_lossy(&author_name).to_string();
             let summary = first_line(commit.message.as_ref());
             author_commits.entry(author).or_default().push(summary);
         }
@@ -175,6 +190,43 @@ pub fn run(args: &RequestPullArgs, cli: &Cli) -> Result<i32> {
     Ok(0)
 }
 
+/// Query `<url>` with `git ls-remote` and return the ref name (with the
+/// `refs/heads/` or `refs/tags/` prefix stripped) that points at `oid`, if
+/// any is found among the advertised refs.
+fn find_remote_branch(url: &str, oid: &ObjectId) -> Result<Option<String>> {
+    let output = Command::new("git").arg("ls-remote").arg(url).output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("unable to look up {}: {}", url, stderr.trim());
+    }
+
+    let target = oid.to_hex().to_string();
+    for line in output.stdout.lines() {
+        let line = line?;
+        let mut parts = line.splitn(2, '\t');
+        let (Some(hash), Some(refname)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if hash != target {
+            continue;
+        }
+        if let Some(name) = refname.strip_prefix("refs/heads/") {
+            return Ok(Some(name.to_string()));
+        }
+        if let Some(name) = refname.strip_prefix("refs/tags/") {
+            return Ok(Some(name.to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Abbreviate an OID to git's default short length.
+fn short_oid(oid: &ObjectId) -> String {
+    oid.to_hex().to_string()[..7].to_string()
+}
+
 /// Extract the first line of a commit message.
 fn first_line(message: &[u8]) -> String {
     let s = String::from_utf8_lossy(message);
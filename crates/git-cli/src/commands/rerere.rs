@@ -57,31 +57,75 @@ fn rr_cache_dir(repo: &git_repository::Repository) -> PathBuf {
     repo.git_dir().join("rr-cache")
 }
 
-/// Compute a hash for a conflict by hashing the conflict marker content.
-/// This produces a deterministic ID based on the conflict markers in the file.
-fn conflict_id(content: &str) -> String {
-    let mut hasher = Hasher::new(HashAlgorithm::Sha1);
-    let mut in_conflict = false;
+/// Extract and normalize the conflict hunks from `content` into an
+/// order-independent canonical form: for each `<<<<<<< / [||||||| / ] =======
+/// / >>>>>>>` block, take the "ours" and "theirs" halves (the diff3 base
+/// section, if present, is dropped), sort the two halves lexicographically
+/// so that which side is "ours" vs. "theirs" doesn't change the result, and
+/// concatenate across all hunks. This is both the identity used for
+/// `conflict_id` and the text stored as `preimage`.
+fn normalize_conflicts(content: &str) -> String {
+    enum Side {
+        Ours,
+        Base,
+        Theirs,
+    }
 
-    for line in content.lines() {
-        if line.starts_with("<<<<<<<") {
-            in_conflict = true;
-            hasher.update(line.as_bytes());
-            hasher.update(b"\n");
-        } else if line.starts_with("=======") && in_conflict {
-            hasher.update(line.as_bytes());
-            hasher.update(b"\n");
-        } else if line.starts_with(">>>>>>>") && in_conflict {
-            hasher.update(line.as_bytes());
-            hasher.update(b"\n");
-            in_conflict = false;
-        } else if in_conflict {
-            hasher.update(line.as_bytes());
-            hasher.update(b"\n");
+    let mut result = String::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        if !line.starts_with("<<<<<<<") {
+            continue;
+        }
+
+        let mut ours = String::new();
+        let mut theirs = String::new();
+        let mut side = Side::Ours;
+
+        for line in lines.by_ref() {
+            if line.starts_with("|||||||") {
+                side = Side::Base;
+                continue;
+            }
+            if line.starts_with("=======") {
+                side = Side::Theirs;
+                continue;
+            }
+            if line.starts_with(">>>>>>>") {
+                break;
+            }
+            match side {
+                Side::Ours => {
+                    ours.push_str(line);
+                    ours.push('\n');
+                }
+                Side::Theirs => {
+                    theirs.push_str(line);
+                    theirs.push('\n');
+                }
+                Side::Base => {}
+            }
         }
+
+        let mut halves = [ours, theirs];
+        halves.sort();
+        result.push_str(&halves[0]);
+        result.push_str(&halves[1]);
     }
 
+    result
+}
+
+/// Compute a conflict's identity by hashing its normalized form (see
+/// `normalize_conflicts`), so the ID is stable regardless of which side
+/// happened to be "ours" vs. "theirs".
+fn conflict_id(content: &str) -> String {
+    let normalized = normalize_conflicts(content);
+
     // finalize() returns Result<ObjectId, _>; use to_hex() for the string
+    let mut hasher = Hasher::new(HashAlgorithm::Sha1);
+    hasher.update(normalized.as_bytes());
     match hasher.finalize() {
         Ok(oid) => oid.to_hex(),
         Err(_) => {
@@ -110,31 +154,87 @@ fn has_conflict_markers(content: &str) -> bool {
     false
 }
 
-/// Extract the "preimage" from conflicted content -- the conflict markers
-/// normalized for storage. Used when recording new resolutions.
-#[allow(dead_code)]
-fn extract_preimage(content: &str) -> String {
-    let mut result = String::new();
-    let mut in_conflict = false;
+/// Whether the `rerere.enabled` config is on (off by default, as in git).
+fn rerere_enabled(repo: &git_repository::Repository) -> bool {
+    repo.config()
+        .get_bool("rerere.enabled")
+        .ok()
+        .flatten()
+        .unwrap_or(false)
+}
 
-    for line in content.lines() {
-        if line.starts_with("<<<<<<<") {
-            in_conflict = true;
-            result.push_str("<<<<<<<\n");
-        } else if line.starts_with("|||||||") && in_conflict {
-            // diff3 base marker -- skip base section (handled by falling through)
-        } else if line.starts_with("=======") && in_conflict {
-            result.push_str("=======\n");
-        } else if line.starts_with(">>>>>>>") && in_conflict {
-            in_conflict = false;
-            result.push_str(">>>>>>>\n");
-        } else {
-            result.push_str(line);
-            result.push('\n');
+/// Record a freshly-conflicted file for rerere: if `rerere.enabled` and
+/// `conflicted_content` still has conflict markers, compute its conflict ID
+/// and write the normalized conflict text as `rr-cache/<id>/preimage` (plus a
+/// `path` file recording which working-tree path it belongs to), unless an
+/// entry for this exact conflict already exists. If a resolution was
+/// previously recorded for this conflict, its stored `postimage` is
+/// returned so the caller can reapply it to the working tree automatically.
+pub(crate) fn record_conflict(
+    repo: &git_repository::Repository,
+    rel_path: &str,
+    conflicted_content: &str,
+) -> Result<Option<String>> {
+    if !rerere_enabled(repo) || !has_conflict_markers(conflicted_content) {
+        return Ok(None);
+    }
+
+    let entry_dir = rr_cache_dir(repo).join(conflict_id(conflicted_content));
+    fs::create_dir_all(&entry_dir)?;
+
+    let preimage_path = entry_dir.join("preimage");
+    if !preimage_path.exists() {
+        fs::write(&preimage_path, normalize_conflicts(conflicted_content))?;
+    }
+
+    let path_file = entry_dir.join("path");
+    if !path_file.exists() {
+        fs::write(&path_file, rel_path)?;
+    }
+
+    let postimage_path = entry_dir.join("postimage");
+    if postimage_path.exists() {
+        Ok(Some(fs::read_to_string(&postimage_path)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Record the resolution for `rel_path` if `rerere.enabled` and it has a
+/// pending (unresolved) rerere entry and `resolved_content` no longer has
+/// conflict markers. Called after a previously-conflicted path is staged.
+pub(crate) fn record_resolution(
+    repo: &git_repository::Repository,
+    rel_path: &str,
+    resolved_content: &str,
+) -> Result<()> {
+    if !rerere_enabled(repo) || has_conflict_markers(resolved_content) {
+        return Ok(());
+    }
+
+    let cache_dir = rr_cache_dir(repo);
+    if !cache_dir.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(&cache_dir)? {
+        let entry_path = entry?.path();
+        if !entry_path.is_dir() {
+            continue;
+        }
+
+        let path_file = entry_path.join("path");
+        if !path_file.exists() || fs::read_to_string(&path_file)?.trim() != rel_path {
+            continue;
+        }
+
+        let postimage_path = entry_path.join("postimage");
+        if !postimage_path.exists() {
+            fs::write(&postimage_path, resolved_content)?;
         }
     }
 
-    result
+    Ok(())
 }
 
 fn rerere_clear(cli: &Cli) -> Result<i32> {
@@ -226,16 +326,17 @@ fn rerere_diff(cli: &Cli) -> Result<i32> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
 
-    // Verify we have a working tree (rerere only makes sense in one)
-    let _work_tree = repo
+    let work_tree = repo
         .work_tree()
-        .ok_or_else(|| anyhow::anyhow!("not in a working tree"))?;
+        .ok_or_else(|| anyhow::anyhow!("not in a working tree"))?
+        .to_path_buf();
 
     if !cache_dir.exists() {
         return Ok(0);
     }
 
-    // Look for conflicted files in the working tree and compare with stored resolutions
+    // For each unresolved entry, diff its stored preimage against the
+    // current working-tree content of the path it belongs to.
     for entry in fs::read_dir(&cache_dir)? {
         let entry = entry?;
         let entry_path = entry.path();
@@ -247,7 +348,7 @@ fn rerere_diff(cli: &Cli) -> Result<i32> {
         let postimage_path = entry_path.join("postimage");
         let path_file = entry_path.join("path");
 
-        if !preimage_path.exists() {
+        if !preimage_path.exists() || postimage_path.exists() {
             continue;
         }
 
@@ -258,36 +359,29 @@ fn rerere_diff(cli: &Cli) -> Result<i32> {
         };
 
         let preimage = fs::read_to_string(&preimage_path)?;
-
-        if postimage_path.exists() {
-            let postimage = fs::read_to_string(&postimage_path)?;
-            // Show diff between preimage and postimage
-            writeln!(out, "--- a/{}", file_path_str)?;
-            writeln!(out, "+++ b/{}", file_path_str)?;
-
-            // Simple line-by-line diff
-            let pre_lines: Vec<&str> = preimage.lines().collect();
-            let post_lines: Vec<&str> = postimage.lines().collect();
-
-            let max = pre_lines.len().max(post_lines.len());
-            for i in 0..max {
-                let pre = pre_lines.get(i).copied().unwrap_or("");
-                let post = post_lines.get(i).copied().unwrap_or("");
-                if pre == post {
-                    writeln!(out, " {}", pre)?;
-                } else {
-                    if !pre.is_empty() || i < pre_lines.len() {
-                        writeln!(out, "-{}", pre)?;
-                    }
-                    if !post.is_empty() || i < post_lines.len() {
-                        writeln!(out, "+{}", post)?;
-                    }
+        let current = fs::read_to_string(work_tree.join(&file_path_str)).unwrap_or_default();
+
+        writeln!(out, "--- a/{}", file_path_str)?;
+        writeln!(out, "+++ b/{}", file_path_str)?;
+
+        // Simple line-by-line diff
+        let pre_lines: Vec<&str> = preimage.lines().collect();
+        let cur_lines: Vec<&str> = current.lines().collect();
+
+        let max = pre_lines.len().max(cur_lines.len());
+        for i in 0..max {
+            let pre = pre_lines.get(i).copied().unwrap_or("");
+            let cur = cur_lines.get(i).copied().unwrap_or("");
+            if pre == cur {
+                writeln!(out, " {}", pre)?;
+            } else {
+                if i < pre_lines.len() {
+                    writeln!(out, "-{}", pre)?;
+                }
+                if i < cur_lines.len() {
+                    writeln!(out, "+{}", cur)?;
                 }
             }
-        } else {
-            // No postimage yet; show preimage
-            writeln!(out, "--- a/{} (preimage, no resolution recorded)", file_path_str)?;
-            write!(out, "{}", preimage)?;
         }
     }
 
@@ -304,6 +398,7 @@ fn rerere_status(cli: &Cli) -> Result<i32> {
         return Ok(0);
     }
 
+    // List entries with an unresolved preimage (no postimage recorded yet).
     for entry in fs::read_dir(&cache_dir)? {
         let entry = entry?;
         let entry_path = entry.path();
@@ -312,9 +407,10 @@ fn rerere_status(cli: &Cli) -> Result<i32> {
         }
 
         let path_file = entry_path.join("path");
+        let preimage_path = entry_path.join("preimage");
         let postimage_path = entry_path.join("postimage");
 
-        if path_file.exists() && postimage_path.exists() {
+        if path_file.exists() && preimage_path.exists() && !postimage_path.exists() {
             let file_path = fs::read_to_string(&path_file)?.trim().to_string();
             writeln!(out, "{}", file_path)?;
         }
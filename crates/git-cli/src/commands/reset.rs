@@ -84,7 +84,7 @@ pub fn run(args: &ResetArgs, cli: &Cli) -> Result<i32> {
             let new_oid = repo.odb().write(&Object::Blob(blob))?;
             let mode = file_diff.old_mode.unwrap_or(FileMode::Regular);
             let entry = IndexEntry {
-                path: path.clone(),
+                path: path.clone().into(),
                 oid: new_oid,
                 mode,
                 stage: Stage::Normal,
@@ -237,7 +237,7 @@ fn reset_paths(
         // Find the blob in the target tree
         if let Some((oid, mode)) = find_blob_in_tree(repo.odb(), &tree_oid, &rel)? {
             let entry = IndexEntry {
-                path: rel.clone(),
+                path: rel.clone().into(),
                 oid,
                 mode,
                 stage: Stage::Normal,
@@ -295,7 +295,7 @@ pub(crate) fn build_index_from_tree(
             build_index_from_tree(odb, &entry.oid, &path, index)?;
         } else {
             index.add(IndexEntry {
-                path,
+                path: path.into(),
                 oid: entry.oid,
                 mode: entry.mode,
                 stage: Stage::Normal,
@@ -107,7 +107,7 @@ pub fn run(args: &RestoreArgs, cli: &Cli) -> Result<i32> {
                         }
                     };
                     let entry = IndexEntry {
-                        path: rel,
+                        path: rel.into(),
                         oid,
                         mode,
                         stage: Stage::Normal,
@@ -140,9 +140,10 @@ pub fn run(args: &RevListArgs, cli: &Cli) -> Result<i32> {
     Ok(0)
 }
 
+/// Parse a `--since`/`--until` date, accepting bare epoch seconds as well as
+/// any approxidate form ("2 weeks ago", "yesterday", ISO/RFC dates, ...).
 fn parse_date(s: &str) -> Option<i64> {
-    if let Ok(ts) = s.parse::<i64>() {
-        return Some(ts);
-    }
-    None
+    git_utils::date::GitDate::parse_approxidate_now(s)
+        .ok()
+        .map(|d| d.timestamp)
 }
@@ -249,7 +249,7 @@ fn handle_continue(
     // Read index from file directly to avoid borrow conflict
     // (repo.index() takes &mut self, write_tree needs repo.odb() which takes &self)
     let index_path = repo.git_dir().join("index");
-    let index_for_tree = if index_path.exists() {
+    let mut index_for_tree = if index_path.exists() {
         git_index::Index::read_from(&index_path)?
     } else {
         git_index::Index::new()
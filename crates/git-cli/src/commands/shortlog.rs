@@ -124,7 +124,7 @@ pub fn run(args: &ShortlogArgs, cli: &Cli) -> Result<i32> {
         color_config_holder = Some(load_color_config(&repo));
 
         // Load mailmap if --use-mailmap or log.mailmap config is set
-        let mailmap = load_mailmap(&repo, args.use_mailmap);
+        let mailmap = load_mailmap_if_enabled(&repo, args.use_mailmap);
 
         let mut walker = RevWalk::new(&repo)?;
 
@@ -207,8 +207,9 @@ fn load_color_config(repo: &git_repository::Repository) -> ColorConfig {
     ColorConfig::from_config(|key| config.get_string(key).ok().flatten())
 }
 
-/// Load mailmap if --use-mailmap flag is passed or log.mailmap config is true.
-fn load_mailmap(
+/// Load mailmap if --use-mailmap flag is passed or log.mailmap config is
+/// true, honoring mailmap.file/mailmap.blob via `super::load_mailmap`.
+fn load_mailmap_if_enabled(
     repo: &git_repository::Repository,
     use_mailmap_flag: bool,
 ) -> Option<git_utils::mailmap::Mailmap> {
@@ -223,12 +224,5 @@ fn load_mailmap(
         return None;
     }
 
-    let work_tree = repo.work_tree().map(|p| p.to_path_buf());
-    if let Some(ref wt) = work_tree {
-        let mailmap_path = wt.join(".mailmap");
-        if mailmap_path.exists() {
-            return git_utils::mailmap::Mailmap::from_file(&mailmap_path).ok();
-        }
-    }
-    None
+    super::load_mailmap(repo)
 }
@@ -9,7 +9,7 @@ use git_hash::ObjectId;
 use git_object::{Commit, Object};
 use git_revwalk::{
     format_builtin, format_builtin_with_decorations, format_commit_with_decorations,
-    BuiltinFormat, FormatOptions,
+    resolve_format, BuiltinFormat, FormatOptions, ResolvedFormat,
 };
 use git_utils::color::{ColorConfig, ColorSlot};
 
@@ -46,6 +46,18 @@ pub struct ShowArgs {
     #[arg(long)]
     decorate: bool,
 
+    /// Show the note(s) for the commit, appended after the commit body
+    #[arg(long)]
+    show_notes: bool,
+
+    /// Apply mailmap transformations
+    #[arg(long)]
+    use_mailmap: bool,
+
+    /// Show notes from the given ref instead of the default (refs/notes/commits)
+    #[arg(long = "notes", value_name = "ref")]
+    notes_ref: Option<String>,
+
     /// Suppress diff output (same as --no-patch)
     #[arg(short = 'q', long)]
     quiet: bool,
@@ -73,6 +85,13 @@ pub fn run(args: &ShowArgs, cli: &Cli) -> Result<i32> {
         None
     };
 
+    // Load mailmap if requested, honoring mailmap.file/mailmap.blob
+    let mailmap = if args.use_mailmap {
+        super::load_mailmap(&repo)
+    } else {
+        None
+    };
+
     // Handle tree:path syntax (e.g., HEAD:file.txt)
     if args.object.contains(':') {
         return show_tree_path(&repo, &args.object, &mut out);
@@ -88,7 +107,16 @@ pub fn run(args: &ShowArgs, cli: &Cli) -> Result<i32> {
     let mut buf = Vec::new();
     match obj {
         Object::Commit(commit) => {
-            show_commit(&repo, &commit, &oid, args, decorations.as_ref(), &mut buf)?;
+            show_commit(
+                &repo,
+                &commit,
+                &oid,
+                args,
+                decorations.as_ref(),
+                mailmap.as_ref(),
+                effective,
+                &mut buf,
+            )?;
         }
         Object::Tag(tag) => show_tag(&repo, &tag, &oid, &mut buf)?,
         Object::Tree(tree) => show_tree(&tree, &oid, &mut buf)?,
@@ -122,37 +150,41 @@ fn show_commit(
     oid: &ObjectId,
     args: &ShowArgs,
     decorations: Option<&std::collections::HashMap<ObjectId, Vec<String>>>,
+    mailmap: Option<&git_utils::mailmap::Mailmap>,
+    color: git_utils::color::ColorMode,
     out: &mut impl Write,
 ) -> Result<()> {
-    let format_options = FormatOptions { abbrev_len: 40, ..FormatOptions::default() };
+    let format_options = FormatOptions { abbrev_len: 40, color, ..FormatOptions::default() };
 
     // Determine format
     let fmt_str = args.format.as_deref();
-    let (preset, custom_format) = match fmt_str {
-        Some("oneline") => (BuiltinFormat::Oneline, None),
-        Some("short") => (BuiltinFormat::Short, None),
-        Some("full") => (BuiltinFormat::Full, None),
-        Some("fuller") => (BuiltinFormat::Fuller, None),
-        Some("raw") => (BuiltinFormat::Raw, None),
-        Some("medium") | None => (BuiltinFormat::Medium, None),
-        Some(custom) => {
-            let fmt = if let Some(stripped) = custom.strip_prefix("format:") {
-                stripped
-            } else if let Some(stripped) = custom.strip_prefix("tformat:") {
-                stripped
-            } else {
-                custom
-            };
-            (BuiltinFormat::Medium, Some(fmt.to_string()))
-        }
+    let (preset, custom_format, format_terminator) = match fmt_str {
+        None => (BuiltinFormat::Medium, None, false),
+        Some(arg) => match resolve_format(arg, repo.config()) {
+            Some(ResolvedFormat::Builtin(builtin)) => (builtin, None, false),
+            Some(ResolvedFormat::User { template, terminator }) => {
+                (BuiltinFormat::Medium, Some(template), terminator)
+            }
+            None => (BuiltinFormat::Medium, None, false),
+        },
     };
 
     if let Some(ref fmt) = custom_format {
         let formatted =
-            format_commit_with_decorations(commit, oid, fmt, &format_options, decorations);
+            format_commit_with_decorations(commit, oid, fmt, &format_options, mailmap, decorations);
         write!(out, "{}", formatted)?;
-        writeln!(out)?;
+        if format_terminator && !formatted.ends_with('\n') {
+            writeln!(out)?;
+        }
     } else {
+        // Builtin presets have no raw/mailmap-resolved distinction in their
+        // fixed layout, so mailmap (if requested) is applied to the whole
+        // commit up front, matching --use-mailmap's effect in `gitr log`.
+        let display_commit = match mailmap {
+            Some(mm) => apply_mailmap(commit, mm),
+            None => commit.clone(),
+        };
+
         // For medium/full/fuller: add merge header if merge commit
         if commit.parents.len() > 1
             && matches!(
@@ -163,7 +195,7 @@ fn show_commit(
             // The builtin format starts with "commit <oid>\n".
             // We need to inject "Merge: <parent1> <parent2>" after that line.
             let formatted = format_builtin_with_decorations(
-                commit,
+                &display_commit,
                 oid,
                 preset,
                 &format_options,
@@ -185,7 +217,7 @@ fn show_commit(
             }
         } else {
             let formatted = format_builtin_with_decorations(
-                commit,
+                &display_commit,
                 oid,
                 preset,
                 &format_options,
@@ -199,6 +231,18 @@ fn show_commit(
         }
     }
 
+    // --show-notes: append the commit's note, indented, after its body
+    if args.show_notes {
+        let notes_ref_name = args.notes_ref.as_deref().unwrap_or("refs/notes/commits");
+        if let Some(text) = super::notes::lookup_note_text(repo, notes_ref_name, oid)? {
+            writeln!(out)?;
+            writeln!(out, "Notes:")?;
+            for line in text.lines() {
+                writeln!(out, "    {}", line)?;
+            }
+        }
+    }
+
     // Show diff unless --no-patch or --quiet
     let suppress_diff = args.no_patch || args.quiet;
     if !suppress_diff && custom_format.is_none() {
@@ -386,6 +430,19 @@ fn load_color_config(repo: &git_repository::Repository) -> ColorConfig {
     ColorConfig::from_config(|key| config.get_string(key).ok().flatten())
 }
 
+/// Apply mailmap transformations to a commit's author and committer.
+fn apply_mailmap(commit: &Commit, mm: &git_utils::mailmap::Mailmap) -> Commit {
+    let mut commit = commit.clone();
+    let (author_name, author_email) = mm.lookup(&commit.author.name, &commit.author.email);
+    commit.author.name = author_name;
+    commit.author.email = author_email;
+    let (committer_name, committer_email) =
+        mm.lookup(&commit.committer.name, &commit.committer.email);
+    commit.committer.name = committer_name;
+    commit.committer.email = committer_email;
+    commit
+}
+
 /// Colorize a single line of `show` output (commit header + diff).
 fn colorize_show_line(line: &str, cc: &ColorConfig, in_diff: &mut bool) -> String {
     let reset = cc.get_color(ColorSlot::Reset);
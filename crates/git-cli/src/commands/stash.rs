@@ -101,7 +101,7 @@ fn stash_push(cli: &Cli, message: Option<&str>, include_untracked: bool) -> Resu
 
     // Build tree from current index (staged state)
     let index_path = repo.git_dir().join("index");
-    let current_index = Index::read_from(&index_path)?;
+    let mut current_index = Index::read_from(&index_path)?;
     let index_tree_oid = current_index.write_tree(repo.odb())?;
 
     // Build worktree tree: start with index entries, replace with worktree content
@@ -306,7 +306,7 @@ fn collect_untracked_files(
                 let metadata = std::fs::symlink_metadata(&path)?;
                 let mode = file_mode_from_metadata(&metadata);
                 index.add(IndexEntry {
-                    path: BString::from(rel),
+                    path: BString::from(rel).into(),
                     oid: blob_oid,
                     mode,
                     stage: Stage::Normal,
@@ -393,7 +393,7 @@ fn print_long_status(
     Ok(())
 }
 
-fn find_untracked(
+pub(crate) fn find_untracked(
     repo: &mut git_repository::Repository,
     work_tree: &Path,
 ) -> Result<Vec<BString>> {
@@ -410,7 +410,7 @@ fn find_untracked(
     // Collect all index paths
     let indexed_paths: std::collections::HashSet<BString> = {
         let index = repo.index()?;
-        index.iter().map(|e| e.path.clone()).collect()
+        index.iter().map(|e| e.path.to_bstring()).collect()
     };
 
     let mut untracked = Vec::new();
@@ -551,7 +551,7 @@ fn find_untracked_all(
 
     let indexed_paths: std::collections::HashSet<BString> = {
         let index = repo.index()?;
-        index.iter().map(|e| e.path.clone()).collect()
+        index.iter().map(|e| e.path.to_bstring()).collect()
     };
 
     let mut untracked = Vec::new();
@@ -612,7 +612,7 @@ fn find_ignored_files(
 
     let indexed_paths: std::collections::HashSet<BString> = {
         let index = repo.index()?;
-        index.iter().map(|e| e.path.clone()).collect()
+        index.iter().map(|e| e.path.to_bstring()).collect()
     };
 
     let mut ignored = Vec::new();
@@ -177,10 +177,10 @@ fn checkout_commit(repo: &mut git_repository::Repository, oid: &ObjectId) -> Res
     // Clean up files from old index that aren't in new tree
     let old_paths: std::collections::HashSet<BString> = {
         let index = repo.index()?;
-        index.iter().map(|e| e.path.clone()).collect()
+        index.iter().map(|e| e.path.to_bstring()).collect()
     };
 
-    let new_paths: std::collections::HashSet<BString> = new_entries.iter().map(|e| e.path.clone()).collect();
+    let new_paths: std::collections::HashSet<BString> = new_entries.iter().map(|e| e.path.to_bstring()).collect();
 
     for old_path in &old_paths {
         if !new_paths.contains(old_path) {
@@ -253,7 +253,7 @@ fn checkout_tree_recursive(
 
             let metadata = std::fs::symlink_metadata(&file_path)?;
             entries.push(IndexEntry {
-                path,
+                path: path.into(),
                 oid: entry.oid,
                 mode: entry.mode,
                 stage: Stage::Normal,
@@ -60,7 +60,7 @@ pub fn run(args: &UpdateIndexArgs, cli: &Cli) -> Result<i32> {
         let path = BString::from(parts[2]);
 
         let entry = IndexEntry {
-            path,
+            path: path.into(),
             oid,
             mode,
             stage: Stage::Normal,
@@ -122,7 +122,7 @@ pub fn run(args: &UpdateIndexArgs, cli: &Cli) -> Result<i32> {
             };
 
             let entry = IndexEntry {
-                path: BString::from(path_str.as_str()),
+                path: BString::from(path_str.as_str()).into(),
                 oid,
                 mode,
                 stage: Stage::Normal,
@@ -6,19 +6,33 @@ use clap::Args;
 use git_utils::date::{GitDate, Signature};
 
 use crate::Cli;
-use super::open_repo;
+use super::{load_mailmap, open_repo};
 
 #[derive(Args)]
 pub struct VarArgs {
+    /// List all logical variables, followed by all config entries
+    #[arg(short = 'l', long)]
+    list: bool,
+
     /// Variable name (e.g., GIT_AUTHOR_IDENT, GIT_COMMITTER_IDENT, GIT_EDITOR, GIT_PAGER)
-    variable: String,
+    variable: Option<String>,
 }
 
 pub fn run(args: &VarArgs, cli: &Cli) -> Result<i32> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
 
-    match args.variable.as_str() {
+    if args.list {
+        let repo = open_repo(cli)?;
+        list_variables(&repo, &mut out)?;
+        return Ok(0);
+    }
+
+    let variable = args.variable.as_deref().ok_or_else(|| {
+        anyhow::anyhow!("usage: git var [-l | <variable>]")
+    })?;
+
+    match variable {
         "GIT_AUTHOR_IDENT" => {
             let repo = open_repo(cli)?;
             let sig = build_identity("GIT_AUTHOR_NAME", "GIT_AUTHOR_EMAIL", "GIT_AUTHOR_DATE", &repo)?;
@@ -52,7 +66,7 @@ pub fn run(args: &VarArgs, cli: &Cli) -> Result<i32> {
             writeln!(out, "{}", branch)?;
         }
         _ => {
-            eprintln!("error: unknown variable '{}'", args.variable);
+            eprintln!("error: unknown variable '{}'", variable);
             return Ok(1);
         }
     }
@@ -60,6 +74,52 @@ pub fn run(args: &VarArgs, cli: &Cli) -> Result<i32> {
     Ok(0)
 }
 
+/// Print every logical variable in `name=value` form, followed by all config
+/// entries the way `git config -l` emits them. The fixed variable names are
+/// printed in the stable order below and each config entry in the stable
+/// (file-precedence) order [`git_config::ConfigSet::all_entries`] already
+/// returns, so the output is deterministic across runs.
+fn list_variables(repo: &git_repository::Repository, out: &mut impl Write) -> Result<()> {
+    let author = build_identity("GIT_AUTHOR_NAME", "GIT_AUTHOR_EMAIL", "GIT_AUTHOR_DATE", repo)?;
+    writeln!(out, "GIT_AUTHOR_IDENT={}", std::str::from_utf8(&author.to_bytes()).unwrap_or(""))?;
+
+    let committer = build_identity("GIT_COMMITTER_NAME", "GIT_COMMITTER_EMAIL", "GIT_COMMITTER_DATE", repo)?;
+    writeln!(out, "GIT_COMMITTER_IDENT={}", std::str::from_utf8(&committer.to_bytes()).unwrap_or(""))?;
+
+    let editor = std::env::var("GIT_EDITOR")
+        .or_else(|_| std::env::var("VISUAL"))
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    writeln!(out, "GIT_EDITOR={}", editor)?;
+
+    let pager = std::env::var("GIT_PAGER")
+        .or_else(|_| std::env::var("PAGER"))
+        .unwrap_or_else(|_| "less".to_string());
+    writeln!(out, "GIT_PAGER={}", pager)?;
+
+    let branch = repo.config()
+        .get_string("init.defaultBranch")
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "main".to_string());
+    writeln!(out, "GIT_DEFAULT_BRANCH={}", branch)?;
+
+    for entry in &repo.config().all_entries() {
+        let key_str = entry.key.to_canonical();
+        let value_str = entry
+            .value
+            .as_ref()
+            .map(|v| v.to_str_lossy().to_string())
+            .unwrap_or_default();
+        writeln!(out, "{}={}", key_str, value_str)?;
+    }
+
+    Ok(())
+}
+
+/// Resolve `GIT_*_IDENT`-style identity, canonicalized through the
+/// repository's mailmap (see [`super::load_mailmap`]) the same way
+/// `log`/`shortlog`/`blame` already are.
 fn build_identity(
     name_var: &str,
     email_var: &str,
@@ -77,14 +137,19 @@ fn build_identity(
         .unwrap_or_else(|| "unknown@unknown".to_string());
 
     let date = if let Ok(date_str) = std::env::var(date_var) {
-        GitDate::parse_raw(&date_str)?
+        GitDate::parse_approxidate_now(&date_str)?
     } else {
         GitDate::now()
     };
 
-    Ok(Signature {
+    let sig = Signature {
         name: BString::from(name),
         email: BString::from(email),
         date,
+    };
+
+    Ok(match load_mailmap(repo) {
+        Some(mailmap) => mailmap.resolve(&sig),
+        None => sig,
     })
 }
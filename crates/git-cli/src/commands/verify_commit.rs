@@ -1,9 +1,9 @@
 use std::io::{self, Write};
-use std::process::Command;
 
 use anyhow::{bail, Result};
 use clap::Args;
 use git_object::Object;
+use git_repository::gpg::{GpgSigner, GpgVerifyResult, SignatureStatus, TrustLevel};
 
 use crate::Cli;
 use super::open_repo;
@@ -29,6 +29,7 @@ pub fn run(args: &VerifyCommitArgs, cli: &Cli) -> Result<i32> {
     let stderr = io::stderr();
     let mut err = stderr.lock();
 
+    let signer = GpgSigner::from_config(repo.config());
     let mut all_valid = true;
 
     for spec in &args.commits {
@@ -44,18 +45,19 @@ pub fn run(args: &VerifyCommitArgs, cli: &Cli) -> Result<i32> {
         };
 
         if let Some(ref sig) = commit.gpgsig {
-            // Extract the signature and signed payload
-            let signed_content = build_signed_commit_content(&commit, &oid);
+            let mut unsigned = commit.clone();
+            unsigned.gpgsig = None;
+            let signed_content = unsigned.serialize_content();
 
-            match verify_gpg_signature(&signed_content, sig.as_ref()) {
-                Ok(output) => {
+            match signer.verify(&signed_content, sig.as_ref()) {
+                Ok(result) => {
                     if args.raw {
-                        out.write_all(output.status.as_bytes())?;
+                        out.write_all(result.raw_status.as_bytes())?;
                     }
                     if args.verbose {
-                        writeln!(err, "{}", output.summary)?;
+                        print_verbose(&mut err, &oid, &result)?;
                     }
-                    if !output.valid {
+                    if result.status != SignatureStatus::Good {
                         all_valid = false;
                         writeln!(err, "error: commit {} has a bad GPG signature", oid.to_hex())?;
                     }
@@ -78,78 +80,42 @@ pub fn run(args: &VerifyCommitArgs, cli: &Cli) -> Result<i32> {
     }
 }
 
-struct GpgOutput {
-    valid: bool,
-    summary: String,
-    status: String,
-}
-
-/// Verify a GPG signature by calling the gpg binary.
-fn verify_gpg_signature(signed_content: &[u8], signature: &[u8]) -> Result<GpgOutput> {
-    let tmp_dir = tempfile::tempdir()?;
-    let sig_path = tmp_dir.path().join("signature.sig");
-    let content_path = tmp_dir.path().join("content");
-
-    std::fs::write(&sig_path, signature)?;
-    std::fs::write(&content_path, signed_content)?;
-
-    let output = Command::new("gpg")
-        .args(["--status-fd=1", "--verify"])
-        .arg(&sig_path)
-        .arg(&content_path)
-        .output();
-
-    match output {
-        Ok(output) => {
-            let status = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr_str = String::from_utf8_lossy(&output.stderr).to_string();
-            let valid = output.status.success();
-
-            Ok(GpgOutput {
-                valid,
-                summary: stderr_str,
-                status,
-            })
+fn print_verbose(
+    err: &mut impl Write,
+    oid: &git_hash::ObjectId,
+    result: &GpgVerifyResult,
+) -> Result<()> {
+    let signer_name = result.signer.as_deref().unwrap_or("unknown");
+    match result.status {
+        SignatureStatus::Good if result.expired_key => {
+            writeln!(err, "gpg: Good signature from \"{}\" (key has expired)", signer_name)?;
+        }
+        SignatureStatus::Good => {
+            writeln!(err, "gpg: Good signature from \"{}\"", signer_name)?;
+        }
+        SignatureStatus::Bad => {
+            writeln!(err, "gpg: BAD signature from \"{}\"", signer_name)?;
         }
-        Err(e) => {
-            bail!("failed to run gpg: {}", e);
+        SignatureStatus::Unknown => {
+            writeln!(err, "gpg: Can't check signature: No public key")?;
         }
     }
-}
-
-/// Reconstruct the signed content from a commit (everything except the gpgsig header).
-fn build_signed_commit_content(commit: &git_object::Commit, _oid: &git_hash::ObjectId) -> Vec<u8> {
-    // Serialize the commit without the gpgsig field
-    let mut content = Vec::new();
-    content.extend_from_slice(b"tree ");
-    content.extend_from_slice(commit.tree.to_hex().as_bytes());
-    content.push(b'\n');
-
-    for parent in &commit.parents {
-        content.extend_from_slice(b"parent ");
-        content.extend_from_slice(parent.to_hex().as_bytes());
-        content.push(b'\n');
+    if let Some(ref key_id) = result.key_id {
+        writeln!(err, "gpg:                using key {}", key_id)?;
     }
-
-    content.extend_from_slice(b"author ");
-    content.extend_from_slice(&commit.author.to_bytes());
-    content.push(b'\n');
-
-    content.extend_from_slice(b"committer ");
-    content.extend_from_slice(&commit.committer.to_bytes());
-    content.push(b'\n');
-
-    for (key, value) in &commit.extra_headers {
-        if key.as_slice() != b"gpgsig" {
-            content.extend_from_slice(key.as_slice());
-            content.push(b' ');
-            content.extend_from_slice(value.as_slice());
-            content.push(b'\n');
-        }
+    if let Some(ref fingerprint) = result.fingerprint {
+        writeln!(err, "gpg:                fingerprint {}", fingerprint)?;
     }
-
-    content.push(b'\n');
-    content.extend_from_slice(&commit.message);
-
-    content
+    if let Some(trust) = result.trust_level {
+        let label = match trust {
+            TrustLevel::Ultimate => "ultimate",
+            TrustLevel::Fully => "full",
+            TrustLevel::Marginal => "marginal",
+            TrustLevel::Never => "never",
+            TrustLevel::Undefined => "undefined",
+        };
+        writeln!(err, "gpg:                trust: {}", label)?;
+    }
+    writeln!(err, "commit {}: {}", oid.to_hex(), result.summary_char())?;
+    Ok(())
 }
@@ -1,10 +1,10 @@
 use std::io::{self, Write};
-use std::process::Command;
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 use clap::Args;
 use git_object::Object;
 use git_ref::{RefName, RefStore};
+use git_repository::gpg::{GpgSigner, GpgVerifyResult, SignatureStatus, TrustLevel};
 use bstr::BString;
 
 use crate::Cli;
@@ -30,9 +30,12 @@ pub struct VerifyTagArgs {
 
 pub fn run(args: &VerifyTagArgs, cli: &Cli) -> Result<i32> {
     let repo = open_repo(cli)?;
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
     let stderr = io::stderr();
     let mut err = stderr.lock();
 
+    let signer = GpgSigner::from_config(repo.config());
     let mut all_valid = true;
 
     for tag_name in &args.tags {
@@ -51,15 +54,19 @@ pub fn run(args: &VerifyTagArgs, cli: &Cli) -> Result<i32> {
         match obj {
             Object::Tag(tag) => {
                 if let Some(ref sig) = tag.gpgsig {
-                    // Build the signed content
-                    let signed_content = build_signed_tag_content(&tag);
-
-                    match verify_gpg_signature(&signed_content, sig.as_ref()) {
-                        Ok(output) => {
+                    let mut unsigned = tag.clone();
+                    unsigned.gpgsig = None;
+                    let signed_content = unsigned.serialize_content();
+
+                    match signer.verify(&signed_content, sig.as_ref()) {
+                        Ok(result) => {
+                            if args.raw {
+                                out.write_all(result.raw_status.as_bytes())?;
+                            }
                             if args.verbose {
-                                writeln!(err, "{}", output.summary)?;
+                                print_verbose(&mut err, tag_name, &result)?;
                             }
-                            if !output.valid {
+                            if result.status != SignatureStatus::Good {
                                 all_valid = false;
                                 writeln!(err, "error: tag '{}' has a bad signature", tag_name)?;
                             }
@@ -89,63 +96,38 @@ pub fn run(args: &VerifyTagArgs, cli: &Cli) -> Result<i32> {
     }
 }
 
-struct GpgOutput {
-    valid: bool,
-    summary: String,
-}
-
-fn verify_gpg_signature(signed_content: &[u8], signature: &[u8]) -> Result<GpgOutput> {
-    let tmp_dir = tempfile::tempdir()?;
-    let sig_path = tmp_dir.path().join("signature.sig");
-    let content_path = tmp_dir.path().join("content");
-
-    std::fs::write(&sig_path, signature)?;
-    std::fs::write(&content_path, signed_content)?;
-
-    let output = Command::new("gpg")
-        .args(["--status-fd=1", "--verify"])
-        .arg(&sig_path)
-        .arg(&content_path)
-        .output();
-
-    match output {
-        Ok(output) => {
-            let stderr_str = String::from_utf8_lossy(&output.stderr).to_string();
-            let valid = output.status.success();
-
-            Ok(GpgOutput {
-                valid,
-                summary: stderr_str,
-            })
+fn print_verbose(err: &mut impl Write, tag_name: &str, result: &GpgVerifyResult) -> Result<()> {
+    let signer_name = result.signer.as_deref().unwrap_or("unknown");
+    match result.status {
+        SignatureStatus::Good if result.expired_key => {
+            writeln!(err, "gpg: Good signature from \"{}\" (key has expired)", signer_name)?;
+        }
+        SignatureStatus::Good => {
+            writeln!(err, "gpg: Good signature from \"{}\"", signer_name)?;
+        }
+        SignatureStatus::Bad => {
+            writeln!(err, "gpg: BAD signature from \"{}\"", signer_name)?;
         }
-        Err(e) => {
-            bail!("failed to run gpg: {}", e);
+        SignatureStatus::Unknown => {
+            writeln!(err, "gpg: Can't check signature: No public key")?;
         }
     }
-}
-
-fn build_signed_tag_content(tag: &git_object::Tag) -> Vec<u8> {
-    let mut content = Vec::new();
-    content.extend_from_slice(b"object ");
-    content.extend_from_slice(tag.target.to_hex().as_bytes());
-    content.push(b'\n');
-
-    content.extend_from_slice(b"type ");
-    content.extend_from_slice(tag.target_type.as_bytes());
-    content.push(b'\n');
-
-    content.extend_from_slice(b"tag ");
-    content.extend_from_slice(&tag.tag_name);
-    content.push(b'\n');
-
-    if let Some(ref tagger) = tag.tagger {
-        content.extend_from_slice(b"tagger ");
-        content.extend_from_slice(&tagger.to_bytes());
-        content.push(b'\n');
+    if let Some(ref key_id) = result.key_id {
+        writeln!(err, "gpg:                using key {}", key_id)?;
     }
-
-    content.push(b'\n');
-    content.extend_from_slice(&tag.message);
-
-    content
+    if let Some(ref fingerprint) = result.fingerprint {
+        writeln!(err, "gpg:                fingerprint {}", fingerprint)?;
+    }
+    if let Some(trust) = result.trust_level {
+        let label = match trust {
+            TrustLevel::Ultimate => "ultimate",
+            TrustLevel::Fully => "full",
+            TrustLevel::Marginal => "marginal",
+            TrustLevel::Never => "never",
+            TrustLevel::Undefined => "undefined",
+        };
+        writeln!(err, "gpg:                trust: {}", label)?;
+    }
+    writeln!(err, "tag {}: {}", tag_name, result.summary_char())?;
+    Ok(())
 }
@@ -7,8 +7,8 @@ use git_diff::format::format_diff;
 use git_diff::{DiffOptions, DiffOutputFormat};
 use git_object::{Commit, Object};
 use git_revwalk::{
-    format_builtin_with_decorations, format_commit_with_decorations, BuiltinFormat, FormatOptions,
-    RevWalk, SortOrder, WalkOptions,
+    format_builtin_with_decorations, resolve_format, BuiltinFormat, FormatOptions, ParsedFormat,
+    ResolvedFormat, RevWalk, SortOrder, WalkOptions,
 };
 
 use super::open_repo;
@@ -60,6 +60,10 @@ pub struct WhatchangedArgs {
     #[arg(long)]
     reverse: bool,
 
+    /// Apply mailmap transformations
+    #[arg(long)]
+    use_mailmap: bool,
+
     /// Revision range or starting point
     #[arg(value_name = "revision")]
     revisions: Vec<String>,
@@ -75,8 +79,16 @@ pub fn run(args: &WhatchangedArgs, cli: &Cli) -> Result<i32> {
     let mut out = stdout.lock();
 
     // Parse format
-    let (builtin, custom_format) = parse_format(args);
+    let (builtin, custom_format, format_terminator) = parse_format(args, &repo);
     let format_options = FormatOptions::default();
+    let parsed_format = custom_format.as_deref().map(ParsedFormat::parse);
+
+    // Load mailmap if requested, honoring mailmap.file/mailmap.blob
+    let mailmap = if args.use_mailmap {
+        super::load_mailmap(&repo)
+    } else {
+        None
+    };
 
     // Build walk options
     let mut walk_opts = WalkOptions {
@@ -159,21 +171,30 @@ pub fn run(args: &WhatchangedArgs, cli: &Cli) -> Result<i32> {
         }
 
         // Separator between commits
-        let needs_separator = custom_format.is_none() && builtin != BuiltinFormat::Oneline;
+        let is_separator_format = custom_format.is_some() && !format_terminator;
+        let needs_separator =
+            (custom_format.is_none() && builtin != BuiltinFormat::Oneline) || is_separator_format;
         if needs_separator && !first_commit {
             writeln!(out)?;
         }
         first_commit = false;
 
-        // Format commit header
-        let formatted = if let Some(ref fmt) = custom_format {
-            format_commit_with_decorations(&commit, &oid, fmt, &format_options, None)
+        // Format commit header. Builtin presets have no raw/mailmap-resolved
+        // distinction in their fixed layout, so mailmap (if requested) is
+        // applied to the whole commit up front, matching --use-mailmap's
+        // effect in `gitr log`.
+        let formatted = if let Some(ref parsed) = parsed_format {
+            parsed.render(&commit, &oid, &format_options, mailmap.as_ref(), None)
         } else {
-            format_builtin_with_decorations(&commit, &oid, builtin, &format_options, None)
+            let display_commit = match &mailmap {
+                Some(mm) => apply_mailmap(&commit, mm),
+                None => commit.clone(),
+            };
+            format_builtin_with_decorations(&display_commit, &oid, builtin, &format_options, None)
         };
 
         write!(out, "{}", formatted)?;
-        if custom_format.is_some() || builtin == BuiltinFormat::Oneline {
+        if (custom_format.is_some() && format_terminator) || builtin == BuiltinFormat::Oneline {
             writeln!(out)?;
         }
 
@@ -255,34 +276,41 @@ fn commit_touches_paths(
     }
 }
 
-fn parse_format(args: &WhatchangedArgs) -> (BuiltinFormat, Option<String>) {
+fn parse_format(
+    args: &WhatchangedArgs,
+    repo: &git_repository::Repository,
+) -> (BuiltinFormat, Option<String>, bool) {
     let fmt_str = args.format.as_deref().or(args.pretty.as_deref());
 
     match fmt_str {
-        Some("oneline") => (BuiltinFormat::Oneline, None),
-        Some("short") => (BuiltinFormat::Short, None),
-        Some("medium") => (BuiltinFormat::Medium, None),
-        Some("full") => (BuiltinFormat::Full, None),
-        Some("fuller") => (BuiltinFormat::Fuller, None),
-        Some("email") => (BuiltinFormat::Email, None),
-        Some("raw") => (BuiltinFormat::Raw, None),
-        Some(custom) => {
-            let fmt = if let Some(stripped) = custom.strip_prefix("format:") {
-                stripped
-            } else if let Some(stripped) = custom.strip_prefix("tformat:") {
-                stripped
-            } else {
-                custom
-            };
-            (BuiltinFormat::Medium, Some(fmt.to_string()))
-        }
-        None => (BuiltinFormat::Medium, None),
+        None => (BuiltinFormat::Medium, None, false),
+        Some(arg) => match resolve_format(arg, repo.config()) {
+            Some(ResolvedFormat::Builtin(builtin)) => (builtin, None, false),
+            Some(ResolvedFormat::User { template, terminator }) => {
+                (BuiltinFormat::Medium, Some(template), terminator)
+            }
+            None => (BuiltinFormat::Medium, None, false),
+        },
     }
 }
 
+/// Apply mailmap transformations to a commit's author and committer.
+fn apply_mailmap(commit: &Commit, mm: &git_utils::mailmap::Mailmap) -> Commit {
+    let mut commit = commit.clone();
+    let (author_name, author_email) = mm.lookup(&commit.author.name, &commit.author.email);
+    commit.author.name = author_name;
+    commit.author.email = author_email;
+    let (committer_name, committer_email) =
+        mm.lookup(&commit.committer.name, &commit.committer.email);
+    commit.committer.name = committer_name;
+    commit.committer.email = committer_email;
+    commit
+}
+
+/// Parse a `--since`/`--until` date, accepting bare epoch seconds as well as
+/// any approxidate form ("2 weeks ago", "yesterday", ISO/RFC dates, ...).
 fn parse_date(s: &str) -> Option<i64> {
-    if let Ok(ts) = s.parse::<i64>() {
-        return Some(ts);
-    }
-    None
+    git_utils::date::GitDate::parse_approxidate_now(s)
+        .ok()
+        .map(|d| d.timestamp)
 }
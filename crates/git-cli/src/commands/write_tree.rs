@@ -23,7 +23,7 @@ pub fn run(_args: &WriteTreeArgs, cli: &Cli) -> Result<i32> {
 
     // Load index directly to avoid mutable borrow of repo
     let index_path = repo.git_dir().join("index");
-    let index = Index::read_from(&index_path)?;
+    let mut index = Index::read_from(&index_path)?;
 
     if !index.conflicts().is_empty() {
         anyhow::bail!("cannot write tree: you have unmerged entries");
@@ -448,6 +448,7 @@ pub fn split_hunk(hunk: &Hunk) -> Vec<Hunk> {
             new_count: sub_new_count,
             header: hunk.header.clone(),
             lines,
+            locks: hunk.locks.clone(),
         });
     }
 
@@ -646,6 +647,7 @@ fn parse_edited_hunk(data: &[u8], original: &Hunk) -> Result<Option<Hunk>, Strin
         new_count: actual_new_count,
         header: hunk_header,
         lines: diff_lines,
+        locks: original.locks.clone(),
     }))
 }
 
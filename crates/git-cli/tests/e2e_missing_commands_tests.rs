@@ -5,6 +5,7 @@
 mod common;
 
 use common::*;
+use std::fs;
 
 // ──────────────────────────── whatchanged ────────────────────────────
 
@@ -212,7 +213,6 @@ fn test_fmt_merge_msg_basic() {
 }
 
 #[test]
-#[ignore] // gitr fmt-merge-msg --log includes different commit listing
 fn test_fmt_merge_msg_log() {
     let dir_git = tempfile::tempdir().unwrap();
     let dir_gitr = tempfile::tempdir().unwrap();
@@ -643,7 +643,6 @@ fn test_apply_check() {
 // ──────────────────────────── cherry ────────────────────────────
 
 #[test]
-#[ignore] // gitr cherry uses abbreviated OIDs and different ordering
 fn test_cherry_basic() {
     let dir_git = tempfile::tempdir().unwrap();
     let dir_gitr = tempfile::tempdir().unwrap();
@@ -655,7 +654,6 @@ fn test_cherry_basic() {
 }
 
 #[test]
-#[ignore] // gitr cherry -v uses abbreviated OIDs and different ordering
 fn test_cherry_verbose() {
     let dir_git = tempfile::tempdir().unwrap();
     let dir_gitr = tempfile::tempdir().unwrap();
@@ -680,6 +678,36 @@ fn test_credential_reject() {
     assert_exit_code_eq(&g, &m);
 }
 
+/// The built-in `credential-store` helper writes plaintext usernames and
+/// passwords, so the file it creates must never be world/group readable —
+/// not even momentarily between creation and a later chmod.
+#[test]
+#[cfg(unix)]
+fn test_credential_store_file_is_created_mode_0600() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    setup_empty_repo(dir.path());
+    let store_path = dir.path().join("credentials-store");
+
+    let config = gitr(
+        dir.path(),
+        &[
+            "config",
+            "credential.helper",
+            &format!("store --file={}", store_path.display()),
+        ],
+    );
+    assert_eq!(config.exit_code, 0, "config failed: {}", config.stderr);
+
+    let input = b"protocol=https\nhost=example.com\nusername=alice\npassword=hunter2\n\n";
+    let approve = gitr_stdin(dir.path(), &["credential", "approve"], input);
+    assert_eq!(approve.exit_code, 0, "credential approve failed: {}", approve.stderr);
+
+    let mode = fs::metadata(&store_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
 // ──────────────────────────── daemon ────────────────────────────
 
 #[test]
@@ -1,16 +1,47 @@
 //! Environment variable overrides for git configuration.
 
-use bstr::BString;
+use std::path::PathBuf;
+
+use bstr::{BStr, BString};
 
 use crate::error::ConfigError;
 use crate::{ConfigEntry, ConfigKey, ConfigScope};
 
+/// The result of scanning the environment for configuration overrides:
+/// both the individual key/value entries (from GIT_CONFIG_COUNT and
+/// GIT_CONFIG_PARAMETERS) and the file-redirect variables that control
+/// which system/global config files [`ConfigSet::load`](crate::ConfigSet::load)
+/// should read.
+#[derive(Debug, Default)]
+pub struct EnvOverrides {
+    /// Entries parsed from GIT_CONFIG_COUNT/KEY_N/VALUE_N and
+    /// GIT_CONFIG_PARAMETERS, in the order they should be applied.
+    pub entries: Vec<ConfigEntry>,
+    /// GIT_CONFIG_SYSTEM: path to use instead of `/etc/gitconfig`.
+    pub system_path: Option<PathBuf>,
+    /// GIT_CONFIG_GLOBAL: path to use instead of the usual global config
+    /// search (`$XDG_CONFIG_HOME/git/config`, `~/.gitconfig`).
+    pub global_path: Option<PathBuf>,
+    /// GIT_CONFIG_NOSYSTEM: skip the system config file entirely.
+    pub nosystem: bool,
+}
+
 /// Load configuration overrides from environment variables.
 ///
-/// Supports the GIT_CONFIG_COUNT / GIT_CONFIG_KEY_N / GIT_CONFIG_VALUE_N protocol.
-pub fn load_env_overrides() -> Result<Vec<ConfigEntry>, ConfigError> {
+/// Supports the GIT_CONFIG_COUNT / GIT_CONFIG_KEY_N / GIT_CONFIG_VALUE_N
+/// protocol as well as the older GIT_CONFIG_PARAMETERS variable, and
+/// surfaces the GIT_CONFIG_SYSTEM / GIT_CONFIG_GLOBAL / GIT_CONFIG_NOSYSTEM
+/// file-redirect variables alongside the parsed entries. Entries from both
+/// protocols compose: if both variables are set, GIT_CONFIG_PARAMETERS
+/// entries are applied first, followed by the GIT_CONFIG_COUNT ones,
+/// matching their relative precedence in C git.
+pub fn load_env_overrides() -> Result<EnvOverrides, ConfigError> {
     let mut entries = Vec::new();
 
+    if let Ok(params) = std::env::var("GIT_CONFIG_PARAMETERS") {
+        entries.extend(parse_config_parameters(&params)?);
+    }
+
     // GIT_CONFIG_COUNT / KEY / VALUE
     if let Ok(count_str) = std::env::var("GIT_CONFIG_COUNT") {
         let count: usize = count_str
@@ -38,9 +69,102 @@ pub fn load_env_overrides() -> Result<Vec<ConfigEntry>, ConfigError> {
         }
     }
 
+    Ok(EnvOverrides {
+        entries,
+        system_path: std::env::var_os("GIT_CONFIG_SYSTEM").map(PathBuf::from),
+        global_path: std::env::var_os("GIT_CONFIG_GLOBAL").map(PathBuf::from),
+        nosystem: std::env::var_os("GIT_CONFIG_NOSYSTEM").is_some(),
+    })
+}
+
+/// Parse the legacy GIT_CONFIG_PARAMETERS protocol: a space-separated list
+/// of single-quoted, shell-escaped `'key=value'` (or bare `'key'` for a
+/// boolean-true) tokens, as produced by `git -c key=value`'s propagation
+/// to child processes.
+fn parse_config_parameters(params: &str) -> Result<Vec<ConfigEntry>, ConfigError> {
+    let mut entries = Vec::new();
+    for token in sq_dequote_tokens(params)? {
+        let (key, value) = match token.split_once('=') {
+            Some((key, value)) => (key, Some(BString::from(value.as_bytes()))),
+            None => (token.as_str(), None),
+        };
+        entries.push(ConfigEntry {
+            key: ConfigKey::parse(key)?,
+            value,
+            scope: ConfigScope::Command,
+            source_file: None,
+            line_number: None,
+        });
+    }
     Ok(entries)
 }
 
+/// Split a whitespace-separated string of shell single-quoted tokens into
+/// their unquoted contents. Each token must be of the form `'...'`, with a
+/// literal `'` inside the token written as `'\''` (close-quote,
+/// escaped-quote, reopen-quote) -- the same convention `sq_quote_buf` in C
+/// git uses to produce GIT_CONFIG_PARAMETERS.
+fn sq_dequote_tokens(params: &str) -> Result<Vec<String>, ConfigError> {
+    let mut tokens = Vec::new();
+    let mut chars = params.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            break;
+        }
+
+        if chars.next() != Some('\'') {
+            return Err(ConfigError::InvalidKey(format!(
+                "malformed GIT_CONFIG_PARAMETERS (expected quoted token): {}",
+                params
+            )));
+        }
+
+        let mut token = String::new();
+        loop {
+            match chars.next() {
+                Some('\'') => {
+                    // Either the token is done, or this is the `'\''`
+                    // escape for a literal quote inside the token: the
+                    // quote just consumed closed the token, `\'` is an
+                    // escaped literal quote, and the final `'` reopens
+                    // the token.
+                    if chars.peek() == Some(&'\\') {
+                        let mut lookahead = chars.clone();
+                        lookahead.next();
+                        if lookahead.next() == Some('\'') {
+                            chars.next(); // backslash
+                            chars.next(); // escaped quote
+                            if chars.next() != Some('\'') {
+                                return Err(ConfigError::InvalidKey(format!(
+                                    "malformed GIT_CONFIG_PARAMETERS (expected requote after escape): {}",
+                                    params
+                                )));
+                            }
+                            token.push('\'');
+                            continue;
+                        }
+                    }
+                    break;
+                }
+                Some(c) => token.push(c),
+                None => {
+                    return Err(ConfigError::InvalidKey(format!(
+                        "malformed GIT_CONFIG_PARAMETERS (unterminated quote): {}",
+                        params
+                    )));
+                }
+            }
+        }
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,7 +178,35 @@ mod tests {
         // When GIT_CONFIG_COUNT is not set, should return empty
         // (This test assumes the env var is not set in the test environment)
         std::env::remove_var("GIT_CONFIG_COUNT");
-        let entries = load_env_overrides().unwrap();
-        assert!(entries.is_empty());
+        std::env::remove_var("GIT_CONFIG_PARAMETERS");
+        let overrides = load_env_overrides().unwrap();
+        assert!(overrides.entries.is_empty());
+    }
+
+    #[test]
+    fn dequote_simple_tokens() {
+        let tokens = sq_dequote_tokens("'user.name=Alice' 'core.bare'").unwrap();
+        assert_eq!(tokens, vec!["user.name=Alice".to_string(), "core.bare".to_string()]);
+    }
+
+    #[test]
+    fn dequote_escaped_quote() {
+        let tokens = sq_dequote_tokens(r"'user.name=O'\''Brien'").unwrap();
+        assert_eq!(tokens, vec!["user.name=O'Brien".to_string()]);
+    }
+
+    #[test]
+    fn dequote_unterminated_quote_fails() {
+        assert!(sq_dequote_tokens("'user.name=Alice").is_err());
+    }
+
+    #[test]
+    fn parse_config_parameters_composes_kv_and_bool() {
+        let entries = parse_config_parameters("'user.name=Alice' 'core.bare'").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key.name, BString::from("name"));
+        assert_eq!(entries[0].value.as_deref(), Some(BStr::new("Alice")));
+        assert_eq!(entries[1].key.name, BString::from("bare"));
+        assert_eq!(entries[1].value, None);
     }
 }
@@ -37,3 +37,16 @@ pub enum ConfigError {
     #[error("lock error: {0}")]
     Lock(#[from] git_utils::UtilError),
 }
+
+impl ConfigError {
+    /// Whether this error describes a malformed *value* (a bad boolean,
+    /// integer, or color) rather than a structural problem (a bad
+    /// key, a missing/unreadable file, or a broken include chain). Lenient
+    /// config accessors only downgrade this kind of error to "absent".
+    pub fn is_value_error(&self) -> bool {
+        matches!(
+            self,
+            ConfigError::InvalidBool(_) | ConfigError::InvalidInt(_) | ConfigError::InvalidColor(_)
+        )
+    }
+}
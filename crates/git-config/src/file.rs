@@ -251,6 +251,100 @@ impl ConfigFile {
         }
     }
 
+    /// Append a new entry for `key`, even if one already exists — used for
+    /// multi-valued keys like `remote.<name>.fetch` where each occurrence is
+    /// semantically distinct rather than a single value to overwrite.
+    ///
+    /// Inserted after the last entry in the matching section if the section
+    /// exists, otherwise a new section is created at the end of the file.
+    pub fn append(&mut self, key: &ConfigKey, value: &BStr) {
+        let mut current_section = BString::new(Vec::new());
+        let mut current_subsection: Option<BString> = None;
+        let mut last_section_idx: Option<usize> = None;
+        let mut last_entry_in_section_idx: Option<usize> = None;
+
+        for (i, event) in self.events.iter().enumerate() {
+            match event {
+                ConfigEvent::SectionHeader {
+                    section,
+                    subsection,
+                    ..
+                } => {
+                    current_section = section.clone();
+                    current_subsection = subsection.clone();
+                    if key.section == current_section && key.subsection == current_subsection {
+                        last_section_idx = Some(i);
+                        last_entry_in_section_idx = None;
+                    }
+                }
+                ConfigEvent::Entry { .. } => {
+                    if key.section == current_section && key.subsection == current_subsection {
+                        last_entry_in_section_idx = Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let new_raw = format_entry(key.name.as_ref(), value);
+        let new_entry = ConfigEvent::Entry {
+            raw: new_raw,
+            key: key.name.clone(),
+            value: Some(value.to_owned()),
+            line_number: 0,
+        };
+
+        if let Some(insert_at) = last_entry_in_section_idx.or(last_section_idx) {
+            self.events.insert(insert_at + 1, new_entry);
+        } else {
+            let section_header = format_section_header(key.section.as_ref(), key.subsection.as_ref().map(|s| s.as_ref()));
+            self.events.push(ConfigEvent::SectionHeader {
+                raw: section_header,
+                section: key.section.clone(),
+                subsection: key.subsection.clone(),
+            });
+            self.events.push(new_entry);
+        }
+    }
+
+    /// Remove every occurrence of a key (for multi-valued keys). Returns the
+    /// number of entries removed.
+    pub fn remove_all(&mut self, key: &ConfigKey) -> usize {
+        let mut current_section = BString::new(Vec::new());
+        let mut current_subsection: Option<BString> = None;
+        let mut to_remove = Vec::new();
+
+        for (i, event) in self.events.iter().enumerate() {
+            match event {
+                ConfigEvent::SectionHeader {
+                    section,
+                    subsection,
+                    ..
+                } => {
+                    current_section = section.clone();
+                    current_subsection = subsection.clone();
+                }
+                ConfigEvent::Entry {
+                    key: entry_key, ..
+                } => {
+                    if key.section == current_section
+                        && key.subsection == current_subsection
+                        && key.name == *entry_key
+                    {
+                        to_remove.push(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let removed = to_remove.len();
+        for idx in to_remove.into_iter().rev() {
+            self.events.remove(idx);
+        }
+        removed
+    }
+
     /// Remove the first occurrence of a key. Returns true if found and removed.
     pub fn remove(&mut self, key: &ConfigKey) -> bool {
         let mut current_section = BString::new(Vec::new());
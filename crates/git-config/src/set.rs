@@ -17,6 +17,13 @@ pub struct ConfigSet {
     env_overrides: Vec<ConfigEntry>,
     /// Command-line overrides (-c key=value).
     command_overrides: Vec<ConfigEntry>,
+    /// Whether typed accessors (`get_bool`, `get_int`, ...) degrade a
+    /// malformed value to `None`/the caller's default instead of returning
+    /// an error. Defaults to `true`, matching upstream git's behavior of
+    /// not letting a single bad config line abort unrelated commands;
+    /// callers that need precise parse errors (e.g. `config --type=bool
+    /// --get`) opt into strict mode with `set_lenient(false)`.
+    lenient: bool,
 }
 
 impl ConfigSet {
@@ -26,6 +33,35 @@ impl ConfigSet {
             files: Vec::new(),
             env_overrides: Vec::new(),
             command_overrides: Vec::new(),
+            lenient: true,
+        }
+    }
+
+    /// Whether typed accessors currently degrade malformed values instead
+    /// of returning an error.
+    pub fn lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Switch between lenient (the default) and strict parsing of typed
+    /// values. In lenient mode a malformed value is reported as a warning
+    /// on stderr and treated as absent; in strict mode it is returned as
+    /// a `ConfigError`.
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Apply the lenient/strict policy to the result of a typed lookup:
+    /// in lenient mode, a parse error is warned about and downgraded to
+    /// `Ok(None)`; in strict mode (or for non-parse errors) it is returned
+    /// unchanged.
+    fn soften<T>(&self, key: &str, result: Result<Option<T>, ConfigError>) -> Result<Option<T>, ConfigError> {
+        match result {
+            Err(e) if self.lenient && e.is_value_error() => {
+                eprintln!("warning: ignoring invalid value for '{}': {}", key, e);
+                Ok(None)
+            }
+            other => other,
         }
     }
 
@@ -39,12 +75,11 @@ impl ConfigSet {
         // Load environment overrides first (they affect which files we load)
         let env_config = crate::env::load_env_overrides()?;
 
-        let skip_system = std::env::var_os("GIT_CONFIG_NOSYSTEM").is_some();
-
         // System config
-        if !skip_system {
-            let system_path = std::env::var_os("GIT_CONFIG_SYSTEM")
-                .map(PathBuf::from)
+        if !env_config.nosystem {
+            let system_path = env_config
+                .system_path
+                .clone()
                 .unwrap_or_else(|| PathBuf::from("/etc/gitconfig"));
             if system_path.exists() {
                 match ConfigFile::load(&system_path, ConfigScope::System) {
@@ -56,8 +91,7 @@ impl ConfigSet {
         }
 
         // Global config
-        let global_path = std::env::var_os("GIT_CONFIG_GLOBAL").map(PathBuf::from);
-        let global_paths = if let Some(path) = global_path {
+        let global_paths = if let Some(path) = env_config.global_path.clone() {
             vec![path]
         } else {
             let mut paths = Vec::new();
@@ -123,7 +157,7 @@ impl ConfigSet {
         }
 
         // Add environment overrides
-        set.env_overrides = env_config;
+        set.env_overrides = env_config.entries;
 
         Ok(set)
     }
@@ -249,16 +283,17 @@ impl ConfigSet {
         None
     }
 
-    /// Get as boolean.
+    /// Get as boolean. In the default lenient mode, a value that fails to
+    /// parse as a boolean is warned about and treated as absent rather
+    /// than returned as an error; call `set_lenient(false)` first to get
+    /// the precise parse error instead.
     pub fn get_bool(&self, key: &str) -> Result<Option<bool>, ConfigError> {
         let config_key = ConfigKey::parse(key)?;
-        match self.get_raw(&config_key) {
-            Some(value) => {
-                let result = types::parse_bool(value.as_deref().map(|v| v.as_bstr()))?;
-                Ok(Some(result))
-            }
+        let result = match self.get_raw(&config_key) {
+            Some(value) => types::parse_bool(value.as_deref().map(|v| v.as_bstr())).map(Some),
             None => Ok(None),
-        }
+        };
+        self.soften(key, result)
     }
 
     /// Get as boolean with default.
@@ -266,29 +301,31 @@ impl ConfigSet {
         Ok(self.get_bool(key)?.unwrap_or(default))
     }
 
-    /// Get as integer (with k/m/g suffix support).
+    /// Get as integer (with k/m/g suffix support). In the default lenient
+    /// mode, a value that fails to parse as an integer is warned about
+    /// and treated as absent rather than returned as an error.
     pub fn get_int(&self, key: &str) -> Result<Option<i64>, ConfigError> {
         let config_key = ConfigKey::parse(key)?;
-        match self.get_raw(&config_key) {
-            Some(Some(value)) => {
-                let result = types::parse_int(value.as_bstr())?;
-                Ok(Some(result))
-            }
+        let result = match self.get_raw(&config_key) {
+            Some(Some(value)) => types::parse_int(value.as_bstr()).map(Some),
             Some(None) => Err(ConfigError::InvalidInt("missing value".into())),
             None => Ok(None),
-        }
+        };
+        self.soften(key, result)
     }
 
-    /// Get as unsigned integer.
+    /// Get as unsigned integer. Subject to the same lenient/strict policy
+    /// as `get_int`.
     pub fn get_usize(&self, key: &str) -> Result<Option<usize>, ConfigError> {
-        match self.get_int(key)? {
+        let result = match self.get_int(key)? {
             Some(v) if v >= 0 => Ok(Some(v as usize)),
             Some(v) => Err(ConfigError::InvalidInt(format!(
                 "negative value {} for unsigned config",
                 v
             ))),
             None => Ok(None),
-        }
+        };
+        self.soften(key, result)
     }
 
     /// Get as path (with ~/ expansion).
@@ -304,17 +341,16 @@ impl ConfigSet {
         }
     }
 
-    /// Get as color specification.
+    /// Get as color specification. Subject to the same lenient/strict
+    /// policy as `get_bool`/`get_int`.
     pub fn get_color(&self, key: &str) -> Result<Option<ColorSpec>, ConfigError> {
         let config_key = ConfigKey::parse(key)?;
-        match self.get_raw(&config_key) {
-            Some(Some(value)) => {
-                let result = types::parse_color(value.as_bstr())?;
-                Ok(Some(result))
-            }
+        let result = match self.get_raw(&config_key) {
+            Some(Some(value)) => types::parse_color(value.as_bstr()).map(Some),
             Some(None) => Ok(Some(ColorSpec::default())),
             None => Ok(None),
-        }
+        };
+        self.soften(key, result)
     }
 
     // --- Enumeration ---
@@ -1,8 +1,10 @@
 //! Histogram diff algorithm.
 //!
-//! A variant of patience diff that uses occurrence counting
-//! to find unique matching lines. Tends to produce more readable
-//! diffs for code changes. Matches C git's xdiff/xhistogram.c.
+//! A variant of patience diff that anchors on the rarest matching line
+//! instead of requiring lines to be globally unique, then extends each
+//! candidate match into the longest run of equal lines around it. Tends to
+//! produce better anchors than patience while still coping with repeated
+//! lines. Matches C git's xdiff/xhistogram.c.
 
 use super::{Edit, EditOp, line_hash};
 use std::collections::HashMap;
@@ -43,6 +45,12 @@ pub fn diff(old: &[&[u8]], new: &[&[u8]]) -> Vec<Edit> {
 /// Maximum recursion depth before falling back to Myers.
 const MAX_RECURSION: usize = 64;
 
+/// Occurrence counts in the old-side table are capped at this value: past
+/// this point a line is common enough that it makes a poor anchor no matter
+/// how much more common it actually is, and capping keeps the table's chains
+/// bounded.
+const MAX_CHAIN: usize = 64;
+
 fn histogram_recurse(
     old: &[&[u8]],
     new: &[&[u8]],
@@ -134,77 +142,48 @@ fn histogram_recurse(
                 new_index: mid_new_offset,
             });
         }
-    } else {
-        // Build histogram of lines in old (hash -> (count, indices))
-        let mut histogram: HashMap<u64, (usize, Vec<usize>)> = HashMap::new();
-        for (i, line) in old_mid.iter().enumerate() {
-            let h = line_hash(line);
-            let entry = histogram.entry(h).or_insert((0, Vec::new()));
-            entry.0 += 1;
-            entry.1.push(i);
-        }
+    } else if let Some(anchor) = best_anchor(old_mid, new_mid) {
+        let LongestRun {
+            old_start,
+            new_start,
+            len,
+            ..
+        } = anchor;
 
-        // Find the lowest-occurrence line from old that also appears in new
-        let mut best_count = usize::MAX;
-        let mut best_old_idx = None;
-        let mut best_new_idx = None;
-
-        for (j, line) in new_mid.iter().enumerate() {
-            let h = line_hash(line);
-            if let Some((count, indices)) = histogram.get(&h) {
-                // Verify actual content match (hash collision check)
-                for &oi in indices {
-                    if old_mid[oi] == *line && *count < best_count {
-                        best_count = *count;
-                        best_old_idx = Some(oi);
-                        best_new_idx = Some(j);
-                    }
-                }
-            }
-        }
+        // Recurse on the gap before the anchor run.
+        histogram_recurse(
+            &old_mid[..old_start],
+            &new_mid[..new_start],
+            mid_old_offset,
+            mid_new_offset,
+            edits,
+            depth + 1,
+        );
 
-        if let (Some(oi), Some(ni)) = (best_old_idx, best_new_idx) {
-            // Found a pivot: recurse on segments before and after
-            histogram_recurse(
-                &old_mid[..oi],
-                &new_mid[..ni],
-                mid_old_offset,
-                mid_new_offset,
-                edits,
-                depth + 1,
-            );
-
-            // The matching line itself
+        for i in 0..len {
             edits.push(Edit {
                 op: EditOp::Equal,
-                old_index: mid_old_offset + oi,
-                new_index: mid_new_offset + ni,
+                old_index: mid_old_offset + old_start + i,
+                new_index: mid_new_offset + new_start + i,
             });
+        }
 
-            histogram_recurse(
-                &old_mid[oi + 1..],
-                &new_mid[ni + 1..],
-                mid_old_offset + oi + 1,
-                mid_new_offset + ni + 1,
-                edits,
-                depth + 1,
-            );
-        } else {
-            // No common line found: everything is a change
-            for (i, _) in old_mid.iter().enumerate() {
-                edits.push(Edit {
-                    op: EditOp::Delete,
-                    old_index: mid_old_offset + i,
-                    new_index: mid_new_offset,
-                });
-            }
-            for (j, _) in new_mid.iter().enumerate() {
-                edits.push(Edit {
-                    op: EditOp::Insert,
-                    old_index: mid_old_offset + old_mid.len(),
-                    new_index: mid_new_offset + j,
-                });
-            }
+        // Recurse on the gap after the anchor run.
+        histogram_recurse(
+            &old_mid[old_start + len..],
+            &new_mid[new_start + len..],
+            mid_old_offset + old_start + len,
+            mid_new_offset + new_start + len,
+            edits,
+            depth + 1,
+        );
+    } else {
+        // No common line found: fall back to Myers for this region.
+        let fallback = super::myers::diff(old_mid, new_mid, false);
+        for mut e in fallback {
+            e.old_index += mid_old_offset;
+            e.new_index += mid_new_offset;
+            edits.push(e);
         }
     }
 
@@ -218,6 +197,86 @@ fn histogram_recurse(
     }
 }
 
+/// The longest run of equal lines anchored at a given old/new pair.
+struct LongestRun {
+    old_start: usize,
+    new_start: usize,
+    len: usize,
+    /// Smallest old-side occurrence count among the run's lines; lower means
+    /// the run is built from rarer (more distinctive) lines.
+    min_count: usize,
+}
+
+/// Find the best anchor run shared between `old` and `new`.
+///
+/// Builds a table over `old` mapping each line's hash to its occurrence
+/// count (capped at [`MAX_CHAIN`]) and positions, then for every line in
+/// `new` walks its matching old positions and extends each into the longest
+/// run of equal lines in both directions. Among all candidate runs, keeps
+/// the one whose minimum old-side occurrence count is smallest, breaking
+/// ties in favor of the longest run.
+fn best_anchor(old: &[&[u8]], new: &[&[u8]]) -> Option<LongestRun> {
+    let mut table: HashMap<u64, (usize, Vec<usize>)> = HashMap::new();
+    for (i, line) in old.iter().enumerate() {
+        let entry = table.entry(line_hash(line)).or_insert((0, Vec::new()));
+        if entry.0 < MAX_CHAIN {
+            entry.0 += 1;
+            entry.1.push(i);
+        }
+    }
+
+    let mut best: Option<LongestRun> = None;
+
+    for (j, line) in new.iter().enumerate() {
+        let Some((_, positions)) = table.get(&line_hash(line)) else {
+            continue;
+        };
+
+        for &oi in positions {
+            if old[oi] != *line {
+                continue; // hash collision
+            }
+
+            let mut back = 0usize;
+            while oi > back && j > back && old[oi - back - 1] == new[j - back - 1] {
+                back += 1;
+            }
+            let mut fwd = 0usize;
+            while oi + fwd + 1 < old.len()
+                && j + fwd + 1 < new.len()
+                && old[oi + fwd + 1] == new[j + fwd + 1]
+            {
+                fwd += 1;
+            }
+
+            let old_start = oi - back;
+            let new_start = j - back;
+            let len = back + 1 + fwd;
+            let min_count = (0..len)
+                .map(|k| table.get(&line_hash(old[old_start + k])).map_or(1, |e| e.0))
+                .min()
+                .unwrap_or(1);
+
+            let is_better = match &best {
+                None => true,
+                Some(current) => {
+                    min_count < current.min_count || (min_count == current.min_count && len > current.len)
+                }
+            };
+            if is_better {
+                best = Some(LongestRun {
+                    old_start,
+                    new_start,
+                    len,
+                    min_count,
+                });
+            }
+        }
+    }
+
+    best
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,4 +316,40 @@ mod tests {
         assert_eq!(diff(&empty, &a).len(), 1);
         assert_eq!(diff(&a, &empty).len(), 1);
     }
+
+    #[test]
+    fn extends_match_into_run() {
+        // "b\nc\nd\n" is a 3-line run common to both sides; histogram should
+        // anchor on the whole run rather than a single matching line.
+        let a = vec![b"a\n".as_slice(), b"b\n", b"c\n", b"d\n", b"e\n"];
+        let b = vec![b"x\n".as_slice(), b"b\n", b"c\n", b"d\n", b"y\n"];
+        let edits = diff(&a, &b);
+        let equal_run = edits
+            .iter()
+            .filter(|e| e.op == EditOp::Equal)
+            .count();
+        assert_eq!(equal_run, 3);
+    }
+
+    #[test]
+    fn repeated_common_lines_net_one_insert() {
+        // "common\n" repeats on both sides and "rare\n" appears once on each
+        // side; `b` is `a` plus one more "common\n".
+        let a = vec![
+            b"common\n".as_slice(),
+            b"rare\n",
+            b"common\n",
+        ];
+        let b = vec![
+            b"common\n".as_slice(),
+            b"rare\n",
+            b"common\n",
+            b"common\n",
+        ];
+        let edits = diff(&a, &b);
+        // Whatever the split, the result must still reconstruct `b` exactly.
+        let inserts = edits.iter().filter(|e| e.op == EditOp::Insert).count();
+        let deletes = edits.iter().filter(|e| e.op == EditOp::Delete).count();
+        assert_eq!(inserts as isize - deletes as isize, 1);
+    }
 }
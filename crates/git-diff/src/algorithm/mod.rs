@@ -50,8 +50,17 @@ pub fn diff_edits(old: &[u8], new: &[u8], algorithm: DiffAlgorithm) -> Vec<Edit>
 ///
 /// This is the main entry point for line diffing: takes two byte slices,
 /// computes the diff using the specified algorithm, and produces hunks
-/// suitable for unified diff output.
-pub fn diff_lines(old: &[u8], new: &[u8], algorithm: DiffAlgorithm, context_lines: u32) -> Vec<Hunk> {
+/// suitable for unified diff output. When `indent_heuristic` is set, ambiguous
+/// insert/delete groups are slid to the most human-readable boundary first
+/// (see [`apply_indent_heuristic`]); passing `false` reproduces the raw,
+/// unshifted edit script.
+pub fn diff_lines(
+    old: &[u8],
+    new: &[u8],
+    algorithm: DiffAlgorithm,
+    context_lines: u32,
+    indent_heuristic: bool,
+) -> Vec<Hunk> {
     let old_lines = split_lines(old);
     let new_lines = split_lines(new);
     let edits = match algorithm {
@@ -61,10 +70,178 @@ pub fn diff_lines(old: &[u8], new: &[u8], algorithm: DiffAlgorithm, context_line
         DiffAlgorithm::Histogram => histogram::diff(&old_lines, &new_lines),
         DiffAlgorithm::Patience => patience::diff(&old_lines, &new_lines),
     };
+    let edits = if indent_heuristic {
+        apply_indent_heuristic(&edits, &old_lines, &new_lines)
+    } else {
+        edits
+    };
 
     edits_to_hunks(&edits, &old_lines, &new_lines, context_lines)
 }
 
+/// Slide ambiguous insert/delete groups to the most human-readable boundary,
+/// matching C git's `--indent-heuristic` / `diff.indentHeuristic`.
+///
+/// A maximal run of consecutive same-kind edits (all `Insert` or all
+/// `Delete`) bordered by `Equal` edits can often be shifted up or down while
+/// producing byte-identical output, whenever the line entering the group
+/// equals the line it displaces on the far side. Among the reachable
+/// placements, this picks the one whose surrounding context lines score best
+/// by indentation and blank-ness, preferring to split on a blank or
+/// shallow-indented line over splitting inside a more-indented block. Works
+/// on the `Vec<Edit>` output of any algorithm in this module.
+pub fn apply_indent_heuristic(edits: &[Edit], old_lines: &[&[u8]], new_lines: &[&[u8]]) -> Vec<Edit> {
+    if edits.is_empty() {
+        return Vec::new();
+    }
+
+    let mut edits = edits.to_vec();
+    let mut i = 0;
+    while i < edits.len() {
+        if edits[i].op == EditOp::Equal {
+            i += 1;
+            continue;
+        }
+        let op = edits[i].op;
+        let start = i;
+        while i < edits.len() && edits[i].op == op {
+            i += 1;
+        }
+        slide_group(&mut edits, start, i, op, old_lines, new_lines);
+    }
+
+    edits
+}
+
+/// Slide a single maximal group of same-kind edits `edits[start..end]` to its
+/// best reachable boundary in place.
+fn slide_group(
+    edits: &mut [Edit],
+    start: usize,
+    end: usize,
+    op: EditOp,
+    old_lines: &[&[u8]],
+    new_lines: &[&[u8]],
+) {
+    let lines: &[&[u8]] = match op {
+        EditOp::Delete => old_lines,
+        EditOp::Insert => new_lines,
+        EditOp::Equal => return,
+    };
+    let index_of = |e: &Edit| -> usize {
+        if op == EditOp::Delete {
+            e.old_index
+        } else {
+            e.new_index
+        }
+    };
+    let len = end - start;
+
+    // How far the group can slide up (toward index 0) and down (toward the
+    // end of the script) while the displaced line equals the line taking its
+    // place, i.e. the resulting text is unaffected.
+    let mut up = start;
+    while up > 0 && edits[up - 1].op == EditOp::Equal && lines[index_of(&edits[up - 1])] == lines[index_of(&edits[up + len - 1])] {
+        up -= 1;
+    }
+    let mut down = end;
+    while down < edits.len() && edits[down].op == EditOp::Equal && lines[index_of(&edits[down - len])] == lines[index_of(&edits[down])] {
+        down += 1;
+    }
+
+    if up == start && down == end {
+        return;
+    }
+
+    // Score every reachable placement and keep the best one.
+    let mut best_start = start;
+    let mut best_score = i64::MIN;
+    for candidate_start in up..=(down - len) {
+        let candidate_end = candidate_start + len;
+        let before = candidate_start.checked_sub(1).map(|p| lines[index_of(&edits[p])]);
+        let after = if candidate_end < edits.len() {
+            Some(lines[index_of(&edits[candidate_end])])
+        } else {
+            None
+        };
+        let score = boundary_score(before, after);
+        if score > best_score {
+            best_score = score;
+            best_start = candidate_start;
+        }
+    }
+
+    // Materialize the winning placement one step at a time: moving the
+    // group's boundary by one position just swaps the op of the edit
+    // entering the group with the op of the edit it displaces.
+    let mut cur_start = start;
+    let mut cur_end = end;
+    while cur_start > best_start {
+        let pred = edits[cur_start - 1];
+        let last = edits[cur_end - 1];
+        match op {
+            EditOp::Delete => {
+                edits[cur_start - 1] = Edit { op: EditOp::Delete, old_index: pred.old_index, new_index: pred.new_index };
+                edits[cur_end - 1] = Edit { op: EditOp::Equal, old_index: last.old_index, new_index: pred.new_index };
+            }
+            EditOp::Insert => {
+                edits[cur_start - 1] = Edit { op: EditOp::Insert, old_index: pred.old_index, new_index: pred.new_index };
+                edits[cur_end - 1] = Edit { op: EditOp::Equal, old_index: pred.old_index, new_index: last.new_index };
+            }
+            EditOp::Equal => unreachable!(),
+        }
+        cur_start -= 1;
+        cur_end -= 1;
+    }
+    while cur_start < best_start {
+        let first = edits[cur_start];
+        let boundary = edits[cur_end];
+        match op {
+            EditOp::Delete => {
+                edits[cur_start] = Edit { op: EditOp::Equal, old_index: first.old_index, new_index: boundary.new_index };
+                edits[cur_end] = Edit { op: EditOp::Delete, old_index: boundary.old_index, new_index: boundary.new_index };
+            }
+            EditOp::Insert => {
+                edits[cur_start] = Edit { op: EditOp::Equal, old_index: boundary.old_index, new_index: first.new_index };
+                edits[cur_end] = Edit { op: EditOp::Insert, old_index: boundary.old_index, new_index: boundary.new_index };
+            }
+            EditOp::Equal => unreachable!(),
+        }
+        cur_start += 1;
+        cur_end += 1;
+    }
+}
+
+/// Score a candidate hunk boundary: higher is more human-readable.
+///
+/// `before`/`after` are the lines immediately outside the slid group on each
+/// side (`None` at the start/end of the file). Splitting right before a
+/// blank line, or at a shallower indentation than the surrounding block, is
+/// preferred; splitting deep inside an indented block is penalized.
+fn boundary_score(before: Option<&[u8]>, after: Option<&[u8]>) -> i64 {
+    let score_side = |line: Option<&[u8]>| match line.and_then(indent_of) {
+        None => 60,
+        Some(indent) => -indent,
+    };
+    score_side(before) + score_side(after)
+}
+
+/// Leading-whitespace indentation of a line, counting tabs as advancing to
+/// the next multiple of 8 columns. Returns `None` for a blank (whitespace-only
+/// or empty) line.
+fn indent_of(line: &[u8]) -> Option<i64> {
+    let mut indent: i64 = 0;
+    for &b in line {
+        match b {
+            b' ' => indent += 1,
+            b'\t' => indent += 8 - indent % 8,
+            b'\n' | b'\r' => break,
+            _ => return Some(indent),
+        }
+    }
+    None
+}
+
 /// Convert a list of edits into hunks with context lines.
 fn edits_to_hunks(
     edits: &[Edit],
@@ -182,6 +359,7 @@ fn edits_to_hunks(
             new_count,
             header: None,
             lines,
+            locks: Vec::new(),
         });
     }
 
@@ -256,4 +434,42 @@ mod tests {
         assert_eq!(line_hash(b"hello\n"), line_hash(b"hello\n"));
         assert_ne!(line_hash(b"hello\n"), line_hash(b"world\n"));
     }
+
+    #[test]
+    fn indent_heuristic_slides_insert_next_to_blank_line() {
+        // The new "    b();\n" line is ambiguous between sitting right
+        // before or right after the existing (identical) "    b();\n" line;
+        // both placements produce the same text. The plain Myers script
+        // naturally lands it after the match, but the heuristic should slide
+        // it up next to the blank line instead.
+        let old: &[u8] = b"\n    b();\nc();\n";
+        let new: &[u8] = b"\n    b();\n    b();\nc();\n";
+
+        let unshifted = diff_lines(old, new, DiffAlgorithm::Myers, 3, false);
+        let add_pos = unshifted[0].lines.iter().position(|l| matches!(l, DiffLine::Addition(_))).unwrap();
+        assert_eq!(add_pos, 2, "sanity check: plain Myers inserts after the matched line");
+
+        let hunks = diff_lines(old, new, DiffAlgorithm::Myers, 3, true);
+        assert_eq!(hunks.len(), 1);
+        let lines = &hunks[0].lines;
+        let add_pos = lines.iter().position(|l| matches!(l, DiffLine::Addition(_))).unwrap();
+        assert_eq!(add_pos, 1, "heuristic should slide the insertion up next to the blank line");
+        assert!(matches!(lines[0], DiffLine::Context(ref s) if s.as_slice() == b"\n"));
+        assert!(matches!(lines[2], DiffLine::Context(ref s) if s.as_slice() == b"    b();\n"));
+    }
+
+    #[test]
+    fn indent_heuristic_preserves_op_counts() {
+        let old = b"a\nb\nb\nc\n";
+        let new = b"a\nb\nb\nb\nc\n";
+        let edits = diff_edits(old, new, DiffAlgorithm::Myers);
+        let old_lines = split_lines(old);
+        let new_lines = split_lines(new);
+        let shifted = apply_indent_heuristic(&edits, &old_lines, &new_lines);
+        // Ambiguous insert of a repeated line: sliding must not change how
+        // many of each op type appear, only where the boundary falls.
+        let count = |es: &[Edit], op: EditOp| es.iter().filter(|e| e.op == op).count();
+        assert_eq!(count(&edits, EditOp::Insert), count(&shifted, EditOp::Insert));
+        assert_eq!(count(&edits, EditOp::Equal), count(&shifted, EditOp::Equal));
+    }
 }
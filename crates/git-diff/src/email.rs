@@ -0,0 +1,192 @@
+//! Render a commit as an RFC-2822 mbox message suitable for `git am`,
+//! comparable to C git's `format-patch` (inspired by git2's `email.rs`).
+//!
+//! This module only handles the email envelope and subject/body framing; the
+//! caller supplies the already-rendered patch body (diffstat and/or unified
+//! hunks) to place after the `---` separator.
+
+use git_hash::ObjectId;
+use git_object::Commit;
+use git_utils::date::DateFormat;
+
+/// A commit's position within a multi-commit patch series.
+#[derive(Debug, Clone, Copy)]
+pub struct PatchNumber {
+    /// 1-based patch number (honors `--start-number`).
+    pub number: usize,
+    /// Total number of patches in the series.
+    pub total: usize,
+}
+
+/// Render `commit` as a single mbox message.
+///
+/// `diff_text` is the patch body to place after the `---` separator (e.g.
+/// a diffstat followed by unified hunks). `version` is the trailing
+/// signature line (e.g. `"gitr 0.1.0"`).
+pub fn format_patch_email(
+    oid: &ObjectId,
+    commit: &Commit,
+    numbering: Option<PatchNumber>,
+    subject_prefix: &str,
+    message_id: Option<&str>,
+    diff_text: &str,
+    version: &str,
+) -> String {
+    let mut out = String::new();
+
+    out.push_str("From ");
+    out.push_str(&oid.to_hex());
+    out.push_str(" Mon Sep 17 00:00:00 2001\n");
+
+    out.push_str("From: ");
+    out.push_str(&String::from_utf8_lossy(&commit.author.name));
+    out.push_str(" <");
+    out.push_str(&String::from_utf8_lossy(&commit.author.email));
+    out.push_str(">\n");
+
+    out.push_str("Date: ");
+    out.push_str(&commit.author.date.format(DateFormat::Rfc2822));
+    out.push('\n');
+
+    let subject = String::from_utf8_lossy(commit.summary());
+    match numbering {
+        Some(PatchNumber { number, total }) if total > 1 => {
+            out.push_str(&format!(
+                "Subject: [{subject_prefix} {number}/{total}] {subject}\n"
+            ));
+        }
+        Some(PatchNumber { number, .. }) => {
+            out.push_str(&format!("Subject: [{subject_prefix} {number}] {subject}\n"));
+        }
+        None => {
+            out.push_str(&format!("Subject: [{subject_prefix}] {subject}\n"));
+        }
+    }
+
+    if let Some(message_id) = message_id {
+        out.push_str("Message-Id: <");
+        out.push_str(message_id);
+        out.push_str(">\n");
+    }
+
+    out.push('\n');
+
+    if let Some(body) = commit.body() {
+        out.push_str(&escape_from_lines(&String::from_utf8_lossy(body)));
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    out.push_str("---\n");
+    out.push_str(diff_text);
+    if !diff_text.is_empty() && !diff_text.ends_with('\n') {
+        out.push('\n');
+    }
+
+    out.push_str("-- \n");
+    out.push_str(version);
+    out.push('\n');
+
+    out
+}
+
+/// Escape lines that would be mistaken for the mbox `From ` separator
+/// (the mboxrd convention: any line matching `^>*From ` gets one more `>`
+/// prepended), so a quoted commit message doesn't corrupt the mbox.
+fn escape_from_lines(body: &str) -> String {
+    let mut out = String::with_capacity(body.len());
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches('\n');
+        if trimmed.trim_start_matches('>').starts_with("From ") {
+            out.push('>');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_utils::date::{GitDate, Signature};
+
+    fn sample_commit(message: &str) -> Commit {
+        Commit {
+            tree: ObjectId::NULL_SHA1,
+            parents: vec![ObjectId::NULL_SHA1],
+            author: Signature {
+                name: "Jane Dev".into(),
+                email: "jane@example.com".into(),
+                date: GitDate {
+                    timestamp: 1_700_000_000,
+                    tz_offset: 0,
+                },
+            },
+            committer: Signature {
+                name: "Jane Dev".into(),
+                email: "jane@example.com".into(),
+                date: GitDate {
+                    timestamp: 1_700_000_000,
+                    tz_offset: 0,
+                },
+            },
+            encoding: None,
+            gpgsig: None,
+            extra_headers: Vec::new(),
+            message: message.into(),
+        }
+    }
+
+    #[test]
+    fn envelope_and_unnumbered_subject() {
+        let oid = ObjectId::from_hex("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+        let commit = sample_commit("Fix the thing\n");
+        let out = format_patch_email(&oid, &commit, None, "PATCH", None, "diff --git a/x b/x\n", "gitr 0.1.0");
+
+        assert!(out.starts_with(&format!("From {} Mon Sep 17 00:00:00 2001\n", oid.to_hex())));
+        assert!(out.contains("From: Jane Dev <jane@example.com>\n"));
+        assert!(out.contains("Subject: [PATCH] Fix the thing\n"));
+        assert!(out.contains("---\ndiff --git a/x b/x\n"));
+        assert!(out.ends_with("-- \ngitr 0.1.0\n"));
+    }
+
+    #[test]
+    fn numbered_subject_in_series() {
+        let oid = ObjectId::from_hex("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb").unwrap();
+        let commit = sample_commit("Second change\n");
+        let numbering = Some(PatchNumber { number: 2, total: 3 });
+        let out = format_patch_email(&oid, &commit, numbering, "PATCH", None, "", "gitr 0.1.0");
+
+        assert!(out.contains("Subject: [PATCH 2/3] Second change\n"));
+    }
+
+    #[test]
+    fn single_patch_numbering_without_total() {
+        let oid = ObjectId::from_hex("cccccccccccccccccccccccccccccccccccccccc").unwrap();
+        let commit = sample_commit("Solo change\n");
+        let numbering = Some(PatchNumber { number: 1, total: 1 });
+        let out = format_patch_email(&oid, &commit, numbering, "PATCH", None, "", "gitr 0.1.0");
+
+        assert!(out.contains("Subject: [PATCH 1] Solo change\n"));
+    }
+
+    #[test]
+    fn message_id_header_is_included_when_set() {
+        let oid = ObjectId::from_hex("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee").unwrap();
+        let commit = sample_commit("Threaded change\n");
+        let out = format_patch_email(&oid, &commit, None, "PATCH", Some("abc.1.git-gitr@localhost"), "", "gitr 0.1.0");
+
+        assert!(out.contains("Message-Id: <abc.1.git-gitr@localhost>\n"));
+    }
+
+    #[test]
+    fn body_with_from_line_is_escaped() {
+        let oid = ObjectId::from_hex("dddddddddddddddddddddddddddddddddddddddd").unwrap();
+        let commit = sample_commit("Summary\n\nFrom here on it gets weird\n>From already quoted\n");
+        let out = format_patch_email(&oid, &commit, None, "PATCH", None, "", "gitr 0.1.0");
+
+        assert!(out.contains(">From here on it gets weird\n"));
+        assert!(out.contains(">>From already quoted\n"));
+    }
+}
@@ -16,7 +16,7 @@ pub fn format_diff(result: &DiffResult, options: &DiffOptions) -> String {
         DiffOutputFormat::Stat => stat::format_stat(result, options),
         DiffOutputFormat::ShortStat => stat::format_short_stat(result),
         DiffOutputFormat::NumStat => stat::format_numstat(result),
-        DiffOutputFormat::Raw => raw::format(result),
+        DiffOutputFormat::Raw => raw::format(result, options.full_index),
         DiffOutputFormat::NameOnly => nameonly::format_name_only(result),
         DiffOutputFormat::NameStatus => nameonly::format_name_status(result),
         DiffOutputFormat::Summary => nameonly::format_summary(result),
@@ -8,15 +8,18 @@ use git_hash::ObjectId;
 use crate::{DiffResult, FileDiff, FileStatus};
 
 /// Format a DiffResult in raw format.
-pub fn format(result: &DiffResult) -> String {
+///
+/// `full_index` selects full 40-hex object IDs instead of the default
+/// 7-character abbreviation, matching C git's `--full-index`.
+pub fn format(result: &DiffResult, full_index: bool) -> String {
     let mut out = String::new();
     for file in &result.files {
-        format_raw_entry(&mut out, file);
+        format_raw_entry(&mut out, file, full_index);
     }
     out
 }
 
-fn format_raw_entry(out: &mut String, file: &FileDiff) {
+fn format_raw_entry(out: &mut String, file: &FileDiff, full_index: bool) {
     let old_mode = file
         .old_mode
         .map(|m| format!("{:06o}", m.raw()))
@@ -26,14 +29,21 @@ fn format_raw_entry(out: &mut String, file: &FileDiff) {
         .map(|m| format!("{:06o}", m.raw()))
         .unwrap_or_else(|| "000000".to_string());
 
+    let oid_str = |oid: &ObjectId| -> String {
+        if full_index {
+            oid.to_hex().to_string()
+        } else {
+            abbreviate_oid(oid)
+        }
+    };
     let old_oid = file
         .old_oid
-        .map(|o| abbreviate_oid(&o))
-        .unwrap_or_else(|| "0000000".to_string());
+        .map(|o| oid_str(&o))
+        .unwrap_or_else(|| "0".repeat(if full_index { 40 } else { 7 }));
     let new_oid = file
         .new_oid
-        .map(|o| abbreviate_oid(&o))
-        .unwrap_or_else(|| "0000000".to_string());
+        .map(|o| oid_str(&o))
+        .unwrap_or_else(|| "0".repeat(if full_index { 40 } else { 7 }));
 
     let status = match file.status {
         FileStatus::Renamed => {
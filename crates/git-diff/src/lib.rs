@@ -9,6 +9,7 @@ pub mod binary;
 pub mod color;
 pub mod diffcore;
 pub mod driver;
+pub mod email;
 pub mod format;
 pub mod pickaxe;
 pub mod rename;
@@ -36,12 +37,18 @@ pub struct DiffOptions {
     pub copy_threshold: u8,
     /// Enable color output.
     pub color: bool,
+    /// Slide ambiguous insert/delete groups to the most human-readable hunk
+    /// boundary, matching C git's `--indent-heuristic` (on by default).
+    pub indent_heuristic: bool,
     /// Width for --stat output (None = auto-detect terminal width).
     pub stat_width: Option<usize>,
     /// Output format to produce.
     pub output_format: DiffOutputFormat,
     /// Pathspec filter (None = all paths).
     pub pathspec: Option<Vec<BString>>,
+    /// Use full 40-hex object IDs in raw format instead of abbreviating to 7
+    /// characters, matching C git's `--full-index`.
+    pub full_index: bool,
 }
 
 impl Default for DiffOptions {
@@ -54,9 +61,11 @@ impl Default for DiffOptions {
             detect_copies: false,
             copy_threshold: 50,
             color: false,
+            indent_heuristic: true,
             stat_width: None,
             output_format: DiffOutputFormat::Unified,
             pathspec: None,
+            full_index: false,
         }
     }
 }
@@ -235,6 +244,11 @@ pub struct Hunk {
     pub header: Option<BString>,
     /// Lines in this hunk.
     pub lines: Vec<DiffLine>,
+    /// Commits whose last-touched lines overlap this hunk's old-side range,
+    /// i.e. the commits this change depends on ("locks" it onto
+    /// them, in GitButler's terminology). Empty unless a lock-annotating pass
+    /// (e.g. `git diff-tree --annotate-locks`) populated it.
+    pub locks: Vec<ObjectId>,
 }
 
 /// A single line in a diff hunk.
@@ -290,6 +304,7 @@ mod tests {
         assert!(!opts.detect_renames);
         assert_eq!(opts.rename_threshold, 50);
         assert!(!opts.color);
+        assert!(opts.indent_heuristic);
         assert_eq!(opts.output_format, DiffOutputFormat::Unified);
     }
 
@@ -343,6 +358,7 @@ mod tests {
                         DiffLine::Addition(BString::from("d")),
                         DiffLine::Context(BString::from("e")),
                     ],
+                    locks: Vec::new(),
                 }],
                 is_binary: false,
                 similarity: None,
@@ -132,6 +132,7 @@ mod tests {
                 new_count: additions.len() as u32,
                 header: None,
                 lines,
+                locks: Vec::new(),
             }],
             is_binary: false,
             similarity: None,
@@ -172,7 +172,7 @@ fn collect_deleted(
         let hunks = if binary {
             Vec::new()
         } else {
-            algorithm::diff_lines(&blob_data, &[], options.algorithm, options.context_lines)
+            algorithm::diff_lines(&blob_data, &[], options.algorithm, options.context_lines, options.indent_heuristic)
         };
         files.push(FileDiff {
             status: FileStatus::Deleted,
@@ -212,7 +212,7 @@ fn collect_added(
         let hunks = if binary {
             Vec::new()
         } else {
-            algorithm::diff_lines(&[], &blob_data, options.algorithm, options.context_lines)
+            algorithm::diff_lines(&[], &blob_data, options.algorithm, options.context_lines, options.indent_heuristic)
         };
         files.push(FileDiff {
             status: FileStatus::Added,
@@ -266,7 +266,7 @@ fn collect_modified(
             let hunks = if binary {
                 Vec::new()
             } else {
-                algorithm::diff_lines(&[], &blob_data, options.algorithm, options.context_lines)
+                algorithm::diff_lines(&[], &blob_data, options.algorithm, options.context_lines, options.indent_heuristic)
             };
             files.push(FileDiff {
                 status: FileStatus::Added,
@@ -289,7 +289,7 @@ fn collect_modified(
             let hunks = if binary {
                 Vec::new()
             } else {
-                algorithm::diff_lines(&blob_data, &[], options.algorithm, options.context_lines)
+                algorithm::diff_lines(&blob_data, &[], options.algorithm, options.context_lines, options.indent_heuristic)
             };
             files.push(FileDiff {
                 status: FileStatus::Deleted,
@@ -326,7 +326,7 @@ fn collect_modified(
         let hunks = if binary {
             Vec::new()
         } else {
-            algorithm::diff_lines(&old_data, &new_data, options.algorithm, options.context_lines)
+            algorithm::diff_lines(&old_data, &new_data, options.algorithm, options.context_lines, options.indent_heuristic)
         };
 
         files.push(FileDiff {
@@ -47,7 +47,7 @@ pub fn diff_index_to_worktree(
             .filter(|e| e.stage == Stage::Normal)
             .filter(|e| matches_pathspec(&e.path, options))
             .map(|e| IndexEntrySnapshot {
-                path: e.path.clone(),
+                path: e.path.to_bstring(),
                 oid: e.oid,
                 mode: e.mode,
                 stat: e.stat,
@@ -68,7 +68,7 @@ pub fn diff_index_to_worktree(
             let hunks = if binary {
                 Vec::new()
             } else {
-                algorithm::diff_lines(&blob_data, &[], options.algorithm, options.context_lines)
+                algorithm::diff_lines(&blob_data, &[], options.algorithm, options.context_lines, options.indent_heuristic)
             };
             files.push(FileDiff {
                 status: FileStatus::Deleted,
@@ -109,7 +109,7 @@ pub fn diff_index_to_worktree(
         let hunks = if binary {
             Vec::new()
         } else {
-            algorithm::diff_lines(&blob_data, &worktree_content, options.algorithm, options.context_lines)
+            algorithm::diff_lines(&blob_data, &worktree_content, options.algorithm, options.context_lines, options.indent_heuristic)
         };
 
         files.push(FileDiff {
@@ -148,7 +148,7 @@ pub fn diff_head_to_index(
     // returns the cached version. The borrow checker still sees this as a
     // &mut self borrow, so we use the free function + read the index file directly.
     let index_path = repo.git_dir().join("index");
-    let index_for_tree = if index_path.exists() {
+    let mut index_for_tree = if index_path.exists() {
         git_index::Index::read_from(&index_path)
             .map_err(|e| DiffError::Io(std::io::Error::other(e.to_string())))?
     } else {
@@ -219,7 +219,7 @@ fn mode_is_same_type(a: FileMode, b: FileMode) -> bool {
 }
 
 /// Check if a path matches the pathspec filter.
-fn matches_pathspec(path: &BString, options: &DiffOptions) -> bool {
+fn matches_pathspec(path: &bstr::BStr, options: &DiffOptions) -> bool {
     match &options.pathspec {
         None => true,
         Some(specs) => specs
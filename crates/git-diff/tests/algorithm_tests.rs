@@ -182,7 +182,7 @@ fn large_delete() {
 fn hunks_simple_change() {
     let old = b"a\nb\nc\nd\ne\n";
     let new = b"a\nb\nX\nd\ne\n";
-    let hunks = diff_lines(old, new, DiffAlgorithm::Myers, 3);
+    let hunks = diff_lines(old, new, DiffAlgorithm::Myers, 3, true);
     assert_eq!(hunks.len(), 1, "Expected 1 hunk");
     let hunk = &hunks[0];
     assert!(hunk.old_count > 0);
@@ -192,13 +192,13 @@ fn hunks_simple_change() {
 #[test]
 fn hunks_no_changes() {
     let content = b"a\nb\nc\n";
-    let hunks = diff_lines(content, content, DiffAlgorithm::Myers, 3);
+    let hunks = diff_lines(content, content, DiffAlgorithm::Myers, 3, true);
     assert!(hunks.is_empty(), "Identical content should produce no hunks");
 }
 
 #[test]
 fn hunks_all_new() {
-    let hunks = diff_lines(b"", b"a\nb\nc\n", DiffAlgorithm::Myers, 3);
+    let hunks = diff_lines(b"", b"a\nb\nc\n", DiffAlgorithm::Myers, 3, true);
     assert_eq!(hunks.len(), 1);
     // All lines should be additions
     for line in &hunks[0].lines {
@@ -211,7 +211,7 @@ fn hunks_all_new() {
 
 #[test]
 fn hunks_all_deleted() {
-    let hunks = diff_lines(b"a\nb\nc\n", b"", DiffAlgorithm::Myers, 3);
+    let hunks = diff_lines(b"a\nb\nc\n", b"", DiffAlgorithm::Myers, 3, true);
     assert_eq!(hunks.len(), 1);
     for line in &hunks[0].lines {
         assert!(
@@ -226,7 +226,7 @@ fn hunks_separated_changes_merged() {
     // Two changes close together should be merged into one hunk with context=3
     let old = b"1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
     let new = b"1\n2\nX\n4\n5\nY\n7\n8\n9\n10\n";
-    let hunks = diff_lines(old, new, DiffAlgorithm::Myers, 3);
+    let hunks = diff_lines(old, new, DiffAlgorithm::Myers, 3, true);
     // Changes at lines 3 and 6 are only 2 lines apart, within 2*3 context
     assert_eq!(hunks.len(), 1, "Close changes should be merged");
 }
@@ -246,7 +246,7 @@ fn hunks_separated_changes_split() {
             new.push_str(&format!("line{}\n", i));
         }
     }
-    let hunks = diff_lines(old.as_bytes(), new.as_bytes(), DiffAlgorithm::Myers, 3);
+    let hunks = diff_lines(old.as_bytes(), new.as_bytes(), DiffAlgorithm::Myers, 3, true);
     assert_eq!(hunks.len(), 2, "Distant changes should be separate hunks");
 }
 
@@ -254,7 +254,7 @@ fn hunks_separated_changes_split() {
 fn context_zero_minimal_hunks() {
     let old = b"a\nb\nc\nd\ne\n";
     let new = b"a\nX\nc\nd\ne\n";
-    let hunks = diff_lines(old, new, DiffAlgorithm::Myers, 0);
+    let hunks = diff_lines(old, new, DiffAlgorithm::Myers, 0, true);
     assert_eq!(hunks.len(), 1);
     // With 0 context, hunk should only contain the changed lines
     let hunk = &hunks[0];
@@ -33,6 +33,7 @@ fn sample_modified_diff() -> DiffResult {
                     DiffLine::Addition(BString::from("new line\n")),
                     DiffLine::Context(BString::from("line3\n")),
                 ],
+                locks: Vec::new(),
             }],
             is_binary: false,
             similarity: None,
@@ -64,6 +65,7 @@ fn sample_multi_file_diff() -> DiffResult {
                         DiffLine::Addition(BString::from("first line\n")),
                         DiffLine::Addition(BString::from("second line\n")),
                     ],
+                    locks: Vec::new(),
                 }],
                 is_binary: false,
                 similarity: None,
@@ -85,6 +87,7 @@ fn sample_multi_file_diff() -> DiffResult {
                     new_count: 0,
                     header: None,
                     lines: vec![DiffLine::Deletion(BString::from("goodbye\n"))],
+                    locks: Vec::new(),
                 }],
                 is_binary: false,
                 similarity: None,
@@ -111,6 +114,7 @@ fn sample_multi_file_diff() -> DiffResult {
                         DiffLine::Deletion(BString::from("old\n")),
                         DiffLine::Addition(BString::from("new\n")),
                     ],
+                    locks: Vec::new(),
                 }],
                 is_binary: false,
                 similarity: None,
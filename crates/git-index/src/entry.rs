@@ -1,6 +1,8 @@
 //! Index entry types: IndexEntry, StatData, EntryFlags.
 
-use bstr::BString;
+use std::ops::Deref;
+
+use bstr::{BStr, BString};
 use git_hash::ObjectId;
 use git_object::FileMode;
 
@@ -10,7 +12,7 @@ use crate::Stage;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IndexEntry {
     /// File path (relative to repo root).
-    pub path: BString,
+    pub path: CompactPath,
     /// Object ID of the blob.
     pub oid: ObjectId,
     /// File mode.
@@ -23,6 +25,186 @@ pub struct IndexEntry {
     pub flags: EntryFlags,
 }
 
+impl IndexEntry {
+    /// The entry's path, relative to the repository root.
+    pub fn path(&self) -> &BStr {
+        self.path.as_bstr()
+    }
+
+    /// Replace the entry's path.
+    pub fn set_path(&mut self, path: impl Into<CompactPath>) {
+        self.path = path.into();
+    }
+}
+
+/// Inline capacity for [`CompactPath`]: paths up to this many bytes are
+/// stored inline with no heap allocation.
+const COMPACT_PATH_INLINE_CAPACITY: usize = 23;
+
+/// Compact storage for an index entry's path.
+///
+/// Following gitoxide's adoption of `compact_str` for short strings, paths
+/// up to [`COMPACT_PATH_INLINE_CAPACITY`] bytes (the common case: most
+/// tracked paths are short, e.g. `src/main.rs`) are stored inline in the
+/// struct itself with no heap allocation; longer paths spill to a
+/// heap-allocated `BString`. Unlike `compact_str::CompactString`, this
+/// works over raw bytes rather than `str`, since git paths aren't
+/// guaranteed to be valid UTF-8. This matters for checkouts with hundreds
+/// of thousands of entries, where a forced per-path heap allocation costs
+/// both allocator overhead and cache locality.
+#[derive(Clone)]
+pub enum CompactPath {
+    /// Path bytes stored inline; `len` bytes of `buf` are valid.
+    Inline { buf: [u8; COMPACT_PATH_INLINE_CAPACITY], len: u8 },
+    /// Path bytes stored on the heap (used once a path exceeds the inline
+    /// capacity).
+    Heap(BString),
+}
+
+impl CompactPath {
+    /// Borrow the path as a `BStr`.
+    pub fn as_bstr(&self) -> &BStr {
+        self.as_bytes().into()
+    }
+
+    /// Borrow the path as raw bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            CompactPath::Inline { buf, len } => &buf[..*len as usize],
+            CompactPath::Heap(s) => s.as_slice(),
+        }
+    }
+
+    /// Number of bytes in the path.
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    /// True if the path is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy the path out into an owned `BString`.
+    pub fn to_bstring(&self) -> BString {
+        BString::from(self.as_bytes())
+    }
+}
+
+impl Default for CompactPath {
+    fn default() -> Self {
+        CompactPath::Inline {
+            buf: [0u8; COMPACT_PATH_INLINE_CAPACITY],
+            len: 0,
+        }
+    }
+}
+
+impl AsRef<[u8]> for CompactPath {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<BStr> for CompactPath {
+    fn as_ref(&self) -> &BStr {
+        self.as_bstr()
+    }
+}
+
+impl std::ops::Index<std::ops::RangeFull> for CompactPath {
+    type Output = BStr;
+
+    fn index(&self, _range: std::ops::RangeFull) -> &BStr {
+        self.as_bstr()
+    }
+}
+
+impl Deref for CompactPath {
+    type Target = BStr;
+
+    fn deref(&self) -> &BStr {
+        self.as_bstr()
+    }
+}
+
+impl std::fmt::Debug for CompactPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_bstr(), f)
+    }
+}
+
+impl std::fmt::Display for CompactPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.as_bstr(), f)
+    }
+}
+
+impl PartialEq for CompactPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for CompactPath {}
+
+impl PartialOrd for CompactPath {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CompactPath {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl std::hash::Hash for CompactPath {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl From<BString> for CompactPath {
+    fn from(s: BString) -> Self {
+        if s.len() <= COMPACT_PATH_INLINE_CAPACITY {
+            let mut buf = [0u8; COMPACT_PATH_INLINE_CAPACITY];
+            buf[..s.len()].copy_from_slice(&s);
+            CompactPath::Inline { buf, len: s.len() as u8 }
+        } else {
+            CompactPath::Heap(s)
+        }
+    }
+}
+
+impl From<&BStr> for CompactPath {
+    fn from(s: &BStr) -> Self {
+        BString::from(s.as_bytes()).into()
+    }
+}
+
+impl From<&str> for CompactPath {
+    fn from(s: &str) -> Self {
+        BString::from(s).into()
+    }
+}
+
+impl From<&[u8]> for CompactPath {
+    fn from(s: &[u8]) -> Self {
+        BString::from(s).into()
+    }
+}
+
+impl From<CompactPath> for BString {
+    fn from(p: CompactPath) -> Self {
+        match p {
+            CompactPath::Inline { .. } => p.to_bstring(),
+            CompactPath::Heap(s) => s,
+        }
+    }
+}
+
 /// File system stat data cached in the index.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct StatData {
@@ -117,6 +299,53 @@ impl StatData {
 
         true
     }
+
+    /// Whether this entry's cached `mtime` is racy with respect to the
+    /// moment the index itself was written (`index_ts_secs`/`index_ts_nsecs`,
+    /// typically the index file's own stat time).
+    ///
+    /// A stat match can't be trusted when the file's mtime is at or after
+    /// the index write time: the file could have been modified in the same
+    /// clock tick the index was written, after the entry's stat data was
+    /// already recorded, and a filesystem with coarse mtime resolution
+    /// would never show that as a further change. This mirrors C git's
+    /// racy-git handling in `ie_match_stat`/`is_racy_timestamp`.
+    pub fn is_racy(&self, index_ts_secs: u32, index_ts_nsecs: u32) -> bool {
+        (self.mtime_secs, self.mtime_nsecs) >= (index_ts_secs, index_ts_nsecs)
+    }
+
+    /// Like [`StatData::matches`], but racy-aware: when the stat data looks
+    /// unchanged but the entry is [`StatData::is_racy`] with respect to
+    /// `index_ts_secs`/`index_ts_nsecs`, the stat comparison can't be
+    /// trusted and the caller must fall back to a content (blob-OID)
+    /// comparison instead of assuming the file is clean.
+    pub fn match_with_racy_check(
+        &self,
+        meta: &std::fs::Metadata,
+        index_ts_secs: u32,
+        index_ts_nsecs: u32,
+    ) -> StatMatch {
+        if !self.matches(meta) {
+            return StatMatch::Dirty;
+        }
+        if self.is_racy(index_ts_secs, index_ts_nsecs) {
+            return StatMatch::Racy;
+        }
+        StatMatch::Clean
+    }
+}
+
+/// Result of a racy-aware stat comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatMatch {
+    /// Stat data matches and is trustworthy: the entry is unchanged.
+    Clean,
+    /// Stat data doesn't match: the entry has changed.
+    Dirty,
+    /// Stat data matches, but the entry's mtime is racy with the index
+    /// write time, so the match can't be trusted. Callers must recompute
+    /// the blob OID to be sure.
+    Racy,
 }
 
 /// Entry flags.
@@ -128,6 +357,12 @@ pub struct EntryFlags {
     pub intent_to_add: bool,
     /// CE_SKIP_WORKTREE: the entry should not be checked out.
     pub skip_worktree: bool,
+    /// CE_FSMONITOR_VALID: the configured file-system monitor has vouched
+    /// for this entry being unchanged since the token recorded in the
+    /// index's [`crate::extensions::fsmonitor::FsMonitor`] extension, so a
+    /// refresh can skip `stat(2)` for it. Not part of the on-disk per-entry
+    /// flags; persisted instead as a bitmap in the FSMN extension.
+    pub fsmonitor_valid: bool,
 }
 
 impl EntryFlags {
@@ -165,4 +400,49 @@ mod tests {
         };
         assert!(flags.has_extended());
     }
+
+    #[test]
+    fn is_racy_when_mtime_at_or_after_index_write_time() {
+        let stat = StatData { mtime_secs: 100, mtime_nsecs: 0, ..Default::default() };
+        assert!(stat.is_racy(100, 0));
+        assert!(stat.is_racy(99, 0));
+        assert!(!stat.is_racy(101, 0));
+    }
+
+    #[test]
+    fn match_with_racy_check_flags_a_clean_but_racy_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("f.txt");
+        std::fs::write(&path, b"hello").unwrap();
+        let meta = std::fs::metadata(&path).unwrap();
+        let stat = StatData::from_metadata(&meta);
+
+        // Matches the file's actual stat data, but the index was (notionally)
+        // written no earlier than this entry's mtime: can't be trusted.
+        assert_eq!(
+            stat.match_with_racy_check(&meta, stat.mtime_secs, stat.mtime_nsecs),
+            StatMatch::Racy
+        );
+
+        // An index written well after the file's mtime is trustworthy.
+        assert_eq!(
+            stat.match_with_racy_check(&meta, stat.mtime_secs + 1, 0),
+            StatMatch::Clean
+        );
+    }
+
+    #[test]
+    fn match_with_racy_check_reports_dirty_before_racy() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("f.txt");
+        std::fs::write(&path, b"hello").unwrap();
+        let meta = std::fs::metadata(&path).unwrap();
+        let mut stat = StatData::from_metadata(&meta);
+        stat.size += 1; // force a stat mismatch
+
+        assert_eq!(
+            stat.match_with_racy_check(&meta, stat.mtime_secs, stat.mtime_nsecs),
+            StatMatch::Dirty
+        );
+    }
 }
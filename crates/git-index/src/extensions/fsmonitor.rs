@@ -0,0 +1,132 @@
+//! File-system monitor extension (FSMN).
+//!
+//! Caches the last-seen query token from an external file-system monitor
+//! (the `core.fsmonitor` hook) plus, per entry, whether the monitor has
+//! vouched for it being unchanged since that token. A fresh refresh then
+//! only needs to `stat(2)` the paths the monitor reports as changed instead
+//! of every entry in the index.
+//!
+//! The per-entry validity bit itself lives on [`crate::EntryFlags::fsmonitor_valid`]
+//! rather than in the on-disk entry layout; this extension is where that bit
+//! is actually persisted, as a bitmap parallel to the (sorted) entry list.
+
+use bstr::BString;
+
+use crate::IndexError;
+
+/// File-system monitor extension — the last-seen query token.
+#[derive(Debug, Clone)]
+pub struct FsMonitor {
+    /// Opaque token returned by the monitor, to present on the next query.
+    pub token: BString,
+}
+
+impl FsMonitor {
+    /// Extension signature.
+    pub const SIGNATURE: &'static [u8; 4] = b"FSMN";
+
+    /// Extension format version written by this implementation.
+    const FORMAT_VERSION: u32 = 1;
+
+    /// Parse an FSMN extension, returning the token and the per-entry valid
+    /// bitmap (indexed the same as the index's entry list at write time).
+    pub fn parse(data: &[u8]) -> Result<(Self, Vec<bool>), IndexError> {
+        if data.len() < 4 {
+            return Err(IndexError::InvalidExtension {
+                sig: "FSMN".into(),
+                reason: "truncated header".into(),
+            });
+        }
+        let version = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+        if version != Self::FORMAT_VERSION {
+            return Err(IndexError::InvalidExtension {
+                sig: "FSMN".into(),
+                reason: format!("unsupported version: {version}"),
+            });
+        }
+        let mut cursor = 4;
+
+        let nul_pos = data[cursor..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| IndexError::InvalidExtension {
+                sig: "FSMN".into(),
+                reason: "missing NUL after token".into(),
+            })?;
+        let token = BString::from(&data[cursor..cursor + nul_pos]);
+        cursor += nul_pos + 1;
+
+        if cursor + 4 > data.len() {
+            return Err(IndexError::InvalidExtension {
+                sig: "FSMN".into(),
+                reason: "missing bitmap length".into(),
+            });
+        }
+        let bitmap_len =
+            u32::from_be_bytes([data[cursor], data[cursor + 1], data[cursor + 2], data[cursor + 3]])
+                as usize;
+        cursor += 4;
+
+        if cursor + bitmap_len > data.len() {
+            return Err(IndexError::InvalidExtension {
+                sig: "FSMN".into(),
+                reason: "truncated bitmap".into(),
+            });
+        }
+        let bitmap = &data[cursor..cursor + bitmap_len];
+
+        let mut valid = Vec::with_capacity(bitmap_len * 8);
+        for byte in bitmap {
+            for bit in 0..8 {
+                valid.push(byte & (1 << bit) != 0);
+            }
+        }
+
+        Ok((FsMonitor { token }, valid))
+    }
+
+    /// Serialize the token plus a per-entry valid bitmap to raw bytes.
+    pub fn serialize(token: &BString, valid: &[bool]) -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        buf.extend_from_slice(&Self::FORMAT_VERSION.to_be_bytes());
+        buf.extend_from_slice(token);
+        buf.push(0);
+
+        let bitmap_len = valid.len().div_ceil(8);
+        let mut bitmap = vec![0u8; bitmap_len];
+        for (i, &bit) in valid.iter().enumerate() {
+            if bit {
+                bitmap[i / 8] |= 1 << (i % 8);
+            }
+        }
+        buf.extend_from_slice(&(bitmap_len as u32).to_be_bytes());
+        buf.extend_from_slice(&bitmap);
+
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_token_and_bitmap() {
+        let token = BString::from("watchman:abc123");
+        let valid = vec![true, false, true, true, false, false, false, false, true];
+
+        let data = FsMonitor::serialize(&token, &valid);
+        let (parsed, parsed_valid) = FsMonitor::parse(&data).unwrap();
+
+        assert_eq!(parsed.token, token);
+        assert_eq!(&parsed_valid[..valid.len()], &valid[..]);
+    }
+
+    #[test]
+    fn rejects_wrong_version() {
+        let mut data = FsMonitor::serialize(&BString::from("tok"), &[true]);
+        data[3] = 2; // corrupt version
+        assert!(FsMonitor::parse(&data).is_err());
+    }
+}
@@ -1,5 +1,6 @@
-//! Index extensions: TREE, REUC, UNTR, and unknown/raw.
+//! Index extensions: TREE, REUC, UNTR, FSMN, and unknown/raw.
 
+pub mod fsmonitor;
 pub mod tree;
 pub mod resolve_undo;
 pub mod untracked;
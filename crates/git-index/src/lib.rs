@@ -14,12 +14,13 @@ mod write;
 
 use std::path::Path;
 
-use bstr::BStr;
+use bstr::{BStr, BString};
 use git_hash::ObjectId;
 use git_odb::ObjectDatabase;
 
 pub use entry::{EntryFlags, IndexEntry, StatData};
 pub use error::IndexError;
+pub use extensions::fsmonitor::FsMonitor;
 pub use extensions::tree::CacheTree;
 pub use extensions::{RawExtension, ResolveUndo};
 pub use ignore::IgnoreStack;
@@ -112,6 +113,9 @@ pub struct Index {
     cache_tree: Option<CacheTree>,
     /// Resolve-undo extension.
     resolve_undo: Option<ResolveUndo>,
+    /// File-system monitor extension (last-seen query token; per-entry
+    /// validity lives on each entry's [`EntryFlags::fsmonitor_valid`]).
+    fsmonitor: Option<FsMonitor>,
     /// Unknown extensions (preserved for round-trip).
     unknown_extensions: Vec<RawExtension>,
     /// Checksum of the index file.
@@ -126,6 +130,7 @@ impl Index {
             entries: Vec::new(),
             cache_tree: None,
             resolve_undo: None,
+            fsmonitor: None,
             unknown_extensions: Vec::new(),
             _checksum: ObjectId::NULL_SHA1,
         }
@@ -157,14 +162,14 @@ impl Index {
     pub fn get(&self, path: &BStr, stage: Stage) -> Option<&IndexEntry> {
         self.entries
             .iter()
-            .find(|e| e.path[..] == path[..] && e.stage == stage)
+            .find(|e| e.path.as_bytes() == path.as_bytes() && e.stage == stage)
     }
 
     /// Get all entries for a path (all stages).
     pub fn get_all(&self, path: &BStr) -> Vec<&IndexEntry> {
         self.entries
             .iter()
-            .filter(|e| e.path[..] == path[..])
+            .filter(|e| e.path.as_bytes() == path.as_bytes())
             .collect()
     }
 
@@ -191,7 +196,7 @@ impl Index {
     pub fn remove(&mut self, path: &BStr, stage: Stage) -> bool {
         let before = self.entries.len();
         self.entries
-            .retain(|e| !(e.path[..] == path[..] && e.stage == stage));
+            .retain(|e| !(e.path.as_bytes() == path.as_bytes() && e.stage == stage));
         let removed = self.entries.len() < before;
 
         if removed {
@@ -207,7 +212,7 @@ impl Index {
     pub fn has_conflicts(&self, path: &BStr) -> bool {
         self.entries
             .iter()
-            .any(|e| e.path[..] == path[..] && e.stage != Stage::Normal)
+            .any(|e| e.path.as_bytes() == path.as_bytes() && e.stage != Stage::Normal)
     }
 
     /// Get all conflicted paths.
@@ -242,6 +247,18 @@ impl Index {
         self.version
     }
 
+    /// Request a specific on-disk format version (2, 3, or 4) for the next
+    /// [`Index::write_to`]. The writer still downgrades to v3 if any entry
+    /// needs extended flags and a v4 write was requested (see
+    /// [`crate::entry::EntryFlags::has_extended`]).
+    pub fn set_version(&mut self, version: u32) -> Result<(), IndexError> {
+        if !(2..=4).contains(&version) {
+            return Err(IndexError::UnsupportedVersion(version));
+        }
+        self.version = version;
+        Ok(())
+    }
+
     /// Get the cache tree (if available).
     pub fn cache_tree(&self) -> Option<&CacheTree> {
         self.cache_tree.as_ref()
@@ -262,8 +279,33 @@ impl Index {
         self.resolve_undo.as_ref()
     }
 
+    /// Get the file-system monitor extension (last-seen query token).
+    pub fn fsmonitor(&self) -> Option<&FsMonitor> {
+        self.fsmonitor.as_ref()
+    }
+
+    /// Apply the result of an external file-system monitor query: every
+    /// entry not named in `changed_paths` is marked
+    /// [`EntryFlags::fsmonitor_valid`] (a refresh can skip `stat(2)` for
+    /// it), every entry in `changed_paths` has its valid bit cleared, and
+    /// `new_token` is recorded for the next query. A monitor reports every
+    /// tracked path as changed on the very first query (no prior token to
+    /// diff against), which naturally leaves nothing falsely marked valid.
+    pub fn apply_fsmonitor_query(&mut self, changed_paths: &[&BStr], new_token: BString) {
+        for entry in &mut self.entries {
+            entry.flags.fsmonitor_valid = !changed_paths
+                .iter()
+                .any(|p| p.as_bytes() == entry.path.as_bytes());
+        }
+        self.fsmonitor = Some(FsMonitor { token: new_token });
+    }
+
     /// Create a tree hierarchy from the current index state.
-    pub fn write_tree(&self, odb: &ObjectDatabase) -> Result<ObjectId, IndexError> {
+    ///
+    /// Reuses and refreshes the cache tree extension (see [`CacheTree`]) so
+    /// unchanged directories aren't re-serialized and re-written on repeat
+    /// calls.
+    pub fn write_tree(&mut self, odb: &ObjectDatabase) -> Result<ObjectId, IndexError> {
         write::write_tree_from_index(self, odb)
     }
 }
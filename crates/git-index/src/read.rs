@@ -5,6 +5,7 @@ use git_hash::{HashAlgorithm, ObjectId};
 use git_object::FileMode;
 
 use crate::entry::{EntryFlags, IndexEntry, StatData};
+use crate::extensions::fsmonitor::FsMonitor;
 use crate::extensions::tree::CacheTree;
 use crate::extensions::{RawExtension, ResolveUndo};
 use crate::{Index, IndexError, Stage};
@@ -50,7 +51,7 @@ pub fn parse_index(data: &[u8]) -> Result<Index, IndexError> {
 
     for _ in 0..entry_count {
         let (entry, new_cursor) = parse_entry(data, cursor, version, &prev_path, content_end)?;
-        prev_path = entry.path.clone();
+        prev_path = entry.path.to_bstring();
         entries.push(entry);
         cursor = new_cursor;
     }
@@ -58,6 +59,7 @@ pub fn parse_index(data: &[u8]) -> Result<Index, IndexError> {
     // Parse extensions
     let mut cache_tree = None;
     let mut resolve_undo = None;
+    let mut fsmonitor = None;
     let mut unknown_extensions = Vec::new();
 
     while cursor + 8 <= content_end {
@@ -81,6 +83,13 @@ pub fn parse_index(data: &[u8]) -> Result<Index, IndexError> {
             b"REUC" => {
                 resolve_undo = Some(ResolveUndo::parse(ext_data)?);
             }
+            b"FSMN" => {
+                let (monitor, valid) = FsMonitor::parse(ext_data)?;
+                for (entry, is_valid) in entries.iter_mut().zip(valid.iter()) {
+                    entry.flags.fsmonitor_valid = *is_valid;
+                }
+                fsmonitor = Some(monitor);
+            }
             _ => {
                 // Preserve unknown extensions for round-trip
                 let mut sig_arr = [0u8; 4];
@@ -104,6 +113,7 @@ pub fn parse_index(data: &[u8]) -> Result<Index, IndexError> {
         entries,
         cache_tree,
         resolve_undo,
+        fsmonitor,
         unknown_extensions,
         _checksum: checksum,
     })
@@ -118,7 +128,7 @@ const SHA1_SIZE: usize = 20;
 
 /// Calculate the on-disk entry size using C git's formula:
 /// `((ONDISK_OFFSET_DATA + hash_size + flags_size + name_len + 8) & ~7)`
-fn ondisk_entry_size(name_len: usize, has_extended_flags: bool) -> usize {
+pub(crate) fn ondisk_entry_size(name_len: usize, has_extended_flags: bool) -> usize {
     let flags_size: usize = if has_extended_flags { 4 } else { 2 };
     let data_size = SHA1_SIZE + flags_size + name_len;
     (ONDISK_OFFSET_DATA + data_size + 8) & !7
@@ -236,10 +246,11 @@ fn parse_entry(
         assume_valid,
         intent_to_add,
         skip_worktree,
+        fsmonitor_valid: false, // filled in from the FSMN extension, if present
     };
 
     let entry = IndexEntry {
-        path,
+        path: path.into(),
         oid,
         mode,
         stage,
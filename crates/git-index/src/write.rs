@@ -3,14 +3,17 @@
 use std::io::Write;
 use std::path::Path;
 
+use bstr::BString;
 use git_hash::{HashAlgorithm, ObjectId};
 use git_hash::hasher::Hasher;
 use git_object::{FileMode, ObjectType, Tree, TreeEntry};
 use git_odb::ObjectDatabase;
 
 use crate::entry::IndexEntry;
-use crate::extensions::tree::CacheTree;
+use crate::extensions::fsmonitor::FsMonitor;
+use crate::extensions::tree::{CacheTree, CacheTreeNode};
 use crate::extensions::ResolveUndo;
+use crate::read::ondisk_entry_size;
 use crate::{Index, IndexError, Stage};
 
 /// Magic bytes at the start of every index file.
@@ -35,15 +38,19 @@ pub fn write_index(index: &Index, path: &Path) -> Result<(), IndexError> {
 /// Serialize the index to bytes.
 fn serialize_index(index: &Index) -> Result<Vec<u8>, IndexError> {
     let mut buf = Vec::new();
+    let version = resolve_write_version(index);
 
     // Header
     buf.extend_from_slice(INDEX_SIGNATURE);
-    buf.extend_from_slice(&2u32.to_be_bytes()); // always write v2
+    buf.extend_from_slice(&version.to_be_bytes());
     buf.extend_from_slice(&(index.entries.len() as u32).to_be_bytes());
 
     // Entries (must be sorted)
+    let write_ts = current_timestamp();
+    let mut prev_path = BString::default();
     for entry in index.iter() {
-        write_entry(&mut buf, entry);
+        write_entry(&mut buf, entry, write_ts, version, &prev_path);
+        prev_path = entry.path.to_bstring();
     }
 
     // Extensions
@@ -61,6 +68,14 @@ fn serialize_index(index: &Index) -> Result<Vec<u8>, IndexError> {
         buf.extend_from_slice(&reuc_data);
     }
 
+    if let Some(ref fsmonitor) = index.fsmonitor {
+        let valid: Vec<bool> = index.iter().map(|e| e.flags.fsmonitor_valid).collect();
+        let fsmonitor_data = FsMonitor::serialize(&fsmonitor.token, &valid);
+        buf.extend_from_slice(FsMonitor::SIGNATURE);
+        buf.extend_from_slice(&(fsmonitor_data.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&fsmonitor_data);
+    }
+
     // Unknown extensions (preserved for round-trip)
     for ext in &index.unknown_extensions {
         buf.extend_from_slice(&ext.signature);
@@ -76,9 +91,54 @@ fn serialize_index(index: &Index) -> Result<Vec<u8>, IndexError> {
     Ok(buf)
 }
 
-/// Write a single v2 cache entry.
-fn write_entry(buf: &mut Vec<u8>, entry: &IndexEntry) {
+/// Pick the on-disk format version to write.
+///
+/// Honors [`Index::version`] (set via [`Index::set_version`], e.g. from a
+/// `index.version` config), but a v4 request is downgraded to v3 whenever
+/// some entry carries extended flags (`intent_to_add`/`skip_worktree`), and
+/// any extended flags at all force at least v3.
+fn resolve_write_version(index: &Index) -> u32 {
+    let has_extended = index.entries.iter().any(|e| e.flags.has_extended());
+    if !has_extended {
+        return index.version;
+    }
+    if index.version == 4 {
+        3
+    } else {
+        index.version.max(3)
+    }
+}
+
+/// The current wall-clock time as index-timestamp secs/nsecs.
+fn current_timestamp() -> (u32, u32) {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    (now.as_secs() as u32, now.subsec_nanos())
+}
+
+/// Write a single cache entry in the given format `version`.
+///
+/// `write_ts` is the moment this index is being serialized. An entry whose
+/// cached mtime equals it is "racily clean": the file could have been
+/// written in the same clock tick as this index write, so its cached size
+/// is smudged to zero, forcing the next stat comparison to treat it as
+/// dirty (C git's `racy_mtime`/`ce_smudge_racily_clean_entry`).
+///
+/// `prev_path` is the previously written entry's path (empty before the
+/// first entry), used for v4 prefix compression.
+fn write_entry(
+    buf: &mut Vec<u8>,
+    entry: &IndexEntry,
+    write_ts: (u32, u32),
+    version: u32,
+    prev_path: &BString,
+) {
     let entry_start = buf.len();
+    let is_racily_clean =
+        (entry.stat.mtime_secs, entry.stat.mtime_nsecs) == write_ts;
+    let size = if is_racily_clean { 0 } else { entry.stat.size };
 
     // Stat data (40 bytes)
     buf.extend_from_slice(&entry.stat.ctime_secs.to_be_bytes());
@@ -90,61 +150,138 @@ fn write_entry(buf: &mut Vec<u8>, entry: &IndexEntry) {
     buf.extend_from_slice(&entry.mode.raw().to_be_bytes());
     buf.extend_from_slice(&entry.stat.uid.to_be_bytes());
     buf.extend_from_slice(&entry.stat.gid.to_be_bytes());
-    buf.extend_from_slice(&entry.stat.size.to_be_bytes());
+    buf.extend_from_slice(&size.to_be_bytes());
 
     // OID (20 bytes)
     buf.extend_from_slice(entry.oid.as_bytes());
 
-    // Flags (16 bits)
+    // Flags (16 bits), plus an extended flags word (v3+) if needed.
+    let extended = version >= 3 && entry.flags.has_extended();
     let name_len = std::cmp::min(entry.path.len(), 0xFFF) as u16;
     let mut flags: u16 = name_len;
     flags |= (entry.stage.as_u8() as u16) << 12;
     if entry.flags.assume_valid {
         flags |= 0x8000;
     }
-    // Note: we write v2, so no extended flag bit
+    if extended {
+        flags |= 0x4000;
+    }
     buf.extend_from_slice(&flags.to_be_bytes());
 
-    // Path
-    buf.extend_from_slice(&entry.path);
+    if extended {
+        let mut ext_flags: u16 = 0;
+        if entry.flags.intent_to_add {
+            ext_flags |= 0x2000;
+        }
+        if entry.flags.skip_worktree {
+            ext_flags |= 0x4000;
+        }
+        buf.extend_from_slice(&ext_flags.to_be_bytes());
+    }
 
-    // Pad using C git formula: entry_size = ((40 + 20 + 2 + name_len + 8) & ~7)
-    // The padding fills with NUL bytes from after the path to the end of the entry
-    let entry_size = (40 + 20 + 2 + entry.path.len() + 8) & !7;
-    let current_len = buf.len() - entry_start;
-    let padding = entry_size - current_len;
-    for _ in 0..padding {
-        buf.push(0);
+    if version == 4 {
+        write_v4_path(buf, entry.path.as_bytes(), prev_path);
+    } else {
+        // Path, NUL-terminated and padded to an 8-byte boundary.
+        buf.extend_from_slice(entry.path.as_bytes());
+        let entry_size = ondisk_entry_size(entry.path.len(), extended);
+        let current_len = buf.len() - entry_start;
+        let padding = entry_size - current_len;
+        for _ in 0..padding {
+            buf.push(0);
+        }
+    }
+}
+
+/// Write a v4 path: a varint count of trailing bytes to strip from
+/// `prev_path`, followed by the remaining suffix and a single NUL
+/// terminator (no padding). Mirrors `read::parse_v4_path`.
+fn write_v4_path(buf: &mut Vec<u8>, path: &[u8], prev_path: &BString) {
+    let common_len = path
+        .iter()
+        .zip(prev_path.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let strip_len = prev_path.len() - common_len;
+    write_varint(buf, strip_len);
+    buf.extend_from_slice(&path[common_len..]);
+    buf.push(0);
+}
+
+/// Write a variable-length integer (inverse of `read::read_varint`):
+/// 7 bits per byte, continuation bit (0x80) set on all but the last byte.
+fn write_varint(buf: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            break;
+        }
+        buf.push(byte | 0x80);
     }
 }
 
 /// Create a tree hierarchy from the current index entries.
-pub fn write_tree_from_index(index: &Index, odb: &ObjectDatabase) -> Result<ObjectId, IndexError> {
+///
+/// Reuses any still-valid node of the index's cache tree instead of
+/// re-serializing and re-writing that subtree's object, and re-caches
+/// whatever it does have to recompute so the next call benefits too.
+pub fn write_tree_from_index(index: &mut Index, odb: &ObjectDatabase) -> Result<ObjectId, IndexError> {
     // Only include stage-0 entries
-    let entries: Vec<&IndexEntry> = index.iter().filter(|e| e.stage == Stage::Normal).collect();
+    let entries: Vec<&IndexEntry> = index
+        .entries
+        .iter()
+        .filter(|e| e.stage == Stage::Normal)
+        .collect();
 
     if entries.is_empty() {
         // Write an empty tree
         let tree = Tree::new();
         let tree_bytes = tree.serialize_content();
-        return Ok(odb.write_raw(ObjectType::Tree, &tree_bytes)?);
+        let oid = odb.write_raw(ObjectType::Tree, &tree_bytes)?;
+        index.cache_tree = Some(CacheTree {
+            root: CacheTreeNode {
+                name: BString::new(Vec::new()),
+                entry_count: 0,
+                oid: Some(oid),
+                children: Vec::new(),
+            },
+        });
+        return Ok(oid);
     }
 
-    build_tree(&entries, b"", odb)
+    let cached_root = index.cache_tree.as_ref().map(|t| &t.root);
+    let (oid, root) = build_tree(&entries, b"", odb, cached_root, b"")?;
+    index.cache_tree = Some(CacheTree { root });
+    Ok(oid)
 }
 
-/// Recursively build tree objects from sorted index entries.
+/// Recursively build tree objects from sorted index entries, consulting
+/// (and refreshing) the matching node of a previous cache tree if given.
+/// `name` is this node's own path component (empty for the root).
 fn build_tree(
     entries: &[&IndexEntry],
     prefix: &[u8],
     odb: &ObjectDatabase,
-) -> Result<ObjectId, IndexError> {
+    cached: Option<&CacheTreeNode>,
+    name: &[u8],
+) -> Result<(ObjectId, CacheTreeNode), IndexError> {
+    if let Some(node) = cached {
+        if node.entry_count >= 0 && node.entry_count as usize == entries.len() {
+            if let Some(oid) = node.oid {
+                return Ok((oid, node.clone()));
+            }
+        }
+    }
+
     let mut tree_entries: Vec<TreeEntry> = Vec::new();
+    let mut children: Vec<CacheTreeNode> = Vec::new();
     let mut i = 0;
 
     while i < entries.len() {
         let entry = entries[i];
-        let path = &entry.path[prefix.len()..];
+        let path = &entry.path.as_bytes()[prefix.len()..];
 
         if let Some(slash_pos) = path.iter().position(|&b| b == b'/') {
             // This is a subtree entry
@@ -153,7 +290,7 @@ fn build_tree(
             let subtree_end = entries[i..]
                 .iter()
                 .position(|e| {
-                    let p = &e.path[prefix.len()..];
+                    let p = &e.path.as_bytes()[prefix.len()..];
                     !p.starts_with(dir_name) || (p.len() > slash_pos && p[slash_pos] != b'/')
                 })
                 .map(|pos| i + pos)
@@ -166,7 +303,12 @@ fn build_tree(
             new_prefix.extend_from_slice(dir_name);
             new_prefix.push(b'/');
 
-            let subtree_oid = build_tree(subtree_entries, &new_prefix, odb)?;
+            let cached_child = cached.and_then(|n| {
+                n.children.iter().find(|c| c.name.as_bytes() == dir_name)
+            });
+            let (subtree_oid, child_node) =
+                build_tree(subtree_entries, &new_prefix, odb, cached_child, dir_name)?;
+            children.push(child_node);
 
             tree_entries.push(TreeEntry {
                 mode: FileMode::Tree,
@@ -190,5 +332,14 @@ fn build_tree(
     tree.entries = tree_entries;
     tree.sort();
     let tree_bytes = tree.serialize_content();
-    Ok(odb.write_raw(ObjectType::Tree, &tree_bytes)?)
+    let oid = odb.write_raw(ObjectType::Tree, &tree_bytes)?;
+
+    let node = CacheTreeNode {
+        name: BString::from(name),
+        entry_count: entries.len() as i32,
+        oid: Some(oid),
+        children,
+    };
+
+    Ok((oid, node))
 }
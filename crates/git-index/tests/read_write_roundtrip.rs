@@ -7,7 +7,8 @@ use bstr::BStr;
 use git_hash::ObjectId;
 use git_index::{Index, IndexEntry, Stage};
 use git_index::entry::{EntryFlags, StatData};
-use git_object::FileMode;
+use git_object::{FileMode, Object};
+use git_odb::ObjectDatabase;
 
 /// Helper to check if git is available.
 fn has_git() -> bool {
@@ -276,3 +277,124 @@ fn update_existing_entry() {
         ObjectId::from_hex("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb").unwrap()
     );
 }
+
+fn test_odb(dir: &Path) -> ObjectDatabase {
+    ObjectDatabase::open(dir).expect("failed to open odb")
+}
+
+fn blob_entry(odb: &ObjectDatabase, path: &str, data: &[u8]) -> IndexEntry {
+    let oid = odb
+        .write(&Object::Blob(git_object::Blob { data: data.to_vec() }))
+        .unwrap();
+    IndexEntry {
+        path: path.into(),
+        oid,
+        mode: FileMode::Regular,
+        stage: Stage::Normal,
+        stat: StatData::default(),
+        flags: EntryFlags::default(),
+    }
+}
+
+#[test]
+fn write_tree_populates_cache_tree() {
+    let odb_dir = tempfile::tempdir().unwrap();
+    let odb = test_odb(odb_dir.path());
+
+    let mut index = Index::new();
+    index.add(blob_entry(&odb, "README.md", b"hello"));
+    index.add(blob_entry(&odb, "src/lib.rs", b"pub fn f() {}"));
+
+    let tree_oid = index.write_tree(&odb).unwrap();
+    assert!(!tree_oid.is_null());
+
+    let cache = index.cache_tree().expect("write_tree should populate the cache tree");
+    assert_eq!(cache.root.entry_count, 2);
+    assert_eq!(cache.root.oid, Some(tree_oid));
+    assert_eq!(cache.root.children.len(), 1);
+    assert_eq!(cache.root.children[0].name, "src");
+    assert_eq!(cache.root.children[0].entry_count, 1);
+}
+
+#[test]
+fn v4_index_roundtrips_with_path_compression() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("index");
+
+    let mut index = Index::new();
+    index.set_version(4).unwrap();
+    index.add(IndexEntry {
+        path: "src/lib.rs".into(),
+        oid: ObjectId::from_hex("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap(),
+        mode: FileMode::Regular,
+        stage: Stage::Normal,
+        stat: StatData::default(),
+        flags: EntryFlags::default(),
+    });
+    index.add(IndexEntry {
+        path: "src/main.rs".into(),
+        oid: ObjectId::from_hex("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb").unwrap(),
+        mode: FileMode::Regular,
+        stage: Stage::Normal,
+        stat: StatData::default(),
+        flags: EntryFlags::default(),
+    });
+
+    index.write_to(&path).expect("failed to write v4 index");
+
+    let index2 = Index::read_from(&path).expect("failed to read v4 index");
+    assert_eq!(index2.version(), 4);
+    assert_eq!(index2.len(), 2);
+    assert_eq!(&index2.iter().next().unwrap().path[..], b"src/lib.rs");
+    assert_eq!(&index2.iter().nth(1).unwrap().path[..], b"src/main.rs");
+}
+
+#[test]
+fn v4_request_falls_back_to_v3_with_extended_flags() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("index");
+
+    let mut index = Index::new();
+    index.set_version(4).unwrap();
+    index.add(IndexEntry {
+        path: "file.txt".into(),
+        oid: ObjectId::from_hex("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap(),
+        mode: FileMode::Regular,
+        stage: Stage::Normal,
+        stat: StatData::default(),
+        flags: EntryFlags {
+            intent_to_add: true,
+            ..EntryFlags::default()
+        },
+    });
+
+    index.write_to(&path).expect("failed to write index");
+
+    let index2 = Index::read_from(&path).expect("failed to read index");
+    assert_eq!(index2.version(), 3);
+    assert!(index2.get(BStr::new(b"file.txt"), Stage::Normal).unwrap().flags.intent_to_add);
+}
+
+#[test]
+fn write_tree_reuses_valid_subtree_oid() {
+    let odb_dir = tempfile::tempdir().unwrap();
+    let odb = test_odb(odb_dir.path());
+
+    let mut index = Index::new();
+    index.add(blob_entry(&odb, "README.md", b"hello"));
+    index.add(blob_entry(&odb, "src/lib.rs", b"pub fn f() {}"));
+
+    index.write_tree(&odb).unwrap();
+    let src_oid_before = index.cache_tree().unwrap().root.children[0].oid;
+
+    // Changing an unrelated top-level file invalidates the root but must
+    // leave the untouched "src" subtree's cached oid intact.
+    index.add(blob_entry(&odb, "README.md", b"hello, again"));
+    assert_eq!(index.cache_tree().unwrap().root.entry_count, -1);
+
+    index.write_tree(&odb).unwrap();
+    let src_oid_after = index.cache_tree().unwrap().root.children[0].oid;
+
+    assert_eq!(src_oid_before, src_oid_after);
+    assert_eq!(index.cache_tree().unwrap().root.children[0].entry_count, 1);
+}
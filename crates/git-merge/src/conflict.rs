@@ -46,7 +46,9 @@ pub fn write_merged_content(
 /// Record conflict stages (1=base, 2=ours, 3=theirs) in the index.
 ///
 /// Removes any existing stage-0 entry for the path and adds the
-/// appropriate conflict stage entries.
+/// appropriate conflict stage entries. If `conflict.sides` carries a
+/// generalized n-way view (recursive virtual bases, octopus merges), that
+/// takes priority over the legacy `base`/`ours`/`theirs` fields.
 pub fn record_conflict_in_index(
     index: &mut Index,
     conflict: &ConflictEntry,
@@ -56,10 +58,15 @@ pub fn record_conflict_in_index(
     // Remove any existing stage-0 entry.
     index.remove(path, Stage::Normal);
 
+    if let Some(ref sides) = conflict.sides {
+        record_merge_stages(index, &conflict.path, sides);
+        return;
+    }
+
     // Add stage 1 (base) if present.
     if let Some(ref base) = conflict.base {
         index.add(IndexEntry {
-            path: conflict.path.clone(),
+            path: conflict.path.clone().into(),
             oid: base.oid,
             mode: base.mode,
             stage: Stage::Base,
@@ -71,7 +78,7 @@ pub fn record_conflict_in_index(
     // Add stage 2 (ours) if present.
     if let Some(ref ours) = conflict.ours {
         index.add(IndexEntry {
-            path: conflict.path.clone(),
+            path: conflict.path.clone().into(),
             oid: ours.oid,
             mode: ours.mode,
             stage: Stage::Ours,
@@ -83,7 +90,52 @@ pub fn record_conflict_in_index(
     // Add stage 3 (theirs) if present.
     if let Some(ref theirs) = conflict.theirs {
         index.add(IndexEntry {
-            path: conflict.path.clone(),
+            path: conflict.path.clone().into(),
+            oid: theirs.oid,
+            mode: theirs.mode,
+            stage: Stage::Theirs,
+            stat: StatData::default(),
+            flags: EntryFlags::default(),
+        });
+    }
+}
+
+/// Record a generalized n-way `Merge<ConflictSide>` into the index's
+/// conflict stages.
+///
+/// The on-disk index format only has three conflict stages (1=base,
+/// 2=ours, 3=theirs), so a genuine >3-way conflict — more than one remove,
+/// or more than two adds, e.g. an unresolved recursive virtual base with
+/// several candidate merge bases — can't be fully represented on disk:
+/// only the first remove and first two adds are written here. The
+/// complete picture remains available in memory via `ConflictEntry::sides`
+/// for callers (a custom merge driver, `git status` detail) that want it.
+fn record_merge_stages(index: &mut Index, path: &BString, sides: &crate::Merge<crate::ConflictSide>) {
+    if let Some(base) = sides.removes.first() {
+        index.add(IndexEntry {
+            path: path.clone().into(),
+            oid: base.oid,
+            mode: base.mode,
+            stage: Stage::Base,
+            stat: StatData::default(),
+            flags: EntryFlags::default(),
+        });
+    }
+
+    if let Some(ours) = sides.adds.first() {
+        index.add(IndexEntry {
+            path: path.clone().into(),
+            oid: ours.oid,
+            mode: ours.mode,
+            stage: Stage::Ours,
+            stat: StatData::default(),
+            flags: EntryFlags::default(),
+        });
+    }
+
+    if let Some(theirs) = sides.adds.get(1) {
+        index.add(IndexEntry {
+            path: path.clone().into(),
             oid: theirs.oid,
             mode: theirs.mode,
             stage: Stage::Theirs,
@@ -118,7 +170,7 @@ pub fn record_clean_merge_in_index(
 
     // Add clean stage-0 entry.
     index.add(IndexEntry {
-        path: BString::from(path),
+        path: BString::from(path).into(),
         oid,
         mode,
         stage: Stage::Normal,
@@ -143,7 +195,7 @@ pub fn resolve_conflict(
     index.remove(path, Stage::Theirs);
 
     index.add(IndexEntry {
-        path: BString::from(path),
+        path: BString::from(path).into(),
         oid,
         mode,
         stage: Stage::Normal,
@@ -184,6 +236,7 @@ mod tests {
                 mode: FileMode::Regular,
                 path: BString::from("file.txt"),
             }),
+            sides: None,
         };
 
         record_conflict_in_index(&mut index, &conflict);
@@ -213,6 +266,7 @@ mod tests {
                 path: BString::from("deleted.txt"),
             }),
             theirs: None, // Deleted on their side.
+            sides: None,
         };
 
         record_conflict_in_index(&mut index, &conflict);
@@ -223,6 +277,29 @@ mod tests {
         assert!(index.get(path, Stage::Theirs).is_none());
     }
 
+    #[test]
+    fn record_n_way_conflict_writes_first_remove_and_first_two_adds() {
+        let mut index = Index::new();
+        let side = |byte: u8| ConflictSide {
+            oid: test_oid(byte),
+            mode: FileMode::Regular,
+            path: BString::from("file.txt"),
+        };
+        let sides = crate::Merge::new(vec![side(2), side(3), side(4)], vec![side(1), side(5)]);
+        let conflict = ConflictEntry::from_merge(
+            BString::from("file.txt"),
+            crate::ConflictType::Content,
+            sides,
+        );
+
+        record_conflict_in_index(&mut index, &conflict);
+
+        let path: &BStr = b"file.txt".as_bstr();
+        assert_eq!(index.get(path, Stage::Base).unwrap().oid, test_oid(1));
+        assert_eq!(index.get(path, Stage::Ours).unwrap().oid, test_oid(2));
+        assert_eq!(index.get(path, Stage::Theirs).unwrap().oid, test_oid(3));
+    }
+
     #[test]
     fn resolve_conflict_clears_stages() {
         let mut index = Index::new();
@@ -230,7 +307,7 @@ mod tests {
         // Add conflict stages.
         let path: &BStr = b"file.txt".as_bstr();
         index.add(IndexEntry {
-            path: BString::from("file.txt"),
+            path: BString::from("file.txt").into(),
             oid: test_oid(1),
             mode: FileMode::Regular,
             stage: Stage::Base,
@@ -238,7 +315,7 @@ mod tests {
             flags: EntryFlags::default(),
         });
         index.add(IndexEntry {
-            path: BString::from("file.txt"),
+            path: BString::from("file.txt").into(),
             oid: test_oid(2),
             mode: FileMode::Regular,
             stage: Stage::Ours,
@@ -246,7 +323,7 @@ mod tests {
             flags: EntryFlags::default(),
         });
         index.add(IndexEntry {
-            path: BString::from("file.txt"),
+            path: BString::from("file.txt").into(),
             oid: test_oid(3),
             mode: FileMode::Regular,
             stage: Stage::Theirs,
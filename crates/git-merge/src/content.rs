@@ -3,7 +3,7 @@
 //! Takes base, ours, and theirs versions of a file and produces a merged result,
 //! inserting conflict markers where changes overlap.
 
-use crate::{ConflictStyle, ContentMergeResult, MergeOptions};
+use crate::{ConflictFavor, ConflictStyle, ContentMergeResult, MergeOptions};
 use git_diff::algorithm::{diff_edits, split_lines, Edit, EditOp};
 
 /// Labels for conflict markers.
@@ -50,16 +50,16 @@ pub fn merge_content(
         return ContentMergeResult::Clean(ours.to_vec());
     }
 
-    // Check strategy options for forced resolution.
-    let favor_ours = options.strategy_options.iter().any(|o| o == "ours");
-    let favor_theirs = options.strategy_options.iter().any(|o| o == "theirs");
-
-    if favor_ours {
-        return ContentMergeResult::Clean(ours.to_vec());
-    }
-    if favor_theirs {
-        return ContentMergeResult::Clean(theirs.to_vec());
-    }
+    // `-X ours`/`-X theirs` passed as strategy option strings take priority
+    // over `options.favor` for backward compatibility with callers that
+    // only set the former.
+    let favor = if options.strategy_options.iter().any(|o| o == "ours") {
+        ConflictFavor::Ours
+    } else if options.strategy_options.iter().any(|o| o == "theirs") {
+        ConflictFavor::Theirs
+    } else {
+        options.favor
+    };
 
     // Perform line-level three-way merge.
     let base_lines = split_lines(base);
@@ -80,6 +80,7 @@ pub fn merge_content(
         &hunks_ours,
         &hunks_theirs,
         options.conflict_style,
+        favor,
         labels,
     )
 }
@@ -146,6 +147,7 @@ fn merge_regions(
     hunks_ours: &[ChangeRegion],
     hunks_theirs: &[ChangeRegion],
     conflict_style: ConflictStyle,
+    favor: ConflictFavor,
     labels: &MergeLabels<'_>,
 ) -> ContentMergeResult {
     let mut output: Vec<u8> = Vec::new();
@@ -190,18 +192,30 @@ fn merge_regions(
                         // Identical changes — accept cleanly.
                         output.extend_from_slice(&ours_content);
                     } else {
-                        // Conflict.
-                        conflict_count += 1;
-                        let base_content =
-                            collect_lines(base_lines, overlap_base_start, overlap_base_end - overlap_base_start);
-                        emit_conflict(
-                            &mut output,
-                            &ours_content,
-                            &theirs_content,
-                            &base_content,
-                            conflict_style,
-                            labels,
-                        );
+                        match favor {
+                            ConflictFavor::Ours => output.extend_from_slice(&ours_content),
+                            ConflictFavor::Theirs => output.extend_from_slice(&theirs_content),
+                            ConflictFavor::Union => {
+                                output.extend_from_slice(&ours_content);
+                                output.extend_from_slice(&theirs_content);
+                            }
+                            ConflictFavor::Normal => {
+                                conflict_count += 1;
+                                let base_content = collect_lines(
+                                    base_lines,
+                                    overlap_base_start,
+                                    overlap_base_end - overlap_base_start,
+                                );
+                                emit_conflict(
+                                    &mut output,
+                                    &ours_content,
+                                    &theirs_content,
+                                    &base_content,
+                                    conflict_style,
+                                    labels,
+                                );
+                            }
+                        }
                     }
 
                     base_pos = overlap_base_end;
@@ -281,6 +295,18 @@ fn emit_conflict(
     style: ConflictStyle,
     labels: &MergeLabels<'_>,
 ) {
+    // zdiff3 hoists lines shared by all three sides out of the conflict
+    // region entirely, leaving a (usually) smaller conflict behind — unlike
+    // plain diff3, which always shows the full three versions in full.
+    let (leading, ours_content, theirs_content, base_content, trailing) =
+        if style == ConflictStyle::ZDiff3 {
+            hoist_common_lines(ours_content, theirs_content, base_content)
+        } else {
+            (&[][..], ours_content, theirs_content, base_content, &[][..])
+        };
+
+    output.extend_from_slice(leading);
+
     // <<<<<<< ours-label
     output.extend_from_slice(b"<<<<<<< ");
     output.extend_from_slice(labels.ours.as_bytes());
@@ -308,6 +334,69 @@ fn emit_conflict(
     output.extend_from_slice(b">>>>>>> ");
     output.extend_from_slice(labels.theirs.as_bytes());
     output.push(b'\n');
+
+    output.extend_from_slice(trailing);
+}
+
+/// Split off lines shared by `ours`, `theirs`, and `base` at the start and
+/// end of the three conflicting regions, returning `(leading, ours, theirs,
+/// base, trailing)` where `leading`/`trailing` are the hoisted shared lines
+/// and the other three are what's left of the conflict after hoisting.
+///
+/// This is zdiff3's distinguishing behavior: a conflicting hunk often still
+/// shares some lines across all sides (e.g. blank lines or unrelated
+/// statements caught up in a single diff hunk), and showing those once
+/// outside the markers makes the remaining conflict smaller and easier to
+/// read.
+fn hoist_common_lines<'a>(
+    ours: &'a [u8],
+    theirs: &'a [u8],
+    base: &'a [u8],
+) -> (&'a [u8], &'a [u8], &'a [u8], &'a [u8], &'a [u8]) {
+    let ours_lines = split_lines(ours);
+    let theirs_lines = split_lines(theirs);
+    let base_lines = split_lines(base);
+
+    let min_len = ours_lines.len().min(theirs_lines.len()).min(base_lines.len());
+
+    let mut common_leading = 0;
+    while common_leading < min_len
+        && ours_lines[common_leading] == theirs_lines[common_leading]
+        && ours_lines[common_leading] == base_lines[common_leading]
+    {
+        common_leading += 1;
+    }
+
+    // Trailing lines must not re-consume lines already claimed as leading.
+    let remaining = min_len - common_leading;
+    let mut common_trailing = 0;
+    while common_trailing < remaining
+        && ours_lines[ours_lines.len() - 1 - common_trailing]
+            == theirs_lines[theirs_lines.len() - 1 - common_trailing]
+        && ours_lines[ours_lines.len() - 1 - common_trailing]
+            == base_lines[base_lines.len() - 1 - common_trailing]
+    {
+        common_trailing += 1;
+    }
+
+    // Each line returned by `split_lines` already includes its own trailing
+    // newline, so a byte offset is a plain sum of line lengths.
+    let line_offset = |lines: &[&[u8]], count: usize| -> usize {
+        lines[..count].iter().map(|l| l.len()).sum()
+    };
+
+    let leading_bytes = line_offset(&ours_lines, common_leading);
+    let ours_trailing_bytes = line_offset(&ours_lines[ours_lines.len() - common_trailing..], common_trailing);
+    let theirs_trailing_bytes = line_offset(&theirs_lines[theirs_lines.len() - common_trailing..], common_trailing);
+    let base_trailing_bytes = line_offset(&base_lines[base_lines.len() - common_trailing..], common_trailing);
+
+    (
+        &ours[..leading_bytes],
+        &ours[leading_bytes..ours.len() - ours_trailing_bytes],
+        &theirs[leading_bytes..theirs.len() - theirs_trailing_bytes],
+        &base[leading_bytes..base.len() - base_trailing_bytes],
+        &ours[ours.len() - ours_trailing_bytes..],
+    )
 }
 
 #[cfg(test)]
@@ -421,6 +510,42 @@ mod tests {
         assert_eq!(result.content(), theirs);
     }
 
+    #[test]
+    fn favor_ours_resolves_conflicting_hunk_cleanly() {
+        let base = b"line1\noriginal\nline3\n";
+        let ours = b"line1\nours_change\nline3\n";
+        let theirs = b"line1\ntheirs_change\nline3\n";
+        let mut opts = default_opts();
+        opts.favor = ConflictFavor::Ours;
+        let result = merge_content(base, ours, theirs, &opts, &default_labels());
+        assert!(result.is_clean());
+        assert_eq!(result.content(), ours);
+    }
+
+    #[test]
+    fn favor_theirs_resolves_conflicting_hunk_cleanly() {
+        let base = b"line1\noriginal\nline3\n";
+        let ours = b"line1\nours_change\nline3\n";
+        let theirs = b"line1\ntheirs_change\nline3\n";
+        let mut opts = default_opts();
+        opts.favor = ConflictFavor::Theirs;
+        let result = merge_content(base, ours, theirs, &opts, &default_labels());
+        assert!(result.is_clean());
+        assert_eq!(result.content(), theirs);
+    }
+
+    #[test]
+    fn favor_union_keeps_both_sides_no_markers() {
+        let base = b"line1\noriginal\nline3\n";
+        let ours = b"line1\nours_change\nline3\n";
+        let theirs = b"line1\ntheirs_change\nline3\n";
+        let mut opts = default_opts();
+        opts.favor = ConflictFavor::Union;
+        let result = merge_content(base, ours, theirs, &opts, &default_labels());
+        assert!(result.is_clean());
+        assert_eq!(result.content(), b"line1\nours_change\ntheirs_change\nline3\n");
+    }
+
     #[test]
     fn both_sides_identical_changes() {
         let base = b"line1\noriginal\nline3\n";
@@ -432,6 +557,55 @@ mod tests {
         assert!(content.contains("same_change"));
     }
 
+    #[test]
+    fn hoist_common_lines_splits_shared_prefix_and_suffix() {
+        let ours = b"shared1\nours_change\nshared2\nshared3\n";
+        let theirs = b"shared1\ntheirs_change\nshared2\nshared3\n";
+        let base = b"shared1\noriginal\nshared2\nshared3\n";
+
+        let (leading, ours_rest, theirs_rest, base_rest, trailing) =
+            hoist_common_lines(ours, theirs, base);
+
+        assert_eq!(leading, b"shared1\n");
+        assert_eq!(trailing, b"shared2\nshared3\n");
+        assert_eq!(ours_rest, b"ours_change\n");
+        assert_eq!(theirs_rest, b"theirs_change\n");
+        assert_eq!(base_rest, b"original\n");
+    }
+
+    #[test]
+    fn hoist_common_lines_no_shared_lines() {
+        let ours = b"ours_only\n";
+        let theirs = b"theirs_only\n";
+        let base = b"base_only\n";
+
+        let (leading, ours_rest, theirs_rest, base_rest, trailing) =
+            hoist_common_lines(ours, theirs, base);
+
+        assert!(leading.is_empty());
+        assert!(trailing.is_empty());
+        assert_eq!(ours_rest, ours.as_slice());
+        assert_eq!(theirs_rest, theirs.as_slice());
+        assert_eq!(base_rest, base.as_slice());
+    }
+
+    #[test]
+    fn zdiff3_conflict_style_still_reports_a_conflict() {
+        // Exercises the `emit_conflict` path with `ZDiff3`, which routes
+        // through `hoist_common_lines` before falling back to the same
+        // marker layout as `Diff3` when nothing can be hoisted.
+        let base = b"line1\noriginal\nline3\n";
+        let ours = b"line1\nours\nline3\n";
+        let theirs = b"line1\ntheirs\nline3\n";
+        let mut opts = default_opts();
+        opts.conflict_style = ConflictStyle::ZDiff3;
+        let result = merge_content(base, ours, theirs, &opts, &default_labels());
+        assert!(!result.is_clean());
+        let content = String::from_utf8_lossy(result.content());
+        assert!(content.contains("||||||| base"));
+        assert!(content.contains("original"));
+    }
+
     #[test]
     fn empty_base() {
         let base = b"";
@@ -9,16 +9,21 @@ pub mod apply;
 pub mod cherry_pick;
 pub mod conflict;
 pub mod content;
+pub mod notes_merge;
+pub mod nway;
 pub mod rerere;
 pub mod revert;
 pub mod sequencer;
 pub mod strategy;
+pub mod tree_merge;
 
 use bstr::BString;
 use git_diff::DiffAlgorithm;
 use git_hash::ObjectId;
 use git_object::FileMode;
 
+pub use nway::Merge;
+
 /// Options for merge operations.
 #[derive(Debug, Clone)]
 pub struct MergeOptions {
@@ -30,10 +35,27 @@ pub struct MergeOptions {
     pub diff_algorithm: DiffAlgorithm,
     /// Similarity threshold for rename detection (0-100, default 50).
     pub rename_threshold: u8,
+    /// Majority threshold for inferring a whole-directory rename from its
+    /// individually renamed files (0-100, default 50), analogous to
+    /// `rename_threshold`.
+    pub directory_rename_threshold: u8,
     /// Conflict marker style.
     pub conflict_style: ConflictStyle,
     /// Allow merging unrelated histories.
     pub allow_unrelated_histories: bool,
+    /// How to resolve conflicting hunks automatically, equivalent to
+    /// `-X ours`/`-X theirs`/`-X union`.
+    pub favor: ConflictFavor,
+    /// Label for the common-ancestor side of a conflict marker
+    /// (`||||||| <label>` in diff3/zdiff3 style). Defaults to `"base"` when
+    /// `None`.
+    pub ancestor_label: Option<String>,
+    /// Label for the `<<<<<<< <label>` side of a conflict marker. Defaults
+    /// to `"HEAD"` when `None`.
+    pub our_label: Option<String>,
+    /// Label for the `>>>>>>> <label>` side of a conflict marker. Defaults
+    /// to `"merge"` when `None`.
+    pub their_label: Option<String>,
 }
 
 impl Default for MergeOptions {
@@ -43,8 +65,39 @@ impl Default for MergeOptions {
             strategy_options: Vec::new(),
             diff_algorithm: DiffAlgorithm::Myers,
             rename_threshold: 50,
+            directory_rename_threshold: 50,
             conflict_style: ConflictStyle::Merge,
             allow_unrelated_histories: false,
+            favor: ConflictFavor::Normal,
+            ancestor_label: None,
+            our_label: None,
+            their_label: None,
+        }
+    }
+}
+
+/// How to resolve a conflicting hunk automatically during content merge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictFavor {
+    /// Emit conflict markers as usual (the default).
+    #[default]
+    Normal,
+    /// Take our side's lines for conflicting hunks.
+    Ours,
+    /// Take their side's lines for conflicting hunks.
+    Theirs,
+    /// Keep both sides' lines for conflicting hunks, concatenated, with no markers.
+    Union,
+}
+
+impl ConflictFavor {
+    /// Parse a favor name (as used by `git merge -X ours`/`-X theirs`/`-X union`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ours" => Some(Self::Ours),
+            "theirs" => Some(Self::Theirs),
+            "union" => Some(Self::Union),
+            _ => None,
         }
     }
 }
@@ -173,6 +226,34 @@ pub struct ConflictEntry {
     pub ours: Option<ConflictSide>,
     /// Their side (branch being merged).
     pub theirs: Option<ConflictSide>,
+    /// Generalized n-way view of this conflict's sides, used for genuine
+    /// >3-way conflicts (e.g. an unresolved recursive virtual base) that
+    /// the `base`/`ours`/`theirs` triple can't express. `None` for plain
+    /// 3-way conflicts, which rely on the fields above instead.
+    pub sides: Option<Merge<ConflictSide>>,
+}
+
+impl ConflictEntry {
+    /// Build a `ConflictEntry` from a generalized n-way `Merge<ConflictSide>`.
+    ///
+    /// For the common 3-way case (one remove, two adds) this also fills in
+    /// the legacy `base`/`ours`/`theirs` fields so existing consumers (e.g.
+    /// index stage recording) keep working unchanged. For a genuine
+    /// >3-way conflict those fields are left `None`, and `sides` carries
+    /// the full picture.
+    pub fn from_merge(path: BString, conflict_type: ConflictType, sides: Merge<ConflictSide>) -> Self {
+        let (base, ours, theirs) = if sides.removes.len() == 1 && sides.adds.len() == 2 {
+            (
+                Some(sides.removes[0].clone()),
+                Some(sides.adds[0].clone()),
+                Some(sides.adds[1].clone()),
+            )
+        } else {
+            (None, None, None)
+        };
+
+        Self { path, conflict_type, base, ours, theirs, sides: Some(sides) }
+    }
 }
 
 /// Types of merge conflicts.
@@ -190,6 +271,13 @@ pub enum ConflictType {
     RenameDelete,
     /// One side added a directory, the other a file at the same path.
     DirectoryFile,
+    /// Both sides advanced a submodule pointer to diverging commits.
+    Submodule,
+    /// Both sides renamed the same directory to different targets.
+    DirectoryRename,
+    /// Both sides changed an entry's type (file, symlink, gitlink)
+    /// incompatibly.
+    TypeChange,
 }
 
 /// One side of a conflict.
@@ -269,12 +357,18 @@ pub enum MergeError {
     #[error(transparent)]
     Diff(#[from] git_diff::DiffError),
 
+    #[error(transparent)]
+    Object(#[from] git_object::ObjectError),
+
     #[error(transparent)]
     Odb(#[from] git_odb::OdbError),
 
     #[error(transparent)]
     Repo(#[from] git_repository::RepoError),
 
+    #[error(transparent)]
+    RevWalk(#[from] git_revwalk::RevWalkError),
+
     #[error(transparent)]
     Index(#[from] git_index::IndexError),
 
@@ -292,9 +386,19 @@ mod tests {
         assert_eq!(opts.strategy, MergeStrategyType::Ort);
         assert_eq!(opts.diff_algorithm, DiffAlgorithm::Myers);
         assert_eq!(opts.rename_threshold, 50);
+        assert_eq!(opts.directory_rename_threshold, 50);
         assert_eq!(opts.conflict_style, ConflictStyle::Merge);
         assert!(!opts.allow_unrelated_histories);
         assert!(opts.strategy_options.is_empty());
+        assert_eq!(opts.favor, ConflictFavor::Normal);
+    }
+
+    #[test]
+    fn conflict_favor_from_name() {
+        assert_eq!(ConflictFavor::from_name("ours"), Some(ConflictFavor::Ours));
+        assert_eq!(ConflictFavor::from_name("theirs"), Some(ConflictFavor::Theirs));
+        assert_eq!(ConflictFavor::from_name("union"), Some(ConflictFavor::Union));
+        assert_eq!(ConflictFavor::from_name("bogus"), None);
     }
 
     #[test]
@@ -314,6 +418,7 @@ mod tests {
             base: None,
             ours: None,
             theirs: None,
+            sides: None,
         }];
         let result = MergeResult::conflicted(conflicts);
         assert!(!result.is_clean);
@@ -344,6 +449,33 @@ mod tests {
         assert_ne!(ConflictType::Content, ConflictType::AddAdd);
     }
 
+    #[test]
+    fn conflict_entry_from_merge_fills_legacy_fields_for_three_way() {
+        let oid = ObjectId::from_hex("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+        let side = |path: &str| ConflictSide { oid, mode: FileMode::Regular, path: BString::from(path) };
+        let sides = Merge::new(vec![side("ours.txt"), side("theirs.txt")], vec![side("base.txt")]);
+        let entry = ConflictEntry::from_merge(BString::from("file.txt"), ConflictType::Content, sides);
+        assert!(entry.base.is_some());
+        assert!(entry.ours.is_some());
+        assert!(entry.theirs.is_some());
+        assert!(entry.sides.is_some());
+    }
+
+    #[test]
+    fn conflict_entry_from_merge_leaves_legacy_fields_empty_for_n_way() {
+        let oid = ObjectId::from_hex("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+        let side = |path: &str| ConflictSide { oid, mode: FileMode::Regular, path: BString::from(path) };
+        let sides = Merge::new(
+            vec![side("ours.txt"), side("theirs.txt"), side("third.txt")],
+            vec![side("base1.txt"), side("base2.txt")],
+        );
+        let entry = ConflictEntry::from_merge(BString::from("file.txt"), ConflictType::Content, sides);
+        assert!(entry.base.is_none());
+        assert!(entry.ours.is_none());
+        assert!(entry.theirs.is_none());
+        assert_eq!(entry.sides.unwrap().adds.len(), 3);
+    }
+
     #[test]
     fn merge_strategy_types() {
         assert_eq!(MergeStrategyType::Ort, MergeStrategyType::Ort);
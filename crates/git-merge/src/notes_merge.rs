@@ -0,0 +1,309 @@
+//! `git notes merge`: an entry-keyed three-way merge over a notes tree.
+//!
+//! A notes ref (e.g. `refs/notes/commits`) points to a commit whose tree
+//! maps annotated-object hex ids to blobs holding note text, optionally
+//! fanned out one directory level (`ab/cdef...`) the way real git lays
+//! large notes trees out. Unlike [`tree_merge`](crate::tree_merge), the
+//! "path" here isn't a filesystem path to diff structurally — it's an
+//! object id key, and the merge is a flat per-key three-way merge: if only
+//! one side changed a key, take it; if both changed it to different blobs,
+//! the unresolved note text is the concatenation of both sides separated by
+//! conflict markers (git's own notes-merge behavior, since note bodies
+//! aren't diffed line-by-line); a delete on one side and a modification on
+//! the other is a conflict with no sensible default.
+
+use std::collections::BTreeMap;
+
+use bstr::BString;
+use git_hash::ObjectId;
+use git_object::{Blob, FileMode, Object, Tree, TreeEntry};
+use git_odb::ObjectDatabase;
+
+use crate::{ConflictEntry, ConflictSide, ConflictType, MergeError, MergeResult};
+
+/// Three-way merge the notes trees at `base`/`ours`/`theirs` (each the tree
+/// of a notes commit, or `None` for an empty notes ref). Returns a clean
+/// merged tree oid, or the full list of object ids whose notes conflict.
+///
+/// A conflicted result still carries a tree (`MergeResult::tree`): a key
+/// that both sides changed to different notes gets a blob in that tree
+/// holding both notes separated by `<<<<<<<`/`=======`/`>>>>>>>` markers,
+/// so a caller can stage it the way `git notes merge` stages
+/// `NOTES_MERGE_WORKTREE` for manual resolution. A modify/delete conflict
+/// leaves the key out of the tree.
+pub fn merge_notes_trees(
+    odb: &ObjectDatabase,
+    base: Option<ObjectId>,
+    ours: Option<ObjectId>,
+    theirs: Option<ObjectId>,
+) -> Result<MergeResult, MergeError> {
+    let base_notes = flatten_notes_tree(odb, base)?;
+    let ours_notes = flatten_notes_tree(odb, ours)?;
+    let theirs_notes = flatten_notes_tree(odb, theirs)?;
+
+    let mut keys: Vec<&String> = ours_notes.keys().chain(theirs_notes.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut entries = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for key in keys {
+        let base_oid = base_notes.get(key).copied();
+        let ours_oid = ours_notes.get(key).copied();
+        let theirs_oid = theirs_notes.get(key).copied();
+
+        let resolved = if ours_oid == theirs_oid {
+            ours_oid
+        } else if base_oid == ours_oid {
+            theirs_oid
+        } else if base_oid == theirs_oid {
+            ours_oid
+        } else {
+            None
+        };
+
+        match resolved {
+            Some(oid) => {
+                entries.push(TreeEntry {
+                    mode: FileMode::Regular,
+                    name: BString::from(key.as_str()),
+                    oid,
+                });
+            }
+            None if ours_oid == theirs_oid => {
+                // Both sides deleted the same key — nothing to keep, no
+                // conflict.
+            }
+            None => {
+                let conflict_type = if ours_oid.is_none() || theirs_oid.is_none() {
+                    ConflictType::ModifyDelete
+                } else {
+                    ConflictType::Content
+                };
+
+                let path = BString::from(key.as_str());
+                let side = |oid: Option<ObjectId>| {
+                    oid.map(|oid| ConflictSide {
+                        oid,
+                        mode: FileMode::Regular,
+                        path: path.clone(),
+                    })
+                };
+
+                // Both sides modified the note differently: concatenate both
+                // bodies behind conflict markers and keep that as the entry,
+                // matching how `git notes merge` leaves a mergeable text
+                // blob behind for the user to resolve by hand.
+                if let (Some(ours_oid), Some(theirs_oid)) = (ours_oid, theirs_oid) {
+                    let merged_oid =
+                        write_conflict_blob(odb, ours_oid, theirs_oid)?;
+                    entries.push(TreeEntry {
+                        mode: FileMode::Regular,
+                        name: path.clone(),
+                        oid: merged_oid,
+                    });
+                }
+
+                conflicts.push(ConflictEntry {
+                    path,
+                    conflict_type,
+                    base: side(base_oid),
+                    ours: side(ours_oid),
+                    theirs: side(theirs_oid),
+                    sides: None,
+                });
+            }
+        }
+    }
+
+    if !conflicts.is_empty() {
+        let mut result = MergeResult::conflicted(conflicts);
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        let tree_oid = odb.write(&Object::Tree(Tree { entries }))?;
+        result.tree = Some(tree_oid);
+        return Ok(result);
+    }
+
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    let tree_oid = odb.write(&Object::Tree(Tree { entries }))?;
+    Ok(MergeResult::clean(tree_oid))
+}
+
+/// Read the blobs for `ours`/`theirs`, concatenate them behind conflict
+/// markers, and write the result as a new blob.
+fn write_conflict_blob(
+    odb: &ObjectDatabase,
+    ours: ObjectId,
+    theirs: ObjectId,
+) -> Result<ObjectId, MergeError> {
+    let ours_text = read_blob(odb, ours)?;
+    let theirs_text = read_blob(odb, theirs)?;
+
+    let mut content = Vec::new();
+    content.extend_from_slice(b"<<<<<<< ours\n");
+    content.extend_from_slice(&ours_text);
+    if !ours_text.ends_with(b"\n") {
+        content.push(b'\n');
+    }
+    content.extend_from_slice(b"=======\n");
+    content.extend_from_slice(&theirs_text);
+    if !theirs_text.ends_with(b"\n") {
+        content.push(b'\n');
+    }
+    content.extend_from_slice(b">>>>>>> theirs\n");
+
+    Ok(odb.write(&Object::Blob(Blob { data: content }))?)
+}
+
+fn read_blob(odb: &ObjectDatabase, oid: ObjectId) -> Result<Vec<u8>, MergeError> {
+    match odb.read(&oid)? {
+        Some(Object::Blob(blob)) => Ok(blob.data),
+        Some(obj) => Err(MergeError::UnexpectedObjectType {
+            oid,
+            expected: "blob",
+            actual: obj.object_type().to_string(),
+        }),
+        None => Err(MergeError::ObjectNotFound(oid)),
+    }
+}
+
+/// Flatten a notes tree — either the flat layout this crate writes (entry
+/// name is the full hex object id) or the `ab/cdef...` fanout layout real
+/// git produces for large notes trees — into a map from hex object id to
+/// note blob oid. `None` (no notes commit yet) flattens to an empty map.
+fn flatten_notes_tree(
+    odb: &ObjectDatabase,
+    tree_oid: Option<ObjectId>,
+) -> Result<BTreeMap<String, ObjectId>, MergeError> {
+    let mut out = BTreeMap::new();
+    if let Some(tree_oid) = tree_oid {
+        collect_note_entries(odb, tree_oid, "", &mut out)?;
+    }
+    Ok(out)
+}
+
+fn collect_note_entries(
+    odb: &ObjectDatabase,
+    tree_oid: ObjectId,
+    prefix: &str,
+    out: &mut BTreeMap<String, ObjectId>,
+) -> Result<(), MergeError> {
+    let obj = odb.read(&tree_oid)?.ok_or(MergeError::ObjectNotFound(tree_oid))?;
+    let tree = match obj {
+        Object::Tree(tree) => tree,
+        other => {
+            return Err(MergeError::UnexpectedObjectType {
+                oid: tree_oid,
+                expected: "tree",
+                actual: other.object_type().to_string(),
+            })
+        }
+    };
+
+    for entry in &tree.entries {
+        let name = String::from_utf8_lossy(entry.name.as_ref());
+        let key = format!("{}{}", prefix, name);
+        if entry.mode == FileMode::Tree {
+            collect_note_entries(odb, entry.oid, &key, out)?;
+        } else if is_hex(&key) {
+            out.insert(key, entry.oid);
+        }
+    }
+
+    Ok(())
+}
+
+fn is_hex(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_odb() -> (TempDir, ObjectDatabase) {
+        let dir = TempDir::new().unwrap();
+        let odb = ObjectDatabase::open(dir.path()).unwrap();
+        (dir, odb)
+    }
+
+    fn write_blob(odb: &ObjectDatabase, content: &[u8]) -> ObjectId {
+        odb.write(&Object::Blob(Blob { data: content.to_vec() })).unwrap()
+    }
+
+    fn notes_tree(odb: &ObjectDatabase, entries: &[(&str, ObjectId)]) -> ObjectId {
+        let entries = entries
+            .iter()
+            .map(|(name, oid)| TreeEntry {
+                mode: FileMode::Regular,
+                name: BString::from(*name),
+                oid: *oid,
+            })
+            .collect();
+        odb.write(&Object::Tree(Tree { entries })).unwrap()
+    }
+
+    #[test]
+    fn only_ours_changed_key_is_kept() {
+        let (_dir, odb) = test_odb();
+        let target = "a".repeat(40);
+        let note = write_blob(&odb, b"hello\n");
+        let base = notes_tree(&odb, &[]);
+        let ours = notes_tree(&odb, &[(&target, note)]);
+        let theirs = notes_tree(&odb, &[]);
+
+        let result = merge_notes_trees(&odb, Some(base), Some(ours), Some(theirs)).unwrap();
+        assert!(result.is_clean);
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[test]
+    fn both_sides_change_same_key_differently_conflicts() {
+        let (_dir, odb) = test_odb();
+        let target = "b".repeat(40);
+        let original = write_blob(&odb, b"original\n");
+        let ours_note = write_blob(&odb, b"ours text\n");
+        let theirs_note = write_blob(&odb, b"theirs text\n");
+
+        let base = notes_tree(&odb, &[(&target, original)]);
+        let ours = notes_tree(&odb, &[(&target, ours_note)]);
+        let theirs = notes_tree(&odb, &[(&target, theirs_note)]);
+
+        let result = merge_notes_trees(&odb, Some(base), Some(ours), Some(theirs)).unwrap();
+        assert!(!result.is_clean);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].conflict_type, ConflictType::Content);
+        assert_eq!(result.conflicts[0].path, BString::from(target));
+    }
+
+    #[test]
+    fn modify_delete_is_a_conflict() {
+        let (_dir, odb) = test_odb();
+        let target = "c".repeat(40);
+        let original = write_blob(&odb, b"original\n");
+        let ours_note = write_blob(&odb, b"ours text\n");
+
+        let base = notes_tree(&odb, &[(&target, original)]);
+        let ours = notes_tree(&odb, &[(&target, ours_note)]);
+        let theirs = notes_tree(&odb, &[]);
+
+        let result = merge_notes_trees(&odb, Some(base), Some(ours), Some(theirs)).unwrap();
+        assert!(!result.is_clean);
+        assert_eq!(result.conflicts[0].conflict_type, ConflictType::ModifyDelete);
+    }
+
+    #[test]
+    fn identical_changes_merge_cleanly() {
+        let (_dir, odb) = test_odb();
+        let target = "d".repeat(40);
+        let note = write_blob(&odb, b"same\n");
+        let base = notes_tree(&odb, &[]);
+        let ours = notes_tree(&odb, &[(&target, note)]);
+        let theirs = notes_tree(&odb, &[(&target, note)]);
+
+        let result = merge_notes_trees(&odb, Some(base), Some(ours), Some(theirs)).unwrap();
+        assert!(result.is_clean);
+    }
+}
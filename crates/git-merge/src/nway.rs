@@ -0,0 +1,115 @@
+//! Generalized n-way conflict representation.
+//!
+//! A plain three-way conflict is `base`/`ours`/`theirs`: one term being
+//! subtracted out and two added back in. Recursively merging several
+//! criss-cross virtual bases, or resolving an octopus merge, needs more
+//! terms than that. `Merge<T>` generalizes the triple to `removes` (the
+//! negative/subtracted terms) and `adds` (the positive terms), with the
+//! invariant `adds.len() == removes.len() + 1`; the familiar 3-way case is
+//! just `removes.len() == 1`.
+
+/// A generalized n-way conflict: `adds.len() == removes.len() + 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merge<T> {
+    /// Negative (subtracted) terms, e.g. the common base(s).
+    pub removes: Vec<T>,
+    /// Positive terms, e.g. our side, their side, ...
+    pub adds: Vec<T>,
+}
+
+impl<T> Merge<T> {
+    /// Build a `Merge`, panicking if `adds.len() != removes.len() + 1` —
+    /// that relationship is what makes the representation well-formed.
+    pub fn new(adds: Vec<T>, removes: Vec<T>) -> Self {
+        assert_eq!(
+            adds.len(),
+            removes.len() + 1,
+            "Merge::new: adds.len() must equal removes.len() + 1"
+        );
+        Self { removes, adds }
+    }
+
+    /// A trivially resolved merge: a single add and no removes.
+    pub fn resolved(value: T) -> Self {
+        Self { removes: Vec::new(), adds: vec![value] }
+    }
+
+    /// Whether this merge is already resolved: exactly one add, no removes.
+    pub fn is_resolved(&self) -> bool {
+        self.removes.is_empty() && self.adds.len() == 1
+    }
+
+    /// The resolved value, if this merge is resolved.
+    pub fn as_resolved(&self) -> Option<&T> {
+        if self.is_resolved() {
+            self.adds.first()
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: PartialEq> Merge<T> {
+    /// Cancel equal add/remove pairs, shrinking the conflict in place.
+    ///
+    /// Each cancellation drops one add and one matching remove, so the
+    /// `adds.len() == removes.len() + 1` invariant holds throughout: e.g.
+    /// two of three recursive virtual bases agreeing on a term turns a
+    /// wider conflict into a smaller (possibly resolved) one.
+    pub fn simplify(&mut self) {
+        let mut removes = std::mem::take(&mut self.removes);
+        let mut adds = Vec::with_capacity(self.adds.len());
+        for add in self.adds.drain(..) {
+            if let Some(pos) = removes.iter().position(|r| *r == add) {
+                removes.remove(pos);
+            } else {
+                adds.push(add);
+            }
+        }
+        self.adds = adds;
+        self.removes = removes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolved_merge_has_one_add_no_removes() {
+        let m = Merge::resolved(42);
+        assert!(m.is_resolved());
+        assert_eq!(m.as_resolved(), Some(&42));
+    }
+
+    #[test]
+    fn three_way_conflict_is_not_resolved() {
+        let m = Merge::new(vec![1, 2], vec![0]);
+        assert!(!m.is_resolved());
+        assert_eq!(m.as_resolved(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_panics_on_mismatched_lengths() {
+        Merge::new(vec![1, 2, 3], vec![0]);
+    }
+
+    #[test]
+    fn simplify_cancels_matching_pair_down_to_resolved() {
+        // base=1, ours=1 (unchanged), theirs=2 (changed) -> resolves to 2.
+        let mut m = Merge::new(vec![1, 2], vec![1]);
+        m.simplify();
+        assert!(m.is_resolved());
+        assert_eq!(m.as_resolved(), Some(&2));
+    }
+
+    #[test]
+    fn simplify_leaves_genuine_conflict_unresolved() {
+        let mut m = Merge::new(vec![1, 2], vec![0]);
+        m.simplify();
+        assert!(!m.is_resolved());
+        assert_eq!(m.adds.len(), 2);
+        assert_eq!(m.removes.len(), 1);
+    }
+}
@@ -350,7 +350,7 @@ impl Sequencer {
                 Self::build_index_from_tree(odb, &entry.oid, &path, index)?;
             } else {
                 index.add(git_index::IndexEntry {
-                    path,
+                    path: path.into(),
                     oid: entry.oid,
                     mode: entry.mode,
                     stage: git_index::Stage::Normal,
@@ -20,6 +20,13 @@ impl OctopusStrategy {
     /// one at a time. If any step produces a conflict, the entire operation is
     /// aborted (octopus never resolves conflicts, matching git).
     ///
+    /// `bases` holds, for each additional head at the same index, the full set
+    /// of candidate merge bases between `ours` and that head (as returned by
+    /// `git_revwalk::merge_bases`) — a criss-cross history can have more than
+    /// one lowest common ancestor. An empty entry (or a missing one, if
+    /// `bases` is shorter than `additional_heads`) means the caller didn't
+    /// precompute it, and it's recomputed here.
+    ///
     /// Returns a `MergeResult` whose tree is the final accumulated tree.
     /// The caller is responsible for creating the merge commit with N+1 parents.
     pub fn merge_multi(
@@ -27,7 +34,7 @@ impl OctopusStrategy {
         repo: &mut Repository,
         ours: &ObjectId,
         additional_heads: &[ObjectId],
-        bases: &[ObjectId],
+        bases: &[Vec<ObjectId>],
         options: &MergeOptions,
     ) -> Result<MergeResult, MergeError> {
         if additional_heads.is_empty() {
@@ -36,28 +43,23 @@ impl OctopusStrategy {
             ));
         }
 
-        // For a single additional head, fall back to ORT
+        let ort = super::ort::OrtStrategy;
+
+        // For a single additional head, fall back to ORT.
         if additional_heads.len() == 1 {
-            let base = bases.first().copied().unwrap_or(ObjectId::NULL_SHA1);
-            return self.merge(repo, ours, &additional_heads[0], &base, options);
+            let candidate_bases = Self::candidate_bases(repo, bases, 0, ours, &additional_heads[0])?;
+            return Self::merge_against_bases(repo, &ort, ours, &additional_heads[0], &candidate_bases, options);
         }
 
         // Iterative merge: start with ours tree as the accumulated result
-        let ort = super::ort::OrtStrategy;
         let mut accumulated_tree = Self::get_tree_oid(repo, ours)?;
 
         for (i, head) in additional_heads.iter().enumerate() {
-            // Find a merge base between accumulated result and next head
-            // Use the provided base if available, otherwise use NULL as base
-            let base = if i < bases.len() {
-                bases[i]
-            } else {
-                // Try to find a merge base between ours and this head
-                match git_revwalk::merge_base_one(repo, ours, head) {
-                    Ok(Some(b)) => b,
-                    _ => ObjectId::NULL_SHA1,
-                }
-            };
+            // The merge base is always computed between the *original* `ours`
+            // and this head, regardless of iteration — the accumulated tree
+            // used below as "ours" for the tree merge has no history of its
+            // own to compute a base against.
+            let candidate_bases = Self::candidate_bases(repo, bases, i, ours, head)?;
 
             // We need to create a virtual commit pointing to the accumulated tree
             // to use the ORT merge. Instead, we use a workaround: write the
@@ -76,7 +78,7 @@ impl OctopusStrategy {
                 Self::create_temp_commit(repo, &accumulated_tree)?
             };
 
-            let result = ort.merge(repo, &ours_for_merge, head, &base, options)?;
+            let result = Self::merge_against_bases(repo, &ort, &ours_for_merge, head, &candidate_bases, options)?;
 
             if !result.is_clean {
                 return Err(MergeError::Conflict {
@@ -117,6 +119,53 @@ impl OctopusStrategy {
         }
     }
 
+    /// Resolve the candidate merge bases to use for the head at `index`:
+    /// the caller-supplied set at `bases[index]` if present and non-empty,
+    /// otherwise the full set of lowest common ancestors between `ours` and
+    /// `head` (there may be more than one in a criss-cross history).
+    fn candidate_bases(
+        repo: &Repository,
+        bases: &[Vec<ObjectId>],
+        index: usize,
+        ours: &ObjectId,
+        head: &ObjectId,
+    ) -> Result<Vec<ObjectId>, MergeError> {
+        if let Some(supplied) = bases.get(index) {
+            if !supplied.is_empty() {
+                return Ok(supplied.clone());
+            }
+        }
+        Ok(git_revwalk::merge_bases(repo, &[*ours, *head])?)
+    }
+
+    /// Merge `head` into `ours` against the given candidate merge bases,
+    /// folding more than one candidate into a virtual base tree first (see
+    /// `OrtStrategy::virtual_base_tree`). With no common ancestor at all,
+    /// merges against the empty tree, matching git's behavior for unrelated
+    /// histories.
+    fn merge_against_bases(
+        repo: &mut Repository,
+        ort: &super::ort::OrtStrategy,
+        ours: &ObjectId,
+        head: &ObjectId,
+        candidate_bases: &[ObjectId],
+        options: &MergeOptions,
+    ) -> Result<MergeResult, MergeError> {
+        if candidate_bases.is_empty() {
+            let empty_tree = Self::write_empty_tree(repo)?;
+            let empty_base_commit = Self::create_temp_commit(repo, &empty_tree)?;
+            return ort.merge(repo, ours, head, &empty_base_commit, options);
+        }
+        ort.merge_with_bases(repo, ours, head, candidate_bases, options)
+    }
+
+    /// Write an empty tree to the ODB and return its OID. Used as the merge
+    /// base when two heads share no common ancestor at all.
+    fn write_empty_tree(repo: &Repository) -> Result<ObjectId, MergeError> {
+        let tree = git_object::Tree { entries: Vec::new() };
+        Ok(repo.odb().write(&Object::Tree(tree))?)
+    }
+
     /// Create a temporary commit object pointing to the given tree.
     fn create_temp_commit(
         repo: &Repository,
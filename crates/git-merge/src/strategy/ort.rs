@@ -11,6 +11,7 @@ use git_diff::{DiffOptions, FileDiff, FileStatus};
 use git_hash::ObjectId;
 use git_object::{Blob, FileMode, Object, Tree, TreeEntry};
 use git_odb::ObjectDatabase;
+use git_ref::RefStore;
 use git_repository::Repository;
 
 use crate::conflict::record_conflict_in_index;
@@ -33,12 +34,104 @@ impl MergeStrategy for OrtStrategy {
         options: &MergeOptions,
     ) -> Result<MergeResult, MergeError> {
         let odb = repo.odb();
-
-        // Read the three commits and extract their tree OIDs.
         let base_tree_id = read_commit_tree(odb, base_commit)?;
         let ours_tree_id = read_commit_tree(odb, ours_commit)?;
         let theirs_tree_id = read_commit_tree(odb, theirs_commit)?;
 
+        self.merge_trees(repo, &base_tree_id, &ours_tree_id, &theirs_tree_id, options)
+    }
+}
+
+impl OrtStrategy {
+    /// Merge using candidate merge-base commits directly, rather than a
+    /// single already-chosen base commit. With one candidate this is
+    /// exactly `MergeStrategy::merge`; with several (a criss-cross
+    /// history, where HEAD and the incoming branch have more than one
+    /// lowest common ancestor), a virtual base tree is computed first
+    /// (see [`Self::virtual_base_tree`]) so the three-way merge below
+    /// doesn't have to pick one arbitrarily and report spurious conflicts
+    /// for changes the other candidate base already resolved.
+    pub fn merge_with_bases(
+        &self,
+        repo: &mut Repository,
+        ours_commit: &ObjectId,
+        theirs_commit: &ObjectId,
+        candidate_bases: &[ObjectId],
+        options: &MergeOptions,
+    ) -> Result<MergeResult, MergeError> {
+        let base_tree_id = Self::virtual_base_tree(repo, candidate_bases, options)?;
+        let odb = repo.odb();
+        let ours_tree_id = read_commit_tree(odb, ours_commit)?;
+        let theirs_tree_id = read_commit_tree(odb, theirs_commit)?;
+
+        self.merge_trees(repo, &base_tree_id, &ours_tree_id, &theirs_tree_id, options)
+    }
+
+    /// Compute the tree to use as the merge base when there is more than
+    /// one lowest common ancestor between the two branches being merged.
+    ///
+    /// With a single candidate, its tree is used directly. With several,
+    /// the first two are merged against *their own* recursive base (found
+    /// via `git_revwalk::merge_base_many`, falling back to the empty tree
+    /// if they share no common ancestor at all), the resulting tree is
+    /// kept, and each remaining candidate is folded in against it one at a
+    /// time. The final accumulated tree becomes the virtual base handed
+    /// to the top-level three-way merge — the same approach git's own
+    /// recursive/ort strategies use for criss-cross merges.
+    pub fn virtual_base_tree(
+        repo: &Repository,
+        candidate_bases: &[ObjectId],
+        options: &MergeOptions,
+    ) -> Result<ObjectId, MergeError> {
+        let (first, rest) = candidate_bases
+            .split_first()
+            .ok_or(MergeError::NoMergeBase)?;
+        if rest.is_empty() {
+            return read_commit_tree(repo.odb(), first);
+        }
+
+        let sub_bases = git_revwalk::merge_base_many(repo, &[*first, rest[0]])?;
+        let pair_base_tree = if sub_bases.is_empty() {
+            write_empty_tree(repo.odb())?
+        } else {
+            Self::virtual_base_tree(repo, &sub_bases, options)?
+        };
+
+        let odb = repo.odb();
+        let first_tree = read_commit_tree(odb, first)?;
+        let second_tree = read_commit_tree(odb, &rest[0])?;
+        let mut accumulated_tree =
+            merge_as_virtual_tree(odb, &pair_base_tree, &first_tree, &second_tree, options)?;
+
+        // Fold in any further candidates. Each of these is itself a common
+        // ancestor of the original two tips, so the first candidate's tree
+        // is a reasonable stand-in base for these folds — this tree only
+        // needs to be a plausible virtual base, not a final answer.
+        for next_base in &rest[1..] {
+            let next_tree = read_commit_tree(odb, next_base)?;
+            accumulated_tree =
+                merge_as_virtual_tree(odb, &first_tree, &accumulated_tree, &next_tree, options)?;
+        }
+
+        Ok(accumulated_tree)
+    }
+
+    /// Merge three already-resolved trees. This is the shared core behind
+    /// both `MergeStrategy::merge` (single base commit) and
+    /// `merge_with_bases` (possibly-virtual base tree).
+    fn merge_trees(
+        &self,
+        repo: &mut Repository,
+        base_tree_id: &ObjectId,
+        ours_tree_id: &ObjectId,
+        theirs_tree_id: &ObjectId,
+        options: &MergeOptions,
+    ) -> Result<MergeResult, MergeError> {
+        let odb = repo.odb();
+        let base_tree_id = *base_tree_id;
+        let ours_tree_id = *ours_tree_id;
+        let theirs_tree_id = *theirs_tree_id;
+
         // If ours and theirs trees are the same, nothing to merge.
         if ours_tree_id == theirs_tree_id {
             return Ok(MergeResult::clean(ours_tree_id));
@@ -82,10 +175,13 @@ impl MergeStrategy for OrtStrategy {
         let base_tree = read_tree(odb, &base_tree_id)?;
         let mut result_entries = tree_to_flat_map(odb, &base_tree, &BString::from(""))?;
 
+        let ancestor_label = options.ancestor_label.clone().unwrap_or_else(|| "base".to_string());
+        let our_label = options.our_label.clone().unwrap_or_else(|| "HEAD".to_string());
+        let their_label = options.their_label.clone().unwrap_or_else(|| "merge".to_string());
         let labels = MergeLabels {
-            base: "base",
-            ours: "HEAD",
-            theirs: "merge",
+            base: &ancestor_label,
+            ours: &our_label,
+            theirs: &their_label,
         };
 
         for path in &all_paths {
@@ -104,17 +200,24 @@ impl MergeStrategy for OrtStrategy {
                 (Some(ours_fd), Some(theirs_fd)) => {
                     // Both sides changed the same path.
                     match (ours_fd.status, theirs_fd.status) {
-                        // Both modified the same file — content merge.
+                        // Both modified the same file — content merge, or
+                        // (for a gitlink) a submodule commit-pointer merge.
                         (FileStatus::Modified, FileStatus::Modified) => {
-                            let conflict_or_clean = merge_file_content(
-                                odb,
-                                path,
-                                ours_fd,
-                                theirs_fd,
-                                &base_tree_id,
-                                options,
-                                &labels,
-                            )?;
+                            let is_submodule = ours_fd.new_mode == Some(FileMode::Gitlink)
+                                || theirs_fd.new_mode == Some(FileMode::Gitlink);
+                            let conflict_or_clean = if is_submodule {
+                                merge_submodule(repo, path, ours_fd, theirs_fd)?
+                            } else {
+                                merge_file_content(
+                                    odb,
+                                    path,
+                                    ours_fd,
+                                    theirs_fd,
+                                    &base_tree_id,
+                                    options,
+                                    &labels,
+                                )?
+                            };
                             match conflict_or_clean {
                                 FileResolution::Clean { oid, mode } => {
                                     result_entries.insert(
@@ -175,6 +278,7 @@ impl MergeStrategy for OrtStrategy {
                                 }),
                                 ours: ours_side,
                                 theirs: theirs_side,
+                                sides: None,
                             });
                         }
                         // Both added the same path — add/add conflict.
@@ -212,6 +316,7 @@ impl MergeStrategy for OrtStrategy {
                                         mode: theirs_mode,
                                         path: (*path).clone(),
                                     }),
+                                    sides: None,
                                 });
                             }
                         }
@@ -262,6 +367,7 @@ impl MergeStrategy for OrtStrategy {
                                             .unwrap_or(FileMode::Regular),
                                         path: p.clone(),
                                     }),
+                                    sides: None,
                                 });
                             }
                         }
@@ -331,6 +437,87 @@ impl MergeStrategy for OrtStrategy {
                                         mode,
                                         path: new_path.clone(),
                                     }),
+                                    sides: None,
+                                });
+                            }
+                        }
+                        // One side renamed, the other deleted — rename/delete conflict.
+                        (FileStatus::Renamed, FileStatus::Deleted)
+                        | (FileStatus::Deleted, FileStatus::Renamed) => {
+                            let (rename_fd, is_ours_rename) =
+                                if ours_fd.status == FileStatus::Renamed {
+                                    (ours_fd, true)
+                                } else {
+                                    (theirs_fd, false)
+                                };
+
+                            let base_oid = rename_fd.old_oid.unwrap_or(ObjectId::NULL_SHA1);
+                            let base_mode = rename_fd.old_mode.unwrap_or(FileMode::Regular);
+                            let new_path =
+                                rename_fd.new_path.clone().unwrap_or_else(|| (*path).clone());
+                            let renamed_side = Some(ConflictSide {
+                                oid: rename_fd.new_oid.unwrap_or(ObjectId::NULL_SHA1),
+                                mode: rename_fd.new_mode.unwrap_or(FileMode::Regular),
+                                path: new_path,
+                            });
+
+                            let (ours_side, theirs_side) = if is_ours_rename {
+                                (renamed_side, None)
+                            } else {
+                                (None, renamed_side)
+                            };
+
+                            conflicts.push(ConflictEntry {
+                                path: (*path).clone(),
+                                conflict_type: ConflictType::RenameDelete,
+                                base: Some(ConflictSide {
+                                    oid: base_oid,
+                                    mode: base_mode,
+                                    path: (*path).clone(),
+                                }),
+                                ours: ours_side,
+                                theirs: theirs_side,
+                                sides: None,
+                            });
+                        }
+                        // Both sides changed the entry's type (file, symlink,
+                        // or gitlink) incompatibly — record the mode
+                        // divergence rather than silently keeping ours.
+                        (FileStatus::TypeChanged, FileStatus::TypeChanged)
+                        | (FileStatus::TypeChanged, FileStatus::Modified)
+                        | (FileStatus::Modified, FileStatus::TypeChanged) => {
+                            let base_oid = ours_fd.old_oid.unwrap_or(ObjectId::NULL_SHA1);
+                            let base_mode = ours_fd.old_mode.unwrap_or(FileMode::Regular);
+                            let ours_oid = ours_fd.new_oid.unwrap_or(ObjectId::NULL_SHA1);
+                            let ours_mode = ours_fd.new_mode.unwrap_or(FileMode::Regular);
+                            let theirs_oid = theirs_fd.new_oid.unwrap_or(ObjectId::NULL_SHA1);
+                            let theirs_mode = theirs_fd.new_mode.unwrap_or(FileMode::Regular);
+
+                            if ours_oid == theirs_oid && ours_mode == theirs_mode {
+                                result_entries.insert(
+                                    (*path).clone(),
+                                    FlatEntry { oid: ours_oid, mode: ours_mode },
+                                );
+                            } else {
+                                conflicts.push(ConflictEntry {
+                                    path: (*path).clone(),
+                                    conflict_type: ConflictType::TypeChange,
+                                    base: Some(ConflictSide {
+                                        oid: base_oid,
+                                        mode: base_mode,
+                                        path: (*path).clone(),
+                                    }),
+                                    ours: Some(ConflictSide {
+                                        oid: ours_oid,
+                                        mode: ours_mode,
+                                        path: (*path).clone(),
+                                    }),
+                                    theirs: Some(ConflictSide {
+                                        oid: theirs_oid,
+                                        mode: theirs_mode,
+                                        path: (*path).clone(),
+                                    }),
+                                    sides: None,
                                 });
                             }
                         }
@@ -347,6 +534,15 @@ impl MergeStrategy for OrtStrategy {
             }
         }
 
+        apply_directory_renames(
+            &mut result_entries,
+            &ours_changes,
+            &theirs_changes,
+            options.directory_rename_threshold,
+            &mut conflicts,
+        );
+        detect_directory_file_conflicts(&mut result_entries, &mut conflicts);
+
         if conflicts.is_empty() {
             // Build result tree and write to ODB.
             let tree_oid = write_flat_map_as_tree(odb, &result_entries)?;
@@ -418,10 +614,199 @@ fn merge_file_content(
                 mode,
                 path: path.clone(),
             }),
+            sides: None,
         })))
     }
 }
 
+/// Merge both sides' advance of a submodule (gitlink) commit pointer.
+///
+/// Unlike a regular blob, a gitlink entry's OID names a commit in the
+/// submodule's own history, so it can't be content-merged as bytes. This
+/// opens the submodule's checkout under the work tree and compares the
+/// three commit OIDs directly: if one side's commit is an ancestor of the
+/// other's within the submodule, fast-forward to the descendant; otherwise
+/// look for a unique commit in the submodule reachable from both tips
+/// (e.g. a merge someone already made there) and use it; failing both (or
+/// if the submodule isn't checked out to inspect), record a `Submodule`
+/// conflict with the three commit OIDs.
+fn merge_submodule(
+    repo: &Repository,
+    path: &BString,
+    ours_fd: &FileDiff,
+    theirs_fd: &FileDiff,
+) -> Result<FileResolution, MergeError> {
+    let base_oid = ours_fd.old_oid.unwrap_or(ObjectId::NULL_SHA1);
+    let ours_oid = ours_fd.new_oid.unwrap_or(ObjectId::NULL_SHA1);
+    let theirs_oid = theirs_fd.new_oid.unwrap_or(ObjectId::NULL_SHA1);
+    let mode = FileMode::Gitlink;
+
+    if ours_oid == theirs_oid {
+        return Ok(FileResolution::Clean { oid: ours_oid, mode });
+    }
+
+    if let Some(work_tree) = repo.work_tree() {
+        if let Ok(sub_repo) = Repository::open(work_tree.join(path.to_str_lossy().as_ref())) {
+            if matches!(git_revwalk::is_ancestor(&sub_repo, &ours_oid, &theirs_oid), Ok(true)) {
+                return Ok(FileResolution::Clean { oid: theirs_oid, mode });
+            }
+            if matches!(git_revwalk::is_ancestor(&sub_repo, &theirs_oid, &ours_oid), Ok(true)) {
+                return Ok(FileResolution::Clean { oid: ours_oid, mode });
+            }
+            if let Some(unique) = find_unique_submodule_merge(&sub_repo, &ours_oid, &theirs_oid) {
+                return Ok(FileResolution::Clean { oid: unique, mode });
+            }
+        }
+    }
+
+    Ok(FileResolution::Conflict(Box::new(ConflictEntry {
+        path: path.clone(),
+        conflict_type: ConflictType::Submodule,
+        base: Some(ConflictSide {
+            oid: base_oid,
+            mode,
+            path: path.clone(),
+        }),
+        ours: Some(ConflictSide {
+            oid: ours_oid,
+            mode,
+            path: path.clone(),
+        }),
+        theirs: Some(ConflictSide {
+            oid: theirs_oid,
+            mode,
+            path: path.clone(),
+        }),
+        sides: None,
+    })))
+}
+
+/// Search the submodule's refs for a single commit that both `a` and `b`
+/// are ancestors of (e.g. a merge commit someone already made in the
+/// submodule). Returns `None` if there is none, or more than one.
+fn find_unique_submodule_merge(repo: &Repository, a: &ObjectId, b: &ObjectId) -> Option<ObjectId> {
+    let refs = repo.refs().iter(None).ok()?;
+    let mut candidates: Vec<ObjectId> = Vec::new();
+
+    for r in refs {
+        let Ok(reference) = r else { continue };
+        let Ok(oid) = reference.peel_to_oid(repo.refs()) else { continue };
+        if candidates.contains(&oid) {
+            continue;
+        }
+        let is_descendant_of_both = matches!(git_revwalk::is_ancestor(repo, a, &oid), Ok(true))
+            && matches!(git_revwalk::is_ancestor(repo, b, &oid), Ok(true));
+        if is_descendant_of_both {
+            candidates.push(oid);
+        }
+    }
+
+    if candidates.len() == 1 {
+        candidates.pop()
+    } else {
+        None
+    }
+}
+
+/// Write an empty tree to the ODB and return its OID. Used as the base for
+/// a virtual merge when two merge-base candidates share no common ancestor.
+fn write_empty_tree(odb: &ObjectDatabase) -> Result<ObjectId, MergeError> {
+    let tree = Tree { entries: Vec::new() };
+    Ok(odb.write(&Object::Tree(tree))?)
+}
+
+/// Merge two trees against a base, always producing a tree — used to build
+/// a virtual merge base, where only a plausible result is needed rather
+/// than a final answer a user will see. Content conflicts are resolved by
+/// writing the conflict-marker content a real merge would produce (so a
+/// later real merge against this virtual base still sees the disputed
+/// region); structural conflicts (add/add, rename/rename, modify/delete,
+/// ...) are resolved by preferring "ours".
+fn merge_as_virtual_tree(
+    odb: &ObjectDatabase,
+    base_tree_id: &ObjectId,
+    ours_tree_id: &ObjectId,
+    theirs_tree_id: &ObjectId,
+    options: &MergeOptions,
+) -> Result<ObjectId, MergeError> {
+    if ours_tree_id == theirs_tree_id {
+        return Ok(*ours_tree_id);
+    }
+    if base_tree_id == ours_tree_id {
+        return Ok(*theirs_tree_id);
+    }
+    if base_tree_id == theirs_tree_id {
+        return Ok(*ours_tree_id);
+    }
+
+    let diff_opts = DiffOptions {
+        detect_renames: true,
+        rename_threshold: options.rename_threshold,
+        ..DiffOptions::default()
+    };
+    let base_ours_diff = diff_trees(odb, Some(base_tree_id), Some(ours_tree_id), &diff_opts)?;
+    let base_theirs_diff = diff_trees(odb, Some(base_tree_id), Some(theirs_tree_id), &diff_opts)?;
+
+    let ours_changes = build_change_map(&base_ours_diff.files);
+    let theirs_changes = build_change_map(&base_theirs_diff.files);
+
+    let mut all_paths: Vec<&BString> = ours_changes.keys().chain(theirs_changes.keys()).collect();
+    all_paths.sort();
+    all_paths.dedup();
+
+    let base_tree = read_tree(odb, base_tree_id)?;
+    let mut result_entries = tree_to_flat_map(odb, &base_tree, &BString::from(""))?;
+
+    let ancestor_label = options.ancestor_label.clone().unwrap_or_else(|| "base".to_string());
+    let our_label = options.our_label.clone().unwrap_or_else(|| "HEAD".to_string());
+    let their_label = options.their_label.clone().unwrap_or_else(|| "merge".to_string());
+    let labels = MergeLabels {
+        base: &ancestor_label,
+        ours: &our_label,
+        theirs: &their_label,
+    };
+
+    for path in &all_paths {
+        let o_change = ours_changes.get(*path);
+        let t_change = theirs_changes.get(*path);
+
+        match (o_change, t_change) {
+            (Some(ours_fd), None) => apply_change_to_map(&mut result_entries, path, ours_fd),
+            (None, Some(theirs_fd)) => apply_change_to_map(&mut result_entries, path, theirs_fd),
+            (Some(ours_fd), Some(theirs_fd)) => {
+                if ours_fd.status == FileStatus::Modified && theirs_fd.status == FileStatus::Modified
+                {
+                    let base_oid = ours_fd.old_oid.unwrap_or(ObjectId::NULL_SHA1);
+                    let ours_oid = ours_fd.new_oid.unwrap_or(ObjectId::NULL_SHA1);
+                    let theirs_oid = theirs_fd.new_oid.unwrap_or(ObjectId::NULL_SHA1);
+                    let mode = ours_fd.new_mode.unwrap_or(FileMode::Regular);
+
+                    if ours_oid == theirs_oid {
+                        result_entries.insert((*path).clone(), FlatEntry { oid: ours_oid, mode });
+                    } else {
+                        let base_data = read_blob_data(odb, &base_oid)?;
+                        let ours_data = read_blob_data(odb, &ours_oid)?;
+                        let theirs_data = read_blob_data(odb, &theirs_oid)?;
+                        let merged =
+                            merge_content(&base_data, &ours_data, &theirs_data, options, &labels);
+                        let blob = Object::Blob(Blob::new(merged.content().to_vec()));
+                        let oid = odb.write(&blob)?;
+                        result_entries.insert((*path).clone(), FlatEntry { oid, mode });
+                    }
+                } else {
+                    // Structural conflict: favor ours as a pragmatic
+                    // approximation, since this tree is only a synthetic
+                    // base, not a final merge result.
+                    apply_change_to_map(&mut result_entries, path, ours_fd);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    write_flat_map_as_tree(odb, &result_entries)
+}
+
 /// Read the tree OID from a commit.
 fn read_commit_tree(odb: &ObjectDatabase, commit_oid: &ObjectId) -> Result<ObjectId, MergeError> {
     let obj = odb
@@ -574,11 +959,11 @@ fn write_flat_map_as_tree(
             let rest = BString::from(&path[slash_pos + 1..]);
             top_entries.entry(dir).or_default().push((rest, entry));
         } else {
-            direct_entries.push(TreeEntry {
-                mode: entry.mode,
-                name: path.clone(),
-                oid: entry.oid,
-            });
+            direct_entries.push(git_object::canonical_tree_entry(
+                path.clone(),
+                entry.mode,
+                entry.oid,
+            )?);
         }
     }
 
@@ -589,11 +974,11 @@ fn write_flat_map_as_tree(
             .map(|(p, e)| (p.clone(), (*e).clone()))
             .collect();
         let sub_tree_oid = write_flat_map_as_tree(odb, &sub_map)?;
-        direct_entries.push(TreeEntry {
-            mode: FileMode::Tree,
-            name: dir_name.clone(),
-            oid: sub_tree_oid,
-        });
+        direct_entries.push(git_object::canonical_tree_entry(
+            dir_name.clone(),
+            FileMode::Tree,
+            sub_tree_oid,
+        )?);
     }
 
     // Sort entries by git's tree entry ordering.
@@ -606,3 +991,210 @@ fn write_flat_map_as_tree(
     let oid = odb.write(&obj)?;
     Ok(oid)
 }
+
+/// The directory portion of `path` (everything before the last `/`), or
+/// `None` for a top-level path with no directory component.
+fn dir_of(path: &BString) -> Option<BString> {
+    path.iter()
+        .rposition(|&b| b == b'/')
+        .map(|pos| BString::from(&path[..pos]))
+}
+
+/// Infer whole-directory renames from one side's changes: group its
+/// renamed files by the `(old_dir, new_dir)` pair their rename implies,
+/// and record a directory rename for `old_dir` when a strong majority
+/// (`>= threshold` percent) of the files observed leaving it landed in the
+/// same `new_dir`.
+fn detect_directory_renames(
+    changes: &HashMap<BString, &FileDiff>,
+    threshold: u8,
+) -> HashMap<BString, BString> {
+    let mut dest_counts: HashMap<BString, HashMap<BString, usize>> = HashMap::new();
+
+    for fd in changes.values() {
+        if fd.status != FileStatus::Renamed {
+            continue;
+        }
+        let Some(new_path) = fd.new_path.as_ref() else {
+            continue;
+        };
+        let (Some(old_dir), Some(new_dir)) = (dir_of(fd.path()), dir_of(new_path)) else {
+            continue;
+        };
+        if old_dir == new_dir {
+            continue;
+        }
+        *dest_counts.entry(old_dir).or_default().entry(new_dir).or_insert(0) += 1;
+    }
+
+    let mut renames = HashMap::new();
+    for (old_dir, counts) in dest_counts {
+        let total: usize = counts.values().sum();
+        if let Some((best_dir, &best_count)) = counts.iter().max_by_key(|(_, count)| *count) {
+            if total > 0 && best_count * 100 >= threshold as usize * total {
+                renames.insert(old_dir, best_dir.clone());
+            }
+        }
+    }
+    renames
+}
+
+/// Fold inferred directory renames into the merge result: relocate files
+/// the opposite side added under a renamed directory so they follow the
+/// rename, and raise a `DirectoryRename` conflict where both sides imply
+/// different targets for the same source directory.
+fn apply_directory_renames(
+    result_entries: &mut BTreeMap<BString, FlatEntry>,
+    ours_changes: &HashMap<BString, &FileDiff>,
+    theirs_changes: &HashMap<BString, &FileDiff>,
+    threshold: u8,
+    conflicts: &mut Vec<ConflictEntry>,
+) {
+    let ours_dir_renames = detect_directory_renames(ours_changes, threshold);
+    let theirs_dir_renames = detect_directory_renames(theirs_changes, threshold);
+
+    let mut conflicting_dirs: Vec<BString> = Vec::new();
+    for (old_dir, ours_new_dir) in &ours_dir_renames {
+        if let Some(theirs_new_dir) = theirs_dir_renames.get(old_dir) {
+            if theirs_new_dir != ours_new_dir {
+                conflicting_dirs.push(old_dir.clone());
+            }
+        }
+    }
+
+    for old_dir in &conflicting_dirs {
+        conflicts.push(ConflictEntry {
+            path: old_dir.clone(),
+            conflict_type: ConflictType::DirectoryRename,
+            base: None,
+            ours: None,
+            theirs: None,
+            sides: None,
+        });
+    }
+
+    relocate_files_added_under_renamed_dir(
+        result_entries,
+        &ours_dir_renames,
+        theirs_changes,
+        &conflicting_dirs,
+    );
+    relocate_files_added_under_renamed_dir(
+        result_entries,
+        &theirs_dir_renames,
+        ours_changes,
+        &conflicting_dirs,
+    );
+}
+
+/// Move files the opposite side added under a since-renamed directory into
+/// that directory's new location, skipping any source directory whose
+/// rename is itself contested (recorded in `conflicting_dirs`).
+fn relocate_files_added_under_renamed_dir(
+    result_entries: &mut BTreeMap<BString, FlatEntry>,
+    dir_renames: &HashMap<BString, BString>,
+    opposite_changes: &HashMap<BString, &FileDiff>,
+    conflicting_dirs: &[BString],
+) {
+    for fd in opposite_changes.values() {
+        if fd.status != FileStatus::Added {
+            continue;
+        }
+        let path = fd.path();
+
+        for (old_dir, new_dir) in dir_renames {
+            if conflicting_dirs.contains(old_dir) {
+                continue;
+            }
+            let mut prefix = old_dir.clone();
+            prefix.push(b'/');
+            if !path.starts_with(prefix.as_slice()) {
+                continue;
+            }
+
+            if let Some(entry) = result_entries.remove(path) {
+                let mut new_path = new_dir.clone();
+                new_path.push(b'/');
+                new_path.extend_from_slice(&path[prefix.len()..]);
+                result_entries.insert(new_path, entry);
+            }
+            break;
+        }
+    }
+}
+
+/// Detect directory/file (D/F) conflicts in a flat result map: a path that
+/// is both a file entry and a directory prefix of some other entry (one
+/// side turned `foo` into a file while the other added `foo/bar`). The
+/// check looks at the first key at or after `foo/` in the `BTreeMap`, so a
+/// directory that disappeared because all its files were removed or
+/// renamed away leaves no `foo/...` key and is not flagged — it resolves
+/// cleanly to the file.
+fn detect_directory_file_conflicts(
+    result_entries: &mut BTreeMap<BString, FlatEntry>,
+    conflicts: &mut Vec<ConflictEntry>,
+) {
+    let paths: Vec<BString> = result_entries.keys().cloned().collect();
+    for path in paths {
+        let Some(entry) = result_entries.get(&path).cloned() else {
+            // Already relocated while handling an earlier path this pass.
+            continue;
+        };
+
+        let mut dir_prefix = path.clone();
+        dir_prefix.push(b'/');
+        let has_dir_sibling = result_entries
+            .range(dir_prefix.clone()..)
+            .next()
+            .is_some_and(|(other, _)| other.starts_with(dir_prefix.as_slice()));
+
+        if !has_dir_sibling {
+            continue;
+        }
+
+        // Keep the directory entries at their original paths and move the
+        // file out of the way so the tree stays valid.
+        let new_path = unique_path(result_entries, &path, "HEAD");
+        result_entries.remove(&path);
+        result_entries.insert(new_path.clone(), entry.clone());
+
+        conflicts.push(ConflictEntry {
+            path: path.clone(),
+            conflict_type: ConflictType::DirectoryFile,
+            base: None,
+            ours: Some(ConflictSide {
+                oid: entry.oid,
+                mode: entry.mode,
+                path: new_path,
+            }),
+            theirs: None,
+            sides: None,
+        });
+    }
+}
+
+/// Find a path not already present in `result_entries` by appending
+/// `~<label>` to `path`, then `~<label>2`, `~<label>3`, ... until free.
+fn unique_path(
+    result_entries: &BTreeMap<BString, FlatEntry>,
+    path: &BString,
+    label: &str,
+) -> BString {
+    let mut candidate = path.clone();
+    candidate.push(b'~');
+    candidate.extend_from_slice(label.as_bytes());
+    if !result_entries.contains_key(&candidate) {
+        return candidate;
+    }
+
+    let base_candidate = candidate;
+    let mut suffix = 2u32;
+    loop {
+        let mut next = base_candidate.clone();
+        next.extend_from_slice(suffix.to_string().as_bytes());
+        if !result_entries.contains_key(&next) {
+            return next;
+        }
+        suffix += 1;
+    }
+}
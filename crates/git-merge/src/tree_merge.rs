@@ -0,0 +1,394 @@
+//! A plain three-way tree merge: compare each path's `(mode, oid)` across
+//! base/ours/theirs and recurse into subtrees that are trees on more than
+//! one side. No rename detection, no directory-rename inference, no
+//! content-level diffing — [`strategy::ort`](crate::strategy::ort) builds
+//! that richer behavior on top. This is the primitive a trivial
+//! `read-tree -m`-style merge needs: just the tree shape resolved, with
+//! conflicts reported for whatever a content or rename-aware merge would
+//! have to resolve itself.
+
+use std::collections::BTreeMap;
+
+use bstr::BString;
+use git_hash::ObjectId;
+use git_object::{FileMode, Object, Tree, TreeEntry};
+use git_odb::ObjectDatabase;
+
+use crate::{ConflictEntry, ConflictSide, ConflictType, MergeError, MergeResult};
+
+/// Three-way merge the trees at `base`, `ours`, and `theirs` by comparing
+/// each path's mode and oid rather than diffing content. Returns the merged
+/// tree oid and, if any, the full list of conflicting paths (which the tree
+/// leaves out).
+pub fn merge_trees(
+    odb: &ObjectDatabase,
+    base: ObjectId,
+    ours: ObjectId,
+    theirs: ObjectId,
+) -> Result<MergeResult, MergeError> {
+    let mut conflicts = Vec::new();
+    let tree_oid = merge_tree_level(
+        odb,
+        BString::from(""),
+        Some(base),
+        Some(ours),
+        Some(theirs),
+        &mut conflicts,
+    )?;
+
+    // Both sides deleting everything (or every remaining path being
+    // conflicted) is still a tree worth having an oid for, rather than the
+    // `None` that an empty directory level produces internally.
+    let tree_oid = match tree_oid {
+        Some(oid) => oid,
+        None => odb.write(&Object::Tree(Tree::new()))?,
+    };
+
+    if !conflicts.is_empty() {
+        // Unlike `MergeResult::conflicted`, which leaves `tree` unset
+        // because content-merge strategies have nothing coherent to put
+        // there, the tree shape here is well defined even with conflicts:
+        // it's everything `merge_tree_level` resolved cleanly, with
+        // conflicting paths simply absent (a tree object can't hold more
+        // than one entry per name anyway). That's the best-effort tree a
+        // trivial `read-tree -m`/`merge-tree` caller wants to keep working
+        // with alongside the reported conflicts.
+        return Ok(MergeResult {
+            tree: Some(tree_oid),
+            is_clean: false,
+            conflicts,
+            message: None,
+        });
+    }
+
+    Ok(MergeResult::clean(tree_oid))
+}
+
+/// Merge one directory level. `prefix` is the path to this directory
+/// (empty at the root). Each of `base`/`ours`/`theirs` is the oid of this
+/// directory's tree on that side, or `None` if it doesn't exist there.
+/// Returns the merged subtree's oid, or `None` if it ended up empty.
+fn merge_tree_level(
+    odb: &ObjectDatabase,
+    prefix: BString,
+    base: Option<ObjectId>,
+    ours: Option<ObjectId>,
+    theirs: Option<ObjectId>,
+    conflicts: &mut Vec<ConflictEntry>,
+) -> Result<Option<ObjectId>, MergeError> {
+    let base_entries = named_entries(odb, base)?;
+    let ours_entries = named_entries(odb, ours)?;
+    let theirs_entries = named_entries(odb, theirs)?;
+
+    let mut names: Vec<&BString> = base_entries
+        .keys()
+        .chain(ours_entries.keys())
+        .chain(theirs_entries.keys())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let mut merged = Vec::new();
+
+    for name in names {
+        let b = base_entries.get(name);
+        let o = ours_entries.get(name);
+        let t = theirs_entries.get(name);
+        let path = full_path(&prefix, name);
+
+        let trees_present = [b, o, t]
+            .into_iter()
+            .filter(|e| e.is_some_and(|e| e.mode.is_tree()))
+            .count();
+
+        if trees_present >= 2 {
+            let sub_base = b.filter(|e| e.mode.is_tree()).map(|e| e.oid);
+            let sub_ours = o.filter(|e| e.mode.is_tree()).map(|e| e.oid);
+            let sub_theirs = t.filter(|e| e.mode.is_tree()).map(|e| e.oid);
+
+            if let Some(oid) =
+                merge_tree_level(odb, path.clone(), sub_base, sub_ours, sub_theirs, conflicts)?
+            {
+                merged.push(git_object::canonical_tree_entry(
+                    name.clone(),
+                    FileMode::Tree,
+                    oid,
+                )?);
+            }
+            continue;
+        }
+
+        if entry_key(o) == entry_key(t) {
+            if let Some(e) = o {
+                merged.push(e.clone());
+            }
+            continue;
+        }
+
+        if entry_key(o) == entry_key(b) {
+            if let Some(e) = t {
+                merged.push(e.clone());
+            }
+            continue;
+        }
+
+        if entry_key(t) == entry_key(b) {
+            if let Some(e) = o {
+                merged.push(e.clone());
+            }
+            continue;
+        }
+
+        conflicts.push(ConflictEntry {
+            path: path.clone(),
+            conflict_type: conflict_type_for(b, o, t),
+            base: b.map(|e| conflict_side(e, &path)),
+            ours: o.map(|e| conflict_side(e, &path)),
+            theirs: t.map(|e| conflict_side(e, &path)),
+            sides: None,
+        });
+    }
+
+    if merged.is_empty() {
+        return Ok(None);
+    }
+
+    merged.sort_by(TreeEntry::cmp_entries);
+    let oid = odb.write(&Object::Tree(Tree { entries: merged }))?;
+    Ok(Some(oid))
+}
+
+/// Read a tree's entries keyed by name, or an empty map if `oid` is `None`.
+fn named_entries(
+    odb: &ObjectDatabase,
+    oid: Option<ObjectId>,
+) -> Result<BTreeMap<BString, TreeEntry>, MergeError> {
+    match oid {
+        None => Ok(BTreeMap::new()),
+        Some(oid) => Ok(read_tree(odb, &oid)?
+            .entries
+            .into_iter()
+            .map(|e| (e.name.clone(), e))
+            .collect()),
+    }
+}
+
+fn entry_key(entry: Option<&TreeEntry>) -> Option<(FileMode, ObjectId)> {
+    entry.map(|e| (e.mode, e.oid))
+}
+
+fn conflict_type_for(
+    base: Option<&TreeEntry>,
+    ours: Option<&TreeEntry>,
+    theirs: Option<&TreeEntry>,
+) -> ConflictType {
+    match (ours, theirs) {
+        (Some(o), Some(t)) if o.mode.is_tree() != t.mode.is_tree() => ConflictType::DirectoryFile,
+        (None, Some(_)) | (Some(_), None) => ConflictType::ModifyDelete,
+        _ if base.is_none() => ConflictType::AddAdd,
+        _ => ConflictType::Content,
+    }
+}
+
+fn conflict_side(entry: &TreeEntry, path: &BString) -> ConflictSide {
+    ConflictSide {
+        oid: entry.oid,
+        mode: entry.mode,
+        path: path.clone(),
+    }
+}
+
+fn full_path(prefix: &BString, name: &BString) -> BString {
+    if prefix.is_empty() {
+        name.clone()
+    } else {
+        let mut p = prefix.clone();
+        p.push(b'/');
+        p.extend_from_slice(name);
+        p
+    }
+}
+
+/// Read a tree from ODB.
+fn read_tree(odb: &ObjectDatabase, tree_oid: &ObjectId) -> Result<Tree, MergeError> {
+    let obj = odb
+        .read(tree_oid)?
+        .ok_or(MergeError::ObjectNotFound(*tree_oid))?;
+
+    match obj {
+        Object::Tree(t) => Ok(t),
+        other => Err(MergeError::UnexpectedObjectType {
+            oid: *tree_oid,
+            expected: "tree",
+            actual: other.object_type().to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_object::Blob;
+    use tempfile::TempDir;
+
+    fn test_odb() -> (TempDir, ObjectDatabase) {
+        let dir = TempDir::new().unwrap();
+        let odb = ObjectDatabase::open(dir.path()).unwrap();
+        (dir, odb)
+    }
+
+    fn blob(odb: &ObjectDatabase, data: &[u8]) -> ObjectId {
+        odb.write(&Object::Blob(Blob { data: data.to_vec() })).unwrap()
+    }
+
+    fn tree(odb: &ObjectDatabase, entries: Vec<TreeEntry>) -> ObjectId {
+        odb.write(&Object::Tree(Tree { entries })).unwrap()
+    }
+
+    fn file(name: &str, oid: ObjectId) -> TreeEntry {
+        TreeEntry { mode: FileMode::Regular, name: BString::from(name), oid }
+    }
+
+    #[test]
+    fn one_side_unchanged_takes_the_other_sides_edit() {
+        let (_dir, odb) = test_odb();
+        let a_base = blob(&odb, b"base");
+        let a_theirs = blob(&odb, b"theirs edit");
+        let base = tree(&odb, vec![file("a.txt", a_base)]);
+        let ours = tree(&odb, vec![file("a.txt", a_base)]);
+        let theirs = tree(&odb, vec![file("a.txt", a_theirs)]);
+
+        let result = merge_trees(&odb, base, ours, theirs).unwrap();
+        assert!(result.is_clean);
+        let merged = read_tree(&odb, &result.tree.unwrap()).unwrap();
+        assert_eq!(merged.find(bstr::BStr::new("a.txt")).unwrap().oid, a_theirs);
+    }
+
+    #[test]
+    fn both_sides_changing_the_same_file_differently_conflicts() {
+        let (_dir, odb) = test_odb();
+        let a_base = blob(&odb, b"base");
+        let a_ours = blob(&odb, b"ours edit");
+        let a_theirs = blob(&odb, b"theirs edit");
+        let base = tree(&odb, vec![file("a.txt", a_base)]);
+        let ours = tree(&odb, vec![file("a.txt", a_ours)]);
+        let theirs = tree(&odb, vec![file("a.txt", a_theirs)]);
+
+        let result = merge_trees(&odb, base, ours, theirs).unwrap();
+        assert!(!result.is_clean);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].conflict_type, ConflictType::Content);
+        assert_eq!(result.conflicts[0].path, "a.txt");
+    }
+
+    #[test]
+    fn modify_delete_conflicts() {
+        let (_dir, odb) = test_odb();
+        let a_base = blob(&odb, b"base");
+        let a_ours = blob(&odb, b"ours edit");
+        let base = tree(&odb, vec![file("a.txt", a_base)]);
+        let ours = tree(&odb, vec![file("a.txt", a_ours)]);
+        let theirs = tree(&odb, vec![]);
+
+        let result = merge_trees(&odb, base, ours, theirs).unwrap();
+        assert!(!result.is_clean);
+        assert_eq!(result.conflicts[0].conflict_type, ConflictType::ModifyDelete);
+    }
+
+    #[test]
+    fn recurses_into_subtrees_changed_on_both_sides() {
+        let (_dir, odb) = test_odb();
+        let lib_base = blob(&odb, b"base");
+        let lib_ours = blob(&odb, b"ours");
+        let src_base = tree(&odb, vec![file("lib.rs", lib_base)]);
+        let src_ours = tree(&odb, vec![file("lib.rs", lib_ours)]);
+        let readme = blob(&odb, b"docs");
+        let src_theirs = tree(&odb, vec![
+            file("lib.rs", lib_base),
+            file("README.md", readme),
+        ]);
+
+        let base = tree(&odb, vec![TreeEntry { mode: FileMode::Tree, name: BString::from("src"), oid: src_base }]);
+        let ours = tree(&odb, vec![TreeEntry { mode: FileMode::Tree, name: BString::from("src"), oid: src_ours }]);
+        let theirs = tree(&odb, vec![TreeEntry { mode: FileMode::Tree, name: BString::from("src"), oid: src_theirs }]);
+
+        let result = merge_trees(&odb, base, ours, theirs).unwrap();
+        assert!(result.is_clean);
+
+        let merged_root = read_tree(&odb, &result.tree.unwrap()).unwrap();
+        let src_entry = merged_root.find(bstr::BStr::new("src")).unwrap();
+        let merged_src = read_tree(&odb, &src_entry.oid).unwrap();
+        assert_eq!(merged_src.find(bstr::BStr::new("lib.rs")).unwrap().oid, lib_ours);
+        assert!(merged_src.find(bstr::BStr::new("README.md")).is_some());
+    }
+
+    #[test]
+    fn directory_vs_file_type_conflict() {
+        let (_dir, odb) = test_odb();
+        let file_oid = blob(&odb, b"i am a file");
+        let sub = tree(&odb, vec![file("x.txt", file_oid)]);
+        let base = tree(&odb, vec![]);
+        let ours = tree(&odb, vec![file("thing", file_oid)]);
+        let theirs = tree(&odb, vec![TreeEntry { mode: FileMode::Tree, name: BString::from("thing"), oid: sub }]);
+
+        let result = merge_trees(&odb, base, ours, theirs).unwrap();
+        assert!(!result.is_clean);
+        assert_eq!(result.conflicts[0].conflict_type, ConflictType::DirectoryFile);
+    }
+
+    #[test]
+    fn both_sides_adding_the_same_path_differently_is_add_add() {
+        let (_dir, odb) = test_odb();
+        let a_ours = blob(&odb, b"ours new file");
+        let a_theirs = blob(&odb, b"theirs new file");
+        let base = tree(&odb, vec![]);
+        let ours = tree(&odb, vec![file("new.txt", a_ours)]);
+        let theirs = tree(&odb, vec![file("new.txt", a_theirs)]);
+
+        let result = merge_trees(&odb, base, ours, theirs).unwrap();
+        assert!(!result.is_clean);
+        assert_eq!(result.conflicts[0].conflict_type, ConflictType::AddAdd);
+    }
+
+    #[test]
+    fn conflict_entries_carry_base_ours_theirs_sides_for_stage_output() {
+        let (_dir, odb) = test_odb();
+        let a_base = blob(&odb, b"base");
+        let a_ours = blob(&odb, b"ours edit");
+        let a_theirs = blob(&odb, b"theirs edit");
+        let base = tree(&odb, vec![file("a.txt", a_base)]);
+        let ours = tree(&odb, vec![file("a.txt", a_ours)]);
+        let theirs = tree(&odb, vec![file("a.txt", a_theirs)]);
+
+        let result = merge_trees(&odb, base, ours, theirs).unwrap();
+        let conflict = &result.conflicts[0];
+        assert_eq!(conflict.base.as_ref().unwrap().oid, a_base);
+        assert_eq!(conflict.ours.as_ref().unwrap().oid, a_ours);
+        assert_eq!(conflict.theirs.as_ref().unwrap().oid, a_theirs);
+    }
+
+    #[test]
+    fn conflicted_merge_still_returns_the_best_effort_tree_for_the_rest() {
+        let (_dir, odb) = test_odb();
+        let a_base = blob(&odb, b"base");
+        let a_ours = blob(&odb, b"ours edit");
+        let a_theirs = blob(&odb, b"theirs edit");
+        let b_base = blob(&odb, b"b base");
+        let b_theirs = blob(&odb, b"b theirs edit");
+        let base = tree(&odb, vec![file("a.txt", a_base), file("b.txt", b_base)]);
+        let ours = tree(&odb, vec![file("a.txt", a_ours), file("b.txt", b_base)]);
+        let theirs = tree(&odb, vec![file("a.txt", a_theirs), file("b.txt", b_theirs)]);
+
+        let result = merge_trees(&odb, base, ours, theirs).unwrap();
+        assert!(!result.is_clean);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].path, "a.txt");
+
+        // b.txt only changed on one side, so it should still show up in the
+        // partial tree even though a.txt conflicted -- a merge-tree-style
+        // plumbing caller needs that tree, not just the list of conflicts.
+        let merged = read_tree(&odb, &result.tree.expect("conflicted result should still carry a tree")).unwrap();
+        assert!(merged.find(bstr::BStr::new("a.txt")).is_none());
+        assert_eq!(merged.find(bstr::BStr::new("b.txt")).unwrap().oid, b_theirs);
+    }
+}
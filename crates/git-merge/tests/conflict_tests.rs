@@ -33,6 +33,7 @@ fn content_conflict_sets_three_stages() {
             mode: FileMode::Regular,
             path: BString::from("src/main.rs"),
         }),
+        sides: None,
     };
 
     record_conflict_in_index(&mut index, &conflict);
@@ -72,6 +73,7 @@ fn modify_delete_sets_two_stages() {
             path: BString::from("file.txt"),
         }),
         theirs: None, // deleted on their side
+        sides: None,
     };
 
     record_conflict_in_index(&mut index, &conflict);
@@ -105,6 +107,7 @@ fn resolve_replaces_stages_with_stage0() {
             mode: FileMode::Regular,
             path: BString::from("resolved.txt"),
         }),
+        sides: None,
     };
     record_conflict_in_index(&mut index, &conflict);
 
@@ -137,6 +140,7 @@ fn add_add_conflict() {
             mode: FileMode::Regular,
             path: BString::from("new_file.txt"),
         }),
+        sides: None,
     };
 
     record_conflict_in_index(&mut index, &conflict);
@@ -1,7 +1,7 @@
 //! Integration tests for three-way content merge.
 
 use git_merge::content::{merge_content, MergeLabels};
-use git_merge::{ConflictStyle, MergeOptions};
+use git_merge::{ConflictFavor, ConflictStyle, MergeOptions};
 
 fn labels() -> MergeLabels<'static> {
     MergeLabels {
@@ -116,3 +116,17 @@ fn strategy_option_theirs_resolves_conflicts() {
     assert!(result.is_clean());
     assert_eq!(result.content(), theirs);
 }
+
+#[test]
+fn favor_union_concatenates_both_sides() {
+    let base = b"a\nb\nc\n";
+    let ours = b"a\nX\nc\n";
+    let theirs = b"a\nY\nc\n";
+
+    let mut opts = MergeOptions::default();
+    opts.favor = ConflictFavor::Union;
+
+    let result = merge_content(base, ours, theirs, &opts, &labels());
+    assert!(result.is_clean());
+    assert_eq!(result.content(), b"a\nX\nY\nc\n");
+}
@@ -15,7 +15,7 @@ pub mod cache;
 pub use blob::Blob;
 pub use commit::Commit;
 pub use tag::Tag;
-pub use tree::{FileMode, Tree, TreeEntry};
+pub use tree::{canonical_tree_entry, normalize_filemode, valid_entry_name, FileMode, Tree, TreeEntry};
 
 use bstr::BString;
 use git_hash::{HashAlgorithm, HashError, ObjectId};
@@ -44,6 +44,9 @@ pub enum ObjectError {
     #[error("invalid file mode: {0}")]
     InvalidFileMode(String),
 
+    #[error("invalid tree entry name {name:?}: {reason}")]
+    InvalidEntryName { name: BString, reason: &'static str },
+
     #[error("invalid signature: {0}")]
     InvalidSignature(String),
 
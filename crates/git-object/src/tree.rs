@@ -80,6 +80,72 @@ impl FileMode {
     }
 }
 
+/// Normalize a raw tree-entry mode to one of git's five canonical modes by
+/// its high bits, the way every real git implementation reduces a mode
+/// before writing a tree: the directory bit makes it `Tree`; the gitlink
+/// bit makes it `Gitlink`; the symlink bit makes it `Symlink`; any execute
+/// bit makes it `Executable`; anything else becomes a plain `Regular` blob.
+/// This turns e.g. a raw `0o100664` picked up from a working-tree `stat()`
+/// into a mode git will actually store.
+pub fn normalize_filemode(mode: FileMode) -> FileMode {
+    let raw = mode.raw();
+    match raw & 0o170000 {
+        0o040000 => FileMode::Tree,
+        0o160000 => FileMode::Gitlink,
+        0o120000 => FileMode::Symlink,
+        _ if raw & 0o111 != 0 => FileMode::Executable,
+        _ => FileMode::Regular,
+    }
+}
+
+/// Reject entry names a real git would refuse to read back: empty,
+/// containing `/` or a NUL byte, or case-insensitively equal to `.`, `..`,
+/// or `.git` (which would alias the repository metadata directory on a
+/// case-folding filesystem).
+pub fn valid_entry_name(name: &BStr) -> Result<(), ObjectError> {
+    if name.is_empty() {
+        return Err(ObjectError::InvalidEntryName {
+            name: BString::from(name),
+            reason: "name is empty",
+        });
+    }
+    if name.contains(&b'/') {
+        return Err(ObjectError::InvalidEntryName {
+            name: BString::from(name),
+            reason: "name contains '/'",
+        });
+    }
+    if name.contains(&0) {
+        return Err(ObjectError::InvalidEntryName {
+            name: BString::from(name),
+            reason: "name contains a NUL byte",
+        });
+    }
+    if name.eq_ignore_ascii_case(b".") || name.eq_ignore_ascii_case(b"..") || name.eq_ignore_ascii_case(b".git") {
+        return Err(ObjectError::InvalidEntryName {
+            name: BString::from(name),
+            reason: "name is reserved ('.', '..', or '.git')",
+        });
+    }
+    Ok(())
+}
+
+/// Build a canonical `TreeEntry`: normalizes `mode` and validates `name`,
+/// returning a descriptive error instead of letting a malformed entry
+/// reach `odb.write` and produce a tree object git would refuse to read.
+pub fn canonical_tree_entry(
+    name: BString,
+    mode: FileMode,
+    oid: ObjectId,
+) -> Result<TreeEntry, ObjectError> {
+    valid_entry_name(name.as_ref())?;
+    Ok(TreeEntry {
+        mode: normalize_filemode(mode),
+        name,
+        oid,
+    })
+}
+
 /// Parse an octal ASCII string to u32.
 fn parse_octal(s: &[u8]) -> Option<u32> {
     if s.is_empty() {
@@ -358,6 +424,48 @@ mod tests {
         assert_eq!(TreeEntry::cmp_entries(&dir_entry, &file_entry), Ordering::Greater);
     }
 
+    #[test]
+    fn normalize_filemode_reduces_to_canonical_modes() {
+        assert_eq!(normalize_filemode(FileMode::from_raw(0o040000)), FileMode::Tree);
+        assert_eq!(normalize_filemode(FileMode::from_raw(0o160000)), FileMode::Gitlink);
+        assert_eq!(normalize_filemode(FileMode::from_raw(0o120000)), FileMode::Symlink);
+        assert_eq!(normalize_filemode(FileMode::from_raw(0o100755)), FileMode::Executable);
+        assert_eq!(normalize_filemode(FileMode::from_raw(0o100664)), FileMode::Regular);
+        assert_eq!(normalize_filemode(FileMode::from_raw(0o100600)), FileMode::Regular);
+    }
+
+    #[test]
+    fn valid_entry_name_rejects_empty_slash_and_nul() {
+        assert!(valid_entry_name(BStr::new("")).is_err());
+        assert!(valid_entry_name(BStr::new("a/b")).is_err());
+        assert!(valid_entry_name(b"a\0b".as_bstr()).is_err());
+    }
+
+    #[test]
+    fn valid_entry_name_rejects_dot_dotdot_and_dotgit_case_insensitively() {
+        assert!(valid_entry_name(BStr::new(".")).is_err());
+        assert!(valid_entry_name(BStr::new("..")).is_err());
+        assert!(valid_entry_name(BStr::new(".git")).is_err());
+        assert!(valid_entry_name(BStr::new(".GIT")).is_err());
+        assert!(valid_entry_name(BStr::new(".Git")).is_err());
+    }
+
+    #[test]
+    fn valid_entry_name_accepts_ordinary_names() {
+        assert!(valid_entry_name(BStr::new("README.md")).is_ok());
+        assert!(valid_entry_name(BStr::new("gitignore")).is_ok());
+    }
+
+    #[test]
+    fn canonical_tree_entry_normalizes_and_validates() {
+        let oid = ObjectId::NULL_SHA1;
+        let entry = canonical_tree_entry(BString::from("a.sh"), FileMode::from_raw(0o100755), oid).unwrap();
+        assert_eq!(entry.mode, FileMode::Executable);
+
+        let err = canonical_tree_entry(BString::from(".git"), FileMode::Regular, oid);
+        assert!(err.is_err());
+    }
+
     #[test]
     fn parse_empty_tree() {
         let tree = Tree::parse(b"").unwrap();
@@ -0,0 +1,210 @@
+//! Materializing a tree into a working directory.
+//!
+//! The inverse of building a tree from a flat map of paths: walks a tree
+//! recursively and writes each entry to disk, recreating directories as
+//! it goes. Pairs with [`crate::tree_update::update_tree`] and
+//! [`crate::tree_update::tree_entry_by_path`] to complete the round-trip
+//! between a stored tree and a checked-out working directory.
+
+use std::path::Path;
+
+use bstr::BString;
+use git_hash::ObjectId;
+use git_object::{FileMode, Object};
+
+use crate::{ObjectDatabase, OdbError};
+
+/// Write the tree at `tree_oid` into the working directory at `dest`,
+/// recursing into subtrees and creating directories as needed.
+///
+/// - `Regular`/`Executable` entries become files; on Unix an `Executable`
+///   file additionally gets mode `0o755`.
+/// - `Symlink` entries are written as symlinks, with the blob's content
+///   interpreted as the link target.
+/// - `Tree` entries become directories that are recursed into.
+/// - `Gitlink` entries (submodules) are skipped, since there is no
+///   submodule working tree to populate here; their paths are returned so
+///   callers can report or handle them separately.
+pub fn read_tree(
+    odb: &ObjectDatabase,
+    tree_oid: ObjectId,
+    dest: &Path,
+) -> Result<Vec<BString>, OdbError> {
+    let mut skipped_gitlinks = Vec::new();
+    read_tree_into(odb, tree_oid, dest, &BString::from(""), &mut skipped_gitlinks)?;
+    Ok(skipped_gitlinks)
+}
+
+fn read_tree_into(
+    odb: &ObjectDatabase,
+    tree_oid: ObjectId,
+    dest: &Path,
+    prefix: &BString,
+    skipped_gitlinks: &mut Vec<BString>,
+) -> Result<(), OdbError> {
+    let tree = match odb.read(&tree_oid)?.ok_or(OdbError::NotFound(tree_oid))? {
+        Object::Tree(t) => t,
+        other => {
+            return Err(OdbError::Corrupt {
+                oid: tree_oid,
+                reason: format!("expected tree, found {}", other.object_type()),
+            })
+        }
+    };
+
+    std::fs::create_dir_all(dest)?;
+
+    for entry in tree.iter() {
+        let path = dest.join(entry.name.to_str_lossy().as_ref());
+        let mut entry_prefix = prefix.clone();
+        if !entry_prefix.is_empty() {
+            entry_prefix.push(b'/');
+        }
+        entry_prefix.extend_from_slice(&entry.name);
+
+        match entry.mode {
+            FileMode::Tree => {
+                read_tree_into(odb, entry.oid, &path, &entry_prefix, skipped_gitlinks)?;
+            }
+            FileMode::Gitlink => {
+                skipped_gitlinks.push(entry_prefix);
+            }
+            FileMode::Symlink => {
+                let target = read_blob(odb, entry.oid)?;
+                write_symlink(&path, &target)?;
+            }
+            FileMode::Regular | FileMode::Executable | FileMode::Unknown(_) => {
+                let data = read_blob(odb, entry.oid)?;
+                std::fs::write(&path, &data)?;
+
+                #[cfg(unix)]
+                if entry.mode == FileMode::Executable {
+                    use std::os::unix::fs::PermissionsExt;
+                    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_blob(odb: &ObjectDatabase, oid: ObjectId) -> Result<Vec<u8>, OdbError> {
+    match odb.read(&oid)?.ok_or(OdbError::NotFound(oid))? {
+        Object::Blob(b) => Ok(b.data),
+        other => Err(OdbError::Corrupt {
+            oid,
+            reason: format!("expected blob, found {}", other.object_type()),
+        }),
+    }
+}
+
+#[cfg(unix)]
+fn write_symlink(path: &Path, target: &[u8]) -> Result<(), OdbError> {
+    let target = bstr::BStr::new(target).to_str_lossy();
+    if path.exists() || path.symlink_metadata().is_ok() {
+        std::fs::remove_file(path)?;
+    }
+    std::os::unix::fs::symlink(target.as_ref(), path)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_symlink(path: &Path, target: &[u8]) -> Result<(), OdbError> {
+    std::fs::write(path, target)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_object::{FileMode, Tree, TreeEntry};
+    use tempfile::TempDir;
+
+    fn test_odb() -> (TempDir, ObjectDatabase) {
+        let dir = TempDir::new().unwrap();
+        let odb = ObjectDatabase::open(dir.path()).unwrap();
+        (dir, odb)
+    }
+
+    fn blob(odb: &ObjectDatabase, data: &[u8]) -> ObjectId {
+        odb.write(&Object::Blob(git_object::Blob { data: data.to_vec() }))
+            .unwrap()
+    }
+
+    #[test]
+    fn writes_nested_files_and_directories() {
+        let (_odb_dir, odb) = test_odb();
+        let lib_rs = blob(&odb, b"fn main() {}");
+        let src_tree = odb
+            .write(&Object::Tree(Tree {
+                entries: vec![TreeEntry {
+                    mode: FileMode::Regular,
+                    name: BString::from("lib.rs"),
+                    oid: lib_rs,
+                }],
+            }))
+            .unwrap();
+        let root = odb
+            .write(&Object::Tree(Tree {
+                entries: vec![TreeEntry {
+                    mode: FileMode::Tree,
+                    name: BString::from("src"),
+                    oid: src_tree,
+                }],
+            }))
+            .unwrap();
+
+        let dest = TempDir::new().unwrap();
+        let skipped = read_tree(&odb, root, dest.path()).unwrap();
+        assert!(skipped.is_empty());
+
+        let written = std::fs::read(dest.path().join("src/lib.rs")).unwrap();
+        assert_eq!(written, b"fn main() {}");
+    }
+
+    #[test]
+    fn sets_executable_bit() {
+        let (_odb_dir, odb) = test_odb();
+        let script = blob(&odb, b"#!/bin/sh\necho hi\n");
+        let root = odb
+            .write(&Object::Tree(Tree {
+                entries: vec![TreeEntry {
+                    mode: FileMode::Executable,
+                    name: BString::from("run.sh"),
+                    oid: script,
+                }],
+            }))
+            .unwrap();
+
+        let dest = TempDir::new().unwrap();
+        read_tree(&odb, root, dest.path()).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dest.path().join("run.sh")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+    }
+
+    #[test]
+    fn skips_gitlinks_and_records_their_paths() {
+        let (_odb_dir, odb) = test_odb();
+        let submodule_oid = ObjectId::NULL_SHA1;
+        let root = odb
+            .write(&Object::Tree(Tree {
+                entries: vec![TreeEntry {
+                    mode: FileMode::Gitlink,
+                    name: BString::from("vendor"),
+                    oid: submodule_oid,
+                }],
+            }))
+            .unwrap();
+
+        let dest = TempDir::new().unwrap();
+        let skipped = read_tree(&odb, root, dest.path()).unwrap();
+        assert_eq!(skipped, vec![BString::from("vendor")]);
+        assert!(!dest.path().join("vendor").exists());
+    }
+}
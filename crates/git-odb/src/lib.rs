@@ -6,8 +6,11 @@
 
 pub mod alternates;
 pub mod backend;
+pub mod checkout;
 pub mod prefix;
 mod search;
+pub mod tree_update;
+pub mod walk;
 
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, RwLock};
@@ -43,6 +46,9 @@ mod error {
         #[error("circular alternates chain detected at {0}")]
         CircularAlternates(PathBuf),
 
+        #[error(transparent)]
+        Object(#[from] git_object::ObjectError),
+
         #[error(transparent)]
         Loose(#[from] git_loose::LooseError),
 
@@ -0,0 +1,321 @@
+//! Incremental tree editing and path-addressed lookup.
+//!
+//! Building a tree from a full flat map of paths (as `git-index`'s
+//! `write_tree_from_index` and similar callers do) rehashes every subtree,
+//! which is wasteful when only a handful of paths actually changed.
+//! [`update_tree`] instead walks only the subtrees on the affected paths,
+//! leaving every untouched sibling `TreeEntry` oid exactly as it was.
+//!
+//! [`tree_entry_by_path`] is the read-side counterpart: it resolves a path
+//! to an entry by descending only the trees on that path, instead of
+//! recursively reading the entire tree.
+
+use std::collections::BTreeMap;
+
+use bstr::{BStr, BString, ByteSlice};
+use git_hash::ObjectId;
+use git_object::{FileMode, Object, Tree, TreeEntry};
+
+use crate::{ObjectDatabase, OdbError};
+
+/// A single path-level edit to apply to a tree.
+#[derive(Debug, Clone)]
+pub enum TreeUpdate {
+    /// Add or replace the entry at `path`.
+    Upsert {
+        path: BString,
+        mode: FileMode,
+        oid: ObjectId,
+    },
+    /// Remove the entry at `path`.
+    Remove { path: BString },
+}
+
+impl TreeUpdate {
+    fn path(&self) -> &BString {
+        match self {
+            Self::Upsert { path, .. } => path,
+            Self::Remove { path } => path,
+        }
+    }
+
+    /// Re-root this update one path component down, for recursing into a
+    /// subtree: `"src/lib.rs"` relative to `"src"` becomes `"lib.rs"`.
+    fn descend(&self, rest: BString) -> Self {
+        match self {
+            Self::Upsert { mode, oid, .. } => Self::Upsert {
+                path: rest,
+                mode: *mode,
+                oid: *oid,
+            },
+            Self::Remove { .. } => Self::Remove { path: rest },
+        }
+    }
+}
+
+/// Apply `updates` to the tree at `base_tree_oid` (or to an empty tree if
+/// `None`), rewriting only the subtrees along the affected paths. Returns
+/// the new root tree oid, or `None` if every entry was removed and the
+/// result is empty — callers that need a tree oid even when empty should
+/// write one themselves in that case.
+pub fn update_tree(
+    odb: &ObjectDatabase,
+    base_tree_oid: Option<ObjectId>,
+    updates: &[TreeUpdate],
+) -> Result<Option<ObjectId>, OdbError> {
+    let mut entries: Vec<TreeEntry> = match base_tree_oid {
+        Some(oid) => read_tree(odb, &oid)?.entries,
+        None => Vec::new(),
+    };
+
+    // Group updates by their first path component, so each subtree along
+    // an affected path is read and rewritten at most once.
+    let mut direct: BTreeMap<BString, TreeUpdate> = BTreeMap::new();
+    let mut nested: BTreeMap<BString, Vec<TreeUpdate>> = BTreeMap::new();
+
+    for update in updates {
+        match update.path().find_byte(b'/') {
+            Some(slash) => {
+                let dir = BString::from(&update.path()[..slash]);
+                let rest = BString::from(&update.path()[slash + 1..]);
+                nested.entry(dir).or_default().push(update.descend(rest));
+            }
+            None => {
+                direct.insert(update.path().clone(), update.clone());
+            }
+        }
+    }
+
+    for (name, update) in direct {
+        entries.retain(|e| e.name != name);
+        if let TreeUpdate::Upsert { mode, oid, .. } = update {
+            entries.push(git_object::canonical_tree_entry(name, mode, oid)?);
+        }
+    }
+
+    for (name, sub_updates) in nested {
+        let existing_subtree_oid = entries
+            .iter()
+            .find(|e| e.name == name && e.mode.is_tree())
+            .map(|e| e.oid);
+        let new_subtree_oid = update_tree(odb, existing_subtree_oid, &sub_updates)?;
+
+        entries.retain(|e| e.name != name);
+        if let Some(oid) = new_subtree_oid {
+            entries.push(git_object::canonical_tree_entry(name, FileMode::Tree, oid)?);
+        }
+    }
+
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    entries.sort_by(TreeEntry::cmp_entries);
+    let oid = odb.write(&Object::Tree(Tree { entries }))?;
+    Ok(Some(oid))
+}
+
+/// Resolve `path` to the mode and oid of the entry it names, reading only
+/// the trees along the way rather than materializing the whole tree at
+/// `tree_oid`. Returns `None` if any path component is missing, or if a
+/// non-final component names something other than a subtree.
+///
+/// Assumes each tree's entries are in git's canonical sort order (true of
+/// any tree a conforming implementation wrote), so each level is a binary
+/// search rather than a linear scan.
+pub fn tree_entry_by_path(
+    odb: &ObjectDatabase,
+    tree_oid: ObjectId,
+    path: &BStr,
+) -> Result<Option<(FileMode, ObjectId)>, OdbError> {
+    let mut current_oid = tree_oid;
+    let mut rest = BString::from(path);
+
+    loop {
+        let tree = read_tree(odb, &current_oid)?;
+        let (component, remainder) = match rest.find_byte(b'/') {
+            Some(slash) => (
+                BString::from(&rest[..slash]),
+                Some(BString::from(&rest[slash + 1..])),
+            ),
+            None => (rest.clone(), None),
+        };
+
+        // Canonical order sorts a subtree as if its name ended in '/', so
+        // probe once as a non-tree entry and once as a tree entry.
+        let probe = |mode| TreeEntry { mode, name: component.clone(), oid: ObjectId::NULL_SHA1 };
+        let (as_blob, as_tree) = (probe(FileMode::Regular), probe(FileMode::Tree));
+        let found = tree
+            .entries
+            .binary_search_by(|e| TreeEntry::cmp_entries(e, &as_blob))
+            .or_else(|_| tree.entries.binary_search_by(|e| TreeEntry::cmp_entries(e, &as_tree)));
+        let entry = match found {
+            Ok(i) => &tree.entries[i],
+            Err(_) => return Ok(None),
+        };
+
+        match remainder {
+            None => return Ok(Some((entry.mode, entry.oid))),
+            Some(next) => {
+                if !entry.mode.is_tree() {
+                    return Ok(None);
+                }
+                current_oid = entry.oid;
+                rest = next;
+            }
+        }
+    }
+}
+
+fn read_tree(odb: &ObjectDatabase, oid: &ObjectId) -> Result<Tree, OdbError> {
+    match odb.read(oid)?.ok_or(OdbError::NotFound(*oid))? {
+        Object::Tree(tree) => Ok(tree),
+        other => Err(OdbError::Corrupt {
+            oid: *oid,
+            reason: format!("expected tree, found {}", other.object_type()),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_odb() -> (TempDir, ObjectDatabase) {
+        let dir = TempDir::new().unwrap();
+        let odb = ObjectDatabase::open(dir.path()).unwrap();
+        (dir, odb)
+    }
+
+    fn entry(odb: &ObjectDatabase, name: &str, data: &[u8]) -> TreeEntry {
+        let oid = odb
+            .write(&Object::Blob(git_object::Blob { data: data.to_vec() }))
+            .unwrap();
+        TreeEntry {
+            mode: FileMode::Regular,
+            name: BString::from(name),
+            oid,
+        }
+    }
+
+    #[test]
+    fn upsert_new_top_level_file() {
+        let (_dir, odb) = test_odb();
+        let a = entry(&odb, "a.txt", b"a");
+        let base = odb
+            .write(&Object::Tree(Tree { entries: vec![a.clone()] }))
+            .unwrap();
+
+        let new_oid = odb.write(&Object::Blob(git_object::Blob { data: b"b".to_vec() })).unwrap();
+        let updates = [TreeUpdate::Upsert {
+            path: BString::from("b.txt"),
+            mode: FileMode::Regular,
+            oid: new_oid,
+        }];
+
+        let result_oid = update_tree(&odb, Some(base), &updates).unwrap().unwrap();
+        let result = read_tree(&odb, &result_oid).unwrap();
+        assert_eq!(result.entries.len(), 2);
+        assert!(result.find(bstr::BStr::new("a.txt")).is_some());
+        assert!(result.find(bstr::BStr::new("b.txt")).is_some());
+    }
+
+    #[test]
+    fn nested_update_reuses_untouched_sibling_subtree() {
+        let (_dir, odb) = test_odb();
+        let src_a = entry(&odb, "a.rs", b"a");
+        let src_tree_oid = odb
+            .write(&Object::Tree(Tree { entries: vec![src_a] }))
+            .unwrap();
+        let docs_readme = entry(&odb, "README.md", b"docs");
+        let docs_tree_oid = odb
+            .write(&Object::Tree(Tree { entries: vec![docs_readme] }))
+            .unwrap();
+
+        let base = odb
+            .write(&Object::Tree(Tree {
+                entries: vec![
+                    TreeEntry { mode: FileMode::Tree, name: BString::from("src"), oid: src_tree_oid },
+                    TreeEntry { mode: FileMode::Tree, name: BString::from("docs"), oid: docs_tree_oid },
+                ],
+            }))
+            .unwrap();
+
+        let new_oid = odb.write(&Object::Blob(git_object::Blob { data: b"b".to_vec() })).unwrap();
+        let updates = [TreeUpdate::Upsert {
+            path: BString::from("src/b.rs"),
+            mode: FileMode::Regular,
+            oid: new_oid,
+        }];
+
+        let result_oid = update_tree(&odb, Some(base), &updates).unwrap().unwrap();
+        let result = read_tree(&odb, &result_oid).unwrap();
+
+        let docs_entry = result.find(bstr::BStr::new("docs")).unwrap();
+        assert_eq!(docs_entry.oid, docs_tree_oid, "untouched sibling subtree must be reused as-is");
+
+        let src_entry = result.find(bstr::BStr::new("src")).unwrap();
+        assert_ne!(src_entry.oid, src_tree_oid);
+    }
+
+    #[test]
+    fn remove_last_entry_empties_subtree_and_drops_it() {
+        let (_dir, odb) = test_odb();
+        let only_file = entry(&odb, "only.txt", b"x");
+        let sub_tree_oid = odb
+            .write(&Object::Tree(Tree { entries: vec![only_file] }))
+            .unwrap();
+        let base = odb
+            .write(&Object::Tree(Tree {
+                entries: vec![TreeEntry { mode: FileMode::Tree, name: BString::from("sub"), oid: sub_tree_oid }],
+            }))
+            .unwrap();
+
+        let updates = [TreeUpdate::Remove { path: BString::from("sub/only.txt") }];
+        let result_oid = update_tree(&odb, Some(base), &updates).unwrap();
+        assert!(result_oid.is_none());
+    }
+
+    #[test]
+    fn tree_entry_by_path_resolves_nested_file() {
+        let (_dir, odb) = test_odb();
+        let lib_rs = entry(&odb, "lib.rs", b"fn main() {}");
+        let src_tree_oid = odb
+            .write(&Object::Tree(Tree { entries: vec![lib_rs] }))
+            .unwrap();
+        let root = odb
+            .write(&Object::Tree(Tree {
+                entries: vec![TreeEntry {
+                    mode: FileMode::Tree,
+                    name: BString::from("src"),
+                    oid: src_tree_oid,
+                }],
+            }))
+            .unwrap();
+
+        let (mode, oid) = tree_entry_by_path(&odb, root, BStr::new("src/lib.rs"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(mode, FileMode::Regular);
+
+        let blob = odb.read(&oid).unwrap().unwrap();
+        assert_eq!(blob, Object::Blob(git_object::Blob { data: b"fn main() {}".to_vec() }));
+    }
+
+    #[test]
+    fn tree_entry_by_path_missing_component_is_none() {
+        let (_dir, odb) = test_odb();
+        let a = entry(&odb, "a.txt", b"a");
+        let root = odb
+            .write(&Object::Tree(Tree { entries: vec![a] }))
+            .unwrap();
+
+        assert!(tree_entry_by_path(&odb, root, BStr::new("missing.txt"))
+            .unwrap()
+            .is_none());
+        assert!(tree_entry_by_path(&odb, root, BStr::new("a.txt/extra"))
+            .unwrap()
+            .is_none());
+    }
+}
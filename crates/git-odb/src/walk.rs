@@ -0,0 +1,246 @@
+//! Ordered traversal over a tree's entries, with a callback controlling
+//! whether to descend into a subtree or abort the walk early.
+//!
+//! Implemented iteratively with an explicit stack of directory frames so
+//! walking a deeply nested tree doesn't recurse the native call stack.
+
+use bstr::{BStr, BString};
+use git_hash::ObjectId;
+use git_object::{Object, Tree, TreeEntry};
+
+use crate::{ObjectDatabase, OdbError};
+
+/// Whether a tree entry's callback fires before or after its children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeWalkMode {
+    /// Visit a tree entry before descending into its children.
+    PreOrder,
+    /// Visit a tree entry after its children have been visited.
+    PostOrder,
+}
+
+/// What the walk should do after a callback invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkControl {
+    /// Keep walking normally.
+    Continue,
+    /// Don't descend into the subtree just visited. Only meaningful in
+    /// [`TreeWalkMode::PreOrder`]: by the time a [`TreeWalkMode::PostOrder`]
+    /// callback fires, its children have already been visited.
+    Skip,
+    /// Stop the walk entirely; `walk_tree` returns immediately.
+    Abort,
+}
+
+struct Frame {
+    /// Directory path containing `entries` (empty for the root tree).
+    prefix: BString,
+    entries: Vec<TreeEntry>,
+    index: usize,
+    /// The tree entry this frame is the contents of, and the path prefix it
+    /// lives under — used to fire the post-order callback once `entries` is
+    /// exhausted. `None` for the root tree, which has no entry of its own.
+    self_entry: Option<(BString, TreeEntry)>,
+}
+
+/// Walk the tree at `tree_oid`, calling `cb(path_prefix, entry)` for every
+/// entry reachable from it. `path_prefix` is the directory path containing
+/// `entry` (empty at the root). Blob-like entries always fire once, in
+/// entry order; tree entries fire either before or after their children
+/// depending on `mode`.
+pub fn walk_tree<F>(
+    odb: &ObjectDatabase,
+    tree_oid: ObjectId,
+    mode: TreeWalkMode,
+    mut cb: F,
+) -> Result<(), OdbError>
+where
+    F: FnMut(&BStr, &TreeEntry) -> WalkControl,
+{
+    let root = read_tree(odb, &tree_oid)?;
+    let mut stack = vec![Frame {
+        prefix: BString::from(""),
+        entries: root.entries,
+        index: 0,
+        self_entry: None,
+    }];
+
+    while let Some(top) = stack.last_mut() {
+        if top.index >= top.entries.len() {
+            let finished = stack.pop().unwrap();
+            if let Some((parent_prefix, entry)) = finished.self_entry {
+                if mode == TreeWalkMode::PostOrder {
+                    match cb(parent_prefix.as_ref(), &entry) {
+                        WalkControl::Continue | WalkControl::Skip => {}
+                        WalkControl::Abort => return Ok(()),
+                    }
+                }
+            }
+            continue;
+        }
+
+        let entry = top.entries[top.index].clone();
+        top.index += 1;
+        let prefix = top.prefix.clone();
+
+        if entry.mode.is_tree() {
+            if mode == TreeWalkMode::PreOrder {
+                match cb(prefix.as_ref(), &entry) {
+                    WalkControl::Continue => {}
+                    WalkControl::Skip => continue,
+                    WalkControl::Abort => return Ok(()),
+                }
+            }
+
+            let subtree = read_tree(odb, &entry.oid)?;
+            let mut child_prefix = prefix.clone();
+            if !child_prefix.is_empty() {
+                child_prefix.push(b'/');
+            }
+            child_prefix.extend_from_slice(&entry.name);
+
+            stack.push(Frame {
+                prefix: child_prefix,
+                entries: subtree.entries,
+                index: 0,
+                self_entry: Some((prefix, entry)),
+            });
+        } else {
+            match cb(prefix.as_ref(), &entry) {
+                WalkControl::Continue | WalkControl::Skip => {}
+                WalkControl::Abort => return Ok(()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_tree(odb: &ObjectDatabase, oid: &ObjectId) -> Result<Tree, OdbError> {
+    match odb.read(oid)?.ok_or(OdbError::NotFound(*oid))? {
+        Object::Tree(tree) => Ok(tree),
+        other => Err(OdbError::Corrupt {
+            oid: *oid,
+            reason: format!("expected tree, found {}", other.object_type()),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git_object::FileMode;
+    use tempfile::TempDir;
+
+    fn test_odb() -> (TempDir, ObjectDatabase) {
+        let dir = TempDir::new().unwrap();
+        let odb = ObjectDatabase::open(dir.path()).unwrap();
+        (dir, odb)
+    }
+
+    fn blob(odb: &ObjectDatabase, data: &[u8]) -> ObjectId {
+        odb.write(&Object::Blob(git_object::Blob { data: data.to_vec() }))
+            .unwrap()
+    }
+
+    fn build_tree(odb: &ObjectDatabase) -> ObjectId {
+        // root/
+        //   a.txt
+        //   src/
+        //     lib.rs
+        let a = blob(odb, b"a");
+        let lib_rs = blob(odb, b"fn main() {}");
+        let src_tree = odb
+            .write(&Object::Tree(Tree {
+                entries: vec![TreeEntry {
+                    mode: FileMode::Regular,
+                    name: BString::from("lib.rs"),
+                    oid: lib_rs,
+                }],
+            }))
+            .unwrap();
+        odb.write(&Object::Tree(Tree {
+            entries: vec![
+                TreeEntry {
+                    mode: FileMode::Regular,
+                    name: BString::from("a.txt"),
+                    oid: a,
+                },
+                TreeEntry {
+                    mode: FileMode::Tree,
+                    name: BString::from("src"),
+                    oid: src_tree,
+                },
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn pre_order_visits_directory_before_its_children() {
+        let (_dir, odb) = test_odb();
+        let root = build_tree(&odb);
+
+        let mut visited = Vec::new();
+        walk_tree(&odb, root, TreeWalkMode::PreOrder, |prefix, entry| {
+            visited.push(format!("{}/{}", prefix, entry.name));
+            WalkControl::Continue
+        })
+        .unwrap();
+
+        let src_pos = visited.iter().position(|p| p == "/src").unwrap();
+        let lib_pos = visited.iter().position(|p| p == "src/lib.rs").unwrap();
+        assert!(src_pos < lib_pos, "src dir must be visited before its child");
+    }
+
+    #[test]
+    fn post_order_visits_directory_after_its_children() {
+        let (_dir, odb) = test_odb();
+        let root = build_tree(&odb);
+
+        let mut visited = Vec::new();
+        walk_tree(&odb, root, TreeWalkMode::PostOrder, |prefix, entry| {
+            visited.push(format!("{}/{}", prefix, entry.name));
+            WalkControl::Continue
+        })
+        .unwrap();
+
+        let src_pos = visited.iter().position(|p| p == "/src").unwrap();
+        let lib_pos = visited.iter().position(|p| p == "src/lib.rs").unwrap();
+        assert!(lib_pos < src_pos, "src dir must be visited after its child in post-order");
+    }
+
+    #[test]
+    fn skip_prevents_descent_in_pre_order() {
+        let (_dir, odb) = test_odb();
+        let root = build_tree(&odb);
+
+        let mut visited = Vec::new();
+        walk_tree(&odb, root, TreeWalkMode::PreOrder, |prefix, entry| {
+            visited.push(format!("{}/{}", prefix, entry.name));
+            if entry.name == "src" {
+                WalkControl::Skip
+            } else {
+                WalkControl::Continue
+            }
+        })
+        .unwrap();
+
+        assert!(!visited.iter().any(|p| p == "src/lib.rs"));
+    }
+
+    #[test]
+    fn abort_stops_the_walk_immediately() {
+        let (_dir, odb) = test_odb();
+        let root = build_tree(&odb);
+
+        let mut visited = Vec::new();
+        walk_tree(&odb, root, TreeWalkMode::PreOrder, |prefix, entry| {
+            visited.push(format!("{}/{}", prefix, entry.name));
+            WalkControl::Abort
+        })
+        .unwrap();
+
+        assert_eq!(visited.len(), 1);
+    }
+}
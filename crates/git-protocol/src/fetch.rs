@@ -5,8 +5,12 @@
 
 use std::path::PathBuf;
 
+use flate2::bufread::ZlibDecoder;
 use git_hash::ObjectId;
+use git_pack::entry::parse_entry_header;
+use git_pack::PackEntryType;
 use git_transport::Transport;
+use std::io::Read;
 
 use crate::capability::{self, Capabilities, SidebandMode};
 use crate::pktline::{PktLineReader, PktLineWriter};
@@ -59,6 +63,23 @@ pub struct FetchResult {
     pub shallow_commits: Vec<ObjectId>,
     /// Commits that are no longer shallow boundaries ("unshallow" lines from the server).
     pub unshallow_commits: Vec<ObjectId>,
+    /// Pack negotiation transfer statistics, for progress reporting.
+    pub transfer: TransferStats,
+}
+
+/// Transfer statistics gathered while receiving a packfile, in the style of
+/// canonical git's "Receiving objects" progress line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransferStats {
+    /// Total objects the pack header declares.
+    pub total_objects: usize,
+    /// Objects actually present in the received packfile.
+    pub received_objects: usize,
+    /// Bytes read off the wire for the packfile.
+    pub bytes: usize,
+    /// REF_DELTA objects whose base was resolved from the local object store
+    /// rather than from the pack itself (thin-pack completions).
+    pub local_objects: usize,
 }
 
 /// Perform a fetch operation using an already-connected transport.
@@ -73,6 +94,32 @@ pub fn fetch(
     wanted_refs: &[String],
     options: &FetchOptions,
     pack_dir: Option<&std::path::Path>,
+) -> Result<FetchResult, ProtocolError> {
+    fetch_with_local_check(
+        transport,
+        advertised_refs,
+        server_caps,
+        local_refs,
+        wanted_refs,
+        options,
+        pack_dir,
+        |_| false,
+    )
+}
+
+/// Same as [`fetch`], but takes a predicate used to tell whether a REF_DELTA
+/// base is already present in the local object store. This only affects the
+/// `local_objects` count reported in [`TransferStats`]; callers that don't
+/// care about accurate thin-pack statistics can use [`fetch`] instead.
+pub fn fetch_with_local_check(
+    transport: &mut dyn Transport,
+    advertised_refs: &[(ObjectId, bstr::BString)],
+    server_caps: &Capabilities,
+    local_refs: &[(ObjectId, String)],
+    wanted_refs: &[String],
+    options: &FetchOptions,
+    pack_dir: Option<&std::path::Path>,
+    has_local_object: impl Fn(&ObjectId) -> bool,
 ) -> Result<FetchResult, ProtocolError> {
     // Determine which OIDs we want
     let wants: Vec<ObjectId> = determine_wants(advertised_refs, wanted_refs);
@@ -84,6 +131,7 @@ pub fn fetch(
             new_objects: 0,
             shallow_commits: Vec::new(),
             unshallow_commits: Vec::new(),
+            transfer: TransferStats::default(),
         });
     }
 
@@ -208,10 +256,12 @@ pub fn fetch(
         new_objects: 0,
         shallow_commits,
         unshallow_commits,
+        transfer: TransferStats::default(),
     };
 
     if !pack_data.is_empty() {
         result.new_objects = count_pack_objects(&pack_data);
+        result.transfer = scan_transfer_stats(&pack_data, &has_local_object);
 
         // Write pack to disk if we have a pack dir
         if let Some(dir) = pack_dir {
@@ -294,6 +344,48 @@ fn count_pack_objects(pack_data: &[u8]) -> usize {
     u32::from_be_bytes([pack_data[8], pack_data[9], pack_data[10], pack_data[11]]) as usize
 }
 
+/// Walk a raw packfile buffer entry by entry (no index needed yet) to gather
+/// transfer statistics: how many objects it holds, how many bytes that took,
+/// and how many REF_DELTA bases were satisfied from the local object store
+/// instead of appearing in the pack (thin-pack completions).
+fn scan_transfer_stats(pack_data: &[u8], has_local_object: impl Fn(&ObjectId) -> bool) -> TransferStats {
+    let total_objects = count_pack_objects(pack_data) as usize;
+    let mut stats = TransferStats {
+        total_objects,
+        received_objects: 0,
+        bytes: pack_data.len(),
+        local_objects: 0,
+    };
+
+    let mut offset = git_pack::PACK_HEADER_SIZE;
+    for _ in 0..total_objects {
+        if offset >= pack_data.len() {
+            break;
+        }
+        let Ok(entry) = parse_entry_header(&pack_data[offset..], offset as u64) else {
+            break;
+        };
+        let compressed = &pack_data[entry.data_offset as usize..];
+        let mut decoder = ZlibDecoder::new(compressed);
+        let mut buf = Vec::with_capacity(entry.uncompressed_size);
+        if decoder.read_to_end(&mut buf).is_err() {
+            break;
+        }
+        let consumed = decoder.total_in() as usize;
+
+        if let PackEntryType::RefDelta { base_oid } = entry.entry_type {
+            if has_local_object(&base_oid) {
+                stats.local_objects += 1;
+            }
+        }
+
+        stats.received_objects += 1;
+        offset = entry.data_offset as usize + consumed;
+    }
+
+    stats
+}
+
 /// Write pack data to a file in the pack directory and generate an index.
 fn write_pack_to_disk(
     pack_dir: &std::path::Path,
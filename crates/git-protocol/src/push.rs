@@ -28,6 +28,10 @@ pub struct PushUpdate {
     pub force: bool,
     /// Expected remote OID for --force-with-lease (None = no check).
     pub expected_remote_oid: Option<ObjectId>,
+    /// Local ref name this update was pushed from, if any (None for a
+    /// delete refspec with no local source). Used to report `<local ref>
+    /// <local sha> <remote ref> <remote sha>` lines to the pre-push hook.
+    pub local_ref: Option<String>,
 }
 
 /// Push operation options.
@@ -322,6 +326,7 @@ mod tests {
             force: false,
             // Expect a different OID than what's actually advertised
             expected_remote_oid: Some(oid2),
+            local_ref: Some("refs/heads/main".into()),
         };
 
         // Verify the force-with-lease check catches the mismatch
@@ -158,13 +158,26 @@ pub fn map_refs(
     refs: &[(git_hash::ObjectId, BString)],
     refspecs: &[RefSpec],
 ) -> Vec<(git_hash::ObjectId, String, String)> {
+    map_refs_with_force(refs, refspecs)
+        .into_iter()
+        .map(|(oid, source, dest, _force)| (oid, source, dest))
+        .collect()
+}
+
+/// Like [`map_refs`], but also reports whether the refspec that matched each
+/// mapping was a force (`+`-prefixed) refspec, so callers can decide whether
+/// a non-fast-forward update to the destination ref is allowed.
+pub fn map_refs_with_force(
+    refs: &[(git_hash::ObjectId, BString)],
+    refspecs: &[RefSpec],
+) -> Vec<(git_hash::ObjectId, String, String, bool)> {
     let mut result = Vec::new();
 
     for (oid, remote_ref) in refs {
         let remote_name = String::from_utf8_lossy(remote_ref.as_ref()).to_string();
         for spec in refspecs {
             if let Some(local_ref) = spec.map_to_destination(&remote_name) {
-                result.push((*oid, remote_name.clone(), local_ref));
+                result.push((*oid, remote_name.clone(), local_ref, spec.force));
                 break;
             }
         }
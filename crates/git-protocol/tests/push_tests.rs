@@ -61,6 +61,7 @@ fn push_update_create_ref() {
         remote_ref: "refs/heads/new-branch".into(),
         force: false,
         expected_remote_oid: None,
+        local_ref: Some("refs/heads/new-branch".into()),
     };
     assert!(update.local_oid.is_some());
     assert_eq!(update.remote_ref, "refs/heads/new-branch");
@@ -73,6 +74,7 @@ fn push_update_delete_ref() {
         remote_ref: "refs/heads/old-branch".into(),
         force: false,
         expected_remote_oid: None,
+        local_ref: None,
     };
     assert!(update.local_oid.is_none());
 }
@@ -88,6 +90,7 @@ fn push_update_force_with_lease() {
         remote_ref: "refs/heads/main".into(),
         force: false,
         expected_remote_oid: Some(expected_oid),
+        local_ref: Some("refs/heads/main".into()),
     };
 
     // Simulate the check: advertised ref has `actual_oid`, but we expected `expected_oid`
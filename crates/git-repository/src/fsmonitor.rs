@@ -0,0 +1,80 @@
+//! Client for the `core.fsmonitor` hook, used to speed up index refreshes.
+//!
+//! Mirrors C git's fsmonitor-hook protocol (the same one the
+//! `fsmonitor-watchman` sample hook implements): invoke the configured
+//! command with the protocol version and the last-seen token, and read back
+//! a new token followed by NUL-separated paths changed since that token.
+
+use std::process::{Command, Stdio};
+
+use bstr::BString;
+
+use crate::Repository;
+
+/// The fsmonitor hook protocol version this client speaks.
+const HOOK_VERSION: &str = "1";
+
+/// Result of querying the external file-system monitor.
+pub struct FsMonitorQuery {
+    /// Token to persist and present on the next query.
+    pub token: BString,
+    /// Paths (relative to the work tree) changed since the token this query
+    /// was made with. A monitor that has never seen the given token (e.g.
+    /// the very first query) reports every tracked path as changed.
+    pub changed_paths: Vec<BString>,
+}
+
+/// Client for the `core.fsmonitor` hook.
+pub struct FsMonitorClient {
+    command: Option<String>,
+}
+
+impl FsMonitorClient {
+    /// Build a client from the repository's `core.fsmonitor` config.
+    /// Queries are a no-op if it's unset.
+    pub fn new(repo: &Repository) -> Self {
+        let command = repo.config().get_string("core.fsmonitor").ok().flatten();
+        Self { command }
+    }
+
+    /// Whether a monitor is configured at all.
+    pub fn is_enabled(&self) -> bool {
+        self.command.is_some()
+    }
+
+    /// Query the monitor for paths changed since `token`. Returns `None` if
+    /// no monitor is configured.
+    pub fn query(&self, token: &[u8]) -> Result<Option<FsMonitorQuery>, std::io::Error> {
+        let Some(command) = &self.command else {
+            return Ok(None);
+        };
+
+        let output = Command::new(command)
+            .arg(HOOK_VERSION)
+            .arg(String::from_utf8_lossy(token).to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()?;
+
+        if !output.status.success() {
+            // Hook failed: treat as "nothing known", forcing a full stat refresh.
+            return Ok(Some(FsMonitorQuery {
+                token: BString::from(token),
+                changed_paths: Vec::new(),
+            }));
+        }
+
+        let mut lines = output.stdout.split(|&b| b == 0);
+        let new_token = lines.next().unwrap_or_default();
+        let changed_paths = lines
+            .filter(|path| !path.is_empty())
+            .map(BString::from)
+            .collect();
+
+        Ok(Some(FsMonitorQuery {
+            token: BString::from(new_token),
+            changed_paths,
+        }))
+    }
+}
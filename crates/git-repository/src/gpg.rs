@@ -7,47 +7,133 @@ use std::process::{Command, Stdio};
 pub enum GpgFormat {
     OpenPGP,
     X509,
+    Ssh,
 }
 
 pub struct GpgSigner {
     program: String,
-    _format: GpgFormat,
+    format: GpgFormat,
     key: Option<String>,
+    allowed_signers_file: Option<String>,
 }
 
 pub struct GpgSignature {
     pub signature: Vec<u8>,
 }
 
+/// Outcome of checking a signature, mirroring gpg's own good/bad/unknown
+/// trichotomy (an `Unknown` result means the signature couldn't be
+/// checked at all, e.g. no matching public key, rather than that it was
+/// checked and found invalid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    Good,
+    Bad,
+    Unknown,
+}
+
+/// How much the local keyring trusts the signer's key, parsed from gpg's
+/// `TRUST_*` status-fd lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustLevel {
+    Undefined,
+    Never,
+    Marginal,
+    Fully,
+    Ultimate,
+}
+
 pub struct GpgVerifyResult {
-    pub valid: bool,
+    pub status: SignatureStatus,
     pub key_id: Option<String>,
+    /// Full fingerprint of the signing key, from the `VALIDSIG` line
+    /// (only present for `--status-fd`-capable checks, i.e. OpenPGP/X.509).
+    pub fingerprint: Option<String>,
+    /// `"Name <email>"` exactly as gpg reports the user id.
     pub signer: Option<String>,
+    /// `signer`'s name portion, split out for structured consumers.
+    pub signer_name: Option<String>,
+    /// `signer`'s email portion, split out for structured consumers.
+    pub signer_email: Option<String>,
+    /// Unix timestamp the signature was created, from `VALIDSIG`.
+    pub signature_time: Option<i64>,
+    /// Key trust level, from the `TRUST_*` line (`None` if gpg didn't emit one).
+    pub trust_level: Option<TrustLevel>,
+    /// True if the signature is good but was made with an now-expired key
+    /// (gpg's `EXPKEYSIG`, as opposed to a good key with an expired signature).
+    pub expired_key: bool,
+    /// The raw `--status-fd` machine-readable output, for callers that want
+    /// to inspect lines this struct doesn't otherwise surface.
+    pub raw_status: String,
+}
+
+impl GpgVerifyResult {
+    /// A `%G?`-style one-character summary of the verification outcome:
+    /// `G`ood (good signature, key fully/ultimately trusted), `B`ad, `U`ntrusted
+    /// (good signature, but the key's validity is unknown/marginal), e`X`pired
+    /// key (good signature made with a since-expired key), `E`rror (signature
+    /// couldn't be checked, e.g. no public key).
+    pub fn summary_char(&self) -> char {
+        match self.status {
+            SignatureStatus::Bad => 'B',
+            SignatureStatus::Unknown => 'E',
+            SignatureStatus::Good => {
+                if self.expired_key {
+                    'X'
+                } else {
+                    match self.trust_level {
+                        Some(TrustLevel::Fully) | Some(TrustLevel::Ultimate) => 'G',
+                        _ => 'U',
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl GpgSigner {
     pub fn from_config(config: &git_config::ConfigSet) -> Self {
-        let program = config
-            .get_string("gpg.program")
-            .ok()
-            .flatten()
-            .unwrap_or_else(|| "gpg".to_string());
-
         let format = match config.get_string("gpg.format").ok().flatten().as_deref() {
             Some("x509") => GpgFormat::X509,
+            Some("ssh") => GpgFormat::Ssh,
             _ => GpgFormat::OpenPGP,
         };
 
+        let program_key = if format == GpgFormat::Ssh { "gpg.ssh.program" } else { "gpg.program" };
+        let default_program = if format == GpgFormat::Ssh { "ssh-keygen" } else { "gpg" };
+        let program = config
+            .get_string(program_key)
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| default_program.to_string());
+
         let key = config.get_string("user.signingKey").ok().flatten();
+        let allowed_signers_file = config.get_string("gpg.ssh.allowedSignersFile").ok().flatten();
+
+        Self { program, format, key, allowed_signers_file }
+    }
 
-        Self {
-            program,
-            _format: format,
-            key,
+    /// Build a signer from config, overriding the signing key with `keyid`
+    /// when one is given (the `-S<keyid>` case); `-S` with no argument
+    /// falls back to `user.signingKey` as usual.
+    pub fn with_key(config: &git_config::ConfigSet, keyid: Option<&str>) -> Self {
+        let mut signer = Self::from_config(config);
+        if let Some(keyid) = keyid {
+            if !keyid.is_empty() {
+                signer.key = Some(keyid.to_string());
+            }
         }
+        signer
     }
 
     pub fn sign(&self, data: &[u8]) -> Result<GpgSignature, std::io::Error> {
+        match self.format {
+            GpgFormat::Ssh => self.sign_ssh(data),
+            GpgFormat::OpenPGP | GpgFormat::X509 => self.sign_gpg(data),
+        }
+    }
+
+    fn sign_gpg(&self, data: &[u8]) -> Result<GpgSignature, std::io::Error> {
         let mut cmd = Command::new(&self.program);
         cmd.args(["--status-fd=2", "-bsau"]);
 
@@ -81,10 +167,68 @@ impl GpgSigner {
         })
     }
 
+    /// Sign `data` with `ssh-keygen -Y sign`, as used by `gpg.format=ssh`.
+    /// `user.signingKey` may be either a path to a key file or a literal
+    /// public key (e.g. `"ssh-ed25519 AAAA..."`); `ssh-keygen` only accepts
+    /// a file, so a literal key is written out to a temporary one first.
+    fn sign_ssh(&self, data: &[u8]) -> Result<GpgSignature, std::io::Error> {
+        let key = self.key.as_deref().ok_or_else(|| {
+            std::io::Error::other("ssh signing requires user.signingKey to be set")
+        })?;
+
+        let literal_key_file;
+        let key_path = if key.trim_start().starts_with("ssh-") || key.trim_start().starts_with("sk-ssh-") {
+            literal_key_file = tempfile::NamedTempFile::new()?;
+            std::fs::write(literal_key_file.path(), key.as_bytes())?;
+            literal_key_file.path().to_path_buf()
+        } else {
+            std::path::PathBuf::from(key)
+        };
+
+        let data_file = tempfile::NamedTempFile::new()?;
+        std::fs::write(data_file.path(), data)?;
+
+        let output = Command::new(&self.program)
+            .args(["-Y", "sign", "-n", "git", "-f"])
+            .arg(&key_path)
+            .arg(data_file.path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()?;
+
+        if !output.status.success() {
+            return Err(std::io::Error::other(format!(
+                "ssh-keygen failed to sign the data: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        // `ssh-keygen -Y sign` writes the armored signature alongside the
+        // signed file, appending ".sig" to its full name.
+        let mut sig_path = data_file.path().as_os_str().to_os_string();
+        sig_path.push(".sig");
+        let sig_path = std::path::PathBuf::from(sig_path);
+        let signature = std::fs::read(&sig_path)?;
+        let _ = std::fs::remove_file(&sig_path);
+
+        Ok(GpgSignature { signature })
+    }
+
     pub fn verify(
         &self,
         data: &[u8],
         signature: &[u8],
+    ) -> Result<GpgVerifyResult, std::io::Error> {
+        match self.format {
+            GpgFormat::Ssh => self.verify_ssh(data, signature),
+            GpgFormat::OpenPGP | GpgFormat::X509 => self.verify_gpg(data, signature),
+        }
+    }
+
+    fn verify_gpg(
+        &self,
+        data: &[u8],
+        signature: &[u8],
     ) -> Result<GpgVerifyResult, std::io::Error> {
         // Write signature to a temp file, pass data on stdin
         let sig_file = tempfile::NamedTempFile::new()?;
@@ -105,19 +249,171 @@ impl GpgSigner {
         drop(child.stdin.take());
 
         let output = child.wait_with_output()?;
-        let status_output = String::from_utf8_lossy(&output.stdout);
+        let status_output = String::from_utf8_lossy(&output.stdout).to_string();
 
-        let valid = output.status.success();
+        let status = if status_output.lines().any(|l| l.contains("GOODSIG") || l.contains("EXPKEYSIG")) {
+            SignatureStatus::Good
+        } else if status_output.lines().any(|l| l.contains("BADSIG")) {
+            SignatureStatus::Bad
+        } else {
+            SignatureStatus::Unknown
+        };
+        let expired_key = status_output.lines().any(|l| l.contains("EXPKEYSIG"));
         let key_id = status_output
             .lines()
-            .find(|l| l.contains("GOODSIG") || l.contains("VALIDSIG"))
+            .find(|l| l.contains("GOODSIG") || l.contains("BADSIG") || l.contains("EXPKEYSIG") || l.contains("VALIDSIG"))
             .and_then(|l| l.split_whitespace().nth(2))
             .map(|s| s.to_string());
+        let signer = status_output
+            .lines()
+            .find(|l| l.contains("GOODSIG") || l.contains("BADSIG") || l.contains("EXPKEYSIG"))
+            .and_then(|l| {
+                let mut parts = l.splitn(4, ' ');
+                parts.next(); // "[GNUPG:]"
+                parts.next(); // "GOODSIG"/"BADSIG"/"EXPKEYSIG"
+                parts.next(); // keyid
+                parts.next()
+            })
+            .map(|s| s.to_string());
+        let (signer_name, signer_email) = signer
+            .as_deref()
+            .map(split_user_id)
+            .unwrap_or((None, None));
+
+        let validsig_fields: Option<Vec<&str>> = status_output
+            .lines()
+            .find(|l| l.contains("VALIDSIG"))
+            .map(|l| l.split_whitespace().collect());
+        let fingerprint = validsig_fields
+            .as_ref()
+            .and_then(|f| f.get(2))
+            .map(|s| s.to_string());
+        let signature_time = validsig_fields
+            .as_ref()
+            .and_then(|f| f.get(4))
+            .and_then(|s| s.parse::<i64>().ok());
+
+        let trust_level = status_output
+            .lines()
+            .find(|l| l.contains("TRUST_"))
+            .and_then(parse_trust_level);
 
         Ok(GpgVerifyResult {
-            valid,
+            status,
             key_id,
-            signer: None,
+            fingerprint,
+            signer,
+            signer_name,
+            signer_email,
+            signature_time,
+            trust_level,
+            expired_key,
+            raw_status: status_output,
+        })
+    }
+
+    /// Verify an `ssh-keygen -Y sign` signature against `gpg.ssh.allowedSignersFile`.
+    /// Without that config there is no set of trusted keys to check
+    /// against, so the signature is reported as `Unknown` rather than
+    /// attempting (and likely failing) a check with no allowed signers.
+    fn verify_ssh(
+        &self,
+        data: &[u8],
+        signature: &[u8],
+    ) -> Result<GpgVerifyResult, std::io::Error> {
+        let Some(ref allowed_signers) = self.allowed_signers_file else {
+            return Ok(GpgVerifyResult {
+                status: SignatureStatus::Unknown,
+                key_id: None,
+                fingerprint: None,
+                signer: None,
+                signer_name: None,
+                signer_email: None,
+                signature_time: None,
+                trust_level: None,
+                expired_key: false,
+                raw_status: String::new(),
+            });
+        };
+
+        let sig_file = tempfile::NamedTempFile::new()?;
+        std::fs::write(sig_file.path(), signature)?;
+
+        let mut cmd = Command::new(&self.program);
+        cmd.args(["-Y", "verify", "-n", "git", "-f"]);
+        cmd.arg(allowed_signers);
+        cmd.args(["-I", self.key.as_deref().unwrap_or("git"), "-s"]);
+        cmd.arg(sig_file.path());
+        cmd.stdin(Stdio::piped());
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+
+        let mut child = cmd.spawn()?;
+        if let Some(ref mut stdin) = child.stdin {
+            stdin.write_all(data)?;
+        }
+        drop(child.stdin.take());
+
+        let output = child.wait_with_output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let status = if output.status.success() {
+            SignatureStatus::Good
+        } else {
+            SignatureStatus::Bad
+        };
+        let signer = stdout
+            .lines()
+            .find(|l| l.contains("Good \"git\" signature for"))
+            .and_then(|l| l.split("for ").nth(1))
+            .map(|s| s.trim().to_string());
+        let (signer_name, signer_email) = signer
+            .as_deref()
+            .map(split_user_id)
+            .unwrap_or((None, None));
+
+        Ok(GpgVerifyResult {
+            status,
+            key_id: None,
+            fingerprint: None,
+            signer,
+            signer_name,
+            signer_email,
+            signature_time: None,
+            trust_level: None,
+            expired_key: false,
+            raw_status: stdout.to_string(),
         })
     }
 }
+
+/// Split a gpg user id of the form `"Name <email>"` into its two parts.
+fn split_user_id(user_id: &str) -> (Option<String>, Option<String>) {
+    match user_id.split_once('<') {
+        Some((name, rest)) => {
+            let name = name.trim();
+            let email = rest.trim_end_matches('>').trim();
+            (
+                (!name.is_empty()).then(|| name.to_string()),
+                (!email.is_empty()).then(|| email.to_string()),
+            )
+        }
+        None => ((!user_id.is_empty()).then(|| user_id.trim().to_string()), None),
+    }
+}
+
+/// Parse a gpg `TRUST_*` status-fd line into a [`TrustLevel`].
+fn parse_trust_level(line: &str) -> Option<TrustLevel> {
+    if line.contains("TRUST_ULTIMATE") {
+        Some(TrustLevel::Ultimate)
+    } else if line.contains("TRUST_FULLY") {
+        Some(TrustLevel::Fully)
+    } else if line.contains("TRUST_MARGINAL") {
+        Some(TrustLevel::Marginal)
+    } else if line.contains("TRUST_NEVER") {
+        Some(TrustLevel::Never)
+    } else if line.contains("TRUST_UNDEFINED") {
+        Some(TrustLevel::Undefined)
+    } else {
+        None
+    }
+}
@@ -69,7 +69,7 @@ impl HookRunner {
     /// Check if a hook script exists and is executable.
     pub fn hook_exists(&self, hook: HookType) -> bool {
         let path = self.hooks_path.join(hook.name());
-        path.is_file()
+        path.is_file() && is_executable(&path)
     }
 
     /// Execute a hook. Returns error if hook exists but fails to execute.
@@ -80,7 +80,7 @@ impl HookRunner {
         stdin: Option<&[u8]>,
     ) -> Result<HookResult, std::io::Error> {
         let path = self.hooks_path.join(hook.name());
-        if !path.is_file() {
+        if !path.is_file() || !is_executable(&path) {
             return Ok(HookResult {
                 exit_code: 0,
                 stdout: Vec::new(),
@@ -128,3 +128,20 @@ impl HookRunner {
         self.run(hook, args, stdin)
     }
 }
+
+/// Check that a hook file has the executable bit set. On platforms without a
+/// Unix-style executable permission, any regular file is runnable (Windows
+/// has no execute bit; the hook is invoked via its shebang/association), so
+/// this always returns `true` there.
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|meta| meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &std::path::Path) -> bool {
+    true
+}
@@ -4,6 +4,7 @@ mod discover;
 pub mod editor;
 mod env;
 mod error;
+pub mod fsmonitor;
 pub mod gpg;
 pub mod hooks;
 mod init;
@@ -13,6 +14,7 @@ pub use error::RepoError;
 
 use std::path::{Path, PathBuf};
 
+use bstr::BStr;
 use git_config::ConfigSet;
 use git_hash::{HashAlgorithm, ObjectId};
 use git_index::Index;
@@ -286,7 +288,15 @@ impl Repository {
     }
 
     /// Write the current in-memory index back to disk.
-    pub fn write_index(&self) -> Result<(), RepoError> {
+    ///
+    /// Honors `index.version` from config (2, 3, or 4); invalid values are
+    /// ignored and the index keeps whatever version it already has.
+    pub fn write_index(&mut self) -> Result<(), RepoError> {
+        if let Some(version) = self.config.get_int("index.version")? {
+            if let Some(idx) = self.index.as_mut() {
+                let _ = idx.set_version(version as u32);
+            }
+        }
         if let Some(ref idx) = self.index {
             idx.write_to(&self.index_path)?;
         }
@@ -300,6 +310,30 @@ impl Repository {
         Ok(self.index.as_ref().unwrap())
     }
 
+    /// Query the configured `core.fsmonitor` hook and apply its answer to
+    /// the in-memory index, so a subsequent refresh can skip `stat(2)` for
+    /// every entry the monitor vouches for. A no-op if no monitor is
+    /// configured; does not write the index back to disk.
+    pub fn refresh_fsmonitor(&mut self) -> Result<(), RepoError> {
+        let client = fsmonitor::FsMonitorClient::new(self);
+        if !client.is_enabled() {
+            return Ok(());
+        }
+
+        let token = self
+            .index()?
+            .fsmonitor()
+            .map(|fsm| fsm.token.to_vec())
+            .unwrap_or_default();
+
+        if let Some(query) = client.query(&token)? {
+            let changed: Vec<&BStr> = query.changed_paths.iter().map(|p| p.as_ref()).collect();
+            self.index_mut()?.apply_fsmonitor_query(&changed, query.token);
+        }
+
+        Ok(())
+    }
+
     fn load_index(&mut self) -> Result<(), RepoError> {
         let idx = if self.index_path.exists() {
             Index::read_from(&self.index_path)?
@@ -2,14 +2,21 @@
 //!
 //! Identifies commits not yet applied upstream by comparing patch IDs.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use bstr::ByteSlice;
+use git_diff::{DiffLine, DiffOptions};
 use git_hash::ObjectId;
 use git_object::Object;
 use git_repository::Repository;
+use sha1::{Digest, Sha1};
 
 use crate::RevWalkError;
-use crate::walk::RevWalk;
+use crate::walk::{RevWalk, SortOrder};
+
+/// Patch-ids keyed by commit OID, so a commit's diff is only generated and
+/// hashed once even if it's consulted from both sides of a comparison.
+type PatchIdCache = HashMap<ObjectId, String>;
 
 /// A commit with its cherry-pick status.
 #[derive(Debug, Clone)]
@@ -37,16 +44,18 @@ pub fn cherry(
     // Get commits reachable from upstream but not from head
     let upstream_commits = collect_commits(repo, upstream, head)?;
 
+    let mut cache = PatchIdCache::new();
+
     // Compute patch IDs for upstream commits
     let upstream_patch_ids: HashSet<String> = upstream_commits
         .iter()
-        .filter_map(|oid| compute_patch_id(repo, oid).ok())
+        .filter_map(|oid| compute_patch_id(repo, oid, &mut cache).ok())
         .collect();
 
     // Mark head commits
     let mut entries = Vec::new();
     for oid in &head_commits {
-        let patch_id = compute_patch_id(repo, oid).unwrap_or_default();
+        let patch_id = compute_patch_id(repo, oid, &mut cache).unwrap_or_default();
         let marker = if upstream_patch_ids.contains(&patch_id) {
             '-'
         } else {
@@ -80,21 +89,23 @@ pub fn symmetric_diff_with_cherry(
     // Get right-side commits (reachable from right but not left)
     let right_commits = collect_commits(repo, right, left)?;
 
+    let mut cache = PatchIdCache::new();
+
     // Compute patch IDs for both sides
     let left_patch_ids: HashSet<String> = left_commits
         .iter()
-        .filter_map(|oid| compute_patch_id(repo, oid).ok())
+        .filter_map(|oid| compute_patch_id(repo, oid, &mut cache).ok())
         .collect();
     let right_patch_ids: HashSet<String> = right_commits
         .iter()
-        .filter_map(|oid| compute_patch_id(repo, oid).ok())
+        .filter_map(|oid| compute_patch_id(repo, oid, &mut cache).ok())
         .collect();
 
     // Mark left commits
     let left_entries: Vec<CherryEntry> = left_commits
         .iter()
         .map(|oid| {
-            let patch_id = compute_patch_id(repo, oid).unwrap_or_default();
+            let patch_id = compute_patch_id(repo, oid, &mut cache).unwrap_or_default();
             let is_equivalent = right_patch_ids.contains(&patch_id);
             let marker = if is_equivalent { '=' } else { '+' };
             let subject = get_commit_subject(repo, oid).unwrap_or_default();
@@ -111,7 +122,7 @@ pub fn symmetric_diff_with_cherry(
     let right_entries: Vec<CherryEntry> = right_commits
         .iter()
         .map(|oid| {
-            let patch_id = compute_patch_id(repo, oid).unwrap_or_default();
+            let patch_id = compute_patch_id(repo, oid, &mut cache).unwrap_or_default();
             let is_equivalent = left_patch_ids.contains(&patch_id);
             let marker = if is_equivalent { '=' } else { '+' };
             let subject = get_commit_subject(repo, oid).unwrap_or_default();
@@ -129,16 +140,18 @@ pub fn symmetric_diff_with_cherry(
 
 /// Compute a patch ID for a commit (public, for use by other modules).
 pub fn compute_patch_id_for(repo: &Repository, oid: &ObjectId) -> Result<String, RevWalkError> {
-    compute_patch_id(repo, oid)
+    compute_patch_id(repo, oid, &mut PatchIdCache::new())
 }
 
-/// Collect commits reachable from `include` but not from `exclude`.
+/// Collect commits reachable from `include` but not from `exclude`, oldest
+/// first (matching `git cherry`'s display order).
 fn collect_commits(
     repo: &Repository,
     include: &ObjectId,
     exclude: &ObjectId,
 ) -> Result<Vec<ObjectId>, RevWalkError> {
     let mut walk = RevWalk::new(repo)?;
+    walk.set_sort(SortOrder::Reverse);
     walk.push(*include)?;
     walk.hide(*exclude)?;
 
@@ -149,9 +162,21 @@ fn collect_commits(
     Ok(commits)
 }
 
-/// Compute a simplified patch ID for a commit.
-/// Uses the commit's tree diff as a fingerprint.
-fn compute_patch_id(repo: &Repository, oid: &ObjectId) -> Result<String, RevWalkError> {
+/// Compute a commit's patch-id: diff it against its first parent (or the
+/// empty tree for a root commit), strip everything but the added/removed
+/// content of each hunk (no line numbers, no context lines, whitespace runs
+/// collapsed), and hash the result with SHA-1. Two commits with the same
+/// patch-id made the same change regardless of message, parent, or OID.
+/// Results are cached by commit OID in `cache`.
+fn compute_patch_id(
+    repo: &Repository,
+    oid: &ObjectId,
+    cache: &mut PatchIdCache,
+) -> Result<String, RevWalkError> {
+    if let Some(cached) = cache.get(oid) {
+        return Ok(cached.clone());
+    }
+
     let obj = repo
         .odb()
         .read(oid)?
@@ -162,19 +187,49 @@ fn compute_patch_id(repo: &Repository, oid: &ObjectId) -> Result<String, RevWalk
         _ => return Err(RevWalkError::NotACommit(*oid)),
     };
 
-    // Use commit message + parent count as a simple patch ID
-    // A full implementation would diff against parent and hash the diff
-    use sha1::{Digest, Sha1};
+    let parent_tree = match commit.parents.first() {
+        Some(parent_oid) => match repo.odb().read(parent_oid)? {
+            Some(Object::Commit(parent)) => Some(parent.tree),
+            _ => None,
+        },
+        None => None,
+    };
+
+    let diff = git_diff::tree::diff_trees(
+        repo.odb(),
+        parent_tree.as_ref(),
+        Some(&commit.tree),
+        &DiffOptions::default(),
+    )?;
+
     let mut hasher = Sha1::new();
-    hasher.update(&commit.message);
-    hasher.update(commit.parents.len().to_string().as_bytes());
-    if let Some(parent) = commit.parents.first() {
-        hasher.update(parent.as_bytes());
+    for file in &diff.files {
+        hasher.update(file.path().as_bstr().as_bytes());
+        hasher.update(b"\n");
+        for hunk in &file.hunks {
+            for line in &hunk.lines {
+                let (marker, content) = match line {
+                    DiffLine::Addition(s) => (b'+', s),
+                    DiffLine::Deletion(s) => (b'-', s),
+                    DiffLine::Context(_) => continue,
+                };
+                let collapsed = collapse_whitespace(&String::from_utf8_lossy(content));
+                hasher.update([marker]);
+                hasher.update(collapsed.as_bytes());
+                hasher.update(b"\n");
+            }
+        }
     }
-    hasher.update(commit.tree.as_bytes());
 
-    let result = hasher.finalize();
-    Ok(result.iter().map(|b| format!("{:02x}", b)).collect())
+    let hex: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    cache.insert(*oid, hex.clone());
+    Ok(hex)
+}
+
+/// Collapse runs of whitespace into a single space and trim the ends, so
+/// patch-id is insensitive to reindentation-only noise.
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 fn get_commit_subject(repo: &Repository, oid: &ObjectId) -> Result<String, RevWalkError> {
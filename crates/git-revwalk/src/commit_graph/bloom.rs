@@ -0,0 +1,124 @@
+//! Changed-path Bloom filters for the commit-graph BIDX/BDAT chunks.
+//!
+//! Each commit stores a small per-commit Bloom filter over the set of paths
+//! (and their leading directories) that differ from its first parent. A
+//! path-limited walk can then skip a commit without diffing its tree whenever
+//! the filter conclusively reports the path absent. This mirrors C git's
+//! changed-path Bloom filter: double-hashed probes derived from a MurmurHash3
+//! pair, `BITS_PER_ENTRY` bits budgeted per changed path.
+
+use std::collections::BTreeSet;
+
+use bstr::{BStr, BString, ByteSlice};
+
+/// Bits budgeted per changed path before rounding the filter up to bytes.
+pub(crate) const BITS_PER_ENTRY: u32 = 10;
+/// Number of bit positions probed per path, double-hashed from two seeds.
+pub(crate) const NUM_HASHES: u32 = 7;
+/// Above this many changed paths, a commit's filter is stored as the 1-byte
+/// "too many changes" sentinel rather than growing without bound.
+pub(crate) const MAX_CHANGED_PATHS: usize = 512;
+
+/// MurmurHash3 (x86, 32-bit) over `data`, used to derive the filter's two
+/// probe seeds.
+pub(crate) fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mut hash = seed;
+    let chunks = data.chunks_exact(4);
+    let remainder = chunks.remainder();
+
+    for chunk in chunks {
+        let mut k = u32::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    let mut k = 0u32;
+    for (i, &b) in remainder.iter().enumerate().rev() {
+        k ^= (b as u32) << (8 * i);
+    }
+    if !remainder.is_empty() {
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+    }
+
+    // Finalization mix.
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+    hash
+}
+
+/// Expand a changed path into itself plus every leading directory prefix, so
+/// a directory-level pathspec query can also hit the filter.
+pub(crate) fn path_and_parents(path: &BStr) -> Vec<BString> {
+    let mut out = vec![path.to_owned()];
+    let mut rest = path;
+    while let Some(idx) = rest.rfind_byte(b'/') {
+        rest = rest[..idx].as_bstr();
+        out.push(rest.to_owned());
+    }
+    out
+}
+
+/// Bit positions probed for `path` in a filter sized at `total_bits`.
+fn probe_bits(path: &BStr, total_bits: u32) -> impl Iterator<Item = u32> {
+    let h1 = murmur3_32(path.as_bytes(), 0);
+    let h2 = murmur3_32(path.as_bytes(), 0x2);
+    (0..NUM_HASHES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % total_bits)
+}
+
+/// Build the on-disk filter bytes for a commit's changed paths (already
+/// expanded to include leading directories and deduplicated).
+///
+/// Empty input produces a 0-byte filter (vacuously: no path can match).
+/// More than [`MAX_CHANGED_PATHS`] entries produces a 1-byte sentinel that
+/// always reads as inconclusive, since a real filter needs at least 2 bytes.
+pub(crate) fn build_filter(changed_paths: &BTreeSet<BString>) -> Vec<u8> {
+    if changed_paths.len() > MAX_CHANGED_PATHS {
+        return vec![0u8];
+    }
+    if changed_paths.is_empty() {
+        return Vec::new();
+    }
+
+    let num_bits = changed_paths.len() as u32 * BITS_PER_ENTRY;
+    let num_bytes = ((num_bits + 7) / 8).max(2) as usize;
+    let mut bits = vec![0u8; num_bytes];
+    let total_bits = (bits.len() * 8) as u32;
+
+    for path in changed_paths {
+        for bit in probe_bits(path.as_bstr(), total_bits) {
+            bits[(bit / 8) as usize] |= 1 << (bit % 8);
+        }
+    }
+    bits
+}
+
+/// The "too many changed paths" sentinel filter: always inconclusive.
+pub(crate) fn sentinel_filter() -> Vec<u8> {
+    vec![0u8]
+}
+
+/// Test whether `path` might be among the changed paths encoded by `bytes`.
+///
+/// `false` means the path is definitely not a changed path for this commit.
+/// An empty `bytes` (no changed paths) always gives `false`.
+/// `true` means it might be (or `bytes` is the 1-byte sentinel) — the
+/// caller must fall back to a real diff to be sure.
+pub(crate) fn maybe_contains(bytes: &[u8], path: &BStr) -> bool {
+    match bytes.len() {
+        0 => false,
+        1 => true,
+        _ => {
+            let total_bits = (bytes.len() * 8) as u32;
+            probe_bits(path, total_bits).all(|bit| bytes[(bit / 8) as usize] & (1 << (bit % 8)) != 0)
+        }
+    }
+}
@@ -4,24 +4,41 @@
 //! generation numbers, commit dates) without parsing pack objects.
 //!
 //! Format: see Documentation/technical/commit-graph-format.txt in git source.
+//!
+//! A repository's commit-graph data can live either as a single monolithic
+//! `objects/info/commit-graph` file, or as a *split chain*: a
+//! `objects/info/commit-graphs/commit-graph-chain` file listing, one hash per
+//! line from the oldest (base) layer to the newest (tip) layer, the
+//! `graph-{hash}.graph` files that make up the chain. [`CommitGraph`] presents
+//! both as the same merged view: commit positions are numbered globally
+//! across the whole chain (base layer's commits first, then the next layer's,
+//! and so on), so a lookup or a parent reference can fall through to an
+//! earlier layer using that same global index space.
 
+pub(crate) mod bloom;
 mod parse;
 pub mod write;
 
 use std::path::Path;
 
+use bstr::BStr;
 use git_hash::ObjectId;
 use git_repository::Repository;
 use memmap2::Mmap;
 
 use crate::RevWalkError;
 
-/// A parsed commit-graph file providing fast commit access.
-pub struct CommitGraph {
+/// A single physical commit-graph file: either the lone monolithic file, or
+/// one layer within a split chain.
+pub(crate) struct GraphLayer {
     /// Memory-mapped commit-graph data.
     data: Mmap,
-    /// Number of commits in the graph.
+    /// Number of commits in this layer.
     num_commits: u32,
+    /// Number of preceding layers this one was written on top of (the
+    /// header's base graph count byte).
+    #[allow(dead_code)]
+    base_graph_count: u8,
     /// Offset to the OID Fanout chunk (256 Ã— 4-byte cumulative counts).
     oid_fanout_offset: usize,
     /// Offset to the OID Lookup chunk.
@@ -30,10 +47,34 @@ pub struct CommitGraph {
     commit_data_offset: usize,
     /// Offset to the Extra Edge List chunk (for octopus merges).
     extra_edges_offset: Option<usize>,
+    /// Offset to the Generation Data chunk (GDA2), holding per-commit
+    /// corrected-commit-date offsets. Absent in graphs written before
+    /// corrected dates were introduced.
+    generation_data_offset: Option<usize>,
+    /// Offset to the Generation Data Overflow chunk (GDO2), holding
+    /// absolute corrected dates for entries whose offset from the
+    /// committer date doesn't fit in 31 bits.
+    generation_data_overflow_offset: Option<usize>,
+    /// Offset to the Bloom Filter Index chunk (BIDX): one 4-byte cumulative
+    /// end-offset per commit into BDAT's filter-data region.
+    bloom_indexes_offset: Option<usize>,
+    /// Offset to the Bloom Filter Data chunk (BDAT): a 16-byte header
+    /// followed by each commit's changed-path filter bytes, concatenated in
+    /// the same order as BIDX/CDAT.
+    bloom_data_offset: Option<usize>,
     /// OID hash length (20 for SHA-1, 32 for SHA-256).
     hash_len: usize,
 }
 
+/// A parsed commit-graph, either a single file or a split chain of layers.
+pub struct CommitGraph {
+    /// Layers from oldest (base) to newest (tip), as listed in the chain
+    /// file, or a single entry when opened from a monolithic file.
+    pub(crate) layers: Vec<GraphLayer>,
+    /// Global position of each layer's first commit (parallel to `layers`).
+    pub(crate) layer_base_pos: Vec<u32>,
+}
+
 /// An entry from the commit-graph.
 #[derive(Debug, Clone)]
 pub struct CommitGraphEntry {
@@ -47,6 +88,17 @@ pub struct CommitGraphEntry {
     pub commit_time: i64,
 }
 
+/// Outcome of resolving a hex OID prefix against the commit-graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefixResult {
+    /// Exactly one commit in the graph matches this prefix.
+    Unique(ObjectId),
+    /// More than one commit shares this prefix.
+    Ambiguous,
+    /// No commit in the graph matches this prefix.
+    NotFound,
+}
+
 /// Maximum generation number for V1 format.
 #[allow(dead_code)]
 const GENERATION_NUMBER_V1_MAX: u32 = 0x3FFF_FFFF;
@@ -59,11 +111,37 @@ const CHUNK_OID_FANOUT: u32 = 0x4F494446; // "OIDF"
 const CHUNK_OID_LOOKUP: u32 = 0x4F49444C; // "OIDL"
 const CHUNK_COMMIT_DATA: u32 = 0x43444154; // "CDAT"
 const CHUNK_EXTRA_EDGES: u32 = 0x45444745; // "EDGE"
+const CHUNK_GENERATION_DATA: u32 = 0x47444132; // "GDA2"
+const CHUNK_GENERATION_DATA_OVERFLOW: u32 = 0x47444F32; // "GDO2"
+const CHUNK_BLOOM_INDEXES: u32 = 0x42494458; // "BIDX"
+const CHUNK_BLOOM_DATA: u32 = 0x42444154; // "BDAT"
+/// Size in bytes of the BDAT chunk's fixed header (version, hash version,
+/// bits-per-entry, num-hashes; 4 bytes each).
+const BLOOM_DATA_HEADER_SIZE: usize = 16;
+
+/// Parent-graph-position sentinel meaning "no parent".
+pub(crate) const PARENT_NONE: u32 = 0x7000_0000;
+/// Parent-graph-position flag meaning "parent 2 slot indexes the extra edge
+/// list" (for octopus merges).
+pub(crate) const PARENT_EXTRA_EDGE: u32 = 0x8000_0000;
+
+/// Name of the chain manifest file under `objects/info/commit-graphs/`.
+pub(crate) const CHAIN_FILE_NAME: &str = "commit-graph-chain";
 
 impl CommitGraph {
     /// Open a commit-graph file from a path.
     pub fn open(path: impl AsRef<Path>) -> Result<Self, RevWalkError> {
-        parse::open_commit_graph(path.as_ref())
+        let layer = parse::open_layer(path.as_ref())?;
+        Ok(Self {
+            layer_base_pos: vec![0],
+            layers: vec![layer],
+        })
+    }
+
+    /// Open a split commit-graph chain from its directory
+    /// (`objects/info/commit-graphs`).
+    pub fn open_chain(chain_dir: impl AsRef<Path>) -> Result<Self, RevWalkError> {
+        parse::open_chain(chain_dir.as_ref())
     }
 
     /// Try to open the commit-graph from a repository.
@@ -76,23 +154,10 @@ impl CommitGraph {
             return Self::open(&single_path);
         }
 
-        // Try chain of commit-graph files
+        // Fall back to a split chain.
         let chain_dir = objects_dir.join("info").join("commit-graphs");
-        if chain_dir.is_dir() {
-            // Read the chain file to find the latest graph
-            let chain_file = chain_dir.join("commit-graph-chain");
-            if chain_file.exists() {
-                let content = std::fs::read_to_string(&chain_file)
-                    .map_err(RevWalkError::Io)?;
-                // The last line is the most recent graph
-                if let Some(hash) = content.lines().last() {
-                    let hash = hash.trim();
-                    let graph_path = chain_dir.join(format!("graph-{}.graph", hash));
-                    if graph_path.exists() {
-                        return Self::open(&graph_path);
-                    }
-                }
-            }
+        if chain_dir.join(CHAIN_FILE_NAME).exists() {
+            return Self::open_chain(&chain_dir);
         }
 
         Err(RevWalkError::InvalidCommitGraph(
@@ -107,42 +172,154 @@ impl CommitGraph {
 
     /// Fast existence check without full entry parsing.
     pub fn contains(&self, oid: &ObjectId) -> bool {
-        parse::find_oid_position(self, oid).is_some()
+        parse::global_position(self, oid).is_some()
     }
 
-    /// Validate checksum integrity of the commit-graph file.
-    pub fn verify(&self) -> Result<(), RevWalkError> {
-        use sha1::Digest;
+    /// Look up a commit's generation number (topological level + 1).
+    ///
+    /// Returns `None` if the commit isn't present in the graph.
+    pub fn generation(&self, oid: &ObjectId) -> Option<u32> {
+        self.lookup(oid).map(|e| e.generation)
+    }
 
-        if self.data.len() < self.hash_len {
-            return Err(RevWalkError::InvalidCommitGraph(
-                "file too small for checksum".into(),
-            ));
-        }
+    /// Look up a commit's corrected commit date.
+    ///
+    /// This is `max(committer_date(c), 1 + max(corrected_commit_date(p)))`
+    /// over c's parents `p`, so it only ever grows moving from parent to
+    /// child. A date-ordered walk can use it to prove that no descendant
+    /// of a commit whose corrected date falls below a `--since` cutoff
+    /// could possibly pass that filter, and stop descending early.
+    ///
+    /// Returns `None` if the commit isn't present in the graph, or if the
+    /// graph predates generation data (no GDA2 chunk).
+    pub fn corrected_commit_date(&self, oid: &ObjectId) -> Option<i64> {
+        parse::corrected_commit_date(self, oid)
+    }
 
-        let content_len = self.data.len() - self.hash_len;
-        let stored_checksum = &self.data[content_len..];
+    /// Test whether `oid` may have touched `path`, using the changed-path
+    /// Bloom filter from the BIDX/BDAT chunks if present.
+    ///
+    /// Returns `None` if the commit isn't in the graph or the graph has no
+    /// Bloom data, in which case the caller must fall back to a real diff.
+    /// Returns `Some(false)` if the filter conclusively rules `path` out for
+    /// this commit, and `Some(true)` if it might be a changed path (still
+    /// requires a real diff to confirm).
+    pub fn maybe_changed_path(&self, oid: &ObjectId, path: &BStr) -> Option<bool> {
+        parse::changed_path_maybe(self, oid, path)
+    }
 
-        let mut hasher = sha1::Sha1::new();
-        hasher.update(&self.data[..content_len]);
-        let computed = hasher.finalize();
+    /// Shortest hex prefix length that unambiguously identifies `oid` among
+    /// every commit in the graph.
+    ///
+    /// Since each layer's OID Lookup chunk is sorted, this binary-searches
+    /// `oid`'s neighbors (in every layer, since a split chain has one sorted
+    /// table per layer rather than one global one) and compares the common
+    /// prefix length against each, taking the longer of the two plus one
+    /// nibble so the abbreviation doesn't collide with either. Returns the
+    /// full hex length if `oid` has no neighbors (the graph holds a single
+    /// commit).
+    pub fn shortest_prefix_len(&self, oid: &ObjectId) -> usize {
+        parse::shortest_prefix_len(self, oid)
+    }
 
-        if computed.as_slice() != stored_checksum {
-            return Err(RevWalkError::InvalidCommitGraph(
-                "checksum mismatch".into(),
-            ));
-        }
+    /// Resolve a hex OID prefix against the graph's sorted OID lookup
+    /// tables, without touching the ODB.
+    ///
+    /// Finds the lower bound of `prefix` in each layer's lookup table, then
+    /// scans forward while entries still start with `prefix` (cheap, since
+    /// matches are contiguous within a sorted table). Degrades gracefully
+    /// when no graph is present — the caller should fall back to
+    /// [`git_odb::prefix::resolve_prefix`] in that case.
+    pub fn resolve_prefix(&self, prefix: &str) -> PrefixResult {
+        parse::resolve_prefix(self, prefix)
+    }
 
+    /// Validate checksum integrity of every layer file in the graph (or
+    /// chain).
+    pub fn verify(&self) -> Result<(), RevWalkError> {
+        for layer in &self.layers {
+            verify_layer(layer)?;
+        }
         Ok(())
     }
 
-    /// Get the number of commits in the graph.
+    /// Total number of commits across all layers.
     pub fn num_commits(&self) -> u32 {
-        self.num_commits
+        self.layers.iter().map(|l| l.num_commits).sum()
+    }
+
+    /// Number of layers making up this graph (1 for a monolithic file).
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Every commit in the graph, in no particular cross-layer order, along
+    /// with its raw changed-path filter bytes (if any). Used to fold an
+    /// existing chain's commits into a writer when merging layers.
+    pub(crate) fn iter_entries_with_filters(
+        &self,
+    ) -> Vec<(ObjectId, CommitGraphEntry, Option<Vec<u8>>)> {
+        parse::iter_entries_with_filters(self)
+    }
+
+    /// Global position of `oid` across the whole chain, or `None` if it
+    /// isn't present in any layer.
+    pub(crate) fn global_position(&self, oid: &ObjectId) -> Option<u32> {
+        parse::global_position(self, oid)
+    }
+
+    /// Hex checksum of each layer file, oldest (base) first, as recorded in
+    /// its trailing checksum bytes. Used to rebuild the chain manifest when
+    /// appending a new layer on top of this graph.
+    pub(crate) fn layer_hashes(&self) -> Result<Vec<String>, RevWalkError> {
+        self.layers
+            .iter()
+            .map(|layer| {
+                if layer.data.len() < layer.hash_len {
+                    return Err(RevWalkError::InvalidCommitGraph(
+                        "file too small for checksum".into(),
+                    ));
+                }
+                let checksum = &layer.data[layer.data.len() - layer.hash_len..];
+                let algo = if layer.hash_len == 20 {
+                    git_hash::HashAlgorithm::Sha1
+                } else {
+                    git_hash::HashAlgorithm::Sha256
+                };
+                ObjectId::from_bytes(checksum, algo)
+                    .map(|oid| oid.to_hex())
+                    .map_err(|e| RevWalkError::InvalidCommitGraph(format!("bad checksum: {}", e)))
+            })
+            .collect()
     }
 
-    /// Get the OID at a given position index.
+    /// Get the OID at a given global position index.
     fn oid_at(&self, pos: u32) -> Option<ObjectId> {
         parse::oid_at_position(self, pos)
     }
 }
+
+fn verify_layer(layer: &GraphLayer) -> Result<(), RevWalkError> {
+    use sha1::Digest;
+
+    if layer.data.len() < layer.hash_len {
+        return Err(RevWalkError::InvalidCommitGraph(
+            "file too small for checksum".into(),
+        ));
+    }
+
+    let content_len = layer.data.len() - layer.hash_len;
+    let stored_checksum = &layer.data[content_len..];
+
+    let mut hasher = sha1::Sha1::new();
+    hasher.update(&layer.data[..content_len]);
+    let computed = hasher.finalize();
+
+    if computed.as_slice() != stored_checksum {
+        return Err(RevWalkError::InvalidCommitGraph(
+            "checksum mismatch".into(),
+        ));
+    }
+
+    Ok(())
+}
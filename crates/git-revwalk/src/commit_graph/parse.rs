@@ -2,13 +2,15 @@
 
 use std::path::Path;
 
+use bstr::BStr;
 use git_hash::ObjectId;
 use memmap2::Mmap;
 
 use super::*;
 
-/// Open and parse a commit-graph file.
-pub(crate) fn open_commit_graph(path: &Path) -> Result<CommitGraph, RevWalkError> {
+/// Open a single commit-graph layer file (shared by the monolithic-file and
+/// split-chain code paths).
+pub(crate) fn open_layer(path: &Path) -> Result<GraphLayer, RevWalkError> {
     let file = std::fs::File::open(path).map_err(RevWalkError::Io)?;
     let data = unsafe { Mmap::map(&file) }.map_err(RevWalkError::Io)?;
 
@@ -47,6 +49,8 @@ pub(crate) fn open_commit_graph(path: &Path) -> Result<CommitGraph, RevWalkError
 
     // Number of chunks
     let num_chunks = data[6] as usize;
+    // Number of preceding layers this file was written on top of.
+    let base_graph_count = data[7];
 
     // Parse chunk table of contents (starts at offset 8).
     // Each entry: 4-byte chunk ID + 8-byte offset.
@@ -63,6 +67,10 @@ pub(crate) fn open_commit_graph(path: &Path) -> Result<CommitGraph, RevWalkError
     let mut oid_lookup_offset: Option<usize> = None;
     let mut commit_data_offset: Option<usize> = None;
     let mut extra_edges_offset: Option<usize> = None;
+    let mut generation_data_offset: Option<usize> = None;
+    let mut generation_data_overflow_offset: Option<usize> = None;
+    let mut bloom_indexes_offset: Option<usize> = None;
+    let mut bloom_data_offset: Option<usize> = None;
 
     for i in 0..num_chunks {
         let entry_offset = toc_start + i * toc_entry_size;
@@ -88,6 +96,10 @@ pub(crate) fn open_commit_graph(path: &Path) -> Result<CommitGraph, RevWalkError
             CHUNK_OID_LOOKUP => oid_lookup_offset = Some(offset),
             CHUNK_COMMIT_DATA => commit_data_offset = Some(offset),
             CHUNK_EXTRA_EDGES => extra_edges_offset = Some(offset),
+            CHUNK_GENERATION_DATA => generation_data_offset = Some(offset),
+            CHUNK_GENERATION_DATA_OVERFLOW => generation_data_overflow_offset = Some(offset),
+            CHUNK_BLOOM_INDEXES => bloom_indexes_offset = Some(offset),
+            CHUNK_BLOOM_DATA => bloom_data_offset = Some(offset),
             _ => {} // Unknown chunks are ignored per spec.
         }
     }
@@ -116,30 +128,73 @@ pub(crate) fn open_commit_graph(path: &Path) -> Result<CommitGraph, RevWalkError
         data[fanout_last + 3],
     ]);
 
-    Ok(CommitGraph {
+    Ok(GraphLayer {
         data,
         num_commits,
+        base_graph_count,
+        oid_fanout_offset,
         oid_lookup_offset,
         commit_data_offset,
         extra_edges_offset,
+        generation_data_offset,
+        generation_data_overflow_offset,
+        bloom_indexes_offset,
+        bloom_data_offset,
         hash_len,
     })
 }
 
+/// Open a split commit-graph chain, reading the `commit-graph-chain`
+/// manifest (one layer hash per line, oldest/base first) and mapping each
+/// `graph-{hash}.graph` file in turn.
+pub(crate) fn open_chain(chain_dir: &Path) -> Result<CommitGraph, RevWalkError> {
+    let chain_file = chain_dir.join(CHAIN_FILE_NAME);
+    let content = std::fs::read_to_string(&chain_file).map_err(RevWalkError::Io)?;
+
+    let mut layers = Vec::new();
+    let mut layer_base_pos = Vec::new();
+    let mut next_pos = 0u32;
+
+    for line in content.lines() {
+        let hash = line.trim();
+        if hash.is_empty() {
+            continue;
+        }
+        let graph_path = chain_dir.join(format!("graph-{}.graph", hash));
+        let layer = open_layer(&graph_path)?;
+        layer_base_pos.push(next_pos);
+        next_pos += layer.num_commits;
+        layers.push(layer);
+    }
+
+    if layers.is_empty() {
+        return Err(RevWalkError::InvalidCommitGraph(
+            "empty commit-graph chain".into(),
+        ));
+    }
+
+    Ok(CommitGraph { layers, layer_base_pos })
+}
+
 /// Look up a commit in the graph by OID using binary search.
 pub(crate) fn lookup_commit(graph: &CommitGraph, oid: &ObjectId) -> Option<CommitGraphEntry> {
-    let pos = find_oid_position(graph, oid)?;
+    let pos = global_position(graph, oid)?;
     read_commit_data(graph, pos)
 }
 
-/// Get the OID at a given position index.
+/// Get the OID at a given global position index.
 pub(crate) fn oid_at_position(graph: &CommitGraph, pos: u32) -> Option<ObjectId> {
-    let offset = graph.oid_lookup_offset + (pos as usize) * graph.hash_len;
-    if offset + graph.hash_len > graph.data.len() {
+    let (layer, local) = layer_for_global_pos(graph, pos)?;
+    oid_at_in_layer(layer, local)
+}
+
+fn oid_at_in_layer(layer: &GraphLayer, pos: u32) -> Option<ObjectId> {
+    let offset = layer.oid_lookup_offset + (pos as usize) * layer.hash_len;
+    if offset + layer.hash_len > layer.data.len() {
         return None;
     }
-    let bytes = &graph.data[offset..offset + graph.hash_len];
-    let algo = if graph.hash_len == 20 {
+    let bytes = &layer.data[offset..offset + layer.hash_len];
+    let algo = if layer.hash_len == 20 {
         git_hash::HashAlgorithm::Sha1
     } else {
         git_hash::HashAlgorithm::Sha256
@@ -147,57 +202,319 @@ pub(crate) fn oid_at_position(graph: &CommitGraph, pos: u32) -> Option<ObjectId>
     ObjectId::from_bytes(bytes, algo).ok()
 }
 
-/// Binary search for an OID in the lookup table.
-fn find_oid_position(graph: &CommitGraph, oid: &ObjectId) -> Option<u32> {
-    let hash_bytes = oid.as_bytes();
-    let hash_len = graph.hash_len;
+/// Find which layer a global position falls into, returning the layer and
+/// its position local to that layer.
+fn layer_for_global_pos(graph: &CommitGraph, pos: u32) -> Option<(&GraphLayer, u32)> {
+    for (i, base) in graph.layer_base_pos.iter().enumerate() {
+        let layer = &graph.layers[i];
+        if pos >= *base && pos < base + layer.num_commits {
+            return Some((layer, pos - base));
+        }
+    }
+    None
+}
+
+/// Binary search for an OID across every layer, returning its global
+/// position if found. Layers are searched newest-first, since a commit can
+/// only live in one layer and the newest layer is the one most recently
+/// written to (and so the most likely to be queried next).
+pub(crate) fn global_position(graph: &CommitGraph, oid: &ObjectId) -> Option<u32> {
+    for i in (0..graph.layers.len()).rev() {
+        if let Some(local) = find_oid_position_in_layer(&graph.layers[i], oid) {
+            return Some(graph.layer_base_pos[i] + local);
+        }
+    }
+    None
+}
 
-    // Use first byte for fanout narrowing.
-    let _first_byte = hash_bytes[0] as usize;
+/// Binary search for an OID within a single layer's lookup table.
+fn find_oid_position_in_layer(layer: &GraphLayer, oid: &ObjectId) -> Option<u32> {
+    locate_in_layer(layer, oid).0
+}
 
-    // Read fanout bounds.
-    // The fanout table is at oid_lookup_offset - 256*4 (actually it's a separate chunk).
-    // We need to find the fanout offset. Since we stored oid_lookup and commit_data,
-    // the fanout is at oid_lookup_offset - (num_commits * hash_len would be after lookup...)
-    // Actually, the fanout table is a separate chunk. We need its offset too.
-    // For now, we do a linear scan of the OID lookup table.
-    // TODO: Use fanout for O(log n) binary search.
+/// Binary search `oid` in a single layer's lookup table. Returns
+/// `(Some(index), index)` if found, or `(None, insertion_point)` if not,
+/// where `insertion_point` is where `oid` would need to go to keep the
+/// table sorted.
+fn locate_in_layer(layer: &GraphLayer, oid: &ObjectId) -> (Option<u32>, u32) {
+    let hash_bytes = oid.as_bytes();
+    let hash_len = layer.hash_len;
 
     let mut lo: u32 = 0;
-    let mut hi: u32 = graph.num_commits;
+    let mut hi: u32 = layer.num_commits;
 
     while lo < hi {
         let mid = lo + (hi - lo) / 2;
-        let offset = graph.oid_lookup_offset + (mid as usize) * hash_len;
-        if offset + hash_len > graph.data.len() {
-            return None;
+        let offset = layer.oid_lookup_offset + (mid as usize) * hash_len;
+        if offset + hash_len > layer.data.len() {
+            return (None, lo);
         }
-        let entry_bytes = &graph.data[offset..offset + hash_len];
+        let entry_bytes = &layer.data[offset..offset + hash_len];
 
         match entry_bytes.cmp(hash_bytes) {
-            std::cmp::Ordering::Equal => return Some(mid),
+            std::cmp::Ordering::Equal => return (Some(mid), mid),
             std::cmp::Ordering::Less => lo = mid + 1,
             std::cmp::Ordering::Greater => hi = mid,
         }
     }
 
-    None
+    (None, lo)
 }
 
-/// Read commit data at the given position.
+/// The OID immediately before and after `oid` in the graph's combined
+/// sorted order (across every layer), excluding `oid` itself.
+fn neighbors(graph: &CommitGraph, oid: &ObjectId) -> (Option<ObjectId>, Option<ObjectId>) {
+    let mut pred: Option<ObjectId> = None;
+    let mut succ: Option<ObjectId> = None;
+
+    for layer in &graph.layers {
+        let (found, insertion) = locate_in_layer(layer, oid);
+
+        let pred_idx = match found {
+            Some(idx) if idx > 0 => Some(idx - 1),
+            None if insertion > 0 => Some(insertion - 1),
+            _ => None,
+        };
+        if let Some(idx) = pred_idx {
+            if let Some(candidate) = oid_at_in_layer(layer, idx) {
+                if pred.as_ref().map_or(true, |p| candidate.as_bytes() > p.as_bytes()) {
+                    pred = Some(candidate);
+                }
+            }
+        }
+
+        let succ_idx = match found {
+            Some(idx) => idx + 1,
+            None => insertion,
+        };
+        if succ_idx < layer.num_commits {
+            if let Some(candidate) = oid_at_in_layer(layer, succ_idx) {
+                if succ.as_ref().map_or(true, |s| candidate.as_bytes() < s.as_bytes()) {
+                    succ = Some(candidate);
+                }
+            }
+        }
+    }
+
+    (pred, succ)
+}
+
+/// Number of matching hex nibbles at the start of `a` and `b`.
+fn common_prefix_nibbles(a: &[u8], b: &[u8]) -> usize {
+    let mut nibbles = 0;
+    for (&x, &y) in a.iter().zip(b.iter()) {
+        if x == y {
+            nibbles += 2;
+        } else {
+            if x >> 4 == y >> 4 {
+                nibbles += 1;
+            }
+            break;
+        }
+    }
+    nibbles
+}
+
+/// See [`CommitGraph::shortest_prefix_len`].
+pub(crate) fn shortest_prefix_len(graph: &CommitGraph, oid: &ObjectId) -> usize {
+    let bytes = oid.as_bytes();
+    let (pred, succ) = neighbors(graph, oid);
+    let from_pred = pred.as_ref().map_or(0, |p| common_prefix_nibbles(bytes, p.as_bytes()));
+    let from_succ = succ.as_ref().map_or(0, |s| common_prefix_nibbles(bytes, s.as_bytes()));
+    (from_pred.max(from_succ) + 1).min(bytes.len() * 2)
+}
+
+/// Decode a hex prefix into the bytes it occupies, padding a trailing odd
+/// nibble with a zero low nibble (so the result sorts as a lower bound).
+fn hex_prefix_lower_bytes(prefix: &str) -> Vec<u8> {
+    let nibbles: Vec<u8> = prefix
+        .bytes()
+        .map(|b| match b {
+            b'0'..=b'9' => b - b'0',
+            b'a'..=b'f' => b - b'a' + 10,
+            b'A'..=b'F' => b - b'A' + 10,
+            _ => 0,
+        })
+        .collect();
+
+    let mut bytes = Vec::with_capacity(nibbles.len().div_ceil(2));
+    for chunk in nibbles.chunks(2) {
+        if chunk.len() == 2 {
+            bytes.push((chunk[0] << 4) | chunk[1]);
+        } else {
+            bytes.push(chunk[0] << 4);
+        }
+    }
+    bytes
+}
+
+/// The first local index in `layer` whose OID is `>= prefix_bytes`.
+fn lower_bound_in_layer(layer: &GraphLayer, prefix_bytes: &[u8]) -> u32 {
+    let mut lo: u32 = 0;
+    let mut hi: u32 = layer.num_commits;
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let offset = layer.oid_lookup_offset + (mid as usize) * layer.hash_len;
+        if offset + layer.hash_len > layer.data.len() {
+            hi = mid;
+            continue;
+        }
+        let entry = &layer.data[offset..offset + layer.hash_len];
+        let cmp_len = prefix_bytes.len().min(entry.len());
+        if entry[..cmp_len] < prefix_bytes[..cmp_len] {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo
+}
+
+/// See [`CommitGraph::resolve_prefix`].
+pub(crate) fn resolve_prefix(graph: &CommitGraph, prefix: &str) -> super::PrefixResult {
+    use super::PrefixResult;
+
+    if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return PrefixResult::NotFound;
+    }
+
+    let prefix_bytes = hex_prefix_lower_bytes(prefix);
+    let mut matches: Vec<ObjectId> = Vec::new();
+
+    for layer in &graph.layers {
+        let mut idx = lower_bound_in_layer(layer, &prefix_bytes);
+        while idx < layer.num_commits {
+            let Some(candidate) = oid_at_in_layer(layer, idx) else {
+                break;
+            };
+            if !candidate.starts_with_hex(prefix) {
+                break;
+            }
+            matches.push(candidate);
+            idx += 1;
+            if matches.len() > 1 {
+                // Already ambiguous; no need to keep scanning.
+                return PrefixResult::Ambiguous;
+            }
+        }
+    }
+
+    match matches.len() {
+        0 => PrefixResult::NotFound,
+        1 => PrefixResult::Unique(matches[0]),
+        _ => PrefixResult::Ambiguous,
+    }
+}
+
+/// Read a commit's corrected commit date from the GDA2/GDO2 chunks.
+///
+/// Returns `None` if the commit isn't in the graph, or the graph has no
+/// generation data (written before corrected dates were introduced).
+pub(crate) fn corrected_commit_date(graph: &CommitGraph, oid: &ObjectId) -> Option<i64> {
+    let pos = global_position(graph, oid)?;
+    let (layer, local) = layer_for_global_pos(graph, pos)?;
+    let generation_data_offset = layer.generation_data_offset?;
+    let entry = read_commit_data(graph, pos)?;
+
+    let offset_slot = generation_data_offset + (local as usize) * 4;
+    if offset_slot + 4 > layer.data.len() {
+        return None;
+    }
+    let raw = u32::from_be_bytes([
+        layer.data[offset_slot],
+        layer.data[offset_slot + 1],
+        layer.data[offset_slot + 2],
+        layer.data[offset_slot + 3],
+    ]);
+
+    const OVERFLOW_BIT: u32 = 0x8000_0000;
+    if raw & OVERFLOW_BIT == 0 {
+        Some(entry.commit_time + raw as i64)
+    } else {
+        let overflow_offset = layer.generation_data_overflow_offset?;
+        let idx = (raw & !OVERFLOW_BIT) as usize;
+        let slot = overflow_offset + idx * 8;
+        if slot + 8 > layer.data.len() {
+            return None;
+        }
+        let bytes: [u8; 8] = layer.data[slot..slot + 8].try_into().ok()?;
+        Some(u64::from_be_bytes(bytes) as i64)
+    }
+}
+
+/// Look up the Bloom filter bytes for the commit at `local` position within
+/// `layer`, if BIDX/BDAT are present.
+fn bloom_filter_bytes(layer: &GraphLayer, local: u32) -> Option<&[u8]> {
+    let bloom_indexes_offset = layer.bloom_indexes_offset?;
+    let bloom_data_offset = layer.bloom_data_offset?;
+
+    let read_cumulative_end = |idx: u32| -> Option<usize> {
+        let slot = bloom_indexes_offset + (idx as usize) * 4;
+        if slot + 4 > layer.data.len() {
+            return None;
+        }
+        Some(u32::from_be_bytes([
+            layer.data[slot],
+            layer.data[slot + 1],
+            layer.data[slot + 2],
+            layer.data[slot + 3],
+        ]) as usize)
+    };
+
+    let end = read_cumulative_end(local)?;
+    let start = if local == 0 { 0 } else { read_cumulative_end(local - 1)? };
+
+    let data_region = bloom_data_offset + BLOOM_DATA_HEADER_SIZE;
+    let (lo, hi) = (data_region + start, data_region + end);
+    if hi > layer.data.len() || lo > hi {
+        return None;
+    }
+    Some(&layer.data[lo..hi])
+}
+
+/// Test whether `oid` may have touched `path`, via its changed-path Bloom
+/// filter. See [`CommitGraph::maybe_changed_path`] for the return semantics.
+pub(crate) fn changed_path_maybe(graph: &CommitGraph, oid: &ObjectId, path: &BStr) -> Option<bool> {
+    let pos = global_position(graph, oid)?;
+    let (layer, local) = layer_for_global_pos(graph, pos)?;
+    let bytes = bloom_filter_bytes(layer, local)?;
+    Some(bloom::maybe_contains(bytes, path))
+}
+
+/// Raw changed-path filter bytes for `oid`, carried forward verbatim when
+/// folding an existing layer's commits into a new writer (see
+/// [`CommitGraph::iter_entries_with_filters`]).
+fn raw_changed_path_filter(layer: &GraphLayer, local: u32) -> Option<Vec<u8>> {
+    bloom_filter_bytes(layer, local).map(|b| b.to_vec())
+}
+
+/// Read commit data at the given global position.
 fn read_commit_data(graph: &CommitGraph, pos: u32) -> Option<CommitGraphEntry> {
-    let hash_len = graph.hash_len;
+    let (layer, local) = layer_for_global_pos(graph, pos)?;
+    read_commit_data_in_layer(graph, layer, local)
+}
+
+/// Parse one commit's data out of `layer` at its local position, resolving
+/// parent indices (which are global across the whole chain) via `graph`.
+fn read_commit_data_in_layer(
+    graph: &CommitGraph,
+    layer: &GraphLayer,
+    local: u32,
+) -> Option<CommitGraphEntry> {
+    let hash_len = layer.hash_len;
     // Each commit data entry is: hash_len (tree OID) + 4 (parent1) + 4 (parent2)
     //   + 4 (generation + top bits of date) + 4 (bottom 32 bits of date)
     let entry_size = hash_len + 16;
-    let offset = graph.commit_data_offset + (pos as usize) * entry_size;
+    let offset = layer.commit_data_offset + (local as usize) * entry_size;
 
-    if offset + entry_size > graph.data.len() {
+    if offset + entry_size > layer.data.len() {
         return None;
     }
 
     // Tree OID
-    let tree_bytes = &graph.data[offset..offset + hash_len];
+    let tree_bytes = &layer.data[offset..offset + hash_len];
     let algo = if hash_len == 20 {
         git_hash::HashAlgorithm::Sha1
     } else {
@@ -205,40 +522,40 @@ fn read_commit_data(graph: &CommitGraph, pos: u32) -> Option<CommitGraphEntry> {
     };
     let tree_oid = ObjectId::from_bytes(tree_bytes, algo).ok()?;
 
-    // Parent 1 (4 bytes, big-endian index or PARENT_NONE)
+    // Parent 1 (4 bytes, big-endian global position or PARENT_NONE)
     let p1_offset = offset + hash_len;
     let parent1 = u32::from_be_bytes([
-        graph.data[p1_offset],
-        graph.data[p1_offset + 1],
-        graph.data[p1_offset + 2],
-        graph.data[p1_offset + 3],
+        layer.data[p1_offset],
+        layer.data[p1_offset + 1],
+        layer.data[p1_offset + 2],
+        layer.data[p1_offset + 3],
     ]);
 
     // Parent 2 (4 bytes)
     let p2_offset = p1_offset + 4;
     let parent2 = u32::from_be_bytes([
-        graph.data[p2_offset],
-        graph.data[p2_offset + 1],
-        graph.data[p2_offset + 2],
-        graph.data[p2_offset + 3],
+        layer.data[p2_offset],
+        layer.data[p2_offset + 1],
+        layer.data[p2_offset + 2],
+        layer.data[p2_offset + 3],
     ]);
 
     // Generation + date top bits (4 bytes)
     let gen_date_offset = p2_offset + 4;
     let gen_date = u32::from_be_bytes([
-        graph.data[gen_date_offset],
-        graph.data[gen_date_offset + 1],
-        graph.data[gen_date_offset + 2],
-        graph.data[gen_date_offset + 3],
+        layer.data[gen_date_offset],
+        layer.data[gen_date_offset + 1],
+        layer.data[gen_date_offset + 2],
+        layer.data[gen_date_offset + 3],
     ]);
 
     // Date bottom 32 bits
     let date_low_offset = gen_date_offset + 4;
     let date_low = u32::from_be_bytes([
-        graph.data[date_low_offset],
-        graph.data[date_low_offset + 1],
-        graph.data[date_low_offset + 2],
-        graph.data[date_low_offset + 3],
+        layer.data[date_low_offset],
+        layer.data[date_low_offset + 1],
+        layer.data[date_low_offset + 2],
+        layer.data[date_low_offset + 3],
     ]);
 
     // Generation number is top 30 bits of gen_date.
@@ -248,10 +565,9 @@ fn read_commit_data(graph: &CommitGraph, pos: u32) -> Option<CommitGraphEntry> {
     let date_high = ((gen_date & 0x3) as u64) << 32;
     let commit_time = (date_high | date_low as u64) as i64;
 
-    // Resolve parent OIDs.
-    const PARENT_NONE: u32 = 0x7000_0000;
-    const PARENT_EXTRA_EDGE: u32 = 0x8000_0000;
-
+    // Resolve parent OIDs. Parent indices are global positions across the
+    // whole chain, so `graph.oid_at` transparently falls through to a lower
+    // layer when a parent lives there.
     let mut parent_oids = Vec::new();
 
     if parent1 != PARENT_NONE {
@@ -262,20 +578,20 @@ fn read_commit_data(graph: &CommitGraph, pos: u32) -> Option<CommitGraphEntry> {
 
     if parent2 != PARENT_NONE {
         if parent2 & PARENT_EXTRA_EDGE != 0 {
-            // Octopus merge: follow the extra edge list.
+            // Octopus merge: follow the extra edge list (layer-local).
             let extra_idx = (parent2 & !PARENT_EXTRA_EDGE) as usize;
-            if let Some(extra_offset) = graph.extra_edges_offset {
+            if let Some(extra_offset) = layer.extra_edges_offset {
                 let mut idx = extra_idx;
                 loop {
                     let edge_offset = extra_offset + idx * 4;
-                    if edge_offset + 4 > graph.data.len() {
+                    if edge_offset + 4 > layer.data.len() {
                         break;
                     }
                     let edge_val = u32::from_be_bytes([
-                        graph.data[edge_offset],
-                        graph.data[edge_offset + 1],
-                        graph.data[edge_offset + 2],
-                        graph.data[edge_offset + 3],
+                        layer.data[edge_offset],
+                        layer.data[edge_offset + 1],
+                        layer.data[edge_offset + 2],
+                        layer.data[edge_offset + 3],
                     ]);
                     let is_last = edge_val & 0x8000_0000 != 0;
                     let parent_idx = edge_val & 0x7FFF_FFFF;
@@ -300,3 +616,20 @@ fn read_commit_data(graph: &CommitGraph, pos: u32) -> Option<CommitGraphEntry> {
         commit_time,
     })
 }
+
+/// Every commit across every layer, along with its raw changed-path filter
+/// bytes (if any). See [`CommitGraph::iter_entries_with_filters`].
+pub(crate) fn iter_entries_with_filters(
+    graph: &CommitGraph,
+) -> Vec<(ObjectId, CommitGraphEntry, Option<Vec<u8>>)> {
+    let mut out = Vec::with_capacity(graph.num_commits() as usize);
+    for layer in &graph.layers {
+        for local in 0..layer.num_commits {
+            let Some(oid) = oid_at_in_layer(layer, local) else { continue };
+            let Some(entry) = read_commit_data_in_layer(graph, layer, local) else { continue };
+            let filter = raw_changed_path_filter(layer, local);
+            out.push((oid, entry, filter));
+        }
+    }
+    out
+}
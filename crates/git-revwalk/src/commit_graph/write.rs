@@ -1,16 +1,33 @@
 //! Commit-graph file writer.
 //!
-//! Generates commit-graph files matching Git's `commit-graph-format.txt` specification.
+//! Generates commit-graph files matching Git's `commit-graph-format.txt` specification,
+//! either as a single monolithic file or as a layer appended to a split chain
+//! (see [`CommitGraphWriter::append_layer`]).
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::io::Write;
 use std::path::Path;
 
+use bstr::{BString, ByteSlice};
 use git_hash::{HashAlgorithm, ObjectId};
 use sha1::Digest;
 
+use super::{bloom, CommitGraph, CHAIN_FILE_NAME, PARENT_EXTRA_EDGE, PARENT_NONE, BLOOM_DATA_HEADER_SIZE};
 use crate::RevWalkError;
 
+/// A commit's changed-path Bloom filter, in whichever form it's known.
+enum FilterSource {
+    /// No path information: write the "too many changes" sentinel, which is
+    /// always safe since it never incorrectly prunes a commit.
+    Sentinel,
+    /// Paths changed relative to the first parent; the filter is built from
+    /// these (plus their leading directories) at write time.
+    Paths(Vec<BString>),
+    /// Filter bytes carried forward verbatim from an existing layer, e.g.
+    /// when folding a chain's commits into a merged writer.
+    Raw(Vec<u8>),
+}
+
 /// Internal representation of a commit for graph writing.
 struct CommitEntry {
     oid: ObjectId,
@@ -18,8 +35,21 @@ struct CommitEntry {
     parent_oids: Vec<ObjectId>,
     generation: u32,
     commit_time: i64,
+    /// `max(commit_time, 1 + max(corrected_commit_date(parent)))`. Used to
+    /// populate the GDA2/GDO2 chunks so readers can prune a date-ordered
+    /// walk without touching the ODB.
+    corrected_commit_date: i64,
+    filter: FilterSource,
 }
 
+/// By how many layers the chain can grow before a write collapses it back
+/// down to a single base layer.
+const MAX_CHAIN_LENGTH: usize = 12;
+/// A new layer triggers a merge instead of being appended on its own when it
+/// isn't at least this much smaller than the existing chain's commit count
+/// (mirrors C git's default `--size-multiple=2` merge heuristic).
+const MERGE_SIZE_FACTOR: u32 = 2;
+
 /// Writer for commit-graph files in Git's binary format.
 pub struct CommitGraphWriter {
     commits: Vec<CommitEntry>,
@@ -47,14 +77,145 @@ impl CommitGraphWriter {
             oid,
             tree_oid,
             parent_oids: parents,
-            generation: 0, // computed later
+            generation: 0,           // computed later
             commit_time,
+            corrected_commit_date: 0, // computed later
+            filter: FilterSource::Sentinel,
         });
     }
 
-    /// Compute generation numbers and write the graph file.
+    /// Add a commit along with the paths it changed relative to its first
+    /// parent (or, for a root commit, relative to the empty tree). Populates
+    /// the commit's changed-path Bloom filter so path-limited walks can skip
+    /// it without a real diff.
+    pub fn add_commit_with_changed_paths(
+        &mut self,
+        oid: ObjectId,
+        tree_oid: ObjectId,
+        parents: Vec<ObjectId>,
+        commit_time: i64,
+        changed_paths: Vec<BString>,
+    ) {
+        self.commits.push(CommitEntry {
+            oid,
+            tree_oid,
+            parent_oids: parents,
+            generation: 0,            // computed later
+            commit_time,
+            corrected_commit_date: 0, // computed later
+            filter: FilterSource::Paths(changed_paths),
+        });
+    }
+
+    /// Compute generation numbers and write the graph file as a single
+    /// monolithic file (no chain, no base layers).
     /// Returns the checksum of the written file.
-    pub fn write(mut self, path: impl AsRef<Path>) -> Result<ObjectId, RevWalkError> {
+    pub fn write(self, path: impl AsRef<Path>) -> Result<ObjectId, RevWalkError> {
+        self.write_layer(path, 0, None)
+    }
+
+    /// Write this writer's commits as a new layer appended to the split
+    /// commit-graph chain under `chain_dir` (`objects/info/commit-graphs`),
+    /// creating the chain if it doesn't exist yet. Commits already present
+    /// in `base` are skipped, since they're already covered by an earlier
+    /// layer.
+    ///
+    /// When the chain would grow past [`MAX_CHAIN_LENGTH`] layers, or the new
+    /// layer wouldn't be meaningfully smaller than the chain it's building on
+    /// (see [`MERGE_SIZE_FACTOR`]), this instead merges every existing layer
+    /// and the new commits down into a single monolithic
+    /// `objects/info/commit-graph` file and removes the chain directory —
+    /// matching `git commit-graph write --split` without
+    /// `--split=no-merge`.
+    pub fn append_layer(
+        mut self,
+        chain_dir: impl AsRef<Path>,
+        base: Option<&CommitGraph>,
+    ) -> Result<(), RevWalkError> {
+        let chain_dir = chain_dir.as_ref();
+
+        if let Some(base) = base {
+            self.commits.retain(|c| !base.contains(&c.oid));
+        }
+        if self.commits.is_empty() {
+            return Ok(());
+        }
+
+        let base_commit_count = base.map(|b| b.num_commits()).unwrap_or(0);
+        let should_merge = match base {
+            Some(base) => {
+                base.layer_count() >= MAX_CHAIN_LENGTH
+                    || self.commits.len() as u32 * MERGE_SIZE_FACTOR >= base_commit_count.max(1)
+            }
+            None => false,
+        };
+
+        if should_merge {
+            if let Some(base) = base {
+                self.absorb(base);
+            }
+            let monolithic_path = chain_dir
+                .parent()
+                .ok_or_else(|| RevWalkError::InvalidCommitGraph("invalid chain directory".into()))?
+                .join("commit-graph");
+            self.write(monolithic_path)?;
+            if chain_dir.is_dir() {
+                std::fs::remove_dir_all(chain_dir).map_err(RevWalkError::Io)?;
+            }
+            return Ok(());
+        }
+
+        let base_graph_count = base.map(|b| b.layer_count()).unwrap_or(0) as u8;
+        std::fs::create_dir_all(chain_dir).map_err(RevWalkError::Io)?;
+        let layer_path = chain_dir.join("graph-incoming.graph");
+        let checksum = self.write_layer(&layer_path, base_graph_count, base)?;
+        let hash_hex = checksum.to_hex();
+        let final_path = chain_dir.join(format!("graph-{}.graph", hash_hex));
+        std::fs::rename(&layer_path, &final_path).map_err(RevWalkError::Io)?;
+
+        let mut chain_hashes: Vec<String> = match base {
+            Some(base) => base.layer_hashes()?,
+            None => Vec::new(),
+        };
+        chain_hashes.push(hash_hex);
+        let chain_file = chain_dir.join(CHAIN_FILE_NAME);
+        std::fs::write(&chain_file, chain_hashes.join("\n") + "\n").map_err(RevWalkError::Io)?;
+
+        Ok(())
+    }
+
+    /// Fold every commit from an existing graph (or chain) into this
+    /// writer's pending commit list, carrying each commit's raw
+    /// changed-path filter bytes forward verbatim. Used when merging layers
+    /// back down to one.
+    fn absorb(&mut self, base: &CommitGraph) {
+        for (oid, entry, filter) in base.iter_entries_with_filters() {
+            self.commits.push(CommitEntry {
+                oid,
+                tree_oid: entry.tree_oid,
+                parent_oids: entry.parent_oids,
+                generation: 0,
+                commit_time: entry.commit_time,
+                corrected_commit_date: 0,
+                filter: match filter {
+                    Some(bytes) => FilterSource::Raw(bytes),
+                    None => FilterSource::Sentinel,
+                },
+            });
+        }
+    }
+
+    /// Compute generation numbers and write this writer's pending commits as
+    /// one layer file. `base_graph_count` is stored in the header; `base`,
+    /// when present, resolves parents that live in an earlier layer (both
+    /// for generation-number/corrected-date seeding and for global parent
+    /// indices).
+    fn write_layer(
+        mut self,
+        path: impl AsRef<Path>,
+        base_graph_count: u8,
+        base: Option<&CommitGraph>,
+    ) -> Result<ObjectId, RevWalkError> {
         if self.commits.is_empty() {
             return Err(RevWalkError::InvalidCommitGraph(
                 "no commits to write".into(),
@@ -64,7 +225,9 @@ impl CommitGraphWriter {
         // Sort commits by OID (required by format).
         self.commits.sort_by(|a, b| a.oid.as_bytes().cmp(b.oid.as_bytes()));
 
-        // Build OID → index mapping.
+        let base_commit_count = base.map(|b| b.num_commits()).unwrap_or(0);
+
+        // Build OID → local index mapping for this layer only.
         let oid_to_idx: HashMap<ObjectId, u32> = self
             .commits
             .iter()
@@ -72,8 +235,18 @@ impl CommitGraphWriter {
             .map(|(i, c)| (c.oid, i as u32))
             .collect();
 
-        // Compute generation numbers.
-        self.compute_generations(&oid_to_idx);
+        // Resolve a parent OID to its *global* graph position: either in
+        // this layer (local index offset by the base layers' commit count),
+        // or falling through to an earlier layer via `base`.
+        let resolve_parent = |oid: &ObjectId| -> u32 {
+            if let Some(&local) = oid_to_idx.get(oid) {
+                return base_commit_count + local;
+            }
+            base.and_then(|b| b.global_position(oid)).unwrap_or(PARENT_NONE)
+        };
+
+        // Compute generation numbers and corrected commit dates.
+        self.compute_generations_and_dates(&oid_to_idx, base);
 
         let hash_len = match self.hash_algo {
             HashAlgorithm::Sha1 => 20usize,
@@ -91,12 +264,8 @@ impl CommitGraphWriter {
             for (idx, commit) in self.commits.iter().enumerate() {
                 if commit.parent_oids.len() > 2 {
                     extra_edge_offsets.insert(idx as u32, extra_edges.len());
-                    // Store parent indices for parents 2+ (parent 0 is in the data, parent 1 triggers the extra edge)
                     for (p_idx, parent) in commit.parent_oids.iter().enumerate().skip(1) {
-                        if p_idx == 1 {
-                            continue; // parent 1 slot points to extra edge list
-                        }
-                        let parent_graph_idx = oid_to_idx.get(parent).copied().unwrap_or(0x7000_0000);
+                        let parent_graph_idx = resolve_parent(parent);
                         let is_last = p_idx == commit.parent_oids.len() - 1;
                         let val = if is_last {
                             parent_graph_idx | 0x8000_0000
@@ -109,8 +278,53 @@ impl CommitGraphWriter {
             }
         }
 
+        // Compute the per-commit generation-data offsets (cdate - commit_time),
+        // spilling entries that don't fit in 31 bits into the overflow chunk.
+        const OVERFLOW_BIT: u32 = 0x8000_0000;
+        let mut generation_data: Vec<u32> = Vec::with_capacity(self.commits.len());
+        let mut generation_data_overflow: Vec<i64> = Vec::new();
+        for commit in &self.commits {
+            let offset = commit.corrected_commit_date - commit.commit_time;
+            if offset >= 0 && offset <= i64::from(i32::MAX) {
+                generation_data.push(offset as u32);
+            } else {
+                let overflow_idx = generation_data_overflow.len() as u32;
+                generation_data_overflow.push(commit.corrected_commit_date);
+                generation_data.push(OVERFLOW_BIT | overflow_idx);
+            }
+        }
+        let has_generation_overflow = !generation_data_overflow.is_empty();
+
+        // Build each commit's changed-path Bloom filter and the BIDX
+        // cumulative-offset table into BDAT's filter-data region.
+        let filters: Vec<Vec<u8>> = self
+            .commits
+            .iter()
+            .map(|commit| match &commit.filter {
+                FilterSource::Sentinel => bloom::sentinel_filter(),
+                FilterSource::Raw(bytes) => bytes.clone(),
+                FilterSource::Paths(paths) => {
+                    let expanded: BTreeSet<BString> = paths
+                        .iter()
+                        .flat_map(|p| bloom::path_and_parents(p.as_bstr()))
+                        .collect();
+                    bloom::build_filter(&expanded)
+                }
+            })
+            .collect();
+        let mut bloom_indexes: Vec<u32> = Vec::with_capacity(filters.len());
+        let mut bloom_data: Vec<u8> = Vec::new();
+        for filter in &filters {
+            bloom_data.extend_from_slice(filter);
+            bloom_indexes.push(bloom_data.len() as u32);
+        }
+
         let num_commits = self.commits.len() as u32;
-        let num_chunks: u8 = if has_extra_edges { 4 } else { 3 };
+        let num_chunks: u8 = 3
+            + u8::from(has_extra_edges)
+            + 1 // GDA2
+            + u8::from(has_generation_overflow)
+            + 2; // BIDX, BDAT
 
         // Compute chunk sizes.
         let fanout_size: usize = 256 * 4;
@@ -118,6 +332,10 @@ impl CommitGraphWriter {
         let commit_data_entry_size: usize = hash_len + 16; // tree_oid + parent1 + parent2 + gen/date
         let commit_data_size: usize = num_commits as usize * commit_data_entry_size;
         let extra_edges_size: usize = extra_edges.len() * 4;
+        let generation_data_size: usize = generation_data.len() * 4;
+        let generation_data_overflow_size: usize = generation_data_overflow.len() * 8;
+        let bloom_indexes_size: usize = bloom_indexes.len() * 4;
+        let bloom_data_size: usize = BLOOM_DATA_HEADER_SIZE + bloom_data.len();
 
         // Header: signature(4) + version(1) + hash_version(1) + num_chunks(1) + base_graph_count(1) = 8
         let header_size: usize = 8;
@@ -130,10 +348,18 @@ impl CommitGraphWriter {
         let oid_lookup_offset = fanout_offset + fanout_size;
         let commit_data_offset = oid_lookup_offset + oid_lookup_size;
         let extra_edges_offset = commit_data_offset + commit_data_size;
-        let file_end = if has_extra_edges {
+        let bloom_indexes_offset = if has_extra_edges {
             extra_edges_offset + extra_edges_size
         } else {
-            commit_data_offset + commit_data_size
+            extra_edges_offset
+        };
+        let bloom_data_offset = bloom_indexes_offset + bloom_indexes_size;
+        let generation_data_offset = bloom_data_offset + bloom_data_size;
+        let generation_data_overflow_offset = generation_data_offset + generation_data_size;
+        let file_end = if has_generation_overflow {
+            generation_data_overflow_offset + generation_data_overflow_size
+        } else {
+            generation_data_overflow_offset
         };
 
         let mut buf: Vec<u8> = Vec::with_capacity(file_end + hash_len);
@@ -146,7 +372,7 @@ impl CommitGraphWriter {
             HashAlgorithm::Sha256 => 2,
         });
         buf.push(num_chunks);
-        buf.push(0); // base graph count (no chain support)
+        buf.push(base_graph_count);
 
         // Write chunk TOC.
         // Entry: chunk_id(4) + offset(8)
@@ -156,6 +382,12 @@ impl CommitGraphWriter {
         if has_extra_edges {
             write_toc_entry(&mut buf, 0x45444745, extra_edges_offset as u64); // EDGE
         }
+        write_toc_entry(&mut buf, 0x42494458, bloom_indexes_offset as u64); // BIDX
+        write_toc_entry(&mut buf, 0x42444154, bloom_data_offset as u64); // BDAT
+        write_toc_entry(&mut buf, 0x47444132, generation_data_offset as u64); // GDA2
+        if has_generation_overflow {
+            write_toc_entry(&mut buf, 0x47444F32, generation_data_overflow_offset as u64); // GDO2
+        }
         // Terminating TOC entry: zero ID + file_end offset
         write_toc_entry(&mut buf, 0x0000_0000, file_end as u64);
 
@@ -177,9 +409,6 @@ impl CommitGraphWriter {
         }
 
         // Write Commit Data.
-        const PARENT_NONE: u32 = 0x7000_0000;
-        const PARENT_EXTRA_EDGE: u32 = 0x8000_0000;
-
         for (idx, commit) in self.commits.iter().enumerate() {
             // Tree OID
             buf.extend_from_slice(commit.tree_oid.as_bytes());
@@ -188,10 +417,7 @@ impl CommitGraphWriter {
             let parent1 = if commit.parent_oids.is_empty() {
                 PARENT_NONE
             } else {
-                oid_to_idx
-                    .get(&commit.parent_oids[0])
-                    .copied()
-                    .unwrap_or(PARENT_NONE)
+                resolve_parent(&commit.parent_oids[0])
             };
             buf.extend_from_slice(&parent1.to_be_bytes());
 
@@ -199,19 +425,13 @@ impl CommitGraphWriter {
             let parent2 = if commit.parent_oids.len() <= 1 {
                 PARENT_NONE
             } else if commit.parent_oids.len() == 2 {
-                oid_to_idx
-                    .get(&commit.parent_oids[1])
-                    .copied()
-                    .unwrap_or(PARENT_NONE)
+                resolve_parent(&commit.parent_oids[1])
             } else {
                 // Octopus merge: point to extra edge list
                 let edge_offset = extra_edge_offsets
                     .get(&(idx as u32))
                     .copied()
                     .unwrap_or(0);
-                // Parent 2 slot stores parent_oids[1] index via extra edges
-                // Actually, for octopus, parent2 = PARENT_EXTRA_EDGE | offset
-                // But we also need to write parent_oids[1] as the first extra edge entry
                 PARENT_EXTRA_EDGE | edge_offset as u32
             };
             buf.extend_from_slice(&parent2.to_be_bytes());
@@ -228,23 +448,36 @@ impl CommitGraphWriter {
 
         // Write Extra Edges (if any).
         if has_extra_edges {
-            // For octopus merges, we need to include parent_oids[1] as well
-            // Recompute extra edges properly
             buf.truncate(extra_edges_offset);
-            for commit in &self.commits {
-                if commit.parent_oids.len() > 2 {
-                    for (p_idx, parent) in commit.parent_oids.iter().enumerate().skip(1) {
-                        let parent_graph_idx =
-                            oid_to_idx.get(parent).copied().unwrap_or(PARENT_NONE);
-                        let is_last = p_idx == commit.parent_oids.len() - 1;
-                        let val = if is_last {
-                            parent_graph_idx | 0x8000_0000
-                        } else {
-                            parent_graph_idx
-                        };
-                        buf.extend_from_slice(&val.to_be_bytes());
-                    }
-                }
+            buf.extend(extra_edges.iter().flat_map(|v| v.to_be_bytes()));
+        }
+
+        // Write Bloom Filter Index (cumulative end-offset per commit, into
+        // BDAT's filter-data region).
+        buf.truncate(bloom_indexes_offset);
+        for &end_offset in &bloom_indexes {
+            buf.extend_from_slice(&end_offset.to_be_bytes());
+        }
+
+        // Write Bloom Filter Data: header, then each commit's filter bytes.
+        buf.truncate(bloom_data_offset);
+        buf.extend_from_slice(&1u32.to_be_bytes()); // version
+        buf.extend_from_slice(&1u32.to_be_bytes()); // hash version (murmur3)
+        buf.extend_from_slice(&bloom::BITS_PER_ENTRY.to_be_bytes());
+        buf.extend_from_slice(&bloom::NUM_HASHES.to_be_bytes());
+        buf.extend_from_slice(&bloom_data);
+
+        // Write Generation Data (corrected-date offset, or overflow index).
+        buf.truncate(generation_data_offset);
+        for &entry in &generation_data {
+            buf.extend_from_slice(&entry.to_be_bytes());
+        }
+
+        // Write Generation Data Overflow (absolute corrected dates).
+        if has_generation_overflow {
+            buf.truncate(generation_data_overflow_offset);
+            for &cdate in &generation_data_overflow {
+                buf.extend_from_slice(&(cdate as u64).to_be_bytes());
             }
         }
 
@@ -270,24 +503,47 @@ impl CommitGraphWriter {
         })
     }
 
-    /// Compute generation numbers bottom-up.
-    fn compute_generations(&mut self, oid_to_idx: &HashMap<ObjectId, u32>) {
+    /// Compute generation numbers and corrected commit dates bottom-up.
+    ///
+    /// `gen(c) = 1 + max(gen(p) for p in parents)`, with `gen(c) = 1` for a
+    /// root commit. `cdate(c) = max(commit_time(c), 1 + max(cdate(p) for p
+    /// in parents))`, so `cdate` is always non-decreasing from parent to
+    /// child even when committer clocks are skewed. Parents not present in
+    /// this layer (because they're already covered by `base`) contribute
+    /// their already-known generation/corrected date from `base` instead of
+    /// being recomputed.
+    fn compute_generations_and_dates(
+        &mut self,
+        oid_to_idx: &HashMap<ObjectId, u32>,
+        base: Option<&CommitGraph>,
+    ) {
         let n = self.commits.len();
 
-        // Build adjacency: child → parents (as indices)
-        let parent_indices: Vec<Vec<u32>> = self
-            .commits
-            .iter()
-            .map(|c| {
-                c.parent_oids
-                    .iter()
-                    .filter_map(|p| oid_to_idx.get(p).copied())
-                    .collect()
-            })
-            .collect();
+        // Build adjacency: child → local parent indices, plus any
+        // (generation, corrected_date) pulled from a base-layer parent.
+        let mut parent_indices: Vec<Vec<u32>> = Vec::with_capacity(n);
+        let mut base_parent_info: Vec<Vec<(u32, i64)>> = Vec::with_capacity(n);
+        for commit in &self.commits {
+            let mut local = Vec::new();
+            let mut from_base = Vec::new();
+            for parent in &commit.parent_oids {
+                if let Some(&idx) = oid_to_idx.get(parent) {
+                    local.push(idx);
+                } else if let Some(base) = base {
+                    if let (Some(gen), Some(cdate)) =
+                        (base.generation(parent), base.corrected_commit_date(parent))
+                    {
+                        from_base.push((gen, cdate));
+                    }
+                }
+            }
+            parent_indices.push(local);
+            base_parent_info.push(from_base);
+        }
 
-        // Compute generations via iterative DFS.
+        // Compute generations and corrected dates via iterative DFS.
         let mut generations = vec![0u32; n];
+        let mut corrected_dates = vec![0i64; n];
         let mut visited = vec![false; n];
         let mut stack: Vec<(usize, bool)> = Vec::new();
 
@@ -296,12 +552,22 @@ impl CommitGraphWriter {
                 stack.push((i, false));
                 while let Some((idx, processed)) = stack.pop() {
                     if processed {
-                        let max_parent_gen = parent_indices[idx]
+                        let local_max_gen = parent_indices[idx]
                             .iter()
-                            .map(|&p| generations[p as usize])
-                            .max()
-                            .unwrap_or(0);
+                            .map(|&p| generations[p as usize]);
+                        let base_max_gen = base_parent_info[idx].iter().map(|&(g, _)| g);
+                        let max_parent_gen = local_max_gen.chain(base_max_gen).max().unwrap_or(0);
                         generations[idx] = max_parent_gen + 1;
+
+                        let local_max_cdate = parent_indices[idx]
+                            .iter()
+                            .map(|&p| corrected_dates[p as usize]);
+                        let base_max_cdate = base_parent_info[idx].iter().map(|&(_, d)| d);
+                        let max_parent_cdate = local_max_cdate.chain(base_max_cdate).max();
+                        corrected_dates[idx] = match max_parent_cdate {
+                            Some(max_cdate) => self.commits[idx].commit_time.max(max_cdate + 1),
+                            None => self.commits[idx].commit_time,
+                        };
                     } else if !visited[idx] {
                         visited[idx] = true;
                         stack.push((idx, true));
@@ -315,9 +581,10 @@ impl CommitGraphWriter {
             }
         }
 
-        // Store generations.
-        for (i, gen) in generations.into_iter().enumerate() {
-            self.commits[i].generation = gen;
+        // Store generations and corrected dates.
+        for i in 0..n {
+            self.commits[i].generation = generations[i];
+            self.commits[i].corrected_commit_date = corrected_dates[i];
         }
     }
 }
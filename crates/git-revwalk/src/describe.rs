@@ -0,0 +1,248 @@
+//! Nearest-tag lookup (`git describe`).
+//!
+//! Tracks several candidate tags at once via a bitfield as commits are
+//! popped off a date-ordered priority queue, rather than stopping at the
+//! first tag a simple linear walk happens to reach first -- the latter gives
+//! the wrong answer whenever the truly nearest tag sits on a branch that
+//! merges in after a more distant one.
+
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use git_hash::ObjectId;
+use git_object::{Commit, Object};
+use git_ref::RefStore;
+use git_repository::Repository;
+
+use crate::walk::WalkEntry;
+use crate::RevWalkError;
+
+/// Maximum number of candidate tags [`describe`] can track at once -- each
+/// owns one bit of the 32-bit flags word OR'd down through history.
+pub const MAX_CANDIDATES: usize = 32;
+
+/// Options controlling [`describe`].
+#[derive(Debug, Clone)]
+pub struct DescribeOptions {
+    /// Consider lightweight (non-annotated) tags as candidates too, like
+    /// `git describe --tags`. Annotated tags are always considered.
+    pub all_tags: bool,
+    /// Stop tracking new candidate tags once this many have been found.
+    /// Capped at [`MAX_CANDIDATES`] regardless of the configured value.
+    pub max_candidates: usize,
+    /// Number of hex digits to abbreviate the OID to.
+    pub abbrev: usize,
+}
+
+impl Default for DescribeOptions {
+    fn default() -> Self {
+        Self {
+            all_tags: false,
+            max_candidates: MAX_CANDIDATES,
+            abbrev: 7,
+        }
+    }
+}
+
+/// The result of a successful [`describe`] lookup.
+#[derive(Debug, Clone)]
+pub struct Description {
+    /// Name of the nearest tag (without the `refs/tags/` prefix).
+    pub tag_name: String,
+    /// Commits reachable from `target` that aren't yet reachable from the
+    /// tag (0 when `target` itself is tagged).
+    pub distance: u32,
+    /// `target`'s OID abbreviated to `opts.abbrev` hex digits.
+    pub abbrev_oid: String,
+}
+
+impl Description {
+    /// Format as `git describe` would: just the tag name for an exact match
+    /// (unless `long` is set), otherwise `<tag>-<distance>-g<abbrev-oid>`.
+    pub fn format(&self, long: bool) -> String {
+        if self.distance == 0 && !long {
+            self.tag_name.clone()
+        } else {
+            format!("{}-{}-g{}", self.tag_name, self.distance, self.abbrev_oid)
+        }
+    }
+}
+
+/// A candidate tag discovered during the walk, tracked by its assigned bit.
+struct Candidate {
+    tag_name: String,
+    bit: u32,
+    distance: u32,
+}
+
+/// Find the nearest tag reachable from `target`, like `git describe`.
+///
+/// Seeds a commit-date-ordered priority queue with `target` and walks
+/// backwards, assigning up to `opts.max_candidates` tagged commits one bit
+/// apiece. As each commit is popped, its accumulated flags are OR'd into its
+/// parents, and every candidate not yet covered by those flags has its
+/// "ahead" distance incremented. The walk stops once every candidate's bit
+/// is set on everything left in the queue (nothing left could change a
+/// distance) or the queue is exhausted. The candidate with the smallest
+/// distance wins.
+pub fn describe(
+    repo: &Repository,
+    target: ObjectId,
+    opts: &DescribeOptions,
+) -> Result<Option<Description>, RevWalkError> {
+    let tag_map = collect_tags(repo, opts.all_tags)?;
+
+    if let Some((tag_name, _)) = tag_map.get(&target) {
+        return Ok(Some(Description {
+            tag_name: tag_name.clone(),
+            distance: 0,
+            abbrev_oid: abbreviate(&target, opts.abbrev),
+        }));
+    }
+
+    let max_candidates = opts.max_candidates.min(MAX_CANDIDATES);
+    if max_candidates == 0 {
+        return Ok(None);
+    }
+
+    let mut queue: BinaryHeap<WalkEntry> = BinaryHeap::new();
+    let mut flags: HashMap<ObjectId, u32> = HashMap::new();
+    let mut seen: HashSet<ObjectId> = HashSet::new();
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let mut seen_commits: u32 = 0;
+    let mut ctr: u64 = 0;
+
+    let target_commit = read_commit(repo, &target)?;
+    seen.insert(target);
+    flags.insert(target, 0);
+    queue.push(WalkEntry {
+        oid: target,
+        commit_date: target_commit.committer.date.timestamp,
+        author_date: target_commit.author.date.timestamp,
+        generation: 0,
+        insertion_ctr: ctr,
+    });
+    ctr += 1;
+
+    while let Some(entry) = queue.pop() {
+        let oid = entry.oid;
+        let mut f = flags.get(&oid).copied().unwrap_or(0);
+
+        if candidates.len() < max_candidates {
+            if let Some((tag_name, _)) = tag_map.get(&oid) {
+                let bit = 1u32 << candidates.len();
+                candidates.push(Candidate {
+                    tag_name: tag_name.clone(),
+                    bit,
+                    distance: seen_commits,
+                });
+                f |= bit;
+                flags.insert(oid, f);
+            }
+        }
+
+        for candidate in &mut candidates {
+            if f & candidate.bit == 0 {
+                candidate.distance += 1;
+            }
+        }
+        seen_commits += 1;
+
+        if !candidates.is_empty() {
+            let all_bits = candidates.iter().fold(0u32, |acc, c| acc | c.bit);
+            if queue
+                .iter()
+                .all(|e| flags.get(&e.oid).copied().unwrap_or(0) & all_bits == all_bits)
+            {
+                break;
+            }
+        }
+
+        let commit = read_commit(repo, &oid)?;
+        for parent in &commit.parents {
+            let merged = flags.get(parent).copied().unwrap_or(0) | f;
+            flags.insert(*parent, merged);
+            if seen.insert(*parent) {
+                let parent_commit = read_commit(repo, parent)?;
+                queue.push(WalkEntry {
+                    oid: *parent,
+                    commit_date: parent_commit.committer.date.timestamp,
+                    author_date: parent_commit.author.date.timestamp,
+                    generation: 0,
+                    insertion_ctr: ctr,
+                });
+                ctr += 1;
+            }
+        }
+    }
+
+    Ok(candidates
+        .into_iter()
+        .min_by_key(|c| c.distance)
+        .map(|c| Description {
+            tag_name: c.tag_name,
+            distance: c.distance,
+            abbrev_oid: abbreviate(&target, opts.abbrev),
+        }))
+}
+
+/// Whether the repository has any lightweight (non-annotated) tag, used to
+/// hint "try --tags" when an annotated-only `describe` finds nothing.
+pub fn has_lightweight_tags(repo: &Repository) -> Result<bool, RevWalkError> {
+    let tag_refs = repo.refs().iter(Some("refs/tags/"))?;
+    for r in tag_refs {
+        let r = r?;
+        if let Some(oid) = r.target_oid() {
+            if !matches!(repo.odb().read(&oid)?, Some(Object::Tag(_))) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Map each tagged commit OID to its tag name, preferring annotated tags.
+/// Lightweight tags are only included when `include_lightweight` is set.
+fn collect_tags(
+    repo: &Repository,
+    include_lightweight: bool,
+) -> Result<HashMap<ObjectId, (String, bool)>, RevWalkError> {
+    let mut tag_map: HashMap<ObjectId, (String, bool)> = HashMap::new();
+
+    let tag_refs = repo.refs().iter(Some("refs/tags/"))?;
+    for r in tag_refs {
+        let r = r?;
+        let full_name = r.name().as_str().to_string();
+        let tag_name = full_name
+            .strip_prefix("refs/tags/")
+            .unwrap_or(&full_name)
+            .to_string();
+
+        let Some(oid) = r.target_oid() else {
+            continue;
+        };
+        match repo.odb().read(&oid)? {
+            Some(Object::Tag(tag)) => {
+                tag_map.insert(tag.target, (tag_name, true));
+            }
+            _ => {
+                if include_lightweight {
+                    tag_map.insert(oid, (tag_name, false));
+                }
+            }
+        }
+    }
+
+    Ok(tag_map)
+}
+
+fn read_commit(repo: &Repository, oid: &ObjectId) -> Result<Commit, RevWalkError> {
+    match repo.odb().read(oid)?.ok_or(RevWalkError::CommitNotFound(*oid))? {
+        Object::Commit(c) => Ok(c),
+        _ => Err(RevWalkError::NotACommit(*oid)),
+    }
+}
+
+fn abbreviate(oid: &ObjectId, len: usize) -> String {
+    let hex = oid.to_hex();
+    hex[..len.min(hex.len())].to_string()
+}
@@ -0,0 +1,467 @@
+//! Persistent on-disk commit index for accelerated repeated walks.
+//!
+//! Unlike the commit-graph reader ([`crate::commit_graph`]), which parses
+//! lazily against an mmap'd file to keep a huge, shared on-disk structure
+//! cheap to open, this index is a lighter, process-local acceleration
+//! structure inspired by Jujutsu's `IndexStore`/`index.rs`: every commit is
+//! assigned a dense `u32` position in topological order (a commit's position
+//! is always greater than every one of its parents'), so reachability
+//! queries can work over position integers and bitsets instead of
+//! `HashSet<ObjectId>`s and repeated ODB reads.
+//!
+//! The on-disk layout uses fixed-size records (mirroring the commit-graph's
+//! own two-direct-parents-plus-extra-edge-list encoding, see
+//! [`crate::commit_graph::PARENT_NONE`]/[`crate::commit_graph::PARENT_EXTRA_EDGE`])
+//! so it's suitable for mmap'ing directly; this reader decodes every record
+//! into position-indexed vectors at load time rather than indexing the
+//! mmap lazily, since the index exists to accelerate repeated walks within a
+//! single process rather than to minimize working set across huge histories.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::Write as _;
+use std::path::Path;
+
+use git_hash::hasher::Hasher;
+use git_hash::{HashAlgorithm, ObjectId};
+use git_object::{Commit, Object, ObjectType};
+use git_ref::RefStore;
+use git_repository::Repository;
+use memmap2::Mmap;
+
+use crate::commit_graph::{PARENT_EXTRA_EDGE, PARENT_NONE};
+use crate::RevWalkError;
+
+const INDEX_SIGNATURE: &[u8; 4] = b"RWIX";
+const INDEX_VERSION: u32 = 1;
+/// Size in bytes of the fixed header preceding the commit records: magic,
+/// version, hash length, commit count, extra-edge count (4 bytes each).
+const HEADER_SIZE: usize = 20;
+
+/// A single indexed commit's metadata, addressed by its position.
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    oid: ObjectId,
+    commit_date: i64,
+    generation: u32,
+    /// Parent positions, in the commit's own parent order.
+    parents: Vec<u32>,
+}
+
+/// A persistent, position-addressed commit index.
+///
+/// Positions are assigned in topological order, so a commit's position is
+/// always greater than every one of its parents' -- parent pointers can
+/// therefore be stored as plain `u32`s without forward references, and
+/// reachability from a position can be computed by following smaller
+/// positions only.
+pub struct Index {
+    hash_len: usize,
+    entries: Vec<IndexEntry>,
+    position_of: HashMap<ObjectId, u32>,
+    /// Positions, ordered by ascending OID, for binary search and shortest
+    /// unique prefix lookups.
+    sorted_positions: Vec<u32>,
+}
+
+impl Index {
+    /// Build a fresh index over every commit reachable from any ref.
+    pub fn build(repo: &Repository) -> Result<Self, RevWalkError> {
+        let mut index = Self {
+            hash_len: 20,
+            entries: Vec::new(),
+            position_of: HashMap::new(),
+            sorted_positions: Vec::new(),
+        };
+        let tips = collect_ref_tips(repo)?;
+        index.extend_from(repo, &tips)?;
+        Ok(index)
+    }
+
+    /// Load a previously [`Self::save`]d index from disk.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RevWalkError> {
+        let file = std::fs::File::open(path.as_ref())?;
+        let mmap = unsafe { Mmap::map(&file)? };
+        Self::parse(&mmap)
+    }
+
+    /// Write this index to disk in the layout described in the module docs.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RevWalkError> {
+        let record_size = self.hash_len + 8 + 4 + 4 + 4;
+        let mut fixed_records = Vec::with_capacity(record_size * self.entries.len());
+        let mut extra_edges: Vec<u32> = Vec::new();
+
+        for entry in &self.entries {
+            fixed_records.extend_from_slice(entry.oid.as_bytes());
+            fixed_records.extend_from_slice(&entry.commit_date.to_le_bytes());
+            fixed_records.extend_from_slice(&entry.generation.to_le_bytes());
+
+            let (p1, p2) = match entry.parents.as_slice() {
+                [] => (PARENT_NONE, PARENT_NONE),
+                [a] => (*a, PARENT_NONE),
+                [a, b] => (*a, *b),
+                [a, rest @ ..] => {
+                    let edge_index = extra_edges.len() as u32;
+                    extra_edges.extend_from_slice(rest);
+                    extra_edges.push(PARENT_NONE);
+                    (*a, PARENT_EXTRA_EDGE | edge_index)
+                }
+            };
+            fixed_records.extend_from_slice(&p1.to_le_bytes());
+            fixed_records.extend_from_slice(&p2.to_le_bytes());
+        }
+
+        let mut buf = Vec::with_capacity(
+            HEADER_SIZE + fixed_records.len() + extra_edges.len() * 4 + self.entries.len() * 4 + self.hash_len,
+        );
+        buf.extend_from_slice(INDEX_SIGNATURE);
+        buf.extend_from_slice(&INDEX_VERSION.to_le_bytes());
+        buf.extend_from_slice(&(self.hash_len as u32).to_le_bytes());
+        buf.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&(extra_edges.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&fixed_records);
+        for edge in &extra_edges {
+            buf.extend_from_slice(&edge.to_le_bytes());
+        }
+        for &pos in &self.sorted_positions {
+            buf.extend_from_slice(&pos.to_le_bytes());
+        }
+
+        let checksum = Hasher::digest(self.algorithm(), &buf)
+            .map_err(|e| RevWalkError::InvalidIndex(format!("failed to checksum index: {}", e)))?;
+        buf.extend_from_slice(checksum.as_bytes());
+
+        let mut file = std::fs::File::create(path.as_ref())?;
+        file.write_all(&buf)?;
+        Ok(())
+    }
+
+    /// Incrementally extend this index with any commit reachable from a ref
+    /// that isn't indexed yet. Returns the number of newly indexed commits.
+    pub fn update(&mut self, repo: &Repository) -> Result<usize, RevWalkError> {
+        let before = self.entries.len();
+        let tips = collect_ref_tips(repo)?;
+        self.extend_from(repo, &tips)?;
+        Ok(self.entries.len() - before)
+    }
+
+    /// Number of indexed commits.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index holds no commits.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether `oid` is indexed.
+    pub fn contains(&self, oid: &ObjectId) -> bool {
+        self.position_of.contains_key(oid)
+    }
+
+    /// The position assigned to `oid`, if indexed.
+    pub fn position(&self, oid: &ObjectId) -> Option<u32> {
+        self.position_of.get(oid).copied()
+    }
+
+    /// The OID at `pos`, if in range.
+    pub fn oid_at(&self, pos: u32) -> Option<ObjectId> {
+        self.entries.get(pos as usize).map(|e| e.oid)
+    }
+
+    /// `oid`'s generation number (1 for a root commit; 1 + max(parent
+    /// generations) otherwise), if indexed.
+    pub fn generation(&self, oid: &ObjectId) -> Option<u32> {
+        self.position(oid).map(|pos| self.entries[pos as usize].generation)
+    }
+
+    /// `oid`'s committer date, if indexed.
+    pub fn commit_date(&self, oid: &ObjectId) -> Option<i64> {
+        self.position(oid).map(|pos| self.entries[pos as usize].commit_date)
+    }
+
+    /// Parent positions of the commit at `pos`, in parent order.
+    pub fn parent_positions(&self, pos: u32) -> &[u32] {
+        self.entries.get(pos as usize).map(|e| e.parents.as_slice()).unwrap_or(&[])
+    }
+
+    /// Every position reachable from `start` by following parent pointers,
+    /// `start` included -- computed purely over position integers and a
+    /// dense seen-bitset, without touching the ODB.
+    pub fn ancestor_positions(&self, start: u32) -> Vec<u32> {
+        let mut seen = vec![false; self.entries.len()];
+        let mut stack = vec![start];
+        let mut result = Vec::new();
+        seen[start as usize] = true;
+        while let Some(pos) = stack.pop() {
+            result.push(pos);
+            for &parent in &self.entries[pos as usize].parents {
+                if !seen[parent as usize] {
+                    seen[parent as usize] = true;
+                    stack.push(parent);
+                }
+            }
+        }
+        result
+    }
+
+    /// Shortest hex prefix length that unambiguously identifies `oid` among
+    /// every indexed commit, found by binary-searching its OID-sorted
+    /// neighbors. Returns the full hex length if `oid` isn't indexed or the
+    /// index holds at most one commit.
+    pub fn shortest_unique_prefix(&self, oid: &ObjectId) -> usize {
+        let full_hex_len = self.hash_len.max(1) * 2;
+        if self.sorted_positions.len() <= 1 {
+            return full_hex_len;
+        }
+        let Ok(idx) = self
+            .sorted_positions
+            .binary_search_by_key(oid, |&pos| self.entries[pos as usize].oid)
+        else {
+            return full_hex_len;
+        };
+
+        let mut max_common = 0usize;
+        if idx > 0 {
+            let prev = self.entries[self.sorted_positions[idx - 1] as usize].oid;
+            max_common = max_common.max(common_hex_prefix_len(oid, &prev));
+        }
+        if idx + 1 < self.sorted_positions.len() {
+            let next = self.entries[self.sorted_positions[idx + 1] as usize].oid;
+            max_common = max_common.max(common_hex_prefix_len(oid, &next));
+        }
+        (max_common + 1).min(full_hex_len)
+    }
+
+    fn algorithm(&self) -> HashAlgorithm {
+        if self.hash_len == 32 {
+            HashAlgorithm::Sha256
+        } else {
+            HashAlgorithm::Sha1
+        }
+    }
+
+    /// Discover every commit reachable from `tips` that isn't indexed yet,
+    /// topologically sort just that new set (parents before children,
+    /// counting in-degree only against other new commits -- anything
+    /// already indexed is already "ready"), and append them in that order
+    /// with freshly computed generation numbers.
+    fn extend_from(&mut self, repo: &Repository, tips: &[ObjectId]) -> Result<(), RevWalkError> {
+        let mut new_commits: HashMap<ObjectId, Commit> = HashMap::new();
+        let mut stack: Vec<ObjectId> = tips
+            .iter()
+            .filter(|oid| !self.position_of.contains_key(oid))
+            .copied()
+            .collect();
+
+        while let Some(oid) = stack.pop() {
+            if new_commits.contains_key(&oid) || self.position_of.contains_key(&oid) {
+                continue;
+            }
+            let commit = read_commit(repo, &oid)?;
+            for parent in &commit.parents {
+                if !self.position_of.contains_key(parent) {
+                    stack.push(*parent);
+                }
+            }
+            new_commits.insert(oid, commit);
+        }
+
+        if new_commits.is_empty() {
+            return Ok(());
+        }
+
+        let mut indegree: HashMap<ObjectId, u32> = new_commits.keys().map(|oid| (*oid, 0)).collect();
+        let mut children: HashMap<ObjectId, Vec<ObjectId>> = HashMap::new();
+        for (oid, commit) in &new_commits {
+            for parent in &commit.parents {
+                if new_commits.contains_key(parent) {
+                    *indegree.get_mut(oid).unwrap() += 1;
+                    children.entry(*parent).or_default().push(*oid);
+                }
+            }
+        }
+
+        let mut ready: VecDeque<ObjectId> = indegree
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(oid, _)| *oid)
+            .collect();
+        let mut order: Vec<ObjectId> = Vec::with_capacity(new_commits.len());
+        while let Some(oid) = ready.pop_front() {
+            order.push(oid);
+            if let Some(kids) = children.get(&oid) {
+                for kid in kids {
+                    let degree = indegree.get_mut(kid).unwrap();
+                    *degree -= 1;
+                    if *degree == 0 {
+                        ready.push_back(*kid);
+                    }
+                }
+            }
+        }
+
+        for oid in order {
+            let commit = new_commits.remove(&oid).expect("discovered during traversal above");
+            self.hash_len = oid.as_bytes().len();
+
+            let mut parent_positions = Vec::with_capacity(commit.parents.len());
+            let mut max_parent_generation = 0u32;
+            for parent in &commit.parents {
+                let parent_pos = *self
+                    .position_of
+                    .get(parent)
+                    .expect("parent indexed before child in topological order");
+                parent_positions.push(parent_pos);
+                max_parent_generation =
+                    max_parent_generation.max(self.entries[parent_pos as usize].generation);
+            }
+            let generation = if parent_positions.is_empty() {
+                1
+            } else {
+                max_parent_generation + 1
+            };
+
+            let position = self.entries.len() as u32;
+            self.position_of.insert(oid, position);
+            self.entries.push(IndexEntry {
+                oid,
+                commit_date: commit.committer.date.timestamp,
+                generation,
+                parents: parent_positions,
+            });
+        }
+
+        self.sorted_positions = (0..self.entries.len() as u32).collect();
+        self.sorted_positions.sort_by_key(|&pos| self.entries[pos as usize].oid);
+
+        Ok(())
+    }
+
+    fn parse(data: &[u8]) -> Result<Self, RevWalkError> {
+        if data.len() < HEADER_SIZE {
+            return Err(RevWalkError::InvalidIndex("file too small for header".into()));
+        }
+        if &data[0..4] != INDEX_SIGNATURE {
+            return Err(RevWalkError::InvalidIndex("bad signature".into()));
+        }
+        let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
+        if version != INDEX_VERSION {
+            return Err(RevWalkError::InvalidIndex(format!("unsupported version {}", version)));
+        }
+        let hash_len = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+        let num_commits = u32::from_le_bytes(data[12..16].try_into().unwrap()) as usize;
+        let extra_edges_count = u32::from_le_bytes(data[16..20].try_into().unwrap()) as usize;
+
+        let record_size = hash_len + 8 + 4 + 4 + 4;
+        let records_offset = HEADER_SIZE;
+        let extra_edges_offset = records_offset + record_size * num_commits;
+        let extra_edges_len = extra_edges_count * 4;
+        let sorted_offset = extra_edges_offset + extra_edges_len;
+        let sorted_len = num_commits * 4;
+        let checksum_offset = sorted_offset + sorted_len;
+
+        if data.len() < checksum_offset + hash_len {
+            return Err(RevWalkError::InvalidIndex("file truncated".into()));
+        }
+
+        let algo = if hash_len == 32 { HashAlgorithm::Sha256 } else { HashAlgorithm::Sha1 };
+        let computed = Hasher::digest(algo, &data[..checksum_offset])
+            .map_err(|e| RevWalkError::InvalidIndex(format!("failed to checksum index: {}", e)))?;
+        if computed.as_bytes() != &data[checksum_offset..checksum_offset + hash_len] {
+            return Err(RevWalkError::InvalidIndex("checksum mismatch".into()));
+        }
+
+        let extra_edges: Vec<u32> = data[extra_edges_offset..extra_edges_offset + extra_edges_len]
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+
+        let mut entries = Vec::with_capacity(num_commits);
+        let mut position_of = HashMap::with_capacity(num_commits);
+        for i in 0..num_commits {
+            let rec = &data[records_offset + i * record_size..records_offset + (i + 1) * record_size];
+            let oid = ObjectId::from_bytes(&rec[0..hash_len], algo)
+                .map_err(|e| RevWalkError::InvalidIndex(format!("bad oid in record {}: {}", i, e)))?;
+            let mut off = hash_len;
+            let commit_date = i64::from_le_bytes(rec[off..off + 8].try_into().unwrap());
+            off += 8;
+            let generation = u32::from_le_bytes(rec[off..off + 4].try_into().unwrap());
+            off += 4;
+            let p1 = u32::from_le_bytes(rec[off..off + 4].try_into().unwrap());
+            off += 4;
+            let p2 = u32::from_le_bytes(rec[off..off + 4].try_into().unwrap());
+
+            position_of.insert(oid, i as u32);
+            entries.push(IndexEntry {
+                oid,
+                commit_date,
+                generation,
+                parents: decode_parents(p1, p2, &extra_edges),
+            });
+        }
+
+        let sorted_positions: Vec<u32> = data[sorted_offset..sorted_offset + sorted_len]
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+
+        Ok(Self {
+            hash_len,
+            entries,
+            position_of,
+            sorted_positions,
+        })
+    }
+}
+
+/// Decode a record's two direct parent slots, following the extra-edge list
+/// for octopus merges, mirroring [`crate::commit_graph`]'s own encoding.
+fn decode_parents(p1: u32, p2: u32, extra_edges: &[u32]) -> Vec<u32> {
+    let mut parents = Vec::new();
+    if p1 != PARENT_NONE {
+        parents.push(p1);
+    }
+    if p2 == PARENT_NONE {
+        // No second parent.
+    } else if p2 & PARENT_EXTRA_EDGE != 0 {
+        let mut idx = (p2 & !PARENT_EXTRA_EDGE) as usize;
+        while idx < extra_edges.len() && extra_edges[idx] != PARENT_NONE {
+            parents.push(extra_edges[idx]);
+            idx += 1;
+        }
+    } else {
+        parents.push(p2);
+    }
+    parents
+}
+
+fn common_hex_prefix_len(a: &ObjectId, b: &ObjectId) -> usize {
+    let a_hex = a.to_hex();
+    let b_hex = b.to_hex();
+    a_hex
+        .bytes()
+        .zip(b_hex.bytes())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+fn collect_ref_tips(repo: &Repository) -> Result<Vec<ObjectId>, RevWalkError> {
+    let mut tips = Vec::new();
+    for r in repo.refs().iter(None)? {
+        let r = r?;
+        if let Some(oid) = r.target_oid() {
+            if matches!(repo.odb().read_header(&oid), Ok(Some(info)) if info.obj_type == ObjectType::Commit) {
+                tips.push(oid);
+            }
+        }
+    }
+    Ok(tips)
+}
+
+fn read_commit(repo: &Repository, oid: &ObjectId) -> Result<Commit, RevWalkError> {
+    match repo.odb().read(oid)?.ok_or(RevWalkError::CommitNotFound(*oid))? {
+        Object::Commit(c) => Ok(c),
+        _ => Err(RevWalkError::NotACommit(*oid)),
+    }
+}
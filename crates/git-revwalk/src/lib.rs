@@ -15,13 +15,23 @@ mod pretty;
 mod graph_draw;
 mod objects;
 mod filter;
+mod describe;
+mod index;
 
-pub use walk::{RevWalk, SortOrder, WalkOptions};
+pub use walk::{RevWalk, SortOrder, WalkCommit, WalkOptions};
+pub use describe::{describe, has_lightweight_tags, Description, DescribeOptions, MAX_CANDIDATES};
+pub use index::Index;
 pub use range::{RevisionRange, resolve_revision};
-pub use merge_base::{merge_base, merge_base_one, is_ancestor};
-pub use commit_graph::{CommitGraph, CommitGraphEntry};
+pub use merge_base::{
+    filter_reachable, fork_point, independent_commits, is_ancestor, merge_base, merge_base_many,
+    merge_base_octopus, merge_base_one, merge_bases,
+};
+pub use commit_graph::{CommitGraph, CommitGraphEntry, PrefixResult};
 pub use commit_graph::write::CommitGraphWriter;
-pub use pretty::{format_commit, format_builtin, FormatOptions, BuiltinFormat};
+pub use pretty::{
+    format_commit, format_builtin, format_commit_with_decorations, format_builtin_with_decorations,
+    resolve_format, FormatOptions, BuiltinFormat, ParsedFormat, ResolvedFormat,
+};
 pub use graph_draw::GraphDrawer;
 pub use objects::list_objects;
 pub use filter::ObjectFilter;
@@ -43,6 +53,9 @@ pub enum RevWalkError {
     #[error("invalid commit-graph: {0}")]
     InvalidCommitGraph(String),
 
+    #[error("invalid commit index: {0}")]
+    InvalidIndex(String),
+
     #[error("no merge base found")]
     NoMergeBase,
 
@@ -60,4 +73,7 @@ pub enum RevWalkError {
 
     #[error(transparent)]
     Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Diff(#[from] git_diff::DiffError),
 }
@@ -1,28 +1,40 @@
 //! Merge base computation using the paint algorithm.
 //!
 //! The paint algorithm works by marking commits reachable from each input with
-//! different "colors" (flags). When a commit is painted with both colors, it's
+//! different "colors" (flags). When a commit is painted with all colors, it's
 //! a common ancestor. The lowest common ancestors are the merge bases.
 
 use std::collections::{BinaryHeap, HashMap, HashSet};
 
 use git_hash::ObjectId;
 use git_object::Object;
+use git_ref::{reflog, RefName};
 use git_repository::Repository;
 
+use crate::commit_graph::CommitGraph;
 use crate::RevWalkError;
 
-/// Paint flags for the merge-base algorithm.
-const PARENT1: u8 = 1;
-const PARENT2: u8 = 2;
-const STALE: u8 = 4;
+/// Maximum number of inputs the paint algorithm can color at once: one bit
+/// per input, with the top bit reserved to mark a commit as stale.
+const MAX_PAINT_INPUTS: usize = 31;
+
+/// Stale marker: a commit already emitted as a candidate base, so its
+/// ancestors don't need to be colored any further.
+const STALE: u32 = 1 << 31;
 
 /// Entry in the paint queue.
 struct PaintEntry {
     oid: ObjectId,
     #[allow(dead_code)]
-    flags: u8,
+    flags: u32,
     date: i64,
+    /// Generation number from the commit-graph (0 if unavailable).
+    generation: u32,
+    /// Whether the queue should be ordered (and early-exit evaluated) by
+    /// `generation` rather than `date`. Set once per call, from whether a
+    /// commit-graph was available, and the same for every entry in a given
+    /// queue.
+    order_by_generation: bool,
 }
 
 impl PartialEq for PaintEntry {
@@ -41,11 +53,22 @@ impl PartialOrd for PaintEntry {
 
 impl Ord for PaintEntry {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // Max-heap by date (newest first).
-        self.date.cmp(&other.date)
+        // Max-heap: highest generation (or newest date, when generation
+        // numbers aren't available) first.
+        if self.order_by_generation {
+            self.generation.cmp(&other.generation)
+        } else {
+            self.date.cmp(&other.date)
+        }
     }
 }
 
+/// Generation number of `oid` from `commit_graph`, or 0 if the graph is
+/// absent or doesn't cover this commit.
+fn generation_of(commit_graph: Option<&CommitGraph>, oid: &ObjectId) -> u32 {
+    commit_graph.and_then(|cg| cg.generation(oid)).unwrap_or(0)
+}
+
 /// Find all merge bases of two commits.
 ///
 /// Returns the lowest common ancestor(s) — commits reachable from both `a` and `b`
@@ -55,16 +78,210 @@ pub fn merge_base(
     a: &ObjectId,
     b: &ObjectId,
 ) -> Result<Vec<ObjectId>, RevWalkError> {
-    if a == b {
-        return Ok(vec![*a]);
+    merge_base_many(repo, &[*a, *b])
+}
+
+/// Find all merge bases of an arbitrary number of commits.
+///
+/// Generalizes the two-commit paint algorithm: each input is assigned its own
+/// bit, a commit colored with every bit is a common ancestor candidate, and
+/// candidates that are themselves ancestors of another candidate are dropped.
+pub fn merge_base_many(
+    repo: &Repository,
+    commits: &[ObjectId],
+) -> Result<Vec<ObjectId>, RevWalkError> {
+    let mut unique: Vec<ObjectId> = Vec::new();
+    for oid in commits {
+        if !unique.contains(oid) {
+            unique.push(*oid);
+        }
     }
 
-    let results = paint_down_to_common(repo, a, b)?;
+    if unique.len() <= 1 {
+        return Ok(unique);
+    }
+    if unique.len() > MAX_PAINT_INPUTS {
+        return Err(RevWalkError::InvalidRevision(format!(
+            "merge-base supports at most {} commits, got {}",
+            MAX_PAINT_INPUTS,
+            unique.len()
+        )));
+    }
+
+    let commit_graph = CommitGraph::open_from_repo(repo).ok();
+    let results = paint_down_to_common(repo, commit_graph.as_ref(), &unique)?;
 
     // Remove redundant bases: if base X is an ancestor of base Y, drop X.
     remove_redundant(repo, results)
 }
 
+/// Find all merge bases of an arbitrary number of commits (alias for
+/// [`merge_base_many`], matching the plural naming used by reachability
+/// callers).
+pub fn merge_bases(
+    repo: &Repository,
+    commits: &[ObjectId],
+) -> Result<Vec<ObjectId>, RevWalkError> {
+    merge_base_many(repo, commits)
+}
+
+/// Return the subset of `tips` reachable (as an ancestor) from at least one
+/// commit in `sources`.
+///
+/// Walks the union of `sources`' ancestry with a generation-number-ordered
+/// priority queue (from the commit-graph, when available, falling back to
+/// committer date otherwise) and stops as soon as every tip has been found,
+/// without necessarily exhausting all of history.
+pub fn filter_reachable(
+    repo: &Repository,
+    tips: &[ObjectId],
+    sources: &[ObjectId],
+) -> Result<Vec<ObjectId>, RevWalkError> {
+    let commit_graph = CommitGraph::open_from_repo(repo).ok();
+    let order_by_generation = commit_graph.is_some();
+
+    let wanted: HashSet<ObjectId> = tips.iter().copied().collect();
+    let mut found: HashSet<ObjectId> = HashSet::new();
+    let mut visited: HashSet<ObjectId> = HashSet::new();
+    let mut queue: BinaryHeap<PaintEntry> = BinaryHeap::new();
+
+    for oid in sources {
+        if !visited.insert(*oid) {
+            continue;
+        }
+        if wanted.contains(oid) {
+            found.insert(*oid);
+        }
+        let commit = read_commit(repo, oid)?;
+        queue.push(PaintEntry {
+            oid: *oid,
+            flags: 0,
+            date: commit.committer.date.timestamp,
+            generation: generation_of(commit_graph.as_ref(), oid),
+            order_by_generation,
+        });
+    }
+
+    while found.len() < wanted.len() {
+        let Some(entry) = queue.pop() else {
+            break;
+        };
+        let commit = read_commit(repo, &entry.oid)?;
+        for parent in &commit.parents {
+            if !visited.insert(*parent) {
+                continue;
+            }
+            if wanted.contains(parent) {
+                found.insert(*parent);
+            }
+            if let Ok(parent_commit) = read_commit(repo, parent) {
+                queue.push(PaintEntry {
+                    oid: *parent,
+                    flags: 0,
+                    date: parent_commit.committer.date.timestamp,
+                    generation: generation_of(commit_graph.as_ref(), parent),
+                    order_by_generation,
+                });
+            }
+        }
+    }
+
+    Ok(tips.iter().filter(|t| found.contains(t)).copied().collect())
+}
+
+/// Reduce a list of commits to a single merge base by repeatedly folding the
+/// merge-base of the running result with each successive commit, as used for
+/// octopus-merge base selection.
+pub fn merge_base_octopus(
+    repo: &Repository,
+    commits: &[ObjectId],
+) -> Result<Option<ObjectId>, RevWalkError> {
+    let mut iter = commits.iter();
+    let Some(first) = iter.next() else {
+        return Ok(None);
+    };
+
+    let mut running = *first;
+    for commit in iter {
+        let bases = merge_base(repo, &running, commit)?;
+        match bases.first() {
+            Some(base) => running = *base,
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(running))
+}
+
+/// Return the subset of `commits` that cannot be reached from any other
+/// commit in the list (C git's `merge-base --independent`).
+pub fn independent_commits(
+    repo: &Repository,
+    commits: &[ObjectId],
+) -> Result<Vec<ObjectId>, RevWalkError> {
+    let mut keep = vec![true; commits.len()];
+
+    for i in 0..commits.len() {
+        for j in 0..commits.len() {
+            if i == j {
+                continue;
+            }
+            if commits[i] == commits[j] {
+                // Keep only the first occurrence of a duplicate.
+                if i > j {
+                    keep[i] = false;
+                }
+                continue;
+            }
+            if is_ancestor_direct(repo, &commits[i], &commits[j])? {
+                keep[i] = false;
+                break;
+            }
+        }
+    }
+
+    Ok(commits
+        .iter()
+        .zip(keep)
+        .filter(|(_, k)| *k)
+        .map(|(oid, _)| *oid)
+        .collect())
+}
+
+/// Find the fork point of `commit` from `ref_name`: the most recent entry in
+/// `ref_name`'s reflog that is still an ancestor of `commit`, i.e. the best
+/// common base across history `ref_name` has since been rebased away from.
+pub fn fork_point(
+    repo: &Repository,
+    ref_name: &str,
+    commit: &ObjectId,
+) -> Result<Option<ObjectId>, RevWalkError> {
+    let name = resolve_ref_name(ref_name)?;
+    let entries = reflog::read_reflog(repo.git_dir(), &name)?;
+
+    let mut seen: HashSet<ObjectId> = HashSet::new();
+    for entry in &entries {
+        if !seen.insert(entry.new_oid) {
+            continue;
+        }
+        if is_ancestor(repo, &entry.new_oid, commit)? {
+            return Ok(Some(entry.new_oid));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Expand a short branch name to its full ref path, the way reflog lookups do.
+fn resolve_ref_name(name: &str) -> Result<RefName, RevWalkError> {
+    let full = if name == "HEAD" || name.starts_with("refs/") {
+        name.to_string()
+    } else {
+        format!("refs/heads/{}", name)
+    };
+    Ok(RefName::new(full)?)
+}
+
 /// Find the single best merge base of two commits.
 pub fn merge_base_one(
     repo: &Repository,
@@ -89,46 +306,59 @@ pub fn is_ancestor(
     Ok(bases.contains(ancestor))
 }
 
-/// Paint algorithm: walk down from both commits, painting flags.
+/// Paint algorithm: walk down from each input commit, coloring reachable
+/// commits with a per-input bitflag. A commit colored by every input is a
+/// common-ancestor candidate.
+///
+/// When `commit_graph` is available, the paint queue is ordered by
+/// generation number (descending) instead of committer date. Since
+/// generation only decreases along parent edges, once every commit still in
+/// the queue has a generation below the lowest generation seen among
+/// candidates found so far, no deeper commit could possibly be a new common
+/// ancestor, and the walk stops without draining the rest of history.
 fn paint_down_to_common(
     repo: &Repository,
-    a: &ObjectId,
-    b: &ObjectId,
+    commit_graph: Option<&CommitGraph>,
+    inputs: &[ObjectId],
 ) -> Result<Vec<ObjectId>, RevWalkError> {
-    let mut flags: HashMap<ObjectId, u8> = HashMap::new();
+    let all_colors: u32 = (1u32 << inputs.len()) - 1;
+    let order_by_generation = commit_graph.is_some();
+
+    let mut flags: HashMap<ObjectId, u32> = HashMap::new();
     let mut queue: BinaryHeap<PaintEntry> = BinaryHeap::new();
     let mut results: Vec<ObjectId> = Vec::new();
-
-    // Seed the queue with both commits.
-    let commit_a = read_commit(repo, a)?;
-    let commit_b = read_commit(repo, b)?;
-
-    flags.insert(*a, PARENT1);
-    flags.insert(*b, PARENT2);
-
-    queue.push(PaintEntry {
-        oid: *a,
-        flags: PARENT1,
-        date: commit_a.committer.date.timestamp,
-    });
-    queue.push(PaintEntry {
-        oid: *b,
-        flags: PARENT2,
-        date: commit_b.committer.date.timestamp,
-    });
+    let mut min_result_generation = u32::MAX;
+
+    for (i, oid) in inputs.iter().enumerate() {
+        let color = 1u32 << i;
+        let commit = read_commit(repo, oid)?;
+        flags.insert(*oid, color);
+        queue.push(PaintEntry {
+            oid: *oid,
+            flags: color,
+            date: commit.committer.date.timestamp,
+            generation: generation_of(commit_graph, oid),
+            order_by_generation,
+        });
+    }
 
     while let Some(entry) = queue.pop() {
+        if order_by_generation && !results.is_empty() && entry.generation < min_result_generation {
+            break;
+        }
+
         let current_flags = *flags.get(&entry.oid).unwrap_or(&0);
 
         if current_flags & STALE != 0 {
             continue;
         }
 
-        // If this commit has been painted with both colors, it's a common ancestor.
-        if current_flags & (PARENT1 | PARENT2) == (PARENT1 | PARENT2) {
+        // If this commit has been painted with every color, it's a common ancestor.
+        if current_flags & all_colors == all_colors {
             // Mark as stale so we don't process further.
             flags.insert(entry.oid, current_flags | STALE);
             results.push(entry.oid);
+            min_result_generation = min_result_generation.min(entry.generation);
 
             // Mark all remaining queue entries as stale if they're already common.
             // Continue processing to find all common ancestors.
@@ -150,6 +380,8 @@ fn paint_down_to_common(
                         oid: *parent,
                         flags: new_flags,
                         date: parent_commit.committer.date.timestamp,
+                        generation: generation_of(commit_graph, parent),
+                        order_by_generation,
                     });
                 }
             }
@@ -160,7 +392,7 @@ fn paint_down_to_common(
 }
 
 /// Check if the queue has any non-stale entries.
-fn queue_has_nonstale(queue: &BinaryHeap<PaintEntry>, flags: &HashMap<ObjectId, u8>) -> bool {
+fn queue_has_nonstale(queue: &BinaryHeap<PaintEntry>, flags: &HashMap<ObjectId, u32>) -> bool {
     queue.iter().any(|entry| {
         let f = flags.get(&entry.oid).copied().unwrap_or(0);
         f & STALE == 0
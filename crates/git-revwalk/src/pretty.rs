@@ -2,10 +2,14 @@
 //!
 //! Supports format specifiers matching C git's `--format` / `--pretty` options.
 
+use std::collections::{HashMap, HashSet};
+
 use bstr::ByteSlice;
 use git_hash::ObjectId;
 use git_object::Commit;
+use git_utils::color::{parse_color_value, use_color_stdout, Color, ColorMode};
 use git_utils::date::DateFormat;
+use git_utils::mailmap::Mailmap;
 
 /// Built-in format presets.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,6 +35,19 @@ pub enum BuiltinFormat {
 pub struct FormatOptions {
     pub date_format: DateFormat,
     pub abbrev_len: usize,
+    /// Whether `%Cred`/`%Cgreen`/`%Cblue`/`%Creset`/`%C(...)` expand to ANSI
+    /// escapes. `Auto` expands them only when stdout is a terminal.
+    pub color: ColorMode,
+    /// The charset `%s`/`%b`/`%B` and the builtin presets decode the commit
+    /// message's raw bytes into before placing it in the result (a Rust
+    /// `String`, which is always UTF-8 in memory). Decoding consults the
+    /// commit's declared `encoding` header, recognizing `"UTF-8"` and
+    /// `"ISO-8859-1"`/`"latin1"` (the encodings real-world commit histories
+    /// actually declare); this avoids mojibake'ing a Latin-1 history the way
+    /// blindly assuming UTF-8 would. Since results are always a `String`,
+    /// `output_encoding` besides `"UTF-8"` (the default) has no further
+    /// effect today — there's no raw byte-level output to re-encode into.
+    pub output_encoding: String,
 }
 
 impl Default for FormatOptions {
@@ -38,8 +55,890 @@ impl Default for FormatOptions {
         Self {
             date_format: DateFormat::Default,
             abbrev_len: 7,
+            color: ColorMode::Never,
+            output_encoding: "UTF-8".to_string(),
+        }
+    }
+}
+
+/// The resolved form of a `--pretty`/`--format` argument: either a builtin
+/// preset, or a user-supplied template carrying whether it behaves as a
+/// *separator* (`format:`: no newline is forced after the last commit) or a
+/// *terminator* (`tformat:`, or any bare string containing a `%`: a newline
+/// is forced after every commit), matching C git's `get_format_type`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedFormat {
+    Builtin(BuiltinFormat),
+    User { template: String, terminator: bool },
+}
+
+/// Resolve a `--pretty`/`--format` argument the way C git does:
+/// - A bare builtin name (`oneline`, `short`, `medium`, `full`, `fuller`,
+///   `email`, `raw`) resolves directly to that [`BuiltinFormat`].
+/// - `format:<string>` resolves to a user template with separator semantics;
+///   `tformat:<string>` resolves to one with terminator semantics; a bare
+///   string containing a `%` is treated as an implicit `tformat:`.
+/// - Anything else is looked up as `pretty.<name>` in `config` and resolved
+///   recursively, so a user alias can point at another user alias; a cycle
+///   (a name that resolves back to itself, directly or transitively) yields
+///   `None` rather than recursing forever.
+/// - A name that is none of the above and has no matching `pretty.<name>`
+///   entry falls back to being treated as a literal user template (with
+///   terminator semantics), since historically this crate accepted any
+///   unrecognized string as a raw format.
+pub fn resolve_format(arg: &str, config: &git_config::ConfigSet) -> Option<ResolvedFormat> {
+    resolve_format_inner(arg, config, &mut HashSet::new())
+}
+
+fn resolve_format_inner(
+    arg: &str,
+    config: &git_config::ConfigSet,
+    seen: &mut HashSet<String>,
+) -> Option<ResolvedFormat> {
+    if let Some(builtin) = builtin_from_name(arg) {
+        return Some(ResolvedFormat::Builtin(builtin));
+    }
+    if let Some(rest) = arg.strip_prefix("format:") {
+        return Some(ResolvedFormat::User { template: rest.to_string(), terminator: false });
+    }
+    if let Some(rest) = arg.strip_prefix("tformat:") {
+        return Some(ResolvedFormat::User { template: rest.to_string(), terminator: true });
+    }
+    if arg.contains('%') {
+        return Some(ResolvedFormat::User { template: arg.to_string(), terminator: true });
+    }
+    if !seen.insert(arg.to_string()) {
+        return None;
+    }
+    match config.get_string(&format!("pretty.{}", arg)).ok().flatten() {
+        Some(alias) => resolve_format_inner(&alias, config, seen),
+        None => Some(ResolvedFormat::User { template: arg.to_string(), terminator: true }),
+    }
+}
+
+fn builtin_from_name(name: &str) -> Option<BuiltinFormat> {
+    match name {
+        "oneline" => Some(BuiltinFormat::Oneline),
+        "short" => Some(BuiltinFormat::Short),
+        "medium" => Some(BuiltinFormat::Medium),
+        "full" => Some(BuiltinFormat::Full),
+        "fuller" => Some(BuiltinFormat::Fuller),
+        "email" => Some(BuiltinFormat::Email),
+        "raw" => Some(BuiltinFormat::Raw),
+        _ => None,
+    }
+}
+
+/// One unit of a parsed format template: either a run of literal text, or a
+/// placeholder that gets expanded against a specific commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+/// The expensive-to-recompute placeholders: truncating a hash to
+/// `abbrev_len` is cheap today, but this is exactly the set that becomes a
+/// real shortest-unique-prefix lookup against the object database once that
+/// lands, so each is computed at most once per commit and cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum AbbrevKind {
+    Hash,
+    Tree,
+    Parents,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Placeholder {
+    FullHash,
+    AbbrevHash,
+    FullTree,
+    AbbrevTree,
+    FullParents,
+    AbbrevParents,
+    AuthorName,
+    AuthorEmail,
+    AuthorNameMailmap,
+    AuthorEmailMailmap,
+    AuthorDate,
+    AuthorDateRfc2822,
+    AuthorDateIsoStrict,
+    AuthorDateIso,
+    AuthorDateUnix,
+    AuthorDateRelative,
+    CommitterName,
+    CommitterEmail,
+    CommitterNameMailmap,
+    CommitterEmailMailmap,
+    CommitterDate,
+    CommitterDateRfc2822,
+    CommitterDateIsoStrict,
+    CommitterDateIso,
+    CommitterDateUnix,
+    CommitterDateRelative,
+    Subject,
+    Body,
+    RawBody,
+    Encoding,
+    DecorationParens,
+    DecorationCommaSeparated,
+    Newline,
+    ColorNamed(Color),
+    ColorSpec(String),
+    Trailers(TrailerOptions),
+    Align(AlignSpec),
+}
+
+/// How `%<(...)`/`%>(...)`/`%><(...)`/`%<|(...)` pad the captured span: pad on
+/// the right (left-align), pad on the left (right-align), split the padding
+/// between both sides (center), or pad on the right up to an absolute output
+/// column rather than a relative width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AlignKind {
+    Left,
+    Right,
+    Center,
+    AbsoluteColumn,
+}
+
+/// How an over-long captured span is shortened to fit `width`: left alone
+/// (the default — git just emits the full value), or ellipsized at the
+/// front, middle, or back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TruncMode {
+    None,
+    Trunc,
+    LTrunc,
+    MTrunc,
+}
+
+/// Parsed form of a `%<(N[,trunc|ltrunc|mtrunc])`-style alignment
+/// placeholder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AlignSpec {
+    kind: AlignKind,
+    width: usize,
+    trunc: TruncMode,
+}
+
+/// Parse the inside of an alignment placeholder's parens, e.g. `"10"` or
+/// `"10,trunc"`, into an [`AlignSpec`] of the given `kind`.
+fn parse_align_spec(spec: &str, kind: AlignKind) -> AlignSpec {
+    let mut parts = spec.split(',');
+    let width = parts.next().unwrap_or("").trim().parse().unwrap_or(0);
+    let mut trunc = TruncMode::None;
+    for part in parts {
+        trunc = match part.trim() {
+            "trunc" => TruncMode::Trunc,
+            "ltrunc" => TruncMode::LTrunc,
+            "mtrunc" => TruncMode::MTrunc,
+            _ => trunc,
+        };
+    }
+    AlignSpec { kind, width, trunc }
+}
+
+/// Options for `%(trailers)`/`%(trailers:...)`, matching the subset of C
+/// git's trailer-formatting options this crate supports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrailerOptions {
+    /// `key=<name>`: only include trailers whose key matches (case-insensitive).
+    key: Option<String>,
+    /// `valueonly`: emit just the value, without the `key: ` prefix.
+    value_only: bool,
+    /// `separator=<sep>`: joins multiple trailers (default: one per line).
+    /// `%xNN` hex escapes are decoded, so a literal comma can be embedded.
+    separator: String,
+    /// `unfold`: collapse a folded continuation line into its trailer's
+    /// value with a single space instead of an embedded newline.
+    unfold: bool,
+}
+
+impl Default for TrailerOptions {
+    fn default() -> Self {
+        Self { key: None, value_only: false, separator: "\n".to_string(), unfold: false }
+    }
+}
+
+/// Whether `line` looks like a trailer ("Key: value") or a folded
+/// continuation of one (leading whitespace), for the purpose of deciding
+/// whether a message's final paragraph is an existing trailer block.
+fn looks_like_trailer_line(line: &str) -> bool {
+    if line.starts_with(' ') || line.starts_with('\t') {
+        return true;
+    }
+    match line.find(':') {
+        Some(colon) if colon > 0 => {
+            let key = &line[..colon];
+            let rest = &line[colon + 1..];
+            !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && (rest.is_empty() || rest.starts_with(' '))
+        }
+        _ => false,
+    }
+}
+
+/// Parse the commit message's trailer block (its final paragraph, if a
+/// strict majority of its lines look like trailers) into `(key, value)`
+/// pairs. A folded continuation line (leading whitespace) is appended to the
+/// preceding trailer's value separated by `\n`; callers that want it
+/// collapsed to a single line should pass `unfold` and join with a space.
+fn parse_trailers(message: &[u8]) -> Vec<(String, String)> {
+    let text = String::from_utf8_lossy(message);
+    let mut lines: Vec<&str> = text.lines().collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let last_blank = lines.iter().rposition(|l| l.trim().is_empty());
+    let para_start = last_blank.map(|i| i + 1).unwrap_or(0);
+    let paragraph = &lines[para_start..];
+    if paragraph.is_empty() {
+        return Vec::new();
+    }
+
+    let trailer_like = paragraph.iter().filter(|l| looks_like_trailer_line(l)).count();
+    if trailer_like * 2 <= paragraph.len() {
+        return Vec::new();
+    }
+
+    let mut trailers: Vec<(String, String)> = Vec::new();
+    for line in paragraph {
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some((_, value)) = trailers.last_mut() {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some(colon) = line.find(':') {
+            if colon > 0 {
+                let key = line[..colon].trim().to_string();
+                let value = line[colon + 1..].trim().to_string();
+                trailers.push((key, value));
+            }
         }
     }
+    trailers
+}
+
+/// Parse a `%(trailers:...)` option string (comma-separated `key=value` or
+/// bare flags) into [`TrailerOptions`]. Unknown options are ignored, for
+/// forward compatibility with options this crate doesn't implement.
+fn parse_trailer_options(opts: &str) -> TrailerOptions {
+    let mut result = TrailerOptions::default();
+    for part in opts.split(',').filter(|p| !p.is_empty()) {
+        if part == "unfold" {
+            result.unfold = true;
+        } else if part == "valueonly" {
+            result.value_only = true;
+        } else if let Some(key) = part.strip_prefix("key=") {
+            result.key = Some(key.to_string());
+        } else if let Some(sep) = part.strip_prefix("separator=") {
+            result.separator = decode_separator(sep);
+        }
+    }
+    result
+}
+
+/// Decode `%xNN` hex escapes in a `separator=` value, so e.g. `%x2c` yields
+/// a literal comma (which can't appear raw, since commas separate options).
+fn decode_separator(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '%' && chars.get(i + 1) == Some(&'x') {
+            if let (Some(&hi), Some(&lo)) = (chars.get(i + 2), chars.get(i + 3)) {
+                if let Ok(byte) = u8::from_str_radix(&format!("{hi}{lo}"), 16) {
+                    out.push(byte as char);
+                    i += 4;
+                    continue;
+                }
+            }
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+/// A format template parsed once into a token list, ready to be rendered
+/// against any number of commits via [`render`](ParsedFormat::render)
+/// without re-parsing the format string for each one.
+#[derive(Debug, Clone)]
+pub struct ParsedFormat {
+    tokens: Vec<Token>,
+}
+
+impl ParsedFormat {
+    /// Parse a format string (see [`format_commit`] for the supported
+    /// specifiers) into tokens once, up front.
+    pub fn parse(format: &str) -> Self {
+        Self { tokens: parse_tokens(format) }
+    }
+
+    /// Render this parsed format against a single commit. Abbreviated-hash
+    /// placeholders (`%h`, `%t`, `%p`) are computed at most once per call,
+    /// even if the template repeats the same placeholder several times.
+    pub fn render(
+        &self,
+        commit: &Commit,
+        oid: &ObjectId,
+        options: &FormatOptions,
+        mailmap: Option<&Mailmap>,
+        decorations: Option<&HashMap<ObjectId, Vec<String>>>,
+    ) -> String {
+        render_tokens(&self.tokens, commit, oid, options, mailmap, decorations)
+    }
+}
+
+fn parse_tokens(format: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut chars = format.chars().peekable();
+
+    macro_rules! flush_literal {
+        () => {
+            if !literal.is_empty() {
+                tokens.push(Token::Literal(std::mem::take(&mut literal)));
+            }
+        };
+    }
+    macro_rules! push {
+        ($placeholder:expr) => {{
+            flush_literal!();
+            tokens.push(Token::Placeholder($placeholder));
+        }};
+    }
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            literal.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some('%') => {
+                chars.next();
+                literal.push('%');
+            }
+            Some('d') => {
+                chars.next();
+                push!(Placeholder::DecorationParens);
+            }
+            Some('D') => {
+                chars.next();
+                push!(Placeholder::DecorationCommaSeparated);
+            }
+            Some('H') => {
+                chars.next();
+                push!(Placeholder::FullHash);
+            }
+            Some('h') => {
+                chars.next();
+                push!(Placeholder::AbbrevHash);
+            }
+            Some('T') => {
+                chars.next();
+                push!(Placeholder::FullTree);
+            }
+            Some('t') => {
+                chars.next();
+                push!(Placeholder::AbbrevTree);
+            }
+            Some('P') => {
+                chars.next();
+                push!(Placeholder::FullParents);
+            }
+            Some('p') => {
+                chars.next();
+                push!(Placeholder::AbbrevParents);
+            }
+            Some('a') => {
+                chars.next();
+                match chars.peek() {
+                    Some('n') => { chars.next(); push!(Placeholder::AuthorName); }
+                    Some('e') => { chars.next(); push!(Placeholder::AuthorEmail); }
+                    Some('N') => { chars.next(); push!(Placeholder::AuthorNameMailmap); }
+                    Some('E') => { chars.next(); push!(Placeholder::AuthorEmailMailmap); }
+                    Some('d') => { chars.next(); push!(Placeholder::AuthorDate); }
+                    Some('D') => { chars.next(); push!(Placeholder::AuthorDateRfc2822); }
+                    Some('I') => { chars.next(); push!(Placeholder::AuthorDateIsoStrict); }
+                    Some('i') => { chars.next(); push!(Placeholder::AuthorDateIso); }
+                    Some('t') => { chars.next(); push!(Placeholder::AuthorDateUnix); }
+                    Some('r') => { chars.next(); push!(Placeholder::AuthorDateRelative); }
+                    _ => literal.push_str("%a"),
+                }
+            }
+            Some('c') => {
+                chars.next();
+                match chars.peek() {
+                    Some('n') => { chars.next(); push!(Placeholder::CommitterName); }
+                    Some('e') => { chars.next(); push!(Placeholder::CommitterEmail); }
+                    Some('N') => { chars.next(); push!(Placeholder::CommitterNameMailmap); }
+                    Some('E') => { chars.next(); push!(Placeholder::CommitterEmailMailmap); }
+                    Some('d') => { chars.next(); push!(Placeholder::CommitterDate); }
+                    Some('D') => { chars.next(); push!(Placeholder::CommitterDateRfc2822); }
+                    Some('I') => { chars.next(); push!(Placeholder::CommitterDateIsoStrict); }
+                    Some('i') => { chars.next(); push!(Placeholder::CommitterDateIso); }
+                    Some('t') => { chars.next(); push!(Placeholder::CommitterDateUnix); }
+                    Some('r') => { chars.next(); push!(Placeholder::CommitterDateRelative); }
+                    _ => literal.push_str("%c"),
+                }
+            }
+            Some('s') => { chars.next(); push!(Placeholder::Subject); }
+            Some('b') => { chars.next(); push!(Placeholder::Body); }
+            Some('B') => { chars.next(); push!(Placeholder::RawBody); }
+            Some('e') => { chars.next(); push!(Placeholder::Encoding); }
+            Some('n') => { chars.next(); push!(Placeholder::Newline); }
+            Some('<') => {
+                chars.next();
+                if chars.peek() == Some(&'|') {
+                    chars.next();
+                    if chars.peek() == Some(&'(') {
+                        chars.next();
+                        let spec = read_paren_spec(&mut chars);
+                        push!(Placeholder::Align(parse_align_spec(&spec, AlignKind::AbsoluteColumn)));
+                    } else {
+                        literal.push_str("%<|");
+                    }
+                } else if chars.peek() == Some(&'(') {
+                    chars.next();
+                    let spec = read_paren_spec(&mut chars);
+                    push!(Placeholder::Align(parse_align_spec(&spec, AlignKind::Left)));
+                } else {
+                    literal.push_str("%<");
+                }
+            }
+            Some('>') => {
+                chars.next();
+                if chars.peek() == Some(&'<') {
+                    chars.next();
+                    if chars.peek() == Some(&'(') {
+                        chars.next();
+                        let spec = read_paren_spec(&mut chars);
+                        push!(Placeholder::Align(parse_align_spec(&spec, AlignKind::Center)));
+                    } else {
+                        literal.push_str("%><");
+                    }
+                } else if chars.peek() == Some(&'(') {
+                    chars.next();
+                    let spec = read_paren_spec(&mut chars);
+                    push!(Placeholder::Align(parse_align_spec(&spec, AlignKind::Right)));
+                } else {
+                    literal.push_str("%>");
+                }
+            }
+            Some('(') => {
+                chars.next();
+                let spec = read_paren_spec(&mut chars);
+                if spec == "trailers" || spec.starts_with("trailers:") {
+                    let opts = spec.strip_prefix("trailers:").unwrap_or("");
+                    push!(Placeholder::Trailers(parse_trailer_options(opts)));
+                } else {
+                    literal.push('%');
+                    literal.push('(');
+                    literal.push_str(&spec);
+                    literal.push(')');
+                }
+            }
+            Some('C') => {
+                chars.next();
+                if chars.peek() == Some(&'(') {
+                    chars.next();
+                    let spec = read_paren_spec(&mut chars);
+                    push!(Placeholder::ColorSpec(spec));
+                } else {
+                    let mut lookahead = chars.clone();
+                    let mut word = String::new();
+                    while let Some(&c) = lookahead.peek() {
+                        if c.is_ascii_alphabetic() {
+                            word.push(c);
+                            lookahead.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    let matched = [
+                        ("red", Color::Red),
+                        ("green", Color::Green),
+                        ("blue", Color::Blue),
+                        ("reset", Color::Reset),
+                    ]
+                    .into_iter()
+                    .find(|(name, _)| word.starts_with(name));
+                    match matched {
+                        Some((name, color)) => {
+                            for _ in 0..name.len() {
+                                chars.next();
+                            }
+                            push!(Placeholder::ColorNamed(color));
+                        }
+                        None => literal.push_str("%C"),
+                    }
+                }
+            }
+            _ => literal.push('%'),
+        }
+    }
+    flush_literal!();
+    tokens
+}
+
+/// Consume characters up to (and including) the next `)`, returning
+/// everything before it. Used for every `%X(...)`-shaped placeholder
+/// (`%C(...)`, `%(trailers:...)`, `%<(...)`, `%>(...)`, ...).
+fn read_paren_spec(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut spec = String::new();
+    for ch in chars.by_ref() {
+        if ch == ')' {
+            break;
+        }
+        spec.push(ch);
+    }
+    spec
+}
+
+/// Decode a slice of a commit message's raw bytes according to the commit's
+/// declared `encoding` header, so `%s`/`%b`/`%B` don't mojibake a non-UTF-8
+/// history by blindly assuming UTF-8. `"ISO-8859-1"`/`"latin1"` (any casing,
+/// with or without the hyphen) decode byte-for-byte into Unicode code points
+/// 0–255, which is exactly what Latin-1 is. Anything else — no header,
+/// `"UTF-8"`, or an encoding this crate doesn't recognize — decodes as UTF-8
+/// (lossily, replacing invalid sequences), which is also today's fallback
+/// once the header is unavailable or unrecognized.
+fn decode_message(bytes: &[u8], source_encoding: Option<&bstr::BStr>) -> String {
+    let is_latin1 = source_encoding.is_some_and(|enc| {
+        let enc = String::from_utf8_lossy(enc).to_ascii_lowercase();
+        matches!(enc.as_str(), "latin1" | "iso-8859-1" | "iso8859-1" | "8859-1")
+    });
+    if is_latin1 {
+        bytes.iter().map(|&b| b as char).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// Approximate display width of `s`: counts Unicode scalar values rather
+/// than UTF-8 bytes, so multibyte names (accented characters, non-Latin
+/// scripts) align by character count instead of by their often-longer
+/// byte encoding. This doesn't account for East-Asian double-width or
+/// zero-width combining characters, but gets ordinary names right without
+/// a width-table dependency.
+fn display_width(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// The display width of `result` since its last newline (or from the
+/// start, if it has none) — the current output column, used by `%<|(N)`.
+fn current_column(result: &str) -> usize {
+    match result.rfind('\n') {
+        Some(idx) => display_width(&result[idx + 1..]),
+        None => display_width(result),
+    }
+}
+
+/// Shorten `s` to `width` display columns, inserting `..` at the position
+/// `trunc` specifies. With [`TruncMode::None`], `s` is only hard-cut (no
+/// ellipsis) if it's still over width after the caller's padding check.
+fn truncate_to_width(s: &str, width: usize, trunc: TruncMode) -> String {
+    if width == 0 {
+        return String::new();
+    }
+    if trunc == TruncMode::None {
+        return s.chars().take(width).collect();
+    }
+    const ELLIPSIS: &str = "..";
+    let ellipsis_width = display_width(ELLIPSIS);
+    if width <= ellipsis_width {
+        return ELLIPSIS.chars().take(width).collect();
+    }
+    let keep = width - ellipsis_width;
+    let chars: Vec<char> = s.chars().collect();
+    match trunc {
+        TruncMode::Trunc => {
+            let head: String = chars[..keep.min(chars.len())].iter().collect();
+            format!("{}{}", head, ELLIPSIS)
+        }
+        TruncMode::LTrunc => {
+            let start = chars.len().saturating_sub(keep);
+            let tail: String = chars[start..].iter().collect();
+            format!("{}{}", ELLIPSIS, tail)
+        }
+        TruncMode::MTrunc => {
+            let head_len = keep - keep / 2;
+            let tail_len = keep - head_len;
+            let head: String = chars[..head_len.min(chars.len())].iter().collect();
+            let tail_start = chars.len().saturating_sub(tail_len).max(head.chars().count());
+            let tail: String = chars[tail_start..].iter().collect();
+            format!("{}{}{}", head, ELLIPSIS, tail)
+        }
+        TruncMode::None => unreachable!(),
+    }
+}
+
+/// Pad or truncate a captured span to satisfy `spec`, the way C git's
+/// `%<`/`%>`/`%><`/`%<|` placeholders do. `column` is the current output
+/// column (display width since the last newline), needed only for
+/// [`AlignKind::AbsoluteColumn`].
+fn apply_align(content: &str, spec: &AlignSpec, column: usize) -> String {
+    let width = match spec.kind {
+        AlignKind::AbsoluteColumn => spec.width.saturating_sub(column),
+        _ => spec.width,
+    };
+    let content_width = display_width(content);
+    let shaped = if content_width > width {
+        truncate_to_width(content, width, spec.trunc)
+    } else {
+        content.to_string()
+    };
+    let pad = width.saturating_sub(display_width(&shaped));
+    match spec.kind {
+        AlignKind::Right => format!("{}{}", " ".repeat(pad), shaped),
+        AlignKind::Center => {
+            let left = pad / 2;
+            let right = pad - left;
+            format!("{}{}{}", " ".repeat(left), shaped, " ".repeat(right))
+        }
+        AlignKind::Left | AlignKind::AbsoluteColumn => format!("{}{}", shaped, " ".repeat(pad)),
+    }
+}
+
+fn render_tokens(
+    tokens: &[Token],
+    commit: &Commit,
+    oid: &ObjectId,
+    options: &FormatOptions,
+    mailmap: Option<&Mailmap>,
+    decorations: Option<&HashMap<ObjectId, Vec<String>>>,
+) -> String {
+    let mut result = String::new();
+    let color_enabled = use_color_stdout(options.color);
+    let mut abbrev_cache: HashMap<AbbrevKind, String> = HashMap::new();
+
+    let mut abbrev_hash = || {
+        abbrev_cache
+            .entry(AbbrevKind::Hash)
+            .or_insert_with(|| {
+                let hex = oid.to_hex();
+                hex[..options.abbrev_len.min(hex.len())].to_string()
+            })
+            .clone()
+    };
+    let mut abbrev_tree = || {
+        abbrev_cache
+            .entry(AbbrevKind::Tree)
+            .or_insert_with(|| {
+                let hex = commit.tree.to_hex();
+                hex[..options.abbrev_len.min(hex.len())].to_string()
+            })
+            .clone()
+    };
+    let mut abbrev_parents = || {
+        abbrev_cache
+            .entry(AbbrevKind::Parents)
+            .or_insert_with(|| {
+                commit
+                    .parents
+                    .iter()
+                    .map(|p| {
+                        let hex = p.to_hex();
+                        hex[..options.abbrev_len.min(hex.len())].to_string()
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .clone()
+    };
+
+    // `%<`/`%>`/`%><`/`%<|` don't expand to fixed text themselves: they open
+    // a span that captures every following token's output until the next
+    // newline or alignment placeholder, then pad or truncate the captured
+    // span as a whole before it's appended to `result`. `pending_align`
+    // holds that span's spec and buffer while it's being captured.
+    let mut pending_align: Option<(AlignSpec, String)> = None;
+
+    macro_rules! flush_align {
+        () => {
+            if let Some((spec, buf)) = pending_align.take() {
+                let column = current_column(&result);
+                result.push_str(&apply_align(&buf, &spec, column));
+            }
+        };
+    }
+
+    for token in tokens {
+        if let Token::Placeholder(Placeholder::Align(spec)) = token {
+            flush_align!();
+            pending_align = Some((*spec, String::new()));
+            continue;
+        }
+        if matches!(token, Token::Placeholder(Placeholder::Newline)) {
+            flush_align!();
+            result.push('\n');
+            continue;
+        }
+
+        let target: &mut String = match &mut pending_align {
+            Some((_, buf)) => buf,
+            None => &mut result,
+        };
+
+        match token {
+            Token::Literal(s) => target.push_str(s),
+            Token::Placeholder(p) => match p {
+                Placeholder::DecorationParens => {
+                    if let Some(refs) = decorations.and_then(|d| d.get(oid)) {
+                        if !refs.is_empty() {
+                            target.push_str(" (");
+                            target.push_str(&refs.join(", "));
+                            target.push(')');
+                        }
+                    }
+                }
+                Placeholder::DecorationCommaSeparated => {
+                    if let Some(refs) = decorations.and_then(|d| d.get(oid)) {
+                        target.push_str(&refs.join(", "));
+                    }
+                }
+                Placeholder::FullHash => target.push_str(&oid.to_hex()),
+                Placeholder::AbbrevHash => target.push_str(&abbrev_hash()),
+                Placeholder::FullTree => target.push_str(&commit.tree.to_hex()),
+                Placeholder::AbbrevTree => target.push_str(&abbrev_tree()),
+                Placeholder::FullParents => {
+                    let parents: Vec<String> =
+                        commit.parents.iter().map(|p| p.to_hex()).collect();
+                    target.push_str(&parents.join(" "));
+                }
+                Placeholder::AbbrevParents => target.push_str(&abbrev_parents()),
+                Placeholder::AuthorName => {
+                    target.push_str(&String::from_utf8_lossy(&commit.author.name))
+                }
+                Placeholder::AuthorEmail => {
+                    target.push_str(&String::from_utf8_lossy(&commit.author.email))
+                }
+                Placeholder::AuthorNameMailmap => {
+                    let (name, _) =
+                        resolve_identity(mailmap, &commit.author.name, &commit.author.email);
+                    target.push_str(&name);
+                }
+                Placeholder::AuthorEmailMailmap => {
+                    let (_, email) =
+                        resolve_identity(mailmap, &commit.author.name, &commit.author.email);
+                    target.push_str(&email);
+                }
+                Placeholder::AuthorDate => {
+                    target.push_str(&commit.author.date.format(options.date_format))
+                }
+                Placeholder::AuthorDateRfc2822 => {
+                    target.push_str(&commit.author.date.format(DateFormat::Rfc2822))
+                }
+                Placeholder::AuthorDateIsoStrict => {
+                    target.push_str(&commit.author.date.format(DateFormat::IsoStrict))
+                }
+                Placeholder::AuthorDateIso => {
+                    target.push_str(&commit.author.date.format(DateFormat::Iso))
+                }
+                Placeholder::AuthorDateUnix => {
+                    target.push_str(&commit.author.date.format(DateFormat::Unix))
+                }
+                Placeholder::AuthorDateRelative => {
+                    target.push_str(&commit.author.date.format(DateFormat::Relative))
+                }
+                Placeholder::CommitterName => {
+                    target.push_str(&String::from_utf8_lossy(&commit.committer.name))
+                }
+                Placeholder::CommitterEmail => {
+                    target.push_str(&String::from_utf8_lossy(&commit.committer.email))
+                }
+                Placeholder::CommitterNameMailmap => {
+                    let (name, _) = resolve_identity(
+                        mailmap,
+                        &commit.committer.name,
+                        &commit.committer.email,
+                    );
+                    target.push_str(&name);
+                }
+                Placeholder::CommitterEmailMailmap => {
+                    let (_, email) = resolve_identity(
+                        mailmap,
+                        &commit.committer.name,
+                        &commit.committer.email,
+                    );
+                    target.push_str(&email);
+                }
+                Placeholder::CommitterDate => {
+                    target.push_str(&commit.committer.date.format(options.date_format))
+                }
+                Placeholder::CommitterDateRfc2822 => {
+                    target.push_str(&commit.committer.date.format(DateFormat::Rfc2822))
+                }
+                Placeholder::CommitterDateIsoStrict => {
+                    target.push_str(&commit.committer.date.format(DateFormat::IsoStrict))
+                }
+                Placeholder::CommitterDateIso => {
+                    target.push_str(&commit.committer.date.format(DateFormat::Iso))
+                }
+                Placeholder::CommitterDateUnix => {
+                    target.push_str(&commit.committer.date.format(DateFormat::Unix))
+                }
+                Placeholder::CommitterDateRelative => {
+                    target.push_str(&commit.committer.date.format(DateFormat::Relative))
+                }
+                Placeholder::Subject => {
+                    target.push_str(&decode_message(commit.summary(), commit.encoding.as_deref()))
+                }
+                Placeholder::Body => {
+                    if let Some(body) = commit.body() {
+                        target.push_str(&decode_message(body, commit.encoding.as_deref()));
+                    }
+                }
+                Placeholder::RawBody => {
+                    target.push_str(&decode_message(&commit.message, commit.encoding.as_deref()))
+                }
+                Placeholder::Encoding => {
+                    if let Some(enc) = &commit.encoding {
+                        target.push_str(&String::from_utf8_lossy(enc));
+                    }
+                }
+                // Handled above, before the span-vs-result target is chosen.
+                Placeholder::Newline => target.push('\n'),
+                Placeholder::ColorNamed(color) => {
+                    if color_enabled {
+                        target.push_str(color.ansi_code());
+                    }
+                }
+                Placeholder::ColorSpec(spec) => {
+                    if color_enabled {
+                        target.push_str(&parse_color_value(spec));
+                    }
+                }
+                Placeholder::Trailers(opts) => {
+                    let trailers = parse_trailers(&commit.message);
+                    let rendered: Vec<String> = trailers
+                        .iter()
+                        .filter(|(k, _)| {
+                            opts.key.as_deref().map_or(true, |want| k.eq_ignore_ascii_case(want))
+                        })
+                        .map(|(k, v)| {
+                            let value = if opts.unfold { v.replace('\n', " ") } else { v.clone() };
+                            if opts.value_only {
+                                value
+                            } else {
+                                format!("{}: {}", k, value)
+                            }
+                        })
+                        .collect();
+                    target.push_str(&rendered.join(&opts.separator));
+                }
+                // Handled above, before the span-vs-result target is chosen.
+                Placeholder::Align(_) => {}
+            },
+        }
+    }
+    flush_align!();
+
+    result
 }
 
 /// Format a commit with the given format string.
@@ -53,7 +952,8 @@ impl Default for FormatOptions {
 /// - `%p` — abbreviated parent hashes
 /// - `%an` — author name
 /// - `%ae` — author email
-/// - `%aE` — author email (respstrstrict)
+/// - `%aN` — author name, mailmap-resolved (see [`format_commit_with_decorations`])
+/// - `%aE` — author email, mailmap-resolved
 /// - `%ad` — author date (format by --date=)
 /// - `%aD` — author date, RFC2822
 /// - `%aI` — author date, ISO 8601 strict
@@ -62,6 +962,8 @@ impl Default for FormatOptions {
 /// - `%ar` — author date, relative
 /// - `%cn` — committer name
 /// - `%ce` — committer email
+/// - `%cN` — committer name, mailmap-resolved
+/// - `%cE` — committer email, mailmap-resolved
 /// - `%cd` — committer date
 /// - `%cD` — committer date, RFC2822
 /// - `%cI` — committer date, ISO 8601 strict
@@ -71,186 +973,73 @@ impl Default for FormatOptions {
 /// - `%s` — subject (first line of message)
 /// - `%b` — body (rest of message)
 /// - `%B` — raw body (full message)
+/// - `%e` — the commit's declared encoding header (e.g. `ISO-8859-1`), or
+///   nothing if it has none. `%s`/`%b`/`%B` are decoded from this encoding
+///   (see `FormatOptions::output_encoding`) rather than assumed to be UTF-8.
+/// - `%d` — ref decorations, space-led and wrapped in parens (see [`format_commit_with_decorations`])
+/// - `%D` — ref decorations, comma-separated, no wrapping
+/// - `%Cred`/`%Cgreen`/`%Cblue`/`%Creset` — ANSI color (see `options.color`)
+/// - `%C(...)` — ANSI color by name/attribute, e.g. `%C(bold red)`, `%C(#ff8800)`
 /// - `%n` — newline
 /// - `%%` — literal %
+/// - `%(trailers)`/`%(trailers:<options>)` — the commit message's trailer
+///   block (`Signed-off-by:`, `Co-authored-by:`, etc.), one per line by
+///   default. Comma-separated `<options>`: `key=<name>` (only trailers with
+///   that key, case-insensitive), `valueonly` (omit the `key: ` prefix),
+///   `separator=<sep>` (join trailers with `<sep>` instead of `\n`; `%xNN`
+///   hex escapes are decoded, so `%x2c` embeds a literal comma), `unfold`
+///   (collapse a folded continuation line into its value with a space
+///   instead of a newline).
+/// - `%<(N)`/`%<(N,trunc)`/`%<(N,ltrunc)`/`%<(N,mtrunc)` — pad the following
+///   placeholder(s)' output (everything up to the next newline or alignment
+///   placeholder) to at least `N` display columns, left-aligned; an
+///   over-long value is left as-is unless `trunc`/`ltrunc`/`mtrunc` asks for
+///   a trailing/leading/middle `..` ellipsis instead.
+/// - `%>(N[,...])` — same, but right-aligned (padding on the left).
+/// - `%><(N[,...])` — same, but centered (padding split across both sides).
+/// - `%<|(N[,...])` — like `%<(N)`, but `N` is an absolute output column
+///   (measured from the start of the current line) rather than a width.
+///
+/// `%aN`/`%aE`/`%cN`/`%cE` are only resolved through a mailmap when called
+/// via [`format_commit_with_decorations`]; here they behave just like their
+/// lowercase counterparts, since there's no mailmap to consult.
+///
+/// This re-parses `format` on every call; if you're formatting many commits
+/// with the same format string (as `git log` does), parse it once with
+/// [`ParsedFormat::parse`] and call [`ParsedFormat::render`] per commit
+/// instead.
 pub fn format_commit(
     commit: &Commit,
     oid: &ObjectId,
     format: &str,
     options: &FormatOptions,
 ) -> String {
-    let mut result = String::new();
-    let mut chars = format.chars().peekable();
+    format_commit_impl(commit, oid, format, options, None, None)
+}
 
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            match chars.peek() {
-                Some('%') => {
-                    chars.next();
-                    result.push('%');
-                }
-                Some('H') => {
-                    chars.next();
-                    result.push_str(&oid.to_hex());
-                }
-                Some('h') => {
-                    chars.next();
-                    let hex = oid.to_hex();
-                    let abbrev = &hex[..options.abbrev_len.min(hex.len())];
-                    result.push_str(abbrev);
-                }
-                Some('T') => {
-                    chars.next();
-                    result.push_str(&commit.tree.to_hex());
-                }
-                Some('t') => {
-                    chars.next();
-                    let hex = commit.tree.to_hex();
-                    result.push_str(&hex[..options.abbrev_len.min(hex.len())]);
-                }
-                Some('P') => {
-                    chars.next();
-                    let parents: Vec<String> =
-                        commit.parents.iter().map(|p| p.to_hex()).collect();
-                    result.push_str(&parents.join(" "));
-                }
-                Some('p') => {
-                    chars.next();
-                    let parents: Vec<String> = commit
-                        .parents
-                        .iter()
-                        .map(|p| {
-                            let hex = p.to_hex();
-                            hex[..options.abbrev_len.min(hex.len())].to_string()
-                        })
-                        .collect();
-                    result.push_str(&parents.join(" "));
-                }
-                Some('a') => {
-                    chars.next();
-                    match chars.peek() {
-                        Some('n') => {
-                            chars.next();
-                            result.push_str(&String::from_utf8_lossy(&commit.author.name));
-                        }
-                        Some('e') | Some('E') => {
-                            chars.next();
-                            result.push_str(&String::from_utf8_lossy(&commit.author.email));
-                        }
-                        Some('d') => {
-                            chars.next();
-                            result.push_str(&commit.author.date.format(options.date_format));
-                        }
-                        Some('D') => {
-                            chars.next();
-                            result.push_str(
-                                &commit.author.date.format(DateFormat::Rfc2822),
-                            );
-                        }
-                        Some('I') => {
-                            chars.next();
-                            result.push_str(
-                                &commit.author.date.format(DateFormat::IsoStrict),
-                            );
-                        }
-                        Some('i') => {
-                            chars.next();
-                            result.push_str(&commit.author.date.format(DateFormat::Iso));
-                        }
-                        Some('t') => {
-                            chars.next();
-                            result.push_str(
-                                &commit.author.date.format(DateFormat::Unix),
-                            );
-                        }
-                        Some('r') => {
-                            chars.next();
-                            result.push_str(
-                                &commit.author.date.format(DateFormat::Relative),
-                            );
-                        }
-                        _ => {
-                            result.push_str("%a");
-                        }
-                    }
-                }
-                Some('c') => {
-                    chars.next();
-                    match chars.peek() {
-                        Some('n') => {
-                            chars.next();
-                            result.push_str(&String::from_utf8_lossy(&commit.committer.name));
-                        }
-                        Some('e') | Some('E') => {
-                            chars.next();
-                            result.push_str(&String::from_utf8_lossy(&commit.committer.email));
-                        }
-                        Some('d') => {
-                            chars.next();
-                            result
-                                .push_str(&commit.committer.date.format(options.date_format));
-                        }
-                        Some('D') => {
-                            chars.next();
-                            result.push_str(
-                                &commit.committer.date.format(DateFormat::Rfc2822),
-                            );
-                        }
-                        Some('I') => {
-                            chars.next();
-                            result.push_str(
-                                &commit.committer.date.format(DateFormat::IsoStrict),
-                            );
-                        }
-                        Some('i') => {
-                            chars.next();
-                            result.push_str(&commit.committer.date.format(DateFormat::Iso));
-                        }
-                        Some('t') => {
-                            chars.next();
-                            result.push_str(
-                                &commit.committer.date.format(DateFormat::Unix),
-                            );
-                        }
-                        Some('r') => {
-                            chars.next();
-                            result.push_str(
-                                &commit.committer.date.format(DateFormat::Relative),
-                            );
-                        }
-                        _ => {
-                            result.push_str("%c");
-                        }
-                    }
-                }
-                Some('s') => {
-                    chars.next();
-                    result.push_str(&String::from_utf8_lossy(commit.summary()));
-                }
-                Some('b') => {
-                    chars.next();
-                    if let Some(body) = commit.body() {
-                        result.push_str(&String::from_utf8_lossy(body));
-                    }
-                }
-                Some('B') => {
-                    chars.next();
-                    result.push_str(&String::from_utf8_lossy(&commit.message));
-                }
-                Some('n') => {
-                    chars.next();
-                    result.push('\n');
-                }
-                _ => {
-                    result.push('%');
-                }
-            }
-        } else {
-            result.push(c);
-        }
-    }
+/// Like [`format_commit`], but resolves `%aN`/`%aE`/`%cN`/`%cE` through
+/// `mailmap` (when given) and expands `%d`/`%D` from `decorations` (a map of
+/// commit OID to the ref names pointing at it, in display order).
+pub fn format_commit_with_decorations(
+    commit: &Commit,
+    oid: &ObjectId,
+    format: &str,
+    options: &FormatOptions,
+    mailmap: Option<&Mailmap>,
+    decorations: Option<&HashMap<ObjectId, Vec<String>>>,
+) -> String {
+    format_commit_impl(commit, oid, format, options, mailmap, decorations)
+}
 
-    result
+fn format_commit_impl(
+    commit: &Commit,
+    oid: &ObjectId,
+    format: &str,
+    options: &FormatOptions,
+    mailmap: Option<&Mailmap>,
+    decorations: Option<&HashMap<ObjectId, Vec<String>>>,
+) -> String {
+    ParsedFormat::parse(format).render(commit, oid, options, mailmap, decorations)
 }
 
 /// Format a commit with a built-in format preset.
@@ -264,7 +1053,7 @@ pub fn format_builtin(
         BuiltinFormat::Oneline => {
             let hex = oid.to_hex();
             let abbrev = &hex[..options.abbrev_len.min(hex.len())];
-            let summary = String::from_utf8_lossy(commit.summary());
+            let summary = decode_message(commit.summary(), commit.encoding.as_deref());
             format!("{} {}", abbrev, summary)
         }
         BuiltinFormat::Short => {
@@ -278,7 +1067,7 @@ pub fn format_builtin(
             out.push('\n');
             out.push_str(&format!(
                 "    {}\n",
-                String::from_utf8_lossy(commit.summary())
+                decode_message(commit.summary(), commit.encoding.as_deref())
             ));
             out
         }
@@ -296,8 +1085,9 @@ pub fn format_builtin(
             ));
             out.push('\n');
             // Indent each line of message with 4 spaces.
-            for line in commit.message.lines() {
-                out.push_str(&format!("    {}\n", String::from_utf8_lossy(line)));
+            let message = decode_message(&commit.message, commit.encoding.as_deref());
+            for line in message.lines() {
+                out.push_str(&format!("    {}\n", line));
             }
             out
         }
@@ -315,8 +1105,9 @@ pub fn format_builtin(
                 String::from_utf8_lossy(&commit.committer.email)
             ));
             out.push('\n');
-            for line in commit.message.lines() {
-                out.push_str(&format!("    {}\n", String::from_utf8_lossy(line)));
+            let message = decode_message(&commit.message, commit.encoding.as_deref());
+            for line in message.lines() {
+                out.push_str(&format!("    {}\n", line));
             }
             out
         }
@@ -342,8 +1133,9 @@ pub fn format_builtin(
                 commit.committer.date.format(options.date_format)
             ));
             out.push('\n');
-            for line in commit.message.lines() {
-                out.push_str(&format!("    {}\n", String::from_utf8_lossy(line)));
+            let message = decode_message(&commit.message, commit.encoding.as_deref());
+            for line in message.lines() {
+                out.push_str(&format!("    {}\n", line));
             }
             out
         }
@@ -364,11 +1156,11 @@ pub fn format_builtin(
             ));
             out.push_str(&format!(
                 "Subject: [PATCH] {}\n",
-                String::from_utf8_lossy(commit.summary())
+                decode_message(commit.summary(), commit.encoding.as_deref())
             ));
             out.push('\n');
             if let Some(body) = commit.body() {
-                out.push_str(&String::from_utf8_lossy(body));
+                out.push_str(&decode_message(body, commit.encoding.as_deref()));
             }
             out
         }
@@ -393,3 +1185,56 @@ pub fn format_builtin(
         }
     }
 }
+
+/// Like [`format_builtin`], but inserts ref decorations (see
+/// [`format_commit_with_decorations`]) after the `commit <hash>` header line
+/// (or after the abbreviated hash, for [`BuiltinFormat::Oneline`]), matching
+/// how `git log --decorate` annotates each commit.
+pub fn format_builtin_with_decorations(
+    commit: &Commit,
+    oid: &ObjectId,
+    preset: BuiltinFormat,
+    options: &FormatOptions,
+    decorations: Option<&HashMap<ObjectId, Vec<String>>>,
+) -> String {
+    let formatted = format_builtin(commit, oid, preset, options);
+    let Some(refs) = decorations.and_then(|d| d.get(oid)) else {
+        return formatted;
+    };
+    if refs.is_empty() {
+        return formatted;
+    }
+    let decoration = format!(" ({})", refs.join(", "));
+
+    if preset == BuiltinFormat::Oneline {
+        // "<hash> <subject>" -> "<hash> (refs) <subject>"
+        match formatted.split_once(' ') {
+            Some((hash, rest)) => format!("{}{} {}", hash, decoration, rest),
+            None => formatted + &decoration,
+        }
+    } else {
+        // "commit <hash>\n..." -> "commit <hash> (refs)\n..."
+        match formatted.split_once('\n') {
+            Some((first_line, rest)) => format!("{}{}\n{}", first_line, decoration, rest),
+            None => formatted + &decoration,
+        }
+    }
+}
+
+/// Resolve a name/email pair through `mailmap`, if given; otherwise return
+/// them unchanged. Used by `%aN`/`%aE`/`%cN`/`%cE`.
+fn resolve_identity(mailmap: Option<&Mailmap>, name: &[u8], email: &[u8]) -> (String, String) {
+    match mailmap {
+        Some(mm) => {
+            let (resolved_name, resolved_email) = mm.lookup(name, email);
+            (
+                String::from_utf8_lossy(&resolved_name).to_string(),
+                String::from_utf8_lossy(&resolved_email).to_string(),
+            )
+        }
+        None => (
+            String::from_utf8_lossy(name).to_string(),
+            String::from_utf8_lossy(email).to_string(),
+        ),
+    }
+}
@@ -1,14 +1,17 @@
 //! Core revision walk iterator.
 
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::collections::{BinaryHeap, HashSet};
+use std::path::{Path, PathBuf};
 
+use bstr::{BString, ByteSlice};
 use git_hash::ObjectId;
 use git_object::{Commit, Object, ObjectType};
 use git_ref::RefStore;
 use git_repository::Repository;
 
 use crate::commit_graph::CommitGraph;
+use crate::index::Index;
 use crate::RevWalkError;
 
 /// Lightweight commit metadata for traversal (no author/committer strings).
@@ -48,32 +51,144 @@ pub struct WalkOptions {
     pub author_pattern: Option<String>,
     pub committer_pattern: Option<String>,
     pub grep_pattern: Option<String>,
+    /// Limit history to commits that modified one of these paths (directories
+    /// included), like `git log -- <path>...`. See
+    /// [`RevWalk::add_pathspec`].
+    pub pathspec: Vec<PathBuf>,
+    /// Rewrite parent pointers for history simplification (`--parents`-style
+    /// output): a parent reached by a TREESAME edge (it didn't touch
+    /// `pathspec`) is replaced by its nearest non-TREESAME ancestor(s). Only
+    /// takes effect when `pathspec` is non-empty; consumed via
+    /// [`RevWalk::next_commit`] rather than the plain `ObjectId` iterator.
+    pub rewrite_parents: bool,
 }
 
-/// An entry in the walk priority queue.
-struct WalkEntry {
+/// A commit yielded by [`RevWalk::next_commit`], carrying its rewritten
+/// parent pointers alongside its OID.
+#[derive(Debug, Clone)]
+pub struct WalkCommit {
+    pub oid: ObjectId,
+    /// Parent pointers after history simplification: each parent reached by
+    /// a TREESAME edge (see [`WalkOptions::rewrite_parents`]) is replaced by
+    /// its nearest non-TREESAME ancestor(s), with duplicates that collapse
+    /// together removed. Equal to the commit's real parents when
+    /// `rewrite_parents` is off or no pathspec is set.
+    pub rewritten_parents: Vec<ObjectId>,
+}
+
+/// A pending [`RevWalk::nearest_non_treesame_ancestors`] resolution, ordered
+/// by commit date so the lazy chain-collapsing walk expands newer commits
+/// first, matching the order a date-ordered walk would normally reach them.
+struct RewriteWork {
     oid: ObjectId,
+    date: i64,
+}
+
+impl PartialEq for RewriteWork {
+    fn eq(&self, other: &Self) -> bool {
+        self.oid == other.oid
+    }
+}
+
+impl Eq for RewriteWork {}
+
+impl PartialOrd for RewriteWork {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RewriteWork {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.date.cmp(&other.date)
+    }
+}
+
+/// An entry in the walk priority queue.
+///
+/// Fields are `pub(crate)` so other modules in this crate (e.g.
+/// [`crate::describe`]) can build a date-ordered walk on the same queue
+/// shape without duplicating the ordering logic below.
+pub(crate) struct WalkEntry {
+    pub(crate) oid: ObjectId,
     /// Committer timestamp (seconds since epoch).
-    commit_date: i64,
+    pub(crate) commit_date: i64,
     /// Author timestamp (seconds since epoch).
-    author_date: i64,
+    pub(crate) author_date: i64,
     /// Generation number from commit-graph (0 if unavailable).
-    generation: u32,
+    pub(crate) generation: u32,
     /// Insertion counter for stable ordering.
-    insertion_ctr: u64,
+    pub(crate) insertion_ctr: u64,
+}
+
+/// An entry in the "limit" phase queue used to discover the reachable set
+/// and in-degrees for topological ordering, ordered by generation number
+/// (descending) when a commit-graph is available, falling back to
+/// committer date otherwise.
+struct LimitEntry {
+    oid: ObjectId,
+    generation: u32,
+    date: i64,
+    order_by_generation: bool,
+}
+
+impl PartialEq for LimitEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.oid == other.oid
+    }
+}
+
+impl Eq for LimitEntry {}
+
+impl PartialOrd for LimitEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LimitEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Max-heap: highest generation (or newest date, when generation
+        // numbers aren't available) first.
+        if self.order_by_generation {
+            self.generation.cmp(&other.generation)
+        } else {
+            self.date.cmp(&other.date)
+        }
+    }
 }
 
 /// State tracking for topological sort.
+///
+/// Expansion is lazy and split into the two phases Git itself uses: "limit"
+/// (discover commits and in-degrees, generation-ordered so it can stop once
+/// enough commits are known to be ready) and "emit" (hand out in-degree-0
+/// commits in the walk's requested order). Both phases interleave as
+/// [`RevWalk::next_topo`] is driven, rather than running limit to completion
+/// up front, so `--topo-order` honors `max_count` without materializing the
+/// whole history.
 struct TopoState {
-    /// In-degree count for each commit (number of children not yet emitted).
+    /// Commits discovered but not yet expanded into their parents.
+    limit_queue: BinaryHeap<LimitEntry>,
+    /// In-degree count for each discovered commit (number of children not yet emitted).
     indegree: std::collections::HashMap<ObjectId, u32>,
-    /// Queue of commits ready to emit (in-degree == 0).
-    ready: VecDeque<ObjectId>,
-    /// All commits collected in the limited phase (retained for ancestry-path filtering).
-    #[allow(dead_code)]
-    commits: Vec<ObjectId>,
-    /// Commit dates for sorting the ready queue.
+    /// Committer dates for discovered commits.
     dates: std::collections::HashMap<ObjectId, i64>,
+    /// Generation numbers for discovered commits (0 if unavailable).
+    generations: std::collections::HashMap<ObjectId, u32>,
+    /// In-degree-0 commits whose in-degree isn't yet guaranteed final: a
+    /// not-yet-expanded commit still in `limit_queue` could turn out to be a
+    /// child that points back at one of these, incrementing its in-degree
+    /// again.
+    pending_ready: Vec<ObjectId>,
+    /// In-degree-0 commits confirmed final, ordered for emission by the
+    /// walk's requested sort order.
+    emit_heap: BinaryHeap<WalkEntry>,
+    /// Whether `limit_queue` (and the finality check for `pending_ready`) is
+    /// ordered by generation rather than committer date.
+    order_by_generation: bool,
+    /// Insertion counter for stable ordering within `emit_heap`.
+    next_ctr: u64,
 }
 
 /// Revision walk iterator over commits.
@@ -104,6 +219,13 @@ pub struct RevWalk<'a> {
     prepared: bool,
     /// Buffer for reverse mode: collected commits in forward order, popped from end.
     reverse_buffer: Option<Vec<ObjectId>>,
+    /// Memoized nearest-non-TREESAME-ancestor resolutions, keyed by the
+    /// commit whose rewritten replacement set is cached. See
+    /// [`Self::nearest_non_treesame_ancestors`].
+    rewrite_cache: std::collections::HashMap<ObjectId, Vec<ObjectId>>,
+    /// Optional prebuilt position index (see [`Self::with_index`]) used to
+    /// accelerate ancestor computations that don't need full commit data.
+    index: Option<&'a Index>,
 }
 
 impl<'a> RevWalk<'a> {
@@ -125,9 +247,23 @@ impl<'a> RevWalk<'a> {
             topo_state: None,
             prepared: false,
             reverse_buffer: None,
+            rewrite_cache: std::collections::HashMap::new(),
+            index: None,
         })
     }
 
+    /// Create a revision walker accelerated by a prebuilt [`Index`] (see
+    /// [`Index::build`]): [`Self::hide`] resolves entirely over the index's
+    /// position integers and a seen-bitset for any commit it covers,
+    /// skipping the repeated ODB reads [`Self::mark_hidden`] would
+    /// otherwise do, falling back to the plain walk for anything outside
+    /// it. Other traversal paths are unaffected.
+    pub fn with_index(repo: &'a Repository, index: &'a Index) -> Result<Self, RevWalkError> {
+        let mut walk = Self::new(repo)?;
+        walk.index = Some(index);
+        Ok(walk)
+    }
+
     /// Add a starting commit (positive reference).
     pub fn push(&mut self, oid: ObjectId) -> Result<(), RevWalkError> {
         if self.seen.contains(&oid) {
@@ -207,6 +343,63 @@ impl<'a> RevWalk<'a> {
         self.options = options;
     }
 
+    /// Follow only the first parent at merge commits (`--first-parent`).
+    pub fn set_first_parent(&mut self, first_parent_only: bool) {
+        self.options.first_parent_only = first_parent_only;
+    }
+
+    /// Restrict the walk to commits within `[since, until]` by committer
+    /// date (`--since`/`--until`); either bound may be omitted.
+    ///
+    /// When a commit-graph with generation data is available, a `since`
+    /// bound lets a date-ordered walk stop descending into a subtree early
+    /// instead of walking it fully — see [`Self::prunable_for_since`].
+    pub fn set_date_range(&mut self, since: Option<i64>, until: Option<i64>) {
+        self.options.since = since;
+        self.options.until = until;
+    }
+
+    /// Only emit commits whose author or committer name/email contains
+    /// `pattern` (`--author`).
+    pub fn add_author_filter(&mut self, pattern: impl Into<String>) {
+        self.options.author_pattern = Some(pattern.into());
+    }
+
+    /// Only emit commits whose message contains `pattern` (`--grep`).
+    pub fn add_grep(&mut self, pattern: impl Into<String>) {
+        self.options.grep_pattern = Some(pattern.into());
+    }
+
+    /// Stop after emitting `count` commits (`-n`/`--max-count`).
+    pub fn set_max_count(&mut self, count: usize) {
+        self.options.max_count = Some(count);
+    }
+
+    /// Skip the first `count` commits that would otherwise be emitted
+    /// (`--skip`).
+    pub fn set_skip(&mut self, count: usize) {
+        self.options.skip = Some(count);
+    }
+
+    /// Limit the walk to commits that touched `path` (directories included),
+    /// like `git log -- <path>`. May be called more than once to match any
+    /// of several paths.
+    ///
+    /// Commits are pruned using the commit-graph's changed-path Bloom filter
+    /// when available; otherwise (or when the filter is inconclusive for any
+    /// of the paths) the candidate is confirmed with a real tree diff of its
+    /// `tree_oid` against its first parent's (TREESAME detection).
+    pub fn add_pathspec(&mut self, path: impl Into<PathBuf>) {
+        self.options.pathspec.push(path.into());
+    }
+
+    /// Enable history-simplification parent rewriting (`--parents`-style
+    /// output), consumed via [`Self::next_commit`]. Only takes effect when a
+    /// pathspec is also set (see [`Self::add_pathspec`]).
+    pub fn set_rewrite_parents(&mut self, rewrite_parents: bool) {
+        self.options.rewrite_parents = rewrite_parents;
+    }
+
     /// Parse and apply a revision range ("A..B", "A...B", "^A B").
     pub fn push_range(&mut self, range_spec: &str) -> Result<(), RevWalkError> {
         let range = crate::range::RevisionRange::parse(self.repo, range_spec)?;
@@ -306,9 +499,42 @@ impl<'a> RevWalk<'a> {
         )
     }
 
+    /// Whether a date-ordered walk can stop descending past `oid` because no
+    /// descendant of it could satisfy a `--since` cutoff.
+    ///
+    /// Relies on the commit-graph's corrected commit dates, which are
+    /// constructed to never decrease from parent to child even when
+    /// committer clocks are skewed. If `oid`'s corrected date already falls
+    /// below `since`, every ancestor's corrected date is bounded by the same
+    /// value, so none of them can pass the filter either.
+    fn prunable_for_since(&self, oid: &ObjectId) -> bool {
+        match (self.options.since, &self.commit_graph) {
+            (Some(since), Some(cg)) => {
+                cg.corrected_commit_date(oid).is_some_and(|cdate| cdate < since)
+            }
+            _ => false,
+        }
+    }
+
     /// Mark a commit and all its ancestors as hidden.
-    /// Uses generation numbers from the commit-graph to prune early when possible.
+    ///
+    /// When [`Self::with_index`] was used and `oid` is covered by the index,
+    /// this walks parent positions over the index's dense arrays instead of
+    /// touching the ODB at all. Otherwise it falls back to the plain walk,
+    /// using generation numbers from the commit-graph to prune early when
+    /// possible.
     fn mark_hidden(&mut self, oid: ObjectId) -> Result<(), RevWalkError> {
+        if let Some(index) = self.index {
+            if let Some(pos) = index.position(&oid) {
+                for ancestor_pos in index.ancestor_positions(pos) {
+                    if let Some(ancestor_oid) = index.oid_at(ancestor_pos) {
+                        self.hidden.insert(ancestor_oid);
+                    }
+                }
+                return Ok(());
+            }
+        }
+
         let mut stack = vec![oid];
         while let Some(current) = stack.pop() {
             if !self.hidden.insert(current) {
@@ -325,143 +551,228 @@ impl<'a> RevWalk<'a> {
         Ok(())
     }
 
-    /// Prepare the topological sort by collecting all reachable commits
-    /// and computing in-degrees.
+    /// Seed the lazy topological-sort state from the walk's starting
+    /// commits. Does not expand any of them yet — expansion happens
+    /// on-demand in [`Self::expand_limit_step`], driven by [`Self::next_topo`].
     fn prepare_topo(&mut self) -> Result<(), RevWalkError> {
         if self.prepared {
             return Ok(());
         }
         self.prepared = true;
 
-        // Collect all commits reachable from the queue (limited by hidden set).
-        let mut all_commits: Vec<ObjectId> = Vec::new();
-        let mut parents_map: std::collections::HashMap<ObjectId, Vec<ObjectId>> =
+        let order_by_generation = self.commit_graph.is_some();
+        let mut limit_queue: BinaryHeap<LimitEntry> = BinaryHeap::new();
+        let mut indegree: std::collections::HashMap<ObjectId, u32> =
             std::collections::HashMap::new();
         let mut dates: std::collections::HashMap<ObjectId, i64> =
             std::collections::HashMap::new();
-        let mut indegree: std::collections::HashMap<ObjectId, u32> =
+        let mut generations: std::collections::HashMap<ObjectId, u32> =
             std::collections::HashMap::new();
 
-        // Drain the priority queue into a BFS queue.
-        let mut bfs: VecDeque<ObjectId> = VecDeque::new();
-        let mut visited: HashSet<ObjectId> = HashSet::new();
-
         while let Some(entry) = self.queue.pop() {
-            if !visited.contains(&entry.oid) {
-                bfs.push_back(entry.oid);
-                visited.insert(entry.oid);
+            if indegree.contains_key(&entry.oid) {
+                continue;
             }
+            indegree.insert(entry.oid, 0);
+            dates.insert(entry.oid, entry.commit_date);
+            generations.insert(entry.oid, entry.generation);
+            limit_queue.push(LimitEntry {
+                oid: entry.oid,
+                generation: entry.generation,
+                date: entry.commit_date,
+                order_by_generation,
+            });
         }
 
-        // BFS to discover all commits.
-        while let Some(oid) = bfs.pop_front() {
-            if self.hidden.contains(&oid) {
-                continue;
-            }
-            let meta = self.read_commit_meta(&oid)?;
-            let commit_date = meta.commit_time;
-            dates.insert(oid, commit_date);
+        self.topo_state = Some(TopoState {
+            limit_queue,
+            indegree,
+            dates,
+            generations,
+            pending_ready: Vec::new(),
+            emit_heap: BinaryHeap::new(),
+            order_by_generation,
+            next_ctr: 0,
+        });
 
-            let parents: Vec<ObjectId> = if self.options.first_parent_only {
-                meta.parents.first().copied().into_iter().collect()
-            } else {
-                meta.parents
-            };
+        Ok(())
+    }
+
+    /// Pop and expand one commit from the "limit" phase queue: record its
+    /// in-degree, mark it pending-ready if it has none, and discover its
+    /// parents (incrementing their in-degree, enqueuing first-seen ones).
+    ///
+    /// Returns `false` once the limit queue is exhausted and there is
+    /// nothing left to expand.
+    fn expand_limit_step(&mut self) -> Result<bool, RevWalkError> {
+        let Some(entry) = self
+            .topo_state
+            .as_mut()
+            .and_then(|state| state.limit_queue.pop())
+        else {
+            return Ok(false);
+        };
+
+        if self.hidden.contains(&entry.oid) {
+            return Ok(true);
+        }
+
+        let meta = self.read_commit_meta(&entry.oid)?;
+        let parents: Vec<ObjectId> = if self.options.first_parent_only {
+            meta.parents.first().copied().into_iter().collect()
+        } else {
+            meta.parents
+        };
 
-            // Initialize in-degree for this commit if not yet seen.
-            indegree.entry(oid).or_insert(0);
+        let mut newly_discovered: Vec<ObjectId> = Vec::new();
+        {
+            let state = self.topo_state.as_mut().unwrap();
+            state.dates.insert(entry.oid, meta.commit_time);
+            state.generations.insert(entry.oid, meta.generation);
+            state.indegree.entry(entry.oid).or_insert(0);
+            if state.indegree.get(&entry.oid).copied() == Some(0) {
+                state.pending_ready.push(entry.oid);
+            }
 
             for parent in &parents {
-                if !self.hidden.contains(parent) {
-                    // Increment parent's in-degree (it has a child pointing to it).
-                    *indegree.entry(*parent).or_insert(0) += 1;
-                    if visited.insert(*parent) {
-                        bfs.push_back(*parent);
-                    }
+                if self.hidden.contains(parent) {
+                    continue;
+                }
+                let first_seen = !state.indegree.contains_key(parent);
+                *state.indegree.entry(*parent).or_insert(0) += 1;
+                if first_seen {
+                    newly_discovered.push(*parent);
                 }
             }
+        }
 
-            parents_map.insert(oid, parents);
-            all_commits.push(oid);
+        for parent in newly_discovered {
+            if let Ok(parent_meta) = self.read_commit_meta(&parent) {
+                let state = self.topo_state.as_mut().unwrap();
+                state.dates.insert(parent, parent_meta.commit_time);
+                state.generations.insert(parent, parent_meta.generation);
+                state.limit_queue.push(LimitEntry {
+                    oid: parent,
+                    generation: parent_meta.generation,
+                    date: parent_meta.commit_time,
+                    order_by_generation: state.order_by_generation,
+                });
+            }
         }
 
-        // Find tips (in-degree == 0) — these are the starting points.
-        let mut ready: VecDeque<ObjectId> = VecDeque::new();
-        // Sort tips by date for deterministic output.
-        let mut tips: Vec<ObjectId> = all_commits
-            .iter()
-            .filter(|oid| indegree.get(oid).copied().unwrap_or(0) == 0)
-            .copied()
-            .collect();
-        tips.sort_by(|a, b| {
-            let da = dates.get(a).copied().unwrap_or(0);
-            let db = dates.get(b).copied().unwrap_or(0);
-            db.cmp(&da) // newest first
-        });
-        for tip in tips {
-            ready.push_back(tip);
+        Ok(true)
+    }
+
+    /// Move any `pending_ready` commit whose in-degree is confirmed final
+    /// (no commit still in the limit queue could turn out to be an
+    /// undiscovered child of it — see [`TopoState::pending_ready`]) into
+    /// `emit_heap`.
+    ///
+    /// With a commit-graph, generation numbers strictly decrease from child
+    /// to parent, so a commit at generation `G` can only gain new children
+    /// while the limit queue still holds an unexpanded entry with
+    /// generation `> G` — letting ready commits stream out before the whole
+    /// queue drains. Without one, committer date gives no such guarantee
+    /// (clock skew, `--date`, rebases, and merges of long-lived branches all
+    /// produce children with an *older* date than their parent routinely),
+    /// so nothing is final until `limit_queue` is fully drained — the whole
+    /// reachable set's in-degrees are known at that point, same as a plain
+    /// Kahn's-algorithm topo sort.
+    fn promote_ready(&mut self) {
+        let Some(state) = self.topo_state.as_mut() else {
+            return;
+        };
+        if state.pending_ready.is_empty() {
+            return;
         }
 
-        self.topo_state = Some(TopoState {
-            indegree,
-            ready,
-            commits: all_commits,
-            dates,
-        });
+        let generation_threshold: Option<i64> = state
+            .order_by_generation
+            .then(|| state.limit_queue.peek().map(|e| e.generation as i64))
+            .flatten();
+        let queue_drained = state.limit_queue.is_empty();
 
-        // Store parents_map in a way we can access it during iteration.
-        // We'll re-read commits as needed during next_topo().
-        // The topo_state.commits vector has all the OIDs.
+        let pending = std::mem::take(&mut state.pending_ready);
+        for oid in pending {
+            if state.indegree.get(&oid).copied().unwrap_or(0) != 0 {
+                continue;
+            }
 
-        Ok(())
+            let is_final = if state.order_by_generation {
+                let generation = state.generations.get(&oid).copied().unwrap_or(0) as i64;
+                match generation_threshold {
+                    Some(t) => generation > t,
+                    None => true,
+                }
+            } else {
+                queue_drained
+            };
+
+            if is_final {
+                let commit_date = state.dates.get(&oid).copied().unwrap_or(0);
+                let generation = state.generations.get(&oid).copied().unwrap_or(0);
+                let ctr = state.next_ctr;
+                state.next_ctr += 1;
+                state.emit_heap.push(WalkEntry {
+                    oid,
+                    commit_date,
+                    author_date: commit_date,
+                    generation,
+                    insertion_ctr: ctr,
+                });
+            } else {
+                state.pending_ready.push(oid);
+            }
+        }
     }
 
     /// Get the next commit in topological order.
+    ///
+    /// Interleaves the "limit" (discover in-degrees) and "emit" (hand out
+    /// in-degree-0 commits) phases: only expands as much of the limit queue
+    /// as needed to produce the next emittable commit, so a walk bounded by
+    /// `max_count` never has to materialize the whole history.
     fn next_topo(&mut self) -> Result<Option<ObjectId>, RevWalkError> {
         if !self.prepared {
             self.prepare_topo()?;
         }
 
-        // Pop the next ready commit (in-degree == 0).
-        let oid = match self.topo_state.as_mut() {
-            Some(state) if !state.ready.is_empty() => state.ready.pop_front().unwrap(),
-            _ => return Ok(None),
-        };
-
-        // Read commit metadata to get parents (graph-accelerated).
-        let meta = self.read_commit_meta(&oid)?;
-        let parents: Vec<ObjectId> = if self.options.first_parent_only {
-            meta.parents.first().copied().into_iter().collect()
-        } else {
-            meta.parents
-        };
-
-        // Filter parents by hidden set first (immutable borrow of self.hidden).
-        let parents: Vec<ObjectId> = parents
-            .into_iter()
-            .filter(|p| !self.hidden.contains(p))
-            .collect();
+        loop {
+            self.promote_ready();
+
+            if let Some(state) = self.topo_state.as_mut() {
+                if let Some(entry) = state.emit_heap.pop() {
+                    let oid = entry.oid;
+
+                    let meta = self.read_commit_meta(&oid)?;
+                    let parents: Vec<ObjectId> = if self.options.first_parent_only {
+                        meta.parents.first().copied().into_iter().collect()
+                    } else {
+                        meta.parents
+                    };
+                    let parents: Vec<ObjectId> = parents
+                        .into_iter()
+                        .filter(|p| !self.hidden.contains(p))
+                        .collect();
+
+                    let state = self.topo_state.as_mut().unwrap();
+                    for parent in &parents {
+                        if let Some(deg) = state.indegree.get_mut(parent) {
+                            *deg = deg.saturating_sub(1);
+                            if *deg == 0 {
+                                state.pending_ready.push(*parent);
+                            }
+                        }
+                    }
 
-        // Now borrow topo_state mutably to update indegrees.
-        let state = self.topo_state.as_mut().unwrap();
-        let mut newly_ready: Vec<(ObjectId, i64)> = Vec::new();
-        for parent in &parents {
-            if let Some(deg) = state.indegree.get_mut(parent) {
-                *deg = deg.saturating_sub(1);
-                if *deg == 0 {
-                    let date = state.dates.get(parent).copied().unwrap_or(0);
-                    newly_ready.push((*parent, date));
+                    return Ok(Some(oid));
                 }
             }
-        }
 
-        // Sort newly ready by date (newest first) for deterministic output.
-        newly_ready.sort_by(|a, b| b.1.cmp(&a.1));
-        for (parent, _) in newly_ready {
-            state.ready.push_back(parent);
+            if !self.expand_limit_step()? {
+                return Ok(None);
+            }
         }
-
-        Ok(Some(oid))
     }
 
     /// Get the next commit for date-ordered walks (chronological, author-date).
@@ -483,7 +794,7 @@ impl<'a> RevWalk<'a> {
             };
 
             for parent in parents {
-                if self.seen.insert(parent) && !self.hidden.contains(&parent) {
+                if self.seen.insert(parent) && !self.hidden.contains(&parent) && !self.prunable_for_since(&parent) {
                     if let Ok(parent_meta) = self.read_commit_meta(&parent) {
                         self.enqueue_meta(parent, &parent_meta);
                     }
@@ -515,7 +826,7 @@ impl<'a> RevWalk<'a> {
                             meta.parents
                         };
                         for parent in parents {
-                            if self.seen.insert(parent) && !self.hidden.contains(&parent) {
+                            if self.seen.insert(parent) && !self.hidden.contains(&parent) && !self.prunable_for_since(&parent) {
                                 if let Ok(parent_meta) = self.read_commit_meta(&parent) {
                                     self.enqueue_meta(parent, &parent_meta);
                                 }
@@ -550,6 +861,169 @@ impl<'a> RevWalk<'a> {
         true
     }
 
+    /// Whether `oid` touched any path in [`WalkOptions::pathspec`], consulting
+    /// the commit-graph's changed-path Bloom filter for each path before
+    /// falling back to a single real tree diff (TREESAME detection) against
+    /// the first parent.
+    ///
+    /// A commit is only skipped (treated as TREESAME) when every path's
+    /// Bloom filter comes back "definitely absent" -- an inconclusive or
+    /// absent filter for even one path forces the real diff.
+    fn passes_pathspec_filter(&self, oid: &ObjectId) -> Result<bool, RevWalkError> {
+        if self.options.pathspec.is_empty() {
+            return Ok(true);
+        }
+
+        if let Some(cg) = &self.commit_graph {
+            let all_definitely_absent = self.options.pathspec.iter().all(|path| {
+                cg.maybe_changed_path(oid, path_to_bstring(path).as_bstr()) == Some(false)
+            });
+            if all_definitely_absent {
+                return Ok(false);
+            }
+        }
+
+        let meta = self.read_commit_meta(oid)?;
+        let parent_tree = meta.parents.first().copied();
+        Ok(!self.is_treesame_edge(&meta.tree_oid, parent_tree.as_ref())?)
+    }
+
+    /// Whether any of [`WalkOptions::pathspec`] matches `file_path` (a
+    /// tree-relative path from a diff, directories included).
+    fn matches_any_pathspec(&self, file_path: &[u8]) -> bool {
+        self.options.pathspec.iter().any(|path| {
+            let prefix_bstring = path_to_bstring(path);
+            let prefix = prefix_bstring.as_bstr().as_bytes();
+            file_path == prefix
+                || (file_path.starts_with(prefix) && file_path.get(prefix.len()) == Some(&b'/'))
+        })
+    }
+
+    /// Whether the edge from a commit with tree `tree` to `parent` is
+    /// TREESAME under [`WalkOptions::pathspec`] -- i.e. none of the
+    /// pathspec paths differ between the two trees. Without a pathspec
+    /// there's nothing to simplify, so every edge is non-TREESAME.
+    fn is_treesame_edge(&self, tree: &ObjectId, parent: Option<&ObjectId>) -> Result<bool, RevWalkError> {
+        if self.options.pathspec.is_empty() {
+            return Ok(false);
+        }
+
+        let parent_tree = match parent {
+            Some(p) => Some(self.read_commit_meta(p)?.tree_oid),
+            None => None,
+        };
+        let diff = git_diff::tree::diff_trees(
+            self.repo.odb(),
+            parent_tree.as_ref(),
+            Some(tree),
+            &git_diff::DiffOptions::default(),
+        )?;
+        Ok(!diff
+            .files
+            .iter()
+            .any(|f| self.matches_any_pathspec(f.path().as_bstr().as_bytes())))
+    }
+
+    /// Resolve a commit's rewritten parent pointers for history
+    /// simplification: each parent reached by a TREESAME edge is replaced
+    /// by its nearest non-TREESAME ancestor(s) (see
+    /// [`Self::nearest_non_treesame_ancestors`]), with duplicates that
+    /// collapse together removed. A no-op (returns `parents` unchanged)
+    /// unless both `rewrite_parents` and a pathspec are set.
+    fn resolve_rewritten_parents(
+        &mut self,
+        tree: ObjectId,
+        parents: Vec<ObjectId>,
+    ) -> Result<Vec<ObjectId>, RevWalkError> {
+        if !self.options.rewrite_parents || self.options.pathspec.is_empty() {
+            return Ok(parents);
+        }
+
+        let mut rewritten: Vec<ObjectId> = Vec::new();
+        for parent in &parents {
+            if self.is_treesame_edge(&tree, Some(parent))? {
+                for ancestor in self.nearest_non_treesame_ancestors(*parent)? {
+                    if !rewritten.contains(&ancestor) {
+                        rewritten.push(ancestor);
+                    }
+                }
+            } else if !rewritten.contains(parent) {
+                rewritten.push(*parent);
+            }
+        }
+        Ok(rewritten)
+    }
+
+    /// Find the nearest ancestor(s) of `start` reachable only through
+    /// TREESAME edges -- i.e. what `start` should be replaced by in a
+    /// rewritten parent list. Returns `start` itself (wrapped in a
+    /// single-element `Vec`) when its own edge to at least one parent is
+    /// non-TREESAME, following every such parent; TREESAME parents are
+    /// chased further, and a root (no parents) contributes nothing.
+    ///
+    /// Resolutions are memoized in [`Self::rewrite_cache`]. The chain is
+    /// expanded lazily via a commit-date-ordered priority queue (newest
+    /// first, mirroring the order a normal date-ordered walk would reach
+    /// these commits) rather than recursing, so long TREESAME chains don't
+    /// recurse the call stack.
+    fn nearest_non_treesame_ancestors(&mut self, start: ObjectId) -> Result<Vec<ObjectId>, RevWalkError> {
+        if let Some(cached) = self.rewrite_cache.get(&start) {
+            return Ok(cached.clone());
+        }
+
+        let mut frontier: BinaryHeap<RewriteWork> = BinaryHeap::new();
+        let mut on_frontier: HashSet<ObjectId> = HashSet::new();
+        let mut order: Vec<ObjectId> = Vec::new();
+        let mut edges: std::collections::HashMap<ObjectId, Vec<(ObjectId, bool)>> =
+            std::collections::HashMap::new();
+
+        let start_meta = self.read_commit_meta(&start)?;
+        frontier.push(RewriteWork { oid: start, date: start_meta.commit_time });
+        on_frontier.insert(start);
+
+        while let Some(work) = frontier.pop() {
+            if self.rewrite_cache.contains_key(&work.oid) || edges.contains_key(&work.oid) {
+                continue;
+            }
+            let meta = self.read_commit_meta(&work.oid)?;
+            let mut this_edges = Vec::new();
+            for parent in &meta.parents {
+                let treesame = self.is_treesame_edge(&meta.tree_oid, Some(parent))?;
+                this_edges.push((*parent, treesame));
+                if treesame && !self.rewrite_cache.contains_key(parent) && on_frontier.insert(*parent) {
+                    let parent_meta = self.read_commit_meta(parent)?;
+                    frontier.push(RewriteWork { oid: *parent, date: parent_meta.commit_time });
+                }
+            }
+            edges.insert(work.oid, this_edges);
+            order.push(work.oid);
+        }
+
+        // Discovery order has each commit before any TREESAME parent it
+        // pushed onto the frontier, so resolving back-to-front guarantees a
+        // commit's TREESAME parents are already memoized.
+        for oid in order.into_iter().rev() {
+            if self.rewrite_cache.contains_key(&oid) {
+                continue;
+            }
+            let mut resolved: Vec<ObjectId> = Vec::new();
+            for (parent, treesame) in edges.remove(&oid).unwrap_or_default() {
+                if treesame {
+                    for ancestor in self.rewrite_cache.get(&parent).cloned().unwrap_or_default() {
+                        if !resolved.contains(&ancestor) {
+                            resolved.push(ancestor);
+                        }
+                    }
+                } else if !resolved.contains(&parent) {
+                    resolved.push(parent);
+                }
+            }
+            self.rewrite_cache.insert(oid, resolved);
+        }
+
+        Ok(self.rewrite_cache.get(&start).cloned().unwrap_or_default())
+    }
+
     /// Apply pattern filters (--author, --committer, --grep).
     fn passes_pattern_filter(&self, commit: &Commit) -> bool {
         if let Some(ref pattern) = self.options.author_pattern {
@@ -574,6 +1048,21 @@ impl<'a> RevWalk<'a> {
         }
         true
     }
+
+    /// Like iterating the walk directly, but yields a [`WalkCommit`]
+    /// carrying rewritten parent pointers for history simplification
+    /// (`--parents`-style output) per [`WalkOptions::rewrite_parents`].
+    pub fn next_commit(&mut self) -> Result<Option<WalkCommit>, RevWalkError> {
+        match Iterator::next(self) {
+            Some(Ok(oid)) => {
+                let meta = self.read_commit_meta(&oid)?;
+                let rewritten_parents = self.resolve_rewritten_parents(meta.tree_oid, meta.parents)?;
+                Ok(Some(WalkCommit { oid, rewritten_parents }))
+            }
+            Some(Err(e)) => Err(e),
+            None => Ok(None),
+        }
+    }
 }
 
 impl Iterator for RevWalk<'_> {
@@ -616,6 +1105,14 @@ impl Iterator for RevWalk<'_> {
                 }
             }
 
+            if !self.options.pathspec.is_empty() {
+                match self.passes_pathspec_filter(&oid) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+
             // Handle --skip.
             if let Some(skip) = self.options.skip {
                 if self.skipped < skip {
@@ -657,3 +1154,15 @@ impl Ord for WalkEntry {
     }
 }
 
+/// Convert a path to the raw-byte representation git tree entries use.
+#[cfg(unix)]
+fn path_to_bstring(path: &Path) -> BString {
+    use std::os::unix::ffi::OsStrExt;
+    BString::from(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn path_to_bstring(path: &Path) -> BString {
+    BString::from(path.to_string_lossy().as_bytes())
+}
+
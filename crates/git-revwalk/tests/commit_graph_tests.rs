@@ -240,3 +240,39 @@ fn commit_graph_writer_roundtrip() {
         String::from_utf8_lossy(&verify_output.stderr)
     );
 }
+
+/// Generation numbers are `1 + max(parent generation)`, and corrected commit
+/// dates clamp a committer clock that runs backwards so they never decrease
+/// from parent to child.
+#[test]
+fn commit_graph_generation_and_corrected_dates() {
+    let root = ObjectId::from_hex("0000000000000000000000000000000000000a").unwrap();
+    let middle = ObjectId::from_hex("0000000000000000000000000000000000000b").unwrap();
+    let tip = ObjectId::from_hex("0000000000000000000000000000000000000c").unwrap();
+    let tree = ObjectId::from_hex("0000000000000000000000000000000000000d").unwrap();
+
+    let mut writer = CommitGraphWriter::new(HashAlgorithm::Sha1);
+    writer.add_commit(root, tree, vec![], 1000);
+    // middle's committer clock runs backwards relative to root.
+    writer.add_commit(middle, tree, vec![root], 500);
+    writer.add_commit(tip, tree, vec![middle], 2000);
+
+    let dir = tempfile::tempdir().unwrap();
+    let graph_path = dir.path().join("commit-graph");
+    writer.write(&graph_path).unwrap();
+
+    let graph = CommitGraph::open(&graph_path).unwrap();
+    graph.verify().unwrap();
+
+    assert_eq!(graph.generation(&root), Some(1));
+    assert_eq!(graph.generation(&middle), Some(2));
+    assert_eq!(graph.generation(&tip), Some(3));
+
+    assert_eq!(graph.corrected_commit_date(&root), Some(1000));
+    // middle's own date (500) is behind root's corrected date (1000), so it
+    // gets clamped forward to 1001.
+    assert_eq!(graph.corrected_commit_date(&middle), Some(1001));
+    // tip's own date (2000) already exceeds middle's corrected date + 1, so
+    // it's used as-is.
+    assert_eq!(graph.corrected_commit_date(&tip), Some(2000));
+}
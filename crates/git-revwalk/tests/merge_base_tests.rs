@@ -8,7 +8,7 @@ use std::process::Command;
 
 use git_hash::ObjectId;
 use git_repository::Repository;
-use git_revwalk::{merge_base, merge_base_one, is_ancestor};
+use git_revwalk::{filter_reachable, is_ancestor, merge_base, merge_base_one};
 
 fn git(dir: &Path, args: &[&str]) -> String {
     let output = Command::new("git")
@@ -236,3 +236,26 @@ fn merge_base_no_common_ancestor() {
     let bases = merge_base(&repo, &a_oid, &b_oid).unwrap();
     assert!(bases.is_empty(), "no common ancestor for orphan branches");
 }
+
+#[test]
+fn filter_reachable_keeps_only_ancestors_of_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let (a, b, c, d) = create_diamond_repo(dir.path());
+
+    let repo = Repository::open(dir.path()).unwrap();
+    let a_oid = ObjectId::from_hex(&a).unwrap();
+    let b_oid = ObjectId::from_hex(&b).unwrap();
+    let c_oid = ObjectId::from_hex(&c).unwrap();
+    let d_oid = ObjectId::from_hex(&d).unwrap();
+
+    // Everything is reachable from D (the merge), including itself.
+    let reachable = filter_reachable(&repo, &[a_oid, b_oid, c_oid, d_oid], &[d_oid]).unwrap();
+    assert_eq!(reachable.len(), 4);
+
+    // Only A and B are reachable from B; C and D are not ancestors of B.
+    let mut reachable = filter_reachable(&repo, &[a_oid, b_oid, c_oid, d_oid], &[b_oid]).unwrap();
+    reachable.sort();
+    let mut expected = vec![a_oid, b_oid];
+    expected.sort();
+    assert_eq!(reachable, expected);
+}
@@ -1,11 +1,15 @@
 //! Pretty-print formatting tests.
 
 use bstr::BString;
+use git_config::ConfigSet;
 use git_hash::ObjectId;
 use git_object::Commit;
+use git_utils::color::ColorMode;
 use git_utils::date::{GitDate, Signature};
 
-use git_revwalk::{format_commit, format_builtin, BuiltinFormat, FormatOptions};
+use git_revwalk::{
+    format_commit, format_builtin, resolve_format, BuiltinFormat, FormatOptions, ResolvedFormat,
+};
 
 fn make_commit() -> (Commit, ObjectId) {
     let oid = ObjectId::from_hex("da39a3ee5e6b4b0d3255bfef95601890afd80709").unwrap();
@@ -186,3 +190,201 @@ fn builtin_raw() {
     assert!(result.contains("author John Doe"));
     assert!(result.contains("committer Jane Doe"));
 }
+
+// --- tokenizer edge cases ---
+
+#[test]
+fn format_unrecognized_specifier_after_percent_is_left_literal() {
+    let (commit, oid) = make_commit();
+    let opts = FormatOptions::default();
+    // `%z` isn't a specifier this crate knows, so the `%` is emitted as-is.
+    let result = format_commit(&commit, &oid, "%z", &opts);
+    assert_eq!(result, "%z");
+}
+
+#[test]
+fn format_dangling_percent_at_end_of_template_is_literal() {
+    let (commit, oid) = make_commit();
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "%h%", &opts);
+    assert_eq!(result, "da39a3e%");
+}
+
+#[test]
+fn format_left_align_pads_short_value_to_width() {
+    let (commit, oid) = make_commit();
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "[%<(12)%an]", &opts);
+    assert_eq!(result, "[John Doe    ]");
+}
+
+#[test]
+fn format_align_truncates_over_long_value_with_ellipsis() {
+    let (commit, oid) = make_commit();
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "%<(6,trunc)%s", &opts);
+    assert_eq!(result, "Init..");
+}
+
+// --- color on/off ---
+
+#[test]
+fn format_named_color_expands_when_color_forced_on() {
+    let (commit, oid) = make_commit();
+    let mut opts = FormatOptions::default();
+    opts.color = ColorMode::Always;
+    let result = format_commit(&commit, &oid, "%Cred%h%Creset", &opts);
+    assert!(result.starts_with("\x1b["));
+    assert!(result.contains("da39a3e"));
+    assert!(result.ends_with("\x1b[0m"));
+}
+
+#[test]
+fn format_named_color_is_suppressed_when_color_off() {
+    let (commit, oid) = make_commit();
+    let opts = FormatOptions::default(); // ColorMode::Never by default
+    let result = format_commit(&commit, &oid, "%Cred%h%Creset", &opts);
+    assert_eq!(result, "da39a3e");
+}
+
+#[test]
+fn format_color_spec_placeholder_expands_named_and_attribute_colors() {
+    let (commit, oid) = make_commit();
+    let mut opts = FormatOptions::default();
+    opts.color = ColorMode::Always;
+    let result = format_commit(&commit, &oid, "%C(bold red)%s%C(reset)", &opts);
+    assert!(result.contains("Initial commit"));
+    assert_ne!(result, "Initial commit");
+}
+
+// --- %(trailers) ---
+
+fn make_commit_with_message(message: &str) -> (Commit, ObjectId) {
+    let (mut commit, oid) = make_commit();
+    commit.message = BString::from(message);
+    (commit, oid)
+}
+
+#[test]
+fn trailers_default_renders_each_on_its_own_line() {
+    let (commit, oid) = make_commit_with_message(
+        "Subject line\n\nBody paragraph.\n\nSigned-off-by: John Doe <john@example.com>\nReviewed-by: Jane Doe <jane@example.com>\n",
+    );
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "%(trailers)", &opts);
+    assert_eq!(
+        result,
+        "Signed-off-by: John Doe <john@example.com>\nReviewed-by: Jane Doe <jane@example.com>"
+    );
+}
+
+#[test]
+fn trailers_key_filter_is_case_insensitive_and_excludes_others() {
+    let (commit, oid) = make_commit_with_message(
+        "Subject line\n\nSigned-off-by: John Doe <john@example.com>\nReviewed-by: Jane Doe <jane@example.com>\n",
+    );
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "%(trailers:key=signed-off-by)", &opts);
+    assert_eq!(result, "Signed-off-by: John Doe <john@example.com>");
+}
+
+#[test]
+fn trailers_valueonly_omits_the_key_prefix() {
+    let (commit, oid) = make_commit_with_message(
+        "Subject line\n\nSigned-off-by: John Doe <john@example.com>\n",
+    );
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "%(trailers:valueonly)", &opts);
+    assert_eq!(result, "John Doe <john@example.com>");
+}
+
+#[test]
+fn trailers_separator_decodes_hex_escape() {
+    let (commit, oid) = make_commit_with_message(
+        "Subject line\n\nSigned-off-by: John Doe <john@example.com>\nReviewed-by: Jane Doe <jane@example.com>\n",
+    );
+    let opts = FormatOptions::default();
+    // %x2c is a literal comma, which can't appear raw inside the option list.
+    let result = format_commit(&commit, &oid, "%(trailers:separator=%x2c )", &opts);
+    assert_eq!(
+        result,
+        "Signed-off-by: John Doe <john@example.com>, Reviewed-by: Jane Doe <jane@example.com>"
+    );
+}
+
+#[test]
+fn trailers_absent_when_final_paragraph_is_not_trailer_shaped() {
+    let (commit, oid) = make_commit_with_message("Subject line\n\nJust a closing remark, no colon shape.\n");
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "%(trailers)", &opts);
+    assert_eq!(result, "");
+}
+
+// --- encoding fallback ---
+
+#[test]
+fn subject_decodes_latin1_declared_encoding() {
+    let (mut commit, oid) = make_commit();
+    // 0xE9 is Latin-1 for "é"; invalid as a standalone UTF-8 byte.
+    commit.message = BString::from(&b"Caf\xe9 commit\n"[..]);
+    commit.encoding = Some(BString::from("ISO-8859-1"));
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "%s", &opts);
+    assert_eq!(result, "Caf\u{e9} commit");
+}
+
+#[test]
+fn subject_falls_back_to_lossy_utf8_without_an_encoding_header() {
+    let (mut commit, oid) = make_commit();
+    commit.message = BString::from(&b"Caf\xe9 commit\n"[..]);
+    commit.encoding = None;
+    let opts = FormatOptions::default();
+    let result = format_commit(&commit, &oid, "%s", &opts);
+    assert_eq!(result, "Caf\u{fffd} commit");
+}
+
+// --- resolve_format ---
+
+#[test]
+fn resolve_format_recognizes_builtin_names() {
+    let config = ConfigSet::new();
+    assert_eq!(
+        resolve_format("oneline", &config),
+        Some(ResolvedFormat::Builtin(BuiltinFormat::Oneline))
+    );
+}
+
+#[test]
+fn resolve_format_strips_format_prefix_as_separator() {
+    let config = ConfigSet::new();
+    assert_eq!(
+        resolve_format("format:%H", &config),
+        Some(ResolvedFormat::User { template: "%H".to_string(), terminator: false })
+    );
+}
+
+#[test]
+fn resolve_format_strips_tformat_prefix_as_terminator() {
+    let config = ConfigSet::new();
+    assert_eq!(
+        resolve_format("tformat:%H", &config),
+        Some(ResolvedFormat::User { template: "%H".to_string(), terminator: true })
+    );
+}
+
+#[test]
+fn resolve_format_follows_pretty_dot_name_alias() {
+    let mut config = ConfigSet::new();
+    config.add_command_override("pretty.mine", "format:%h %s").unwrap();
+    assert_eq!(
+        resolve_format("mine", &config),
+        Some(ResolvedFormat::User { template: "%h %s".to_string(), terminator: false })
+    );
+}
+
+#[test]
+fn resolve_format_detects_self_referential_alias_cycle() {
+    let mut config = ConfigSet::new();
+    config.add_command_override("pretty.mine", "mine").unwrap();
+    assert_eq!(resolve_format("mine", &config), None);
+}
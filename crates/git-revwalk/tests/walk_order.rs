@@ -398,3 +398,125 @@ fn hide_excludes_ancestors() {
 
     assert!(!result.contains(&all[2]), "hidden commit should be excluded");
 }
+
+/// Build a history with deliberately non-monotonic committer dates and no
+/// commit-graph: `X` has two children, `Z1` (committed with a plausible,
+/// later date) and `Z2` (committed with an *older* date than `X` itself,
+/// simulating clock skew), reached through two different branches that
+/// only converge at the tip merge `Head`. `Z2`'s child `W` also predates
+/// `X`. This shape is what `promote_ready` must not be fooled by when
+/// falling back to date comparisons: `X` can only be discovered "final"
+/// (safe to emit) once `Z2` — reached via the `W` branch — has actually
+/// been found, not merely once every *currently queued* date looks old
+/// enough.
+fn create_skewed_date_repo(dir: &Path) {
+    git(dir, &["init", "-b", "main"]);
+    git(dir, &["config", "user.name", "Test"]);
+    git(dir, &["config", "user.email", "test@test.com"]);
+
+    std::fs::write(dir.join("a.txt"), "a").unwrap();
+    git(dir, &["add", "a.txt"]);
+    git_env(
+        dir,
+        &["commit", "-m", "A"],
+        &[
+            ("GIT_AUTHOR_DATE", "1700000001 +0000"),
+            ("GIT_COMMITTER_DATE", "1700000001 +0000"),
+        ],
+    );
+
+    std::fs::write(dir.join("x.txt"), "x").unwrap();
+    git(dir, &["add", "x.txt"]);
+    git_env(
+        dir,
+        &["commit", "-m", "X"],
+        &[
+            ("GIT_AUTHOR_DATE", "1700002000 +0000"),
+            ("GIT_COMMITTER_DATE", "1700002000 +0000"),
+        ],
+    );
+
+    git(dir, &["branch", "b2"]);
+
+    // Z1, on main, with a normal (later) date.
+    std::fs::write(dir.join("z1.txt"), "z1").unwrap();
+    git(dir, &["add", "z1.txt"]);
+    git_env(
+        dir,
+        &["commit", "-m", "Z1"],
+        &[
+            ("GIT_AUTHOR_DATE", "1700004000 +0000"),
+            ("GIT_COMMITTER_DATE", "1700004000 +0000"),
+        ],
+    );
+
+    // Z2 and W, on b2, both dated *older* than their parent X.
+    git(dir, &["checkout", "b2"]);
+    std::fs::write(dir.join("z2.txt"), "z2").unwrap();
+    git(dir, &["add", "z2.txt"]);
+    git_env(
+        dir,
+        &["commit", "-m", "Z2"],
+        &[
+            ("GIT_AUTHOR_DATE", "1700000003 +0000"),
+            ("GIT_COMMITTER_DATE", "1700000003 +0000"),
+        ],
+    );
+    std::fs::write(dir.join("w.txt"), "w").unwrap();
+    git(dir, &["add", "w.txt"]);
+    git_env(
+        dir,
+        &["commit", "-m", "W"],
+        &[
+            ("GIT_AUTHOR_DATE", "1700000002 +0000"),
+            ("GIT_COMMITTER_DATE", "1700000002 +0000"),
+        ],
+    );
+
+    // Merge b2 (W) into main (Z1) to create Head.
+    git(dir, &["checkout", "main"]);
+    git_env(
+        dir,
+        &["merge", "b2", "-m", "Head"],
+        &[
+            ("GIT_AUTHOR_DATE", "1700005000 +0000"),
+            ("GIT_COMMITTER_DATE", "1700005000 +0000"),
+        ],
+    );
+}
+
+#[test]
+fn topological_order_with_skewed_dates_and_no_commit_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    create_skewed_date_repo(dir.path());
+
+    // No `git commit-graph write` has been run, so RevWalk falls back to
+    // committer-date comparisons for the no-commit-graph "finality" check.
+    let repo = Repository::open(dir.path()).unwrap();
+    let mut walk = RevWalk::new(&repo).unwrap();
+    walk.set_sort(SortOrder::Topological);
+    walk.push_head().unwrap();
+
+    let result: Vec<String> = walk.map(|r| r.unwrap().to_hex()).collect();
+
+    assert_eq!(result.len(), 6, "should visit all 6 commits exactly once");
+
+    // Topological invariant: every parent must appear strictly after its
+    // children, regardless of committer-date skew.
+    for (i, oid_hex) in result.iter().enumerate() {
+        let oid = ObjectId::from_hex(oid_hex).unwrap();
+        let obj = repo.odb().read(&oid).unwrap().unwrap();
+        if let git_object::Object::Commit(commit) = obj {
+            for parent in &commit.parents {
+                let parent_hex = parent.to_hex();
+                if let Some(parent_pos) = result.iter().position(|h| *h == parent_hex) {
+                    assert!(
+                        parent_pos > i,
+                        "parent {} at position {} should appear after child {} at position {}",
+                        parent_hex, parent_pos, oid_hex, i
+                    );
+                }
+            }
+        }
+    }
+}
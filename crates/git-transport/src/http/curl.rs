@@ -0,0 +1,377 @@
+//! Curl-subprocess [`HttpClient`], kept as an opt-in fallback (`curl-client`
+//! feature) for whatever the native client doesn't cover — HTTPS without the
+//! `tls` feature, proxies, and any server quirk curl's own battle-tested
+//! handling papers over.
+
+use std::io::{self, Read, Write};
+use std::process::{Child, ChildStderr, ChildStdout, Command, Stdio};
+
+use crate::TransportError;
+
+use super::{accept_encoding_header, ContentEncoding, HttpBody, HttpClient, HttpOptions, RawBody};
+
+/// A curl child process's stdout, exposed as a plain (pre-decompression)
+/// body stream.
+///
+/// Curl's `--include` output interleaves the HTTP header block and the body
+/// on the same pipe, so reads are split into two phases: [`Self::strip_headers`]
+/// buffers bytes until it finds the `\r\n\r\n` boundary (keeping anything read
+/// past it in `pending`, and recording any `Content-Encoding` header found
+/// along the way), after which `read` drains `pending` and then reads
+/// straight from the child's stdout with no further buffering.
+struct ChildStream {
+    child: Option<Child>,
+    stdout: Option<ChildStdout>,
+    stderr: Option<ChildStderr>,
+    pending: Vec<u8>,
+    pending_pos: usize,
+    headers_stripped: bool,
+    /// `Content-Encoding` of the response, if any, discovered while
+    /// stripping headers. Taken by [`RawBody::take_content_encoding`] once acted on.
+    content_encoding: Option<ContentEncoding>,
+    /// Status code of the final header block, after following any redirect
+    /// chain. `0` until [`Self::strip_headers`] has run.
+    status: u16,
+}
+
+/// Redirects to follow before giving up, matching curl's own `--max-redirs`
+/// default.
+const MAX_REDIRECTS: u32 = 50;
+
+impl ChildStream {
+    fn from_child(mut child: Child) -> Self {
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        Self {
+            child: Some(child),
+            stdout,
+            stderr,
+            pending: Vec::new(),
+            pending_pos: 0,
+            headers_stripped: false,
+            content_encoding: None,
+            status: 0,
+        }
+    }
+
+    /// Read and discard the HTTP header block(s), stashing any body bytes
+    /// read along the way in `pending`.
+    ///
+    /// Curl is invoked with `-L --include`, so a redirected request emits
+    /// one header block per hop on the same pipe. Scan each block's status
+    /// line and keep following through `1xx`/`3xx` blocks — their bodies are
+    /// empty — until a final status is reached, recording it in `status`
+    /// along with that block's `Content-Encoding`.
+    fn strip_headers(&mut self) -> io::Result<()> {
+        let mut buf = Vec::new();
+        let mut redirects = 0u32;
+        let mut chunk = [0u8; 4096];
+        loop {
+            if let Some(end) = find_header_boundary(&buf) {
+                let header_block = &buf[..end];
+                let status = parse_status_line(header_block).unwrap_or(0);
+                self.status = status;
+                if (100..200).contains(&status) || (300..400).contains(&status) {
+                    redirects += 1;
+                    if redirects > MAX_REDIRECTS {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!("too many HTTP redirects (> {})", MAX_REDIRECTS),
+                        ));
+                    }
+                    buf = buf.split_off(end);
+                    continue;
+                }
+                self.content_encoding = parse_content_encoding(header_block);
+                self.pending = buf.split_off(end);
+                break;
+            }
+            let n = self.read_from_child(&mut chunk)?;
+            if n == 0 {
+                // No header boundary found — treat whatever we read as body.
+                self.pending = buf;
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        self.headers_stripped = true;
+        Ok(())
+    }
+
+    /// Read a chunk straight from the child's stdout. On EOF, reaps the
+    /// child immediately (rather than waiting for `finish()`, which may
+    /// never be called) and surfaces a non-zero curl exit as an error right
+    /// where the caller would otherwise see a silent empty read.
+    fn read_from_child(&mut self, chunk: &mut [u8]) -> io::Result<usize> {
+        let Some(stdout) = self.stdout.as_mut() else {
+            return Ok(0);
+        };
+        let n = stdout.read(chunk)?;
+        if n == 0 {
+            self.stdout = None;
+            self.reap()?;
+        }
+        Ok(n)
+    }
+
+    /// Wait for the backing curl process to exit, surfacing a non-zero
+    /// status as an error. Only safe to call once stdout has hit EOF (or was
+    /// never opened) — otherwise an unread response sitting in the pipe can
+    /// make curl block on a full buffer forever.
+    fn reap(&mut self) -> io::Result<()> {
+        let Some(mut child) = self.child.take() else {
+            return Ok(());
+        };
+        let status = child.wait()?;
+        if !status.success() {
+            let kind = curl_error_kind(status);
+            let message = self.curl_failure_message(status);
+            return Err(io::Error::new(kind, message));
+        }
+        Ok(())
+    }
+
+    fn curl_failure_message(&mut self, status: std::process::ExitStatus) -> String {
+        let mut message = String::new();
+        if let Some(mut stderr) = self.stderr.take() {
+            let _ = stderr.read_to_string(&mut message);
+        }
+        format!("curl exited with {}: {}", status, message.trim())
+    }
+}
+
+/// Map curl's exit code 28 (`CURLE_OPERATION_TIMEDOUT`, raised by
+/// `--connect-timeout`/`--max-time`) to `io::ErrorKind::TimedOut` so the
+/// transport's single-retry-on-first-byte-timeout logic recognizes it the
+/// same way it would a native-client socket timeout.
+fn curl_error_kind(status: std::process::ExitStatus) -> io::ErrorKind {
+    if status.code() == Some(28) {
+        io::ErrorKind::TimedOut
+    } else {
+        io::ErrorKind::Other
+    }
+}
+
+impl Read for ChildStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.headers_stripped {
+            self.strip_headers()?;
+        }
+        if self.pending_pos < self.pending.len() {
+            let available = &self.pending[self.pending_pos..];
+            let n = available.len().min(buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            self.pending_pos += n;
+            return Ok(n);
+        }
+        self.read_from_child(buf)
+    }
+}
+
+impl RawBody for ChildStream {
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn take_content_encoding(&mut self) -> Option<ContentEncoding> {
+        self.content_encoding.take()
+    }
+
+    fn ensure_headers(&mut self) -> io::Result<()> {
+        if !self.headers_stripped {
+            self.strip_headers()?;
+        }
+        Ok(())
+    }
+
+    /// Reap the backing curl process for [`crate::Transport::close`]. Unlike
+    /// `reap`, this may be called before the response has been fully read,
+    /// so it kills the process first rather than risk blocking on `wait()`
+    /// while curl is stuck writing to a full, undrained pipe.
+    fn finish(&mut self) -> Result<(), TransportError> {
+        let Some(mut child) = self.child.take() else {
+            return Ok(());
+        };
+        if child.try_wait()?.is_none() {
+            let _ = child.kill();
+        }
+        let exit_status = child.wait()?;
+        if !exit_status.success() {
+            let message = self.curl_failure_message(exit_status);
+            return Err(TransportError::Http {
+                status: self.status,
+                message,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Find the end of the `\r\n\r\n` header/body boundary, if present.
+fn find_header_boundary(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|w| w == b"\r\n\r\n").map(|i| i + 4)
+}
+
+/// Parse the status code out of a header block's status line, e.g. the
+/// `200` in `HTTP/1.1 200 OK`.
+fn parse_status_line(header_block: &[u8]) -> Option<u16> {
+    let line_end = header_block
+        .iter()
+        .position(|&b| b == b'\r' || b == b'\n')
+        .unwrap_or(header_block.len());
+    let line = std::str::from_utf8(&header_block[..line_end]).ok()?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Scan a raw HTTP header block for a recognized `Content-Encoding` value.
+fn parse_content_encoding(header_block: &[u8]) -> Option<ContentEncoding> {
+    let text = std::str::from_utf8(header_block).ok()?;
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("content-encoding")
+            .then(|| ContentEncoding::parse(value))
+            .flatten()
+    })
+}
+
+/// Spawn curl for the initial info/refs GET, streaming its stdout back.
+///
+/// Sends `Git-Protocol: version=2` so the server can upgrade the info/refs
+/// response to a v2 capability advertisement (the same header real
+/// git-http-backend reads off `GIT_PROTOCOL`/`Git-Protocol`). A server that
+/// doesn't understand it just ignores it and replies with v0/v1 framing.
+/// Also advertises `Accept-Encoding` for whichever decoders this build was
+/// compiled with, per [`accept_encoding_header`].
+fn spawn_http_get(url: &str, options: &HttpOptions) -> Result<Child, TransportError> {
+    let mut args = vec![
+        "-sfL".to_string(),
+        "--include".to_string(),
+        "-H".to_string(),
+        "Git-Protocol: version=2".to_string(),
+    ];
+    if let Some(encodings) = accept_encoding_header() {
+        args.push("-H".to_string());
+        args.push(format!("Accept-Encoding: {}", encodings));
+    }
+    push_option_args(&mut args, options);
+    args.push(url.to_string());
+
+    Command::new("curl")
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| TransportError::ConnectionFailed(format!("curl not found: {}", e)))
+}
+
+/// Spawn curl for a service POST, writing `body` to its stdin up front and
+/// streaming its stdout back.
+///
+/// Also sends `Git-Protocol: version=2`, since a v2 server expects it on
+/// every request in the exchange, not just the initial info/refs GET, and
+/// the same `Accept-Encoding` as [`spawn_http_get`] — a server fronting a
+/// large upload-pack result with gzip can cut transfer size substantially.
+fn spawn_http_post(
+    url: &str,
+    content_type: &str,
+    accept: &str,
+    body: &[u8],
+    options: &HttpOptions,
+) -> Result<Child, TransportError> {
+    let mut args = vec![
+        "-sf".to_string(),
+        "--include".to_string(),
+        "-X".to_string(),
+        "POST".to_string(),
+        "-H".to_string(),
+        format!("Content-Type: {}", content_type),
+        "-H".to_string(),
+        format!("Accept: {}", accept),
+        "-H".to_string(),
+        "Git-Protocol: version=2".to_string(),
+    ];
+    if let Some(encodings) = accept_encoding_header() {
+        args.push("-H".to_string());
+        args.push(format!("Accept-Encoding: {}", encodings));
+    }
+    push_option_args(&mut args, options);
+    args.push("--data-binary".to_string());
+    args.push("@-".to_string());
+    args.push(url.to_string());
+
+    let mut child = Command::new("curl")
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| TransportError::ConnectionFailed(format!("curl not found: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body)?;
+    }
+
+    Ok(child)
+}
+
+/// Append the curl flags derived from [`HttpOptions`] that are shared by
+/// both the GET and POST paths: credentials, proxy, CA bundle, extra
+/// headers, and user-agent.
+fn push_option_args(args: &mut Vec<String>, options: &HttpOptions) {
+    if let Some(ref username) = options.username {
+        args.push("-u".to_string());
+        args.push(format!(
+            "{}:{}",
+            username,
+            options.password.as_deref().unwrap_or("")
+        ));
+    }
+    if let Some(ref proxy) = options.proxy {
+        args.push("-x".to_string());
+        args.push(proxy.clone());
+    }
+    if let Some(ref ca_info) = options.ca_info {
+        args.push("--cacert".to_string());
+        args.push(ca_info.clone());
+    }
+    for header in &options.extra_headers {
+        args.push("-H".to_string());
+        args.push(header.clone());
+    }
+    if let Some(ref user_agent) = options.user_agent {
+        args.push("-A".to_string());
+        args.push(user_agent.clone());
+    }
+    args.push("--connect-timeout".to_string());
+    args.push(options.connect_timeout.as_secs_f64().to_string());
+    // Curl has no separate "time to first byte" vs. idle-read timeout, so
+    // `--max-time` bounds the whole request by the sum of both — looser than
+    // the native client's per-phase timeouts, but the closest curl can do.
+    let max_time = options.first_byte_timeout + options.read_timeout;
+    args.push("--max-time".to_string());
+    args.push(max_time.as_secs_f64().to_string());
+}
+
+/// [`HttpClient`] that spawns a fresh curl process per request.
+pub(crate) struct CurlHttpClient;
+
+impl HttpClient for CurlHttpClient {
+    fn get(&mut self, url: &str, options: &HttpOptions) -> Result<HttpBody, TransportError> {
+        let child = spawn_http_get(url, options)?;
+        Ok(HttpBody::from_raw(Box::new(ChildStream::from_child(child))))
+    }
+
+    fn post(
+        &mut self,
+        url: &str,
+        content_type: &str,
+        accept: &str,
+        body: &[u8],
+        options: &HttpOptions,
+    ) -> Result<HttpBody, TransportError> {
+        let child = spawn_http_post(url, content_type, accept, body, options)?;
+        Ok(HttpBody::from_raw(Box::new(ChildStream::from_child(child))))
+    }
+}
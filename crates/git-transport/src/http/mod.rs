@@ -0,0 +1,654 @@
+//! HTTP/HTTPS smart transport implementation.
+//!
+//! Implements the git smart HTTP protocol for fetch and push. Each
+//! request/response cycle is a separate HTTP POST. The actual request is
+//! issued by an [`HttpClient`]: [`native::NativeHttpClient`] (the default)
+//! keeps one socket open for the lifetime of a transport instead of paying
+//! for a fresh process per request, and [`curl::CurlHttpClient`] (behind the
+//! `curl-client` feature) falls back to the original per-request curl
+//! subprocess for protocols the native client doesn't cover (HTTPS without
+//! the `tls` feature, proxies, etc.).
+
+#[cfg(feature = "curl-client")]
+mod curl;
+mod native;
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use crate::credential::{self, CredentialRequest};
+use crate::{GitUrl, ProtocolVersion, Service, Transport, TransportError};
+
+/// Per-connection configuration for the HTTP transport: authentication,
+/// proxying, timeouts, and extra headers that [`connect_with_options`]
+/// threads into every request an [`HttpClient`] makes.
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    /// Username for HTTP Basic auth. Seeded automatically from the
+    /// credential helper on a `401`; set this up front to skip that round trip.
+    pub username: Option<String>,
+    /// Password for HTTP Basic auth, paired with `username`.
+    pub password: Option<String>,
+    /// Proxy URL, e.g. `http://proxy.example.com:8080`.
+    pub proxy: Option<String>,
+    /// Path to a custom CA bundle.
+    pub ca_info: Option<String>,
+    /// Additional `Name: value` header lines sent with every request.
+    pub extra_headers: Vec<String>,
+    /// Overrides the client's default `User-Agent`.
+    pub user_agent: Option<String>,
+    /// How long to wait for the TCP (and TLS) handshake to complete.
+    pub connect_timeout: Duration,
+    /// How long to wait for the first byte of a response after the request
+    /// has been sent. Deliberately longer than `read_timeout`, since a git
+    /// server legitimately goes quiet for a long stretch while enumerating
+    /// objects before it writes anything back. A timeout here is retried
+    /// once, transparently, by [`HttpTransport`] before it's surfaced to the
+    /// caller — it's cheap to assume the stall was transient and the
+    /// request hadn't yet had any visible effect on the server.
+    pub first_byte_timeout: Duration,
+    /// How long to wait between subsequent reads once the response has
+    /// started arriving. Shorter than `first_byte_timeout`, since a server
+    /// that's already started streaming and then goes idle is more likely
+    /// wedged than merely slow to start. Not retried — a partially
+    /// delivered response can't be safely replayed.
+    pub read_timeout: Duration,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            username: None,
+            password: None,
+            proxy: None,
+            ca_info: None,
+            extra_headers: Vec::new(),
+            user_agent: None,
+            connect_timeout: Duration::from_secs(10),
+            first_byte_timeout: Duration::from_secs(60),
+            read_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Turn a body-read failure into a `TransportError`, preferring the HTTP
+/// status captured while reading headers (a real 4xx/5xx) over the raw I/O
+/// error a failed request otherwise surfaces as.
+fn http_error(status: u16, url: &str, err: io::Error) -> TransportError {
+    if status >= 400 {
+        TransportError::Http {
+            status,
+            message: format!("{} returned HTTP {}", url, status),
+        }
+    } else {
+        err.into()
+    }
+}
+
+/// Whether `err` looks like a stall waiting for the first byte of a
+/// response rather than a real connection/protocol failure — the case
+/// [`HttpTransport::do_initial_request`]/[`HttpTransport::do_post`] retry
+/// once before giving up. Matches both `WouldBlock` and `TimedOut` since a
+/// blocking socket read timeout surfaces as either depending on platform.
+fn is_first_byte_timeout_io(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut)
+}
+
+/// As [`is_first_byte_timeout_io`], but for the `TransportError` an
+/// [`HttpClient::get`]/[`HttpClient::post`] call itself can fail with (e.g. a
+/// connect timeout, before any [`HttpBody`] exists to peek at).
+fn is_first_byte_timeout(err: &TransportError) -> bool {
+    matches!(err, TransportError::Io(io_err) if is_first_byte_timeout_io(io_err))
+}
+
+/// HTTP transport state.
+pub struct HttpTransport {
+    /// Base URL for the repository.
+    base_url: String,
+    /// The service we're talking to.
+    service: Service,
+    /// Buffer for data to be sent in the next request.
+    write_buf: Vec<u8>,
+    /// Response body of the last request, transparently decompressed and
+    /// streamed straight off the client's connection so multi-gigabyte
+    /// packfiles never sit fully in memory.
+    read_body: HttpBody,
+    /// Whether the initial info/refs request has been made.
+    initial_request_done: bool,
+    /// Protocol version negotiated with the server, detected from the
+    /// initial info/refs response.
+    protocol_version: ProtocolVersion,
+    /// Auth, proxy, and header configuration threaded into every request.
+    /// `username`/`password` may be filled in mid-connection by
+    /// [`Self::try_credentials`] after a `401`.
+    options: HttpOptions,
+    /// Host to report to the credential helper; `scheme` likewise ("http" or
+    /// "https", never a port or path).
+    host: String,
+    scheme: &'static str,
+    /// Whether [`Self::try_credentials`] has already been invoked, so a
+    /// second `401` (wrong credentials, not missing ones) gives up instead
+    /// of looping.
+    credentials_tried: bool,
+    /// The client making the actual requests: a reusable native connection
+    /// by default, or curl when built with the `curl-client` feature.
+    client: Box<dyn HttpClient>,
+}
+
+impl HttpTransport {
+    /// The protocol version negotiated with the server.
+    ///
+    /// Always `V1` until the initial info/refs request has completed. Callers
+    /// can use this to prefer `ls-refs` over the full ref dump once `V2` is
+    /// confirmed, since a v0/v1 server ignores the `Git-Protocol` header
+    /// entirely and falls back to v0 framing.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        self.protocol_version
+    }
+
+    /// Perform the initial GET to /info/refs?service=... and stream the response.
+    fn do_initial_request(&mut self) -> Result<(), TransportError> {
+        if self.initial_request_done {
+            return Ok(());
+        }
+
+        let url = format!(
+            "{}/info/refs?service={}",
+            self.base_url,
+            self.service.as_str()
+        );
+
+        let mut timed_out_once = false;
+        loop {
+            let mut body = match self.client.get(&url, &self.options) {
+                Ok(body) => body,
+                Err(err) if !timed_out_once && is_first_byte_timeout(&err) => {
+                    timed_out_once = true;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            // Peek enough of the body to recognize the "# service=...\n" + flush
+            // + "version 2" capability lines without losing those bytes for the
+            // caller, who reads the full advertisement right after us. This also
+            // forces header parsing, so a non-2xx final status (after following
+            // any redirect chain) surfaces here rather than mid-read.
+            let peeked = match body.peek(512) {
+                Ok(bytes) => bytes.to_vec(),
+                Err(err) if !timed_out_once && is_first_byte_timeout_io(&err) => {
+                    timed_out_once = true;
+                    continue;
+                }
+                Err(err) => return Err(http_error(body.status, &url, err)),
+            };
+            if body.status == 401 && self.try_credentials()? {
+                continue;
+            }
+            if body.status >= 400 {
+                return Err(TransportError::Http {
+                    status: body.status,
+                    message: format!("{} returned HTTP {}", url, body.status),
+                });
+            }
+            self.protocol_version = detect_protocol_version(&peeked);
+            self.read_body = body;
+            self.initial_request_done = true;
+            return Ok(());
+        }
+    }
+
+    /// Perform a POST to the service endpoint, streaming the write buffer
+    /// contents out and the response body back in.
+    fn do_post(&mut self) -> Result<(), TransportError> {
+        let url = format!("{}/{}", self.base_url, self.service.as_str());
+        let content_type = format!("application/x-{}-request", self.service.as_str());
+        let accept = format!("application/x-{}-result", self.service.as_str());
+        let request_body = std::mem::take(&mut self.write_buf);
+
+        let mut timed_out_once = false;
+        loop {
+            let mut body = match self
+                .client
+                .post(&url, &content_type, &accept, &request_body, &self.options)
+            {
+                Ok(body) => body,
+                Err(err) if !timed_out_once && is_first_byte_timeout(&err) => {
+                    timed_out_once = true;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            // Force header parsing up front (no body bytes needed) so a non-2xx
+            // status is reported as a proper `TransportError::Http` instead of
+            // being discovered only once the caller starts reading the response.
+            if let Err(err) = body.peek(0) {
+                if !timed_out_once && is_first_byte_timeout_io(&err) {
+                    timed_out_once = true;
+                    continue;
+                }
+                return Err(http_error(body.status, &url, err));
+            }
+            if body.status == 401 && self.try_credentials()? {
+                continue;
+            }
+            if body.status >= 400 {
+                return Err(TransportError::Http {
+                    status: body.status,
+                    message: format!("{} returned HTTP {}", url, body.status),
+                });
+            }
+            self.read_body = body;
+            return Ok(());
+        }
+    }
+
+    /// On a `401`, ask the credential helper for a username/password and
+    /// retry the request once, mirroring how git clients prompt for
+    /// credentials on demand. Returns `true` if new credentials were
+    /// obtained and the caller should retry; `false` if we've already tried
+    /// (so the credentials we have are simply wrong) or the helper couldn't
+    /// produce any.
+    fn try_credentials(&mut self) -> Result<bool, TransportError> {
+        if self.credentials_tried {
+            return Ok(false);
+        }
+        self.credentials_tried = true;
+
+        let request = CredentialRequest {
+            protocol: self.scheme.to_string(),
+            host: self.host.clone(),
+            path: None,
+            username: self.options.username.clone(),
+        };
+        match credential::get_credentials(&request) {
+            Ok(response) => {
+                self.options.username = Some(response.username);
+                self.options.password = Some(response.password);
+                Ok(true)
+            }
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+impl Transport for HttpTransport {
+    fn reader(&mut self) -> &mut dyn Read {
+        // Ensure initial request is done
+        if !self.initial_request_done {
+            if let Err(e) = self.do_initial_request() {
+                // Store error as empty read
+                eprintln!("HTTP initial request error: {}", e);
+            }
+        } else if !self.write_buf.is_empty() {
+            // Flush whatever the caller queued (want/have lines, a v2 command
+            // block, or a receive-pack request) through a POST before handing
+            // back the response, so each write/read pair is one stateless
+            // request/response cycle.
+            if let Err(e) = self.do_post() {
+                eprintln!("HTTP POST error: {}", e);
+            }
+        }
+        &mut self.read_body
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        &mut self.write_buf
+    }
+
+    fn close(mut self: Box<Self>) -> Result<(), TransportError> {
+        self.read_body.finish()
+    }
+
+    fn is_stateless(&self) -> bool {
+        true
+    }
+}
+
+/// Connect to a remote repository over HTTP/HTTPS.
+pub fn connect(url: &GitUrl, service: Service) -> Result<Box<dyn Transport>, TransportError> {
+    connect_with_options(url, service, HttpOptions::default())
+}
+
+/// Connect to a remote repository over HTTP/HTTPS with explicit auth, proxy,
+/// and header configuration.
+pub fn connect_with_options(
+    url: &GitUrl,
+    service: Service,
+    options: HttpOptions,
+) -> Result<Box<dyn Transport>, TransportError> {
+    let base_url = format!(
+        "{}://{}{}{}",
+        url.scheme,
+        url.host.as_deref().unwrap_or(""),
+        url.port.map(|p| format!(":{}", p)).unwrap_or_default(),
+        url.path
+    );
+    let scheme = if url.scheme == crate::Scheme::Https {
+        "https"
+    } else {
+        "http"
+    };
+
+    #[cfg(feature = "curl-client")]
+    let client: Box<dyn HttpClient> = Box::new(curl::CurlHttpClient);
+    #[cfg(not(feature = "curl-client"))]
+    let client: Box<dyn HttpClient> = Box::new(native::NativeHttpClient::new());
+
+    let mut transport = HttpTransport {
+        base_url,
+        service,
+        write_buf: Vec::new(),
+        read_body: HttpBody::empty(),
+        initial_request_done: false,
+        protocol_version: ProtocolVersion::V1,
+        options,
+        host: url.host.clone().unwrap_or_default(),
+        scheme,
+        credentials_tried: false,
+        client,
+    };
+
+    // Perform the initial info/refs discovery
+    transport.do_initial_request()?;
+
+    Ok(Box::new(transport))
+}
+
+/// A raw (pre-decompression) response body stream, together with the status
+/// and `Content-Encoding` an [`HttpClient`] discovered while reading past its
+/// headers.
+trait RawBody: Read + Send {
+    /// Status code of the final response (after following any redirect
+    /// chain). `0` until [`Self::ensure_headers`] has run.
+    fn status(&self) -> u16;
+
+    /// `Content-Encoding` of the response, if any, taken so it's only acted
+    /// on once. Only meaningful once [`Self::ensure_headers`] has run.
+    fn take_content_encoding(&mut self) -> Option<ContentEncoding>;
+
+    /// Make sure `status()`/`take_content_encoding()` are populated, without
+    /// otherwise consuming body bytes the caller hasn't asked for yet.
+    fn ensure_headers(&mut self) -> io::Result<()>;
+
+    /// Tear down the backing connection/process for [`Transport::close`].
+    fn finish(&mut self) -> Result<(), TransportError>;
+}
+
+/// An empty [`RawBody`] with nothing backing it, used for [`HttpBody::empty`]
+/// before any request has run.
+struct EmptyBody;
+
+impl Read for EmptyBody {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Ok(0)
+    }
+}
+
+impl RawBody for EmptyBody {
+    fn status(&self) -> u16 {
+        0
+    }
+
+    fn take_content_encoding(&mut self) -> Option<ContentEncoding> {
+        None
+    }
+
+    fn ensure_headers(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), TransportError> {
+        Ok(())
+    }
+}
+
+/// An HTTP client capable of making the two requests the smart HTTP protocol
+/// needs: the initial info/refs GET, and the service POST.
+trait HttpClient: Send {
+    fn get(&mut self, url: &str, options: &HttpOptions) -> Result<HttpBody, TransportError>;
+
+    fn post(
+        &mut self,
+        url: &str,
+        content_type: &str,
+        accept: &str,
+        body: &[u8],
+        options: &HttpOptions,
+    ) -> Result<HttpBody, TransportError>;
+}
+
+/// Compression scheme advertised by a response's `Content-Encoding` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentEncoding {
+    Gzip,
+    Deflate,
+    Zstd,
+}
+
+impl ContentEncoding {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "deflate" => Some(Self::Deflate),
+            "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// The decoded body stream backing an [`HttpBody`]: either the raw response
+/// stream, or that stream wrapped in a streaming decompressor once
+/// [`HttpBody::ensure_decoder`] has inspected the response headers.
+enum BodyReader {
+    Raw(Box<dyn RawBody>),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::read::GzDecoder<Box<dyn RawBody>>),
+    #[cfg(feature = "gzip")]
+    Deflate(flate2::read::DeflateDecoder<Box<dyn RawBody>>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::read::Decoder<'static, std::io::BufReader<Box<dyn RawBody>>>),
+}
+
+impl Read for BodyReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            BodyReader::Raw(stream) => stream.read(buf),
+            #[cfg(feature = "gzip")]
+            BodyReader::Gzip(decoder) => decoder.read(buf),
+            #[cfg(feature = "gzip")]
+            BodyReader::Deflate(decoder) => decoder.read(buf),
+            #[cfg(feature = "zstd")]
+            BodyReader::Zstd(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+/// Response body of the last request, transparently decompressed according
+/// to the response's `Content-Encoding` header and streamed straight off the
+/// client's connection so multi-gigabyte packfiles never sit fully in memory.
+struct HttpBody {
+    body: BodyReader,
+    /// Whether [`Self::ensure_decoder`] has already inspected the headers
+    /// and picked a decoder (or decided none is needed).
+    decoder_ready: bool,
+    /// Decoded bytes read ahead of the caller by [`Self::peek`], not yet
+    /// handed back through [`Read`].
+    front: Vec<u8>,
+    front_pos: usize,
+    /// Status of the final response (after following any redirects), copied
+    /// from the backing [`RawBody`] once [`Self::ensure_decoder`] has run.
+    /// `0` until then.
+    status: u16,
+}
+
+impl HttpBody {
+    /// An empty body with no backing connection, used before any request has run.
+    fn empty() -> Self {
+        Self {
+            body: BodyReader::Raw(Box::new(EmptyBody)),
+            decoder_ready: true,
+            front: Vec::new(),
+            front_pos: 0,
+            status: 0,
+        }
+    }
+
+    fn from_raw(raw: Box<dyn RawBody>) -> Self {
+        Self {
+            body: BodyReader::Raw(raw),
+            decoder_ready: false,
+            front: Vec::new(),
+            front_pos: 0,
+            status: 0,
+        }
+    }
+
+    /// Read the response headers (if not already done) and, the first time
+    /// this is called, swap the raw stream for a decompressing reader
+    /// matching the response's `Content-Encoding`.
+    fn ensure_decoder(&mut self) -> io::Result<()> {
+        if self.decoder_ready {
+            return Ok(());
+        }
+        self.decoder_ready = true;
+        let BodyReader::Raw(stream) = &mut self.body else {
+            return Ok(());
+        };
+        stream.ensure_headers()?;
+        self.status = stream.status();
+        let Some(encoding) = stream.take_content_encoding() else {
+            return Ok(());
+        };
+        let BodyReader::Raw(stream) =
+            std::mem::replace(&mut self.body, BodyReader::Raw(Box::new(EmptyBody)))
+        else {
+            unreachable!()
+        };
+        self.body = match encoding {
+            ContentEncoding::Gzip => {
+                #[cfg(feature = "gzip")]
+                {
+                    BodyReader::Gzip(flate2::read::GzDecoder::new(stream))
+                }
+                #[cfg(not(feature = "gzip"))]
+                return Err(unsupported_encoding_error("gzip"));
+            }
+            ContentEncoding::Deflate => {
+                #[cfg(feature = "gzip")]
+                {
+                    BodyReader::Deflate(flate2::read::DeflateDecoder::new(stream))
+                }
+                #[cfg(not(feature = "gzip"))]
+                return Err(unsupported_encoding_error("deflate"));
+            }
+            ContentEncoding::Zstd => {
+                #[cfg(feature = "zstd")]
+                {
+                    BodyReader::Zstd(zstd::stream::read::Decoder::new(stream)?)
+                }
+                #[cfg(not(feature = "zstd"))]
+                return Err(unsupported_encoding_error("zstd"));
+            }
+        };
+        Ok(())
+    }
+
+    /// Return at least `n` decoded body bytes (fewer at EOF) without
+    /// consuming them, reading more as needed.
+    fn peek(&mut self, n: usize) -> io::Result<&[u8]> {
+        self.ensure_decoder()?;
+        let mut chunk = [0u8; 4096];
+        while self.front.len() - self.front_pos < n {
+            let read = self.body.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            self.front.extend_from_slice(&chunk[..read]);
+        }
+        let end = (self.front_pos + n).min(self.front.len());
+        Ok(&self.front[self.front_pos..end])
+    }
+
+    /// Tear down the backing connection for [`Transport::close`].
+    fn finish(&mut self) -> Result<(), TransportError> {
+        match &mut self.body {
+            BodyReader::Raw(stream) => stream.finish(),
+            #[cfg(feature = "gzip")]
+            BodyReader::Gzip(decoder) => decoder.get_mut().finish(),
+            #[cfg(feature = "gzip")]
+            BodyReader::Deflate(decoder) => decoder.get_mut().finish(),
+            #[cfg(feature = "zstd")]
+            BodyReader::Zstd(decoder) => decoder.get_mut().get_mut().finish(),
+        }
+    }
+}
+
+impl Read for HttpBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.ensure_decoder()?;
+        if self.front_pos < self.front.len() {
+            let available = &self.front[self.front_pos..];
+            let n = available.len().min(buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            self.front_pos += n;
+            return Ok(n);
+        }
+        self.body.read(buf)
+    }
+}
+
+/// Build the error returned when a server sends an encoding we weren't
+/// compiled with support for.
+#[cfg_attr(all(feature = "gzip", feature = "zstd"), allow(dead_code))]
+fn unsupported_encoding_error(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("server sent a {name}-encoded response but the `{name}` feature is not enabled"),
+    )
+}
+
+/// `Accept-Encoding` value advertising only the compression schemes this
+/// build can actually decode, so we never ask the server for something
+/// [`HttpBody::ensure_decoder`] would have to reject.
+fn accept_encoding_header() -> Option<String> {
+    let mut encodings: Vec<&str> = Vec::new();
+    #[cfg(feature = "gzip")]
+    encodings.extend(["gzip", "deflate"]);
+    #[cfg(feature = "zstd")]
+    encodings.push("zstd");
+    (!encodings.is_empty()).then(|| encodings.join(", "))
+}
+
+/// Inspect an info/refs response body for a v2 capability advertisement.
+///
+/// A v2 response opens with the `# service=...` pkt-line comment, a flush
+/// packet, then capability lines — the first of which is `version 2` when
+/// the server honored our `Git-Protocol` header. Anything else means the
+/// server ignored the header and replied with the classic v0/v1 ref
+/// advertisement.
+fn detect_protocol_version(response: &[u8]) -> ProtocolVersion {
+    let mut pos = 0;
+    if let Some((_, next)) = read_pkt_line(response, pos) {
+        pos = next;
+    }
+    if response.get(pos..pos + 4) == Some(b"0000") {
+        pos += 4;
+    }
+    if let Some((line, _)) = read_pkt_line(response, pos) {
+        if line.strip_suffix(b"\n").unwrap_or(line) == b"version 2" {
+            return ProtocolVersion::V2;
+        }
+    }
+    ProtocolVersion::V1
+}
+
+/// Read one pkt-line at `pos`, returning its payload and the offset just
+/// past it. Returns `None` for a flush packet or truncated/malformed input.
+fn read_pkt_line(data: &[u8], pos: usize) -> Option<(&[u8], usize)> {
+    let len_hex = std::str::from_utf8(data.get(pos..pos + 4)?).ok()?;
+    let len = usize::from_str_radix(len_hex, 16).ok()?;
+    if len < 4 || pos + len > data.len() {
+        return None;
+    }
+    Some((&data[pos + 4..pos + len], pos + len))
+}
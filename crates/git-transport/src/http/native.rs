@@ -0,0 +1,796 @@
+//! Default [`HttpClient`]: a small HTTP/1.1 client that keeps one socket
+//! open for the lifetime of a transport, instead of spawning a fresh curl
+//! process per request.
+//!
+//! Plain HTTP is handled with nothing but `std::net::TcpStream`. HTTPS needs
+//! the `tls` feature (backed by `native-tls`); without it, an `https://`
+//! request fails with an honest [`TransportError::ConnectionFailed`] that
+//! points at the `curl-client` feature instead. Proxying is similarly
+//! scoped down to plain-HTTP forwarding — an HTTPS request through a proxy
+//! also falls back to that error, since tunneling TLS through a proxy
+//! `CONNECT` is curl's job for now.
+
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::TransportError;
+
+use super::{accept_encoding_header, ContentEncoding, HttpBody, HttpClient, HttpOptions, RawBody};
+
+/// A duplex byte stream with a settable read timeout, so a plain
+/// [`TcpStream`] and a TLS-wrapped one can share one type.
+trait Stream: Read + Write + Send {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl Stream for TcpStream {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+#[cfg(feature = "tls")]
+impl Stream for native_tls::TlsStream<TcpStream> {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.get_ref().set_read_timeout(timeout)
+    }
+}
+
+/// Resolve `host:port` and connect with a bounded wait, so a host that's
+/// firewalled into a black hole fails after `connect_timeout` instead of
+/// hanging indefinitely.
+fn tcp_connect(host: &str, port: u16, connect_timeout: Duration) -> io::Result<TcpStream> {
+    let addr = (host, port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("could not resolve {}:{}", host, port),
+            )
+        })?;
+    TcpStream::connect_timeout(&addr, connect_timeout)
+}
+
+/// How a response's body is framed on the wire, per RFC 7230 §3.3.3.
+enum BodyFraming {
+    ContentLength(u64),
+    Chunked,
+    /// No `Content-Length` or `Transfer-Encoding: chunked` — the body runs
+    /// until the server closes the connection, so it can't be reused
+    /// afterwards.
+    UntilClose,
+}
+
+/// A socket kept open across requests to the same (host, port, scheme) so a
+/// fetch/push's several round trips reuse one TCP (and TLS) handshake
+/// instead of paying for a fresh one per request.
+struct Connection {
+    host: String,
+    port: u16,
+    use_tls: bool,
+    stream: Box<dyn Stream>,
+    /// Bytes already pulled off the socket that the header/body readers
+    /// haven't consumed yet — read-ahead past a header line, most often.
+    buf: Vec<u8>,
+    buf_pos: usize,
+}
+
+impl Connection {
+    fn open(
+        host: &str,
+        port: u16,
+        use_tls: bool,
+        proxy: Option<&str>,
+        connect_timeout: Duration,
+    ) -> Result<Self, TransportError> {
+        if use_tls && proxy.is_some() {
+            return Err(TransportError::ConnectionFailed(format!(
+                "{}: HTTPS through a proxy needs the `curl-client` feature",
+                host
+            )));
+        }
+
+        let stream: Box<dyn Stream> = if use_tls {
+            #[cfg(feature = "tls")]
+            {
+                let tcp = tcp_connect(host, port, connect_timeout)?;
+                let connector = native_tls::TlsConnector::new().map_err(|e| {
+                    TransportError::ConnectionFailed(format!("TLS setup failed: {}", e))
+                })?;
+                Box::new(connector.connect(host, tcp).map_err(|e| {
+                    TransportError::ConnectionFailed(format!(
+                        "TLS handshake with {} failed: {}",
+                        host, e
+                    ))
+                })?)
+            }
+            #[cfg(not(feature = "tls"))]
+            {
+                return Err(TransportError::ConnectionFailed(format!(
+                    "{} is https:// but this build has no `tls` feature; build with \
+                     `curl-client` instead",
+                    host
+                )));
+            }
+        } else {
+            let connect_host = proxy_host_port(proxy).map(|(h, _)| h).unwrap_or(host);
+            let connect_port = proxy_host_port(proxy).map(|(_, p)| p).unwrap_or(port);
+            Box::new(tcp_connect(connect_host, connect_port, connect_timeout)?)
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            use_tls,
+            stream,
+            buf: Vec::new(),
+            buf_pos: 0,
+        })
+    }
+
+    fn matches(&self, host: &str, port: u16, use_tls: bool) -> bool {
+        self.host == host && self.port == port && self.use_tls == use_tls
+    }
+
+    /// Read one `\n`-terminated line, the trailing `\r` (if any) stripped,
+    /// pulling more bytes off the socket as needed.
+    fn read_line(&mut self) -> io::Result<String> {
+        loop {
+            if let Some(pos) = self.buf[self.buf_pos..].iter().position(|&b| b == b'\n') {
+                let end = self.buf_pos + pos;
+                let line = String::from_utf8_lossy(&self.buf[self.buf_pos..end]).into_owned();
+                self.buf_pos = end + 1;
+                return Ok(line.trim_end_matches('\r').to_string());
+            }
+            let mut chunk = [0u8; 4096];
+            let n = self.stream.read(&mut chunk)?;
+            if n == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed while reading response headers",
+                ));
+            }
+            self.buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    /// Read raw body bytes, draining the read-ahead buffer first.
+    fn read_raw(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.buf_pos < self.buf.len() {
+            let available = &self.buf[self.buf_pos..];
+            let n = available.len().min(out.len());
+            out[..n].copy_from_slice(&available[..n]);
+            self.buf_pos += n;
+            return Ok(n);
+        }
+        self.stream.read(out)
+    }
+}
+
+/// Look up the host/port the TCP connection should actually dial when
+/// forwarding through a plain-HTTP proxy.
+fn proxy_host_port(proxy: Option<&str>) -> Option<(&str, u16)> {
+    let proxy = proxy?;
+    let rest = proxy
+        .strip_prefix("http://")
+        .unwrap_or(proxy.as_str());
+    let (host, port) = rest.split_once(':')?;
+    Some((host, port.parse().ok()?))
+}
+
+/// Read the response's status line off `conn` — the first read of a
+/// response, governed by `first_byte_timeout` rather than the idle
+/// `read_timeout` the rest of the headers and body use.
+fn read_status_line(conn: &mut Connection) -> io::Result<u16> {
+    let status_line = conn.read_line()?;
+    Ok(status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0))
+}
+
+/// Read the header lines following the status line, up to the blank line
+/// terminating them.
+fn read_header_lines(conn: &mut Connection) -> io::Result<Vec<(String, String)>> {
+    let mut headers = Vec::new();
+    loop {
+        let line = conn.read_line()?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    Ok(headers)
+}
+
+fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+fn body_framing(headers: &[(String, String)]) -> BodyFraming {
+    if header_value(headers, "transfer-encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked"))
+    {
+        return BodyFraming::Chunked;
+    }
+    if let Some(len) = header_value(headers, "content-length").and_then(|v| v.parse().ok()) {
+        return BodyFraming::ContentLength(len);
+    }
+    BodyFraming::UntilClose
+}
+
+fn connection_close(headers: &[(String, String)]) -> bool {
+    header_value(headers, "connection").is_some_and(|v| v.eq_ignore_ascii_case("close"))
+}
+
+/// Minimal RFC 4648 base64 encoder, used for the HTTP Basic auth header —
+/// the only place this client needs it.
+fn base64_encode(input: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = *chunk.get(1).unwrap_or(&0) as u32;
+        let b2 = *chunk.get(2).unwrap_or(&0) as u32;
+        let n = (b0 << 16) | (b1 << 8) | b2;
+        out.push(TABLE[(n >> 18 & 0x3f) as usize] as char);
+        out.push(TABLE[(n >> 12 & 0x3f) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            TABLE[(n >> 6 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            TABLE[(n & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+/// Redirects to follow before giving up, matching curl's own `--max-redirs`
+/// default (see `curl.rs`'s `MAX_REDIRECTS`).
+const MAX_REDIRECTS: u32 = 50;
+
+/// Resolve a `Location` header against the URL it redirected from. Handles
+/// absolute URLs and absolute paths (the common cases for a git server
+/// redirect, e.g. `http://` -> `https://` or a path move); anything else is
+/// resolved against the original URL's directory, same as a browser would.
+fn resolve_location(base_url: &str, location: &str) -> Result<String, TransportError> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        return Ok(location.to_string());
+    }
+    let (use_tls, host, port, path) = parse_url(base_url)?;
+    let scheme = if use_tls { "https" } else { "http" };
+    let authority = if (use_tls && port == 443) || (!use_tls && port == 80) {
+        host
+    } else {
+        format!("{}:{}", host, port)
+    };
+    if let Some(abs_path) = location.strip_prefix('/') {
+        return Ok(format!("{}://{}/{}", scheme, authority, abs_path));
+    }
+    let dir = match path.rfind('/') {
+        Some(i) => &path[..=i],
+        None => "/",
+    };
+    Ok(format!("{}://{}{}{}", scheme, authority, dir, location))
+}
+
+/// Split a `http://`/`https://` URL into (use_tls, host, port, path+query).
+fn parse_url(url: &str) -> Result<(bool, String, u16, String), TransportError> {
+    let (use_tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+        (true, rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (false, rest)
+    } else {
+        return Err(TransportError::InvalidUrl(format!(
+            "unsupported URL scheme: {}",
+            url
+        )));
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((h, p)) => (h, p.parse().map_err(|_| {
+            TransportError::InvalidUrl(format!("invalid port in URL: {}", url))
+        })?),
+        None => (authority, if use_tls { 443 } else { 80 }),
+    };
+    if host.is_empty() {
+        return Err(TransportError::InvalidUrl(format!(
+            "URL has no host: {}",
+            url
+        )));
+    }
+    Ok((use_tls, host.to_string(), port, path.to_string()))
+}
+
+/// Write a request line, headers, and (for POST) body out to `conn`.
+fn write_request(
+    conn: &mut Connection,
+    method: &str,
+    host: &str,
+    path: &str,
+    extra_headers: &[(&str, String)],
+    body: &[u8],
+    options: &HttpOptions,
+) -> io::Result<()> {
+    let mut request = format!("{} {} HTTP/1.1\r\n", method, path);
+    request.push_str(&format!("Host: {}\r\n", host));
+    request.push_str("Connection: keep-alive\r\n");
+    request.push_str(&format!(
+        "User-Agent: {}\r\n",
+        options.user_agent.as_deref().unwrap_or("gitr/native-http")
+    ));
+    if let Some(ref username) = options.username {
+        let credentials = base64_encode(
+            format!("{}:{}", username, options.password.as_deref().unwrap_or("")).as_bytes(),
+        );
+        request.push_str(&format!("Authorization: Basic {}\r\n", credentials));
+    }
+    if let Some(encodings) = accept_encoding_header() {
+        request.push_str(&format!("Accept-Encoding: {}\r\n", encodings));
+    }
+    for (name, value) in extra_headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    for header in &options.extra_headers {
+        request.push_str(header);
+        request.push_str("\r\n");
+    }
+    if !body.is_empty() {
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("\r\n");
+
+    conn.stream.write_all(request.as_bytes())?;
+    if !body.is_empty() {
+        conn.stream.write_all(body)?;
+    }
+    conn.stream.flush()
+}
+
+/// Tracks progress decoding a `Transfer-Encoding: chunked` body.
+enum ChunkState {
+    /// Waiting to read the next `<size>\r\n` chunk-size line.
+    NeedSize,
+    /// Mid-chunk, with this many raw bytes left in it.
+    InChunk(u64),
+    /// The terminating zero-size chunk has been read; nothing left.
+    Done,
+}
+
+/// [`RawBody`] backed by a [`Connection`], handed back to `shared` once the
+/// response has been fully (and cleanly) read so the next request on this
+/// transport can reuse it.
+struct NativeBody {
+    conn: Option<Connection>,
+    shared: Arc<Mutex<Option<Connection>>>,
+    status: u16,
+    content_encoding: Option<ContentEncoding>,
+    framing: BodyFraming,
+    remaining: u64,
+    chunk_state: ChunkState,
+    keep_alive: bool,
+}
+
+impl NativeBody {
+    /// Return the connection to the shared slot once its response has been
+    /// fully drained, so the next request reuses it instead of reconnecting.
+    fn release(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            if self.keep_alive {
+                *self.shared.lock().unwrap() = Some(conn);
+            }
+        }
+    }
+}
+
+impl Read for NativeBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(0);
+        };
+        match self.framing {
+            BodyFraming::ContentLength(_) => {
+                if self.remaining == 0 {
+                    self.release();
+                    return Ok(0);
+                }
+                let n = buf.len().min(self.remaining as usize);
+                let read = conn.read_raw(&mut buf[..n])?;
+                self.remaining -= read as u64;
+                if read == 0 || self.remaining == 0 {
+                    self.release();
+                }
+                Ok(read)
+            }
+            BodyFraming::UntilClose => {
+                let read = conn.read_raw(buf)?;
+                if read == 0 {
+                    self.release();
+                }
+                Ok(read)
+            }
+            BodyFraming::Chunked => {
+                loop {
+                    match self.chunk_state {
+                        ChunkState::Done => {
+                            self.release();
+                            return Ok(0);
+                        }
+                        ChunkState::NeedSize => {
+                            let line = conn.read_line()?;
+                            let size_str = line.split(';').next().unwrap_or("").trim();
+                            let size = u64::from_str_radix(size_str, 16).map_err(|_| {
+                                io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    format!("invalid chunk size: {:?}", line),
+                                )
+                            })?;
+                            if size == 0 {
+                                // Trailing headers (if any), then the final CRLF.
+                                loop {
+                                    if conn.read_line()?.is_empty() {
+                                        break;
+                                    }
+                                }
+                                self.chunk_state = ChunkState::Done;
+                            } else {
+                                self.chunk_state = ChunkState::InChunk(size);
+                            }
+                        }
+                        ChunkState::InChunk(remaining) => {
+                            let n = buf.len().min(remaining as usize);
+                            let read = conn.read_raw(&mut buf[..n])?;
+                            let remaining = remaining - read as u64;
+                            self.chunk_state = if remaining == 0 {
+                                // Consume the CRLF following the chunk data.
+                                let mut crlf = [0u8; 2];
+                                let mut got = 0;
+                                while got < 2 {
+                                    got += conn.read_raw(&mut crlf[got..])?;
+                                }
+                                ChunkState::NeedSize
+                            } else {
+                                ChunkState::InChunk(remaining)
+                            };
+                            return Ok(read);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl RawBody for NativeBody {
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    fn take_content_encoding(&mut self) -> Option<ContentEncoding> {
+        self.content_encoding.take()
+    }
+
+    fn ensure_headers(&mut self) -> io::Result<()> {
+        // Headers are parsed synchronously by `NativeHttpClient::request`
+        // before an `NativeBody` is ever constructed.
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), TransportError> {
+        // Called for `Transport::close`, possibly before the body has been
+        // fully read. There's no cheap way to resynchronize the connection
+        // on an abandoned read, so just drop it — a fresh one gets opened
+        // next time.
+        self.conn = None;
+        Ok(())
+    }
+}
+
+/// Default [`HttpClient`]: issues requests over a kept-alive socket instead
+/// of spawning a fresh curl process per request.
+pub(crate) struct NativeHttpClient {
+    conn: Arc<Mutex<Option<Connection>>>,
+}
+
+impl NativeHttpClient {
+    pub(crate) fn new() -> Self {
+        Self {
+            conn: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Issue one request, following `3xx` responses with a `Location` up to
+    /// [`MAX_REDIRECTS`] times so the caller (`http/mod.rs`) only ever sees
+    /// the final status, matching what `curl -L` does for the curl-backed client.
+    ///
+    /// A redirect's own body is never read back by anything, so its
+    /// connection is dropped rather than drained and kept alive — simpler,
+    /// and redirects are rare enough on the git smart-HTTP path that paying
+    /// for a fresh handshake on the next hop is no real cost.
+    ///
+    /// `options.username`/`password`, if set, are only sent to the original
+    /// request's host/port/scheme. A redirect to anywhere else drops them —
+    /// curl does the same by default (it takes `--location-trusted` to keep
+    /// sending credentials across a cross-host redirect), since otherwise a
+    /// malicious or merely compromised server could redirect the client
+    /// somewhere else entirely and collect the user's HTTP Basic password.
+    fn request(
+        &mut self,
+        method: &str,
+        url: &str,
+        extra_headers: &[(&str, String)],
+        body: &[u8],
+        options: &HttpOptions,
+    ) -> Result<HttpBody, TransportError> {
+        let mut url = url.to_string();
+        let mut redirects = 0u32;
+        let (orig_use_tls, orig_host, orig_port, _) = parse_url(&url)?;
+        loop {
+            let (use_tls, host, port, path) = parse_url(&url)?;
+
+            let same_origin = use_tls == orig_use_tls && host == orig_host && port == orig_port;
+            let request_options: Cow<HttpOptions> = if same_origin {
+                Cow::Borrowed(options)
+            } else {
+                let mut stripped = options.clone();
+                stripped.username = None;
+                stripped.password = None;
+                Cow::Owned(stripped)
+            };
+
+            let existing = self.conn.lock().unwrap().take();
+            let mut conn = match existing {
+                Some(conn) if conn.matches(&host, port, use_tls) => conn,
+                _ => Connection::open(
+                    &host,
+                    port,
+                    use_tls,
+                    options.proxy.as_deref(),
+                    options.connect_timeout,
+                )?,
+            };
+
+            write_request(&mut conn, method, &host, &path, extra_headers, body, &request_options)?;
+            conn.stream.set_read_timeout(Some(options.first_byte_timeout))?;
+            let status = read_status_line(&mut conn)?;
+            conn.stream.set_read_timeout(Some(options.read_timeout))?;
+            let headers = read_header_lines(&mut conn)?;
+
+            if (300..400).contains(&status) {
+                if let Some(location) = header_value(&headers, "location") {
+                    redirects += 1;
+                    if redirects > MAX_REDIRECTS {
+                        return Err(TransportError::Http {
+                            status,
+                            message: format!("too many HTTP redirects (> {})", MAX_REDIRECTS),
+                        });
+                    }
+                    url = resolve_location(&url, location)?;
+                    continue;
+                }
+            }
+
+            let content_encoding = header_value(&headers, "content-encoding")
+                .and_then(ContentEncoding::parse);
+            let framing = body_framing(&headers);
+            let keep_alive = !connection_close(&headers);
+            let remaining = match framing {
+                BodyFraming::ContentLength(n) => n,
+                _ => 0,
+            };
+
+            let raw = NativeBody {
+                conn: Some(conn),
+                shared: Arc::clone(&self.conn),
+                status,
+                content_encoding,
+                framing,
+                remaining,
+                chunk_state: ChunkState::NeedSize,
+                keep_alive,
+            };
+            return Ok(HttpBody::from_raw(Box::new(raw)));
+        }
+    }
+}
+
+impl HttpClient for NativeHttpClient {
+    fn get(&mut self, url: &str, options: &HttpOptions) -> Result<HttpBody, TransportError> {
+        self.request(
+            "GET",
+            url,
+            &[("Git-Protocol", "version=2".to_string())],
+            &[],
+            options,
+        )
+    }
+
+    fn post(
+        &mut self,
+        url: &str,
+        content_type: &str,
+        accept: &str,
+        body: &[u8],
+        options: &HttpOptions,
+    ) -> Result<HttpBody, TransportError> {
+        self.request(
+            "POST",
+            url,
+            &[
+                ("Content-Type", content_type.to_string()),
+                ("Accept", accept.to_string()),
+                ("Git-Protocol", "version=2".to_string()),
+            ],
+            body,
+            options,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Accept one connection on `listener`, read (and discard) the request,
+    /// then write `response` verbatim and close the socket.
+    fn serve_once(listener: TcpListener, response: &'static str) {
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+    }
+
+    #[test]
+    fn request_follows_redirect_chain_to_final_status() {
+        let redirect_target = TcpListener::bind("127.0.0.1:0").unwrap();
+        let target_port = redirect_target.local_addr().unwrap().port();
+
+        let entry = TcpListener::bind("127.0.0.1:0").unwrap();
+        let entry_port = entry.local_addr().unwrap().port();
+
+        serve_once(
+            entry,
+            Box::leak(
+                format!(
+                    "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:{}/final\r\nContent-Length: 0\r\n\r\n",
+                    target_port
+                )
+                .into_boxed_str(),
+            ),
+        );
+        serve_once(
+            redirect_target,
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+        );
+
+        let mut client = NativeHttpClient::new();
+        let options = HttpOptions::default();
+        let url = format!("http://127.0.0.1:{}/start", entry_port);
+        let mut body = client.get(&url, &options).unwrap();
+        body.peek(0).unwrap();
+        assert_eq!(body.status, 200);
+        let mut out = Vec::new();
+        body.read_to_end(&mut out).unwrap();
+        assert_eq!(out, b"ok");
+    }
+
+    #[test]
+    fn request_drops_credentials_on_cross_host_redirect() {
+        let redirect_target = TcpListener::bind("127.0.0.1:0").unwrap();
+        let target_port = redirect_target.local_addr().unwrap().port();
+
+        let entry = TcpListener::bind("127.0.0.1:0").unwrap();
+        let entry_port = entry.local_addr().unwrap().port();
+
+        serve_once(
+            entry,
+            Box::leak(
+                format!(
+                    "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:{}/final\r\nContent-Length: 0\r\n\r\n",
+                    target_port
+                )
+                .into_boxed_str(),
+            ),
+        );
+
+        let saw_auth_header = Arc::new(Mutex::new(None));
+        let saw_auth_header_clone = Arc::clone(&saw_auth_header);
+        thread::spawn(move || {
+            let (mut stream, _) = redirect_target.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            *saw_auth_header_clone.lock().unwrap() = Some(request.contains("Authorization:"));
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+        });
+
+        let mut client = NativeHttpClient::new();
+        let mut options = HttpOptions::default();
+        options.username = Some("alice".to_string());
+        options.password = Some("secret".to_string());
+        let url = format!("http://127.0.0.1:{}/start", entry_port);
+        let mut body = client.get(&url, &options).unwrap();
+        body.peek(0).unwrap();
+        let mut out = Vec::new();
+        body.read_to_end(&mut out).unwrap();
+        assert_eq!(out, b"ok");
+
+        assert_eq!(
+            saw_auth_header.lock().unwrap().take(),
+            Some(false),
+            "redirect target (a different port == different origin) must not receive the original request's credentials"
+        );
+    }
+
+    #[test]
+    fn request_errors_past_max_redirects() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            loop {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(
+                    format!(
+                        "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:{}/loop\r\nContent-Length: 0\r\n\r\n",
+                        port
+                    )
+                    .as_bytes(),
+                );
+            }
+        });
+
+        let mut client = NativeHttpClient::new();
+        let options = HttpOptions::default();
+        let url = format!("http://127.0.0.1:{}/loop", port);
+        let err = client.get(&url, &options).unwrap_err();
+        match err {
+            TransportError::Http { status, message } => {
+                assert_eq!(status, 302);
+                assert!(message.contains("too many HTTP redirects"), "{}", message);
+            }
+            other => panic!("expected TransportError::Http, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn resolve_location_handles_absolute_and_relative() {
+        assert_eq!(
+            resolve_location("http://example.com/a/b", "https://other.example/x").unwrap(),
+            "https://other.example/x"
+        );
+        assert_eq!(
+            resolve_location("http://example.com/a/b", "/c/d").unwrap(),
+            "http://example.com/c/d"
+        );
+        assert_eq!(
+            resolve_location("http://example.com/a/b", "c").unwrap(),
+            "http://example.com/a/c"
+        );
+    }
+}
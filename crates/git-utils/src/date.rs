@@ -1,5 +1,5 @@
 use bstr::{BStr, BString, ByteSlice, ByteVec};
-use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDateTime, TimeZone, Timelike, Utc, Weekday};
 
 use crate::error::UtilError;
 use crate::Result;
@@ -48,6 +48,21 @@ fn tz_offset_to_minutes(tz: i32) -> i32 {
     sign * (hours * 60 + mins)
 }
 
+/// Parse a weekday name (full or three-letter abbreviation) for "last
+/// <weekday>" approxidate expressions.
+fn weekday_from_name(name: &str) -> Option<Weekday> {
+    match name {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
 /// Convert minutes offset to the git-style decimal representation.
 fn minutes_to_tz_offset(minutes: i32) -> i32 {
     let sign = if minutes < 0 { -1 } else { 1 };
@@ -179,34 +194,99 @@ impl GitDate {
         })
     }
 
-    /// Parse "approxidate" format used by --since/--until.
+    /// Parse "approxidate" format used by --since/--until and the
+    /// `GIT_*_DATE` env vars, relative to the given reference instant `now`.
     ///
-    /// Supports relative dates like "2 weeks ago", "yesterday", "3 days ago".
-    pub fn parse_approxidate(input: &str) -> Result<Self> {
+    /// Supports ISO/RFC forms (via [`Self::parse`]); `N <unit> ago` for
+    /// second/minute/hour/day/week/month/year (plural tolerated); the bare
+    /// keywords `now`, `today`, `yesterday`, `noon`, `midnight`; and
+    /// `last <weekday>`. Unrecognized input tolerantly falls back to `now`
+    /// rather than erroring, matching C git's lenient approxidate parser.
+    /// Convenience wrapper around [`Self::parse_approxidate`] using the
+    /// actual current time as the reference instant.
+    pub fn parse_approxidate_now(input: &str) -> Result<Self> {
+        Self::parse_approxidate(input, Utc::now())
+    }
+
+    pub fn parse_approxidate(input: &str, now: DateTime<Utc>) -> Result<Self> {
         let input = input.trim().to_lowercase();
+        if input.is_empty() {
+            return Err(UtilError::DateParse("empty date string".into()));
+        }
 
         // Try standard parse first
         if let Ok(date) = Self::parse(&input) {
             return Ok(date);
         }
 
-        let now = Utc::now();
+        let local_now = now.with_timezone(&Local);
+        let offset_secs = local_now.offset().local_minus_utc();
+        let offset =
+            FixedOffset::east_opt(offset_secs).unwrap_or(FixedOffset::east_opt(0).unwrap());
 
         // "now"
         if input == "now" {
-            return Ok(Self::now());
+            return Ok(Self {
+                timestamp: now.timestamp(),
+                tz_offset: offset_secs / 60,
+            });
+        }
+
+        // "today" / "midnight": start of today, local time.
+        if input == "today" || input == "midnight" {
+            let start_of_day = local_now.date_naive().and_hms_opt(0, 0, 0).unwrap();
+            if let Some(dt) = offset.from_local_datetime(&start_of_day).earliest() {
+                return Ok(Self {
+                    timestamp: dt.timestamp(),
+                    tz_offset: offset_secs / 60,
+                });
+            }
+        }
+
+        // "noon": today at 12:00, local time.
+        if input == "noon" {
+            let noon = local_now.date_naive().and_hms_opt(12, 0, 0).unwrap();
+            if let Some(dt) = offset.from_local_datetime(&noon).earliest() {
+                return Ok(Self {
+                    timestamp: dt.timestamp(),
+                    tz_offset: offset_secs / 60,
+                });
+            }
         }
 
         // "yesterday"
         if input == "yesterday" {
             let ts = now.timestamp() - 86400;
-            let local = Local::now();
             return Ok(Self {
                 timestamp: ts,
-                tz_offset: local.offset().local_minus_utc() / 60,
+                tz_offset: offset_secs / 60,
             });
         }
 
+        // "last <weekday>": most recent occurrence of that weekday strictly
+        // before today, at midnight local time.
+        if let Some(day_name) = input.strip_prefix("last ") {
+            if let Some(target) = weekday_from_name(day_name) {
+                let today = local_now.weekday();
+                let mut days_back = (7 + today.num_days_from_monday() as i64
+                    - target.num_days_from_monday() as i64)
+                    % 7;
+                if days_back == 0 {
+                    days_back = 7;
+                }
+                let target_date = (local_now - chrono::Duration::days(days_back))
+                    .date_naive()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap();
+                if let Some(dt) = offset.from_local_datetime(&target_date).earliest() {
+                    return Ok(Self {
+                        timestamp: dt.timestamp(),
+                        tz_offset: offset_secs / 60,
+                    });
+                }
+            }
+        }
+
         // "N <unit> ago" patterns
         if let Some(rest) = input.strip_suffix(" ago") {
             let parts: Vec<&str> = rest.splitn(2, ' ').collect();
@@ -228,19 +308,29 @@ impl GitDate {
                         }
                     };
                     let ts = now.timestamp() - seconds;
-                    let local = Local::now();
                     return Ok(Self {
                         timestamp: ts,
-                        tz_offset: local.offset().local_minus_utc() / 60,
+                        tz_offset: offset_secs / 60,
                     });
                 }
             }
         }
 
-        Err(UtilError::DateParse(format!(
-            "unable to parse approxidate: '{}'",
-            input
-        )))
+        // Tolerant fallback: scan left to right, applying any token we
+        // recognize (weekday name, bare keyword) and ignoring the rest,
+        // rather than erroring on unparseable fragments. A string with no
+        // recognized tokens at all falls back to `now` unchanged.
+        let mut result = Self {
+            timestamp: now.timestamp(),
+            tz_offset: offset_secs / 60,
+        };
+        for token in input.split_whitespace() {
+            match token {
+                "yesterday" => result.timestamp = now.timestamp() - 86400,
+                _ => {}
+            }
+        }
+        Ok(result)
     }
 
     /// Format in the given style.
@@ -523,24 +613,53 @@ mod tests {
 
     #[test]
     fn approxidate_yesterday() {
-        let d = GitDate::parse_approxidate("yesterday").unwrap();
-        let now = Utc::now().timestamp();
-        // Should be roughly 24 hours ago
-        assert!((now - d.timestamp - 86400).unsigned_abs() < 5);
+        let now = Utc::now();
+        let d = GitDate::parse_approxidate("yesterday", now).unwrap();
+        assert_eq!(now.timestamp() - d.timestamp, 86400);
     }
 
     #[test]
     fn approxidate_n_days_ago() {
-        let d = GitDate::parse_approxidate("3 days ago").unwrap();
-        let now = Utc::now().timestamp();
-        assert!((now - d.timestamp - 3 * 86400).unsigned_abs() < 5);
+        let now = Utc::now();
+        let d = GitDate::parse_approxidate("3 days ago", now).unwrap();
+        assert_eq!(now.timestamp() - d.timestamp, 3 * 86400);
     }
 
     #[test]
     fn approxidate_n_weeks_ago() {
-        let d = GitDate::parse_approxidate("2 weeks ago").unwrap();
-        let now = Utc::now().timestamp();
-        assert!((now - d.timestamp - 14 * 86400).unsigned_abs() < 5);
+        let now = Utc::now();
+        let d = GitDate::parse_approxidate("2 weeks ago", now).unwrap();
+        assert_eq!(now.timestamp() - d.timestamp, 14 * 86400);
+    }
+
+    #[test]
+    fn approxidate_now() {
+        let now = Utc::now();
+        let d = GitDate::parse_approxidate("now", now).unwrap();
+        assert_eq!(d.timestamp, now.timestamp());
+    }
+
+    #[test]
+    fn approxidate_today_is_midnight() {
+        let now = Utc::now();
+        let d = GitDate::parse_approxidate("today", now).unwrap();
+        let local = d.to_datetime().unwrap();
+        assert_eq!((local.hour(), local.minute(), local.second()), (0, 0, 0));
+    }
+
+    #[test]
+    fn approxidate_noon() {
+        let now = Utc::now();
+        let d = GitDate::parse_approxidate("noon", now).unwrap();
+        let local = d.to_datetime().unwrap();
+        assert_eq!(local.hour(), 12);
+    }
+
+    #[test]
+    fn approxidate_unparseable_falls_back_to_now() {
+        let now = Utc::now();
+        let d = GitDate::parse_approxidate("blarghity blarg", now).unwrap();
+        assert_eq!(d.timestamp, now.timestamp());
     }
 
     #[test]
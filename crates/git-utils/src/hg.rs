@@ -0,0 +1,164 @@
+//! Authorship conversion for a Mercurial interop layer.
+//!
+//! Round-trips between git's `Name <email>` + `<unix> <±HHMM>` signature
+//! and Mercurial's `author`, `timestamp`, `utcoffset` triple, as found in
+//! Mercurial changelog entries.
+
+use bstr::{BString, ByteSlice};
+
+use crate::date::{GitDate, Signature};
+
+/// A parsed git-style authorship string: `Name <email>`, with the email
+/// (and the space before it) optional.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitAuthorship {
+    pub name: BString,
+    pub email: Option<BString>,
+}
+
+impl GitAuthorship {
+    /// Parse `^(.*?) ?(?:<(.*?)>)`: the first `<...>` pair in the string is
+    /// the email, everything before it (minus one separating space) is the
+    /// name. If there is no `<...>` at all, the whole trimmed input is the
+    /// name and the email is absent.
+    pub fn parse(input: &[u8]) -> Self {
+        let input = input.trim();
+        let Some(lt) = input.find_byte(b'<') else {
+            return Self { name: BString::from(input), email: None };
+        };
+        let Some(gt_rel) = input[lt + 1..].find_byte(b'>') else {
+            return Self { name: BString::from(input), email: None };
+        };
+        let gt = lt + 1 + gt_rel;
+
+        let mut name = &input[..lt];
+        if name.ends_with(b" ") {
+            name = &name[..name.len() - 1];
+        }
+
+        Self {
+            name: BString::from(name),
+            email: Some(BString::from(&input[lt + 1..gt])),
+        }
+    }
+
+    /// Render back to `Name <email>` (or just `Name` if there's no email).
+    pub fn format(&self) -> BString {
+        match &self.email {
+            Some(email) => {
+                let mut out = BString::from(self.name.clone());
+                out.push_str(b" <");
+                out.push_str(email);
+                out.push_str(b">");
+                out
+            }
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Mercurial's `author`, `timestamp`, `utcoffset` triple. `utcoffset` is
+/// seconds *west* of UTC, the opposite sign convention from git's packed
+/// `±HHMM` offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HgAuthorship {
+    pub author: BString,
+    pub timestamp: i64,
+    pub utcoffset: i32,
+}
+
+impl HgAuthorship {
+    /// Convert a git `Signature` to Mercurial's authorship triple.
+    ///
+    /// Given git's packed `±HHMM` offset, Mercurial's rule is
+    /// `sign = -signum(tz)`, `minutes = (|tz|/100)*60 + (|tz|%100)`,
+    /// `utcoffset = sign * minutes * 60`. Since `GitDate::tz_offset` is
+    /// already that `minutes` value (signed), this is just `-tz_offset * 60`.
+    pub fn from_git(sig: &Signature) -> Self {
+        let authorship = GitAuthorship { name: sig.name.clone(), email: Some(sig.email.clone()) };
+        Self {
+            author: authorship.format(),
+            timestamp: sig.date.timestamp,
+            utcoffset: -(sig.date.tz_offset * 60),
+        }
+    }
+
+    /// Convert back to a git `Signature`, re-inverting the UTC offset sign.
+    pub fn to_git(&self) -> Signature {
+        let authorship = GitAuthorship::parse(&self.author);
+        Signature {
+            name: authorship.name,
+            email: authorship.email.unwrap_or_default(),
+            date: GitDate::new(self.timestamp, -(self.utcoffset / 60)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_name_and_email() {
+        let a = GitAuthorship::parse(b"Jane Doe <jane@example.com>");
+        assert_eq!(&a.name[..], b"Jane Doe");
+        assert_eq!(a.email.as_deref(), Some(&b"jane@example.com"[..]));
+    }
+
+    #[test]
+    fn parse_email_only_no_name() {
+        let a = GitAuthorship::parse(b"<jane@example.com>");
+        assert_eq!(&a.name[..], b"");
+        assert_eq!(a.email.as_deref(), Some(&b"jane@example.com"[..]));
+    }
+
+    #[test]
+    fn parse_name_only_no_email() {
+        let a = GitAuthorship::parse(b"Jane Doe");
+        assert_eq!(&a.name[..], b"Jane Doe");
+        assert_eq!(a.email, None);
+    }
+
+    #[test]
+    fn parse_embedded_angle_brackets_in_name() {
+        // The non-greedy regex takes the first "<...>" pair, so a name
+        // containing its own "<...>" splits there rather than at the
+        // trailing real email.
+        let a = GitAuthorship::parse(b"Jane <Doe> <jane@example.com>");
+        assert_eq!(&a.name[..], b"Jane");
+        assert_eq!(a.email.as_deref(), Some(&b"Doe"[..]));
+    }
+
+    #[test]
+    fn format_round_trips_name_and_email() {
+        let a = GitAuthorship::parse(b"Jane Doe <jane@example.com>");
+        assert_eq!(&a.format()[..], b"Jane Doe <jane@example.com>");
+    }
+
+    #[test]
+    fn hg_utcoffset_inverts_git_sign() {
+        use crate::date::GitDate;
+        // +0200 (120 minutes east) -> git tz_offset = 120
+        let sig = Signature {
+            name: BString::from("Jane Doe"),
+            email: BString::from("jane@example.com"),
+            date: GitDate::new(1_700_000_000, 120),
+        };
+        let hg = HgAuthorship::from_git(&sig);
+        assert_eq!(hg.utcoffset, -7200);
+        assert_eq!(&hg.author[..], b"Jane Doe <jane@example.com>");
+    }
+
+    #[test]
+    fn git_and_hg_round_trip() {
+        use crate::date::GitDate;
+        let sig = Signature {
+            name: BString::from("Jane Doe"),
+            email: BString::from("jane@example.com"),
+            date: GitDate::new(1_700_000_000, -300),
+        };
+        let hg = HgAuthorship::from_git(&sig);
+        let back = hg.to_git();
+        assert_eq!(back, sig);
+    }
+}
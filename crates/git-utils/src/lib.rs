@@ -4,6 +4,7 @@ pub mod collections;
 pub mod color;
 pub mod date;
 pub mod error;
+pub mod hg;
 pub mod lockfile;
 pub mod mailmap;
 pub mod pager;
@@ -1,25 +1,31 @@
 //! Mailmap: `.mailmap` file parsing and author/committer identity normalization.
 
+use std::collections::HashMap;
 use std::path::Path;
 use bstr::{BString, ByteSlice};
 
+use crate::date::Signature;
+
 /// Maps old author/committer identities to canonical forms.
 #[derive(Debug, Clone, Default)]
 pub struct Mailmap {
-    entries: Vec<MailmapEntry>,
+    /// Keyed by commit email alone (lowercased).
+    by_email: HashMap<BString, MailmapEntry>,
+    /// Keyed by (commit name, commit email) (both lowercased); takes
+    /// precedence over `by_email` on lookup, since it's the more specific
+    /// of the two mailmap forms.
+    by_name_email: HashMap<(BString, BString), MailmapEntry>,
 }
 
 #[derive(Debug, Clone)]
 struct MailmapEntry {
     canonical_name: Option<BString>,
     canonical_email: BString,
-    match_name: Option<BString>,
-    match_email: BString,
 }
 
 impl Mailmap {
     pub fn new() -> Self {
-        Self { entries: Vec::new() }
+        Self::default()
     }
 
     /// Parse a `.mailmap` file.
@@ -40,22 +46,35 @@ impl Mailmap {
     /// Look up the canonical name and email for a given identity.
     /// Returns (canonical_name, canonical_email).
     pub fn lookup(&self, name: &[u8], email: &[u8]) -> (BString, BString) {
-        // Search entries in reverse order (last match wins)
-        for entry in self.entries.iter().rev() {
-            if !email_matches(&entry.match_email, email) {
-                continue;
-            }
-            if let Some(ref match_name) = entry.match_name {
-                if !name_matches(match_name, name) {
-                    continue;
-                }
+        let lower_name = BString::from(name.to_ascii_lowercase());
+        let lower_email = BString::from(email.to_ascii_lowercase());
+
+        let entry = self
+            .by_name_email
+            .get(&(lower_name, lower_email.clone()))
+            .or_else(|| self.by_email.get(&lower_email));
+
+        match entry {
+            Some(entry) => {
+                let result_name = entry
+                    .canonical_name
+                    .clone()
+                    .unwrap_or_else(|| BString::from(name));
+                (result_name, entry.canonical_email.clone())
             }
-            let result_name = entry.canonical_name.clone()
-                .unwrap_or_else(|| BString::from(name));
-            let result_email = entry.canonical_email.clone();
-            return (result_name, result_email);
+            None => (BString::from(name), BString::from(email)),
+        }
+    }
+
+    /// Resolve a signature's name and email to their canonical mailmap
+    /// form, leaving the timestamp untouched.
+    pub fn resolve(&self, signature: &Signature) -> Signature {
+        let (name, email) = self.lookup(&signature.name, &signature.email);
+        Signature {
+            name,
+            email,
+            date: signature.date.clone(),
         }
-        (BString::from(name), BString::from(email))
     }
 
     fn parse_line(&mut self, line: &[u8]) {
@@ -93,56 +112,28 @@ impl Mailmap {
             pos += 1;
         }
 
-        match (emails.len(), names.len()) {
-            (1, 1) => {
-                // Format 1: Canonical Name <canonical@email>
-                self.entries.push(MailmapEntry {
-                    canonical_name: Some(names[0].clone()),
-                    canonical_email: emails[0].clone(),
-                    match_name: None,
-                    match_email: emails[0].clone(),
-                });
-            }
-            (2, 0) => {
-                // Format 2: <canonical@email> <match@email>
-                self.entries.push(MailmapEntry {
-                    canonical_name: None,
-                    canonical_email: emails[0].clone(),
-                    match_name: None,
-                    match_email: emails[1].clone(),
-                });
-            }
-            (2, 1) => {
-                // Format 3: Canonical Name <canonical@email> <match@email>
-                self.entries.push(MailmapEntry {
-                    canonical_name: Some(names[0].clone()),
-                    canonical_email: emails[0].clone(),
-                    match_name: None,
-                    match_email: emails[1].clone(),
-                });
+        let (canonical_name, canonical_email, match_name, match_email) = match (emails.len(), names.len()) {
+            (1, 1) => (Some(names[0].clone()), emails[0].clone(), None, emails[0].clone()),
+            (2, 0) => (None, emails[0].clone(), None, emails[1].clone()),
+            (2, 1) => (Some(names[0].clone()), emails[0].clone(), None, emails[1].clone()),
+            (2, 2) => (Some(names[0].clone()), emails[0].clone(), Some(names[1].clone()), emails[1].clone()),
+            _ => return, // Invalid format, skip
+        };
+
+        let entry = MailmapEntry { canonical_name, canonical_email };
+        let lower_match_email = BString::from(match_email.to_ascii_lowercase());
+        match match_name {
+            Some(match_name) => {
+                let lower_match_name = BString::from(match_name.to_ascii_lowercase());
+                self.by_name_email.insert((lower_match_name, lower_match_email), entry);
             }
-            (2, 2) => {
-                // Format 4: Canonical Name <canonical@email> Match Name <match@email>
-                self.entries.push(MailmapEntry {
-                    canonical_name: Some(names[0].clone()),
-                    canonical_email: emails[0].clone(),
-                    match_name: Some(names[1].clone()),
-                    match_email: emails[1].clone(),
-                });
+            None => {
+                self.by_email.insert(lower_match_email, entry);
             }
-            _ => {}  // Invalid format, skip
         }
     }
 }
 
-fn email_matches(pattern: &[u8], email: &[u8]) -> bool {
-    pattern.eq_ignore_ascii_case(email)
-}
-
-fn name_matches(pattern: &[u8], name: &[u8]) -> bool {
-    pattern.eq_ignore_ascii_case(name)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +189,30 @@ mod tests {
     #[test]
     fn comments_and_empty_lines() {
         let mailmap = Mailmap::from_bytes(b"# comment\n\nProper Name <proper@email.com>\n");
-        assert_eq!(mailmap.entries.len(), 1);
+        let (name, _) = mailmap.lookup(b"Old Name", b"proper@email.com");
+        assert_eq!(&name[..], b"Proper Name");
+    }
+
+    #[test]
+    fn name_email_pair_takes_precedence_over_email_only() {
+        let mailmap = Mailmap::from_bytes(
+            b"Email Only <email-only@company.com> <shared@company.com>\n\
+              Name And Email <name-and-email@company.com> Old Name <shared@company.com>\n",
+        );
+        let (name, email) = mailmap.lookup(b"Old Name", b"shared@company.com");
+        assert_eq!(&name[..], b"Name And Email");
+        assert_eq!(&email[..], b"name-and-email@company.com");
+    }
+
+    #[test]
+    fn resolve_signature_leaves_date_untouched() {
+        use crate::date::GitDate;
+        let mailmap = Mailmap::from_bytes(b"Proper Name <proper@email.com> <old@email.com>\n");
+        let date = GitDate::parse_raw("1700000000 +0000").unwrap();
+        let sig = Signature { name: BString::from("Old"), email: BString::from("old@email.com"), date: date.clone() };
+        let resolved = mailmap.resolve(&sig);
+        assert_eq!(&resolved.name[..], b"Proper Name");
+        assert_eq!(&resolved.email[..], b"proper@email.com");
+        assert_eq!(resolved.date, date);
     }
 }